@@ -1,5 +1,7 @@
 use crate::handlers::artists::delete_artist::DeleteArtistQuery;
+use crate::handlers::artists::merge_artists::MergeArtistsQuery;
 use crate::handlers::edition_groups::delete_edition_group::DeleteEditionGroupQuery;
+use crate::handlers::entities::delete_entity::DeleteEntityQuery;
 use crate::handlers::title_groups::delete_title_group::DeleteTitleGroupQuery;
 use crate::handlers::title_groups::merge_title_groups::MergeTitleGroupsQuery;
 use arcadia_storage::models::artist::SearchArtistsQuery;
@@ -12,6 +14,7 @@ use arcadia_storage::models::collage::{
 use arcadia_storage::models::donation::{
     DeletedDonation, DonationOrderBy, EditedDonation, SearchDonationsQuery, UserCreatedDonation,
 };
+use arcadia_storage::models::entity::SearchEntitiesQuery;
 use arcadia_storage::models::forum::{
     DeleteForumCategoryQuery, DeleteForumPostQuery, DeleteForumSubCategoryQuery,
     DeleteForumThreadQuery, ForumSearchQuery, ForumSubCategoryAllowedPoster,
@@ -43,6 +46,10 @@ use utoipa::{
 };
 
 use arcadia_storage::models::arcadia_settings::AvailableShopItem;
+use arcadia_storage::models::auth_attempt::SearchAuthAttemptsQuery;
+use arcadia_storage::models::cheat_flag::SearchCheatFlagsQuery;
+use arcadia_storage::models::ip_ban::SearchIpBansQuery;
+use arcadia_storage::models::promotion_event::SearchPromotionEventsQuery;
 use arcadia_storage::models::shop::{
     BuyFreeleechTokensRequest, BuyUploadRequest, FreeleechTokenDiscountTier,
     FreeleechTokensPriceCalculation, PromotionPricing, ShopPricing, UploadDiscountTier,
@@ -52,6 +59,7 @@ use arcadia_storage::models::unauthorized_access::SearchUnauthorizedAccessQuery;
 use arcadia_storage::models::user_edit_change_log::{
     DeleteUserEditChangeLogQuery, SearchUserEditChangeLogsQuery,
 };
+use arcadia_storage::models::webhook::{SearchWebhookDeliveriesQuery, WebhookPayload};
 
 use crate::handlers::auth::irc_auth::{IrcAuthRequest, IrcAuthResponse};
 use crate::handlers::image_host::upload_image::{UploadImageForm, UploadImageResponse};
@@ -87,6 +95,12 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::auth::login::exec,
         crate::handlers::auth::logout::exec,
         crate::handlers::auth::refresh_token::exec,
+        crate::handlers::auth::forgot_password::exec,
+        crate::handlers::auth::reset_password::exec,
+        crate::handlers::auth::login_two_factor::exec,
+        crate::handlers::auth::setup_two_factor::exec,
+        crate::handlers::auth::enable_two_factor::exec,
+        crate::handlers::auth::disable_two_factor::exec,
         crate::handlers::users::get_user::exec,
         crate::handlers::users::edit_user::exec,
         crate::handlers::users::warn_user::exec,
@@ -104,6 +118,9 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::users::create_irc_account::exec,
         crate::handlers::users::reset_irc_password::exec,
         crate::handlers::users::get_user_torrent_activities::exec,
+        crate::handlers::users::get_user_hit_and_runs::exec,
+        crate::handlers::users::clear_hit_and_run::exec,
+        crate::handlers::users::get_user_unregistered_info_hashes::exec,
         crate::handlers::users::get_user_torrent_activities_overview::exec,
         crate::handlers::users::search_bonus_points_logs::exec,
         crate::handlers::shop::buy_promotion::exec,
@@ -115,6 +132,20 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::user_applications::get_user_applications::exec,
         crate::handlers::user_applications::update_user_application_status::exec,
         crate::handlers::unauthorized_access::search::exec,
+        crate::handlers::auth_attempts::search::exec,
+        crate::handlers::cheat_flags::search::exec,
+        crate::handlers::ip_bans::search_ip_bans::exec,
+        crate::handlers::ip_bans::create_ip_ban::exec,
+        crate::handlers::ip_bans::delete_ip_ban::exec,
+        crate::handlers::webhooks::get_webhooks::exec,
+        crate::handlers::webhooks::create_webhook::exec,
+        crate::handlers::webhooks::edit_webhook::exec,
+        crate::handlers::webhooks::delete_webhook::exec,
+        crate::handlers::webhooks::search_webhook_deliveries::exec,
+        crate::handlers::promotion_events::search_promotion_events::exec,
+        crate::handlers::promotion_events::create_promotion_event::exec,
+        crate::handlers::promotion_events::edit_promotion_event::exec,
+        crate::handlers::promotion_events::delete_promotion_event::exec,
         crate::handlers::user_edit_change_logs::search::exec,
         crate::handlers::user_edit_change_logs::delete_user_edit_change_log::exec,
         crate::handlers::user_edit_change_logs::delete_all_user_edit_change_logs::exec,
@@ -123,9 +154,20 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::artists::create_artists::exec,
         crate::handlers::artists::edit_artist::exec,
         crate::handlers::artists::delete_artist::exec,
+        crate::handlers::artists::merge_artists::exec,
         crate::handlers::affiliated_artists::create_affiliated_artists::exec,
         crate::handlers::affiliated_artists::remove_affiliated_artists::exec,
+        crate::handlers::entities::get_entity::exec,
+        crate::handlers::entities::create_entities::exec,
+        crate::handlers::entities::edit_entity::exec,
+        crate::handlers::entities::delete_entity::exec,
+        crate::handlers::affiliated_entities::create_affiliated_entities::exec,
+        crate::handlers::affiliated_entities::remove_affiliated_entities::exec,
         crate::handlers::torrents::download_dottorrent_file::exec,
+        crate::handlers::feeds::get_feed::exec,
+        crate::handlers::feeds::create_feed_filter::exec,
+        crate::handlers::feeds::get_feed_filters::exec,
+        crate::handlers::feeds::delete_feed_filter::exec,
         crate::handlers::torrents::create_torrent::exec,
         crate::handlers::torrents::edit_torrent::exec,
         crate::handlers::torrents::get_upload_information::exec,
@@ -138,6 +180,7 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::torrents::get_torrent_title_group::exec,
         crate::handlers::torrents::edit_torrent_up_down_factors::exec,
         crate::handlers::torrents::move_torrent_to_edition_group::exec,
+        crate::handlers::torrents::use_freeleech_token::exec,
         crate::handlers::edition_groups::create_edition_group::exec,
         crate::handlers::edition_groups::edit_edition_group::exec,
         crate::handlers::edition_groups::delete_edition_group::exec,
@@ -184,10 +227,13 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::search::search_title_group_tags::exec,
         crate::handlers::search::search_title_group_tags_lite::exec,
         crate::handlers::search::search_torrents::exec,
+        crate::handlers::search::search_torrent_suggestions::exec,
         crate::handlers::search::search_title_group_info_lite::exec,
         crate::handlers::search::search_torrent_requests::exec,
         crate::handlers::search::search_artists::exec,
         crate::handlers::search::search_artists_lite::exec,
+        crate::handlers::search::search_entities::exec,
+        crate::handlers::search::search_entities_lite::exec,
         crate::handlers::search::search_collages::exec,
         crate::handlers::search::search_collages_lite::exec,
         crate::handlers::search::search_series::exec,
@@ -269,6 +315,10 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         crate::handlers::external_db::get_musicbrainz_data::exec,
         crate::handlers::external_db::get_tmdb_data::exec,
         crate::handlers::external_db::get_comic_vine_data::exec,
+        crate::handlers::external_db::get_igdb_data::exec,
+        crate::handlers::external_db::get_discogs_data::exec,
+        crate::handlers::external_db::get_anilist_data::exec,
+        crate::handlers::external_db::get_tvmaze_data::exec,
         crate::handlers::title_group_bookmarks::create_title_group_bookmark::exec,
         crate::handlers::title_group_bookmarks::edit_title_group_bookmark::exec,
         crate::handlers::title_group_bookmarks::get_title_group_bookmark::exec,
@@ -295,12 +345,21 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         GetUserApplicationsQuery,
         UserApplicationHierarchy,
         SearchUnauthorizedAccessQuery,
+        SearchAuthAttemptsQuery,
+        SearchCheatFlagsQuery,
+        SearchIpBansQuery,
+        SearchWebhookDeliveriesQuery,
+        WebhookPayload,
+        SearchPromotionEventsQuery,
         SearchUserEditChangeLogsQuery,
         DeleteUserEditChangeLogQuery,
         SearchTorrentRequestsQuery,
         TorrentRequestSearchOrderBy,
         SearchArtistsQuery,
         DeleteArtistQuery,
+        MergeArtistsQuery,
+        SearchEntitiesQuery,
+        DeleteEntityQuery,
         DeleteEditionGroupQuery,
         DeleteTitleGroupQuery,
         MergeTitleGroupsQuery,
@@ -406,6 +465,7 @@ use arcadia_storage::models::user_application::UserApplicationHierarchy;
         arcadia_storage::models::forum::DeleteRelatedForumThreadQuery,
         arcadia_storage::models::series::SeriesEnriched,
         arcadia_storage::models::artist::ArtistEnriched,
+        arcadia_storage::models::entity::EntityEnriched,
     ),)
 )]
 pub struct ApiDoc;
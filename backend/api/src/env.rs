@@ -1,5 +1,7 @@
+use arcadia_storage::sqlx::types::ipnetwork::IpNetwork;
 use envconfig::Envconfig;
 use reqwest::Url;
+use std::{net::IpAddr, str::FromStr};
 
 #[derive(Envconfig, Clone)]
 pub struct Env {
@@ -21,6 +23,12 @@ pub struct Env {
     pub redis: RedisConfig,
     #[envconfig(from = "TMDB_API_KEY")]
     pub tmdb_api_key: Option<String>,
+    #[envconfig(from = "IGDB_CLIENT_ID")]
+    pub igdb_client_id: Option<String>,
+    #[envconfig(from = "IGDB_CLIENT_SECRET")]
+    pub igdb_client_secret: Option<String>,
+    #[envconfig(from = "DISCOGS_TOKEN")]
+    pub discogs_token: Option<String>,
     #[envconfig(nested)]
     pub image_host: ImageHostConfig,
     #[envconfig(from = "OTEL_SERVICE_NAME")]
@@ -33,6 +41,10 @@ pub struct Env {
     pub ergo: ErgoConfig,
     #[envconfig(from = "HTTP_PROXY")]
     pub http_proxy: Option<String>,
+    #[envconfig(nested)]
+    pub auth_rate_limit: AuthRateLimitConfig,
+    #[envconfig(nested)]
+    pub reverse_proxy: ReverseProxyConfig,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -107,6 +119,59 @@ impl ErgoConfig {
     }
 }
 
+#[derive(Envconfig, Clone)]
+pub struct AuthRateLimitConfig {
+    /// length of the sliding window in which attempts are counted, an attempt counts for this
+    /// long after it is made
+    #[envconfig(from = "AUTH_RATE_LIMIT_WINDOW_SECONDS", default = "900")]
+    pub window_seconds: i64,
+    /// failed logins allowed per ip and per username within the window
+    #[envconfig(from = "AUTH_RATE_LIMIT_MAX_FAILURES", default = "5")]
+    pub max_failures: usize,
+    /// registrations, applications and password reset requests allowed per ip within the window
+    #[envconfig(from = "AUTH_RATE_LIMIT_MAX_ATTEMPTS", default = "20")]
+    pub max_attempts: usize,
+    /// password reset requests allowed per email within the window
+    #[envconfig(from = "AUTH_RATE_LIMIT_MAX_PASSWORD_RESETS", default = "3")]
+    pub max_password_resets: usize,
+    #[envconfig(from = "AUTH_RATE_LIMIT_LOCKOUT_SECONDS", default = "900")]
+    pub lockout_seconds: i64,
+}
+
+#[derive(Envconfig, Clone)]
+pub struct ReverseProxyConfig {
+    /// header in which the reverse proxy passes the client's ip, the last address of the comma
+    /// separated list is used. The connecting address is used when not set
+    #[envconfig(from = "REVERSE_PROXY_CLIENT_IP_HEADER_NAME")]
+    pub client_ip_header_name: Option<String>,
+    /// addresses of the reverse proxies, the header is ignored on connections from anywhere else
+    #[envconfig(from = "REVERSE_PROXY_TRUSTED_IPS", default = "127.0.0.1,::1")]
+    pub trusted_ips: TrustedIps,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrustedIps(pub Vec<IpNetwork>);
+
+impl TrustedIps {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|network| network.contains(ip))
+    }
+}
+
+impl FromStr for TrustedIps {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| ip.parse::<IpNetwork>().map_err(|e| format!("{ip}: {e}")))
+            .collect::<Result<Vec<_>, _>>()
+            .map(TrustedIps)
+    }
+}
+
 #[derive(Envconfig, Clone)]
 pub struct ImageHostConfig {
     #[envconfig(from = "CHEVERETO_API_URL")]
@@ -0,0 +1,76 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::{
+        entity::{AffiliatedEntityHierarchy, UserCreatedAffiliatedEntity},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create entity affiliation",
+    tag = "Affiliated Entity",
+    path = "/api/affiliated-entities",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully created the entity affiliations", body=Vec<AffiliatedEntityHierarchy>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    entities: Json<Vec<UserCreatedAffiliatedEntity>>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let mut title_group_ids: Vec<i32> = entities.iter().map(|e| e.title_group_id).collect();
+    title_group_ids.sort_unstable();
+    title_group_ids.dedup();
+    ensure_can_edit_affiliations(&arc, user.sub, &title_group_ids).await?;
+
+    let affiliations = arc
+        .pool
+        .create_entities_affiliation(&entities, user.sub)
+        .await?;
+
+    Ok(HttpResponse::Created().json(affiliations))
+}
+
+/// Affiliations can be edited with the `EditTitleGroup` permission, or by the uploader of every
+/// title group involved
+pub async fn ensure_can_edit_affiliations<R: RedisPoolInterface + 'static>(
+    arc: &Arcadia<R>,
+    user_id: i32,
+    title_group_ids: &[i32],
+) -> Result<()> {
+    if arc
+        .pool
+        .user_has_permission(user_id, &UserPermission::EditTitleGroup)
+        .await?
+    {
+        return Ok(());
+    }
+
+    for title_group_id in title_group_ids {
+        if arc
+            .pool
+            .find_title_group(*title_group_id)
+            .await?
+            .created_by_id
+            != user_id
+        {
+            return Err(Error::InsufficientPermissions(format!(
+                "{:?}",
+                UserPermission::EditTitleGroup
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,13 @@
+pub mod create_affiliated_entities;
+pub mod remove_affiliated_entities;
+
+use actix_web::web::{delete, post, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(
+        resource("")
+            .route(post().to(self::create_affiliated_entities::exec::<R>))
+            .route(delete().to(self::remove_affiliated_entities::exec::<R>)),
+    );
+}
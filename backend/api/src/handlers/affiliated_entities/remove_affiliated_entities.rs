@@ -0,0 +1,46 @@
+use super::create_affiliated_entities::ensure_can_edit_affiliations;
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::redis::RedisPoolInterface;
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RemoveAffiliatedEntitiesForm {
+    pub affiliation_ids: Vec<i64>,
+}
+
+#[utoipa::path(
+    delete,
+    operation_id = "Delete entity affiliation",
+    tag = "Affiliated Entity",
+    path = "/api/affiliated-entities",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully removed the entity affiliations"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    form: Json<RemoveAffiliatedEntitiesForm>,
+) -> Result<HttpResponse> {
+    let title_group_ids = arc
+        .pool
+        .find_entities_affiliation_title_group_ids(&form.affiliation_ids)
+        .await?;
+    ensure_can_edit_affiliations(&arc, user.sub, &title_group_ids).await?;
+
+    arc.pool
+        .delete_entities_affiliation(&form.affiliation_ids, user.sub)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({"result": "success"})))
+}
@@ -17,6 +17,7 @@ use arcadia_storage::{models::arcadia_settings::PublicArcadiaSettings, redis::Re
 )]
 pub async fn exec<R: RedisPoolInterface + 'static>(arc: Data<Arcadia<R>>) -> Result<HttpResponse> {
     let settings = arc.settings.lock().unwrap().clone();
+    let promotion_events = arc.pool.find_public_promotion_events().await?;
     Ok(HttpResponse::Ok().json(PublicArcadiaSettings {
         global_download_factor: settings.global_download_factor,
         global_upload_factor: settings.global_upload_factor,
@@ -35,5 +36,6 @@ pub async fn exec<R: RedisPoolInterface + 'static>(arc: Data<Arcadia<R>>) -> Res
         irc_webchat_default_channels: settings.irc_webchat_default_channels.clone(),
         min_amount_tags_title_group: settings.min_amount_tags_title_group,
         custom_js_code: settings.custom_js_code,
+        promotion_events,
     }))
 }
@@ -4,7 +4,6 @@ use actix_web::{
     HttpRequest, HttpResponse,
 };
 use arcadia_common::error::Result;
-use arcadia_shared::tracker::models::env::ArcadiaSettingsForTracker;
 use arcadia_storage::{
     models::{arcadia_settings::ArcadiaSettings, user::UserPermission},
     redis::RedisPoolInterface,
@@ -74,37 +73,52 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         ));
     }
 
-    let updated_settings = arc.pool.update_arcadia_settings(&settings).await?;
+    if settings.freeleech_token_duration_hours <= 0 {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "freeleech_token_duration_hours must be greater than 0".to_string(),
+        ));
+    }
 
-    // Update the in-memory settings
-    *arc.settings.lock().unwrap() = updated_settings.clone();
+    if let Some(hours) = settings.hit_and_run_min_seed_time_hours
+        && hours <= 0
+    {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "hit_and_run_min_seed_time_hours must be greater than 0".to_string(),
+        ));
+    }
 
-    // Notify tracker of settings change
-    let mut url = arc.env.tracker.url_internal.clone();
-    url.path_segments_mut()
-        .unwrap()
-        .push("api")
-        .push("settings");
+    if settings.hit_and_run_min_ratio < 0.0 {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "hit_and_run_min_ratio must be greater than or equal to 0".to_string(),
+        ));
+    }
 
-    let payload = ArcadiaSettingsForTracker {
-        global_upload_factor: updated_settings.global_upload_factor,
-        global_download_factor: updated_settings.global_download_factor,
-        snatched_torrent_bonus_points_transferred_to: updated_settings
-            .snatched_torrent_bonus_points_transferred_to
-            .clone(),
-    };
+    if settings.hit_and_run_grace_period_days < 0 {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "hit_and_run_grace_period_days must be greater than or equal to 0".to_string(),
+        ));
+    }
 
-    let res = arc
-        .internal_http_client
-        .put(url)
-        .header("x-api-key", arc.env.tracker.api_key.clone())
-        .json(&payload)
-        .send()
-        .await;
+    if let Some(threshold) = settings.hit_and_run_warning_threshold
+        && threshold <= 0
+    {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "hit_and_run_warning_threshold must be greater than 0".to_string(),
+        ));
+    }
 
-    if res.is_err() {
-        log::warn!("Tried to update settings in tracker and got: {:?}", res);
+    if let Some(cost) = settings.hit_and_run_clear_bonus_points_cost
+        && cost < 0
+    {
+        return Err(arcadia_common::error::Error::InvalidArcadiaSettings(
+            "hit_and_run_clear_bonus_points_cost must be greater than or equal to 0".to_string(),
+        ));
     }
 
+    let updated_settings = arc.pool.update_arcadia_settings(&settings).await?;
+
+    // Update the in-memory settings
+    *arc.settings.lock().unwrap() = updated_settings.clone();
+
     Ok(HttpResponse::Ok().json(updated_settings))
 }
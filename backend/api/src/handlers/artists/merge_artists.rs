@@ -0,0 +1,52 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{web::Data, web::Query, HttpRequest, HttpResponse};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::models::artist::Artist;
+use arcadia_storage::models::user::UserPermission;
+use arcadia_storage::redis::RedisPoolInterface;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct MergeArtistsQuery {
+    pub source_artist_id: i64,
+    pub target_artist_id: i64,
+}
+
+#[utoipa::path(
+    post,
+    operation_id = "Merge artists",
+    tag = "Artist",
+    path = "/api/artists/merge",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(MergeArtistsQuery),
+    responses(
+        (status = 200, description = "Successfully merged the artists", body=Artist),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<MergeArtistsQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    if query.source_artist_id == query.target_artist_id {
+        return Err(Error::CannotMergeArtistIntoItself);
+    }
+
+    arc.pool
+        .require_permission(user.sub, &UserPermission::MergeArtist, req.path())
+        .await?;
+
+    let source_artist = arc.pool.find_artist_by_id(query.source_artist_id).await?;
+    let target_artist = arc.pool.find_artist_by_id(query.target_artist_id).await?;
+
+    let artist = arc
+        .pool
+        .merge_artists(source_artist.id, target_artist.id, user.sub)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(artist))
+}
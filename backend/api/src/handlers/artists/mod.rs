@@ -2,6 +2,7 @@ pub mod create_artists;
 pub mod delete_artist;
 pub mod edit_artist;
 pub mod get_artist;
+pub mod merge_artists;
 
 use actix_web::web::{delete, get, post, put, resource, ServiceConfig};
 use arcadia_storage::redis::RedisPoolInterface;
@@ -14,4 +15,5 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
             .route(put().to(self::edit_artist::exec::<R>))
             .route(delete().to(self::delete_artist::exec::<R>)),
     );
+    cfg.service(resource("/merge").route(post().to(self::merge_artists::exec::<R>)));
 }
@@ -1,11 +1,14 @@
-use crate::Arcadia;
+use crate::{services::client_ip_service, Arcadia};
 use actix_web::{
     web::{Data, Json},
     HttpRequest, HttpResponse,
 };
-use arcadia_common::error::Result;
+use arcadia_common::error::{Error, Result};
 use arcadia_storage::{
-    models::user_application::{UserApplication, UserCreatedUserApplication},
+    models::{
+        auth_attempt::AuthAttemptAction,
+        user_application::{UserApplication, UserCreatedUserApplication},
+    },
     redis::RedisPoolInterface,
     sqlx::types::ipnetwork::IpNetwork,
 };
@@ -16,7 +19,9 @@ use arcadia_storage::{
     tag = "User Application",
     path = "/api/auth/apply",
     responses(
-        (status = 201, description = "Successfully created user application", body = UserApplication)
+        (status = 201, description = "Successfully created user application", body = UserApplication),
+        (status = 403, description = "The ip address is banned"),
+        (status = 429, description = "Too many attempts from this ip, see the Retry-After header"),
     )
 )]
 pub async fn exec<R: RedisPoolInterface + 'static>(
@@ -24,16 +29,37 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     req: HttpRequest,
     application: Json<UserCreatedUserApplication>,
 ) -> Result<HttpResponse> {
-    let client_ip = req
-        .connection_info()
-        .realip_remote_addr()
-        .and_then(|ip| ip.parse::<IpNetwork>().ok())
+    let client_ip = client_ip_service::client_ip(&req, &arc.env.reverse_proxy)
+        .map(IpNetwork::from)
         .unwrap();
+    if arc.pool.is_ip_banned(client_ip.ip()).await? {
+        return Err(Error::IpBanned);
+    }
+    let ip_key = format!("apply:ip:{}", client_ip.ip());
+
+    if let Err(error) = arc.rate_limiter.check(&[&ip_key]).await {
+        arc.pool
+            .create_auth_attempt(AuthAttemptAction::Apply, Some(client_ip), None, false, true)
+            .await?;
+        return Err(error);
+    }
+    arc.rate_limiter
+        .hit(&ip_key, arc.rate_limiter.config.max_attempts)
+        .await?;
 
     let created_application = arc
         .pool
         .create_user_application(&application.into_inner(), client_ip)
+        .await;
+    arc.pool
+        .create_auth_attempt(
+            AuthAttemptAction::Apply,
+            Some(client_ip),
+            None,
+            created_application.is_ok(),
+            false,
+        )
         .await?;
 
-    Ok(HttpResponse::Created().json(created_application))
+    Ok(HttpResponse::Created().json(created_application?))
 }
@@ -0,0 +1,40 @@
+use crate::{
+    middlewares::auth_middleware::Authdata, services::two_factor_service::verify_second_factor,
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{models::two_factor::TwoFactorCode, redis::RedisPoolInterface};
+
+#[utoipa::path(
+    post,
+    operation_id = "Disable two-factor authentication",
+    tag = "Auth",
+    path = "/api/auth/2fa/disable",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Two-factor authentication is disabled"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    form: Json<TwoFactorCode>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    if !arc.pool.is_two_factor_enabled(user.sub).await? {
+        return Err(Error::TwoFactorNotSetUp);
+    }
+
+    if !verify_second_factor(&arc.pool, user.sub, &form.code).await? {
+        return Err(Error::InvalidTwoFactorCode);
+    }
+
+    arc.pool.disable_user_totp(user.sub).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
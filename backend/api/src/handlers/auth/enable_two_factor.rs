@@ -0,0 +1,61 @@
+use crate::{
+    middlewares::auth_middleware::Authdata,
+    services::two_factor_service::{check_totp_code, generate_recovery_codes, hash_recovery_code},
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::two_factor::{TwoFactorCode, TwoFactorRecoveryCodes},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Enable two-factor authentication",
+    tag = "Auth",
+    path = "/api/auth/2fa/enable",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Two-factor authentication is now required to log in", body=TwoFactorRecoveryCodes),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    form: Json<TwoFactorCode>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let user_totp = arc
+        .pool
+        .find_user_totp(user.sub)
+        .await?
+        .ok_or(Error::TwoFactorNotSetUp)?;
+
+    if user_totp.enabled_at.is_some() {
+        return Err(Error::TwoFactorAlreadyEnabled);
+    }
+
+    // proves that the user's authenticator app is set up correctly
+    let Some(step) = check_totp_code(&user_totp.secret, &form.code)? else {
+        return Err(Error::InvalidTwoFactorCode);
+    };
+    // so that the code can't also be used to log in
+    arc.pool.use_totp_step(user.sub, step).await?;
+
+    let recovery_codes = generate_recovery_codes();
+    let recovery_code_hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|c| hash_recovery_code(c))
+        .collect();
+
+    arc.pool
+        .enable_user_totp(user.sub, &recovery_code_hashes)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(TwoFactorRecoveryCodes { recovery_codes }))
+}
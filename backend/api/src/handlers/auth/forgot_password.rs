@@ -0,0 +1,75 @@
+use crate::{
+    services::{client_ip_service, email_service::EmailService},
+    Arcadia,
+};
+use actix_web::{web, HttpRequest, HttpResponse};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::user::ForgotPassword, redis::RedisPoolInterface, sqlx::types::ipnetwork::IpNetwork,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Forgot password",
+    tag = "Auth",
+    path = "/api/auth/forgot-password",
+    responses(
+        (status = 200, description = "A reset link was sent if the email belongs to an account"),
+        (status = 429, description = "Too many requests from this ip or for this email, see the Retry-After header"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: web::Data<Arcadia<R>>,
+    form: web::Json<ForgotPassword>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    let client_ip = client_ip_service::client_ip(&req, &arc.env.reverse_proxy).map(IpNetwork::from);
+    let email = form.email.trim();
+
+    // the email is limited on its own so that nobody can flood someone's inbox from many
+    // addresses, the ip so that nobody can go through many emails
+    let mut rate_limit_keys = vec![format!("forgot_password:email:{}", email.to_lowercase())];
+    if let Some(client_ip) = client_ip {
+        rate_limit_keys.push(format!("forgot_password:ip:{}", client_ip.ip()));
+    }
+    let keys: Vec<&str> = rate_limit_keys.iter().map(String::as_str).collect();
+
+    arc.rate_limiter.check(&keys).await?;
+    arc.rate_limiter
+        .hit(keys[0], arc.rate_limiter.config.max_password_resets)
+        .await?;
+    if let Some(ip_key) = keys.get(1) {
+        arc.rate_limiter
+            .hit(ip_key, arc.rate_limiter.config.max_attempts)
+            .await?;
+    }
+
+    // always answer the same way, so that this can't be used to find out which emails are
+    // registered. The email is sent in the background, so that the response time doesn't
+    // tell either
+    let Some(user) = arc.pool.find_user_lite_with_email(email).await? else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+    if user.banned {
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    let reset_token = arc.auth.create_password_reset_token(user.id).await?;
+
+    match EmailService::new(&arc) {
+        Ok(email_service) => {
+            let email = email.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = email_service
+                    .send_password_reset_email(&email, &user.username, &reset_token)
+                    .await
+                {
+                    log::warn!("Failed to send password reset email to {}: {}", email, e);
+                }
+            });
+        }
+        Err(_) => log::warn!("Email service not configured, skipping password reset email"),
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,12 +1,20 @@
 use crate::{
-    services::auth::{AUTH_TOKEN_LONG_DURATION, AUTH_TOKEN_SHORT_DURATION, REFRESH_TOKEN_DURATION},
+    services::{
+        auth::{AUTH_TOKEN_LONG_DURATION, AUTH_TOKEN_SHORT_DURATION, REFRESH_TOKEN_DURATION},
+        client_ip_service,
+    },
     Arcadia,
 };
-use actix_web::{web, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
 use arcadia_common::error::{Error, Result};
 use arcadia_storage::{
-    models::user::{Claims, Login, LoginResponse},
+    models::{
+        auth_attempt::AuthAttemptAction,
+        two_factor::TwoFactorChallenge,
+        user::{Claims, Login, LoginResponse},
+    },
     redis::RedisPoolInterface,
+    sqlx::types::ipnetwork::IpNetwork,
 };
 use chrono::prelude::Utc;
 use jsonwebtoken::{encode, EncodingKey, Header};
@@ -18,30 +26,115 @@ use jsonwebtoken::{encode, EncodingKey, Header};
     path = "/api/auth/login",
     responses(
         (status = 200, description = "Successfully logged in", body=LoginResponse),
+        (status = 202, description = "Correct credentials, a second factor is now required at /api/auth/2fa/login", body=TwoFactorChallenge),
+        (status = 403, description = "The account or the ip address is banned"),
+        (status = 429, description = "Too many failed attempts from this ip or for this username, see the Retry-After header"),
     )
 )]
 pub async fn exec<R: RedisPoolInterface + 'static>(
     arc: web::Data<Arcadia<R>>,
     user_login: web::Json<Login>,
+    req: HttpRequest,
 ) -> Result<HttpResponse> {
-    let user = arc.pool.find_user_with_password(&user_login).await?;
+    let client_ip = client_ip_service::client_ip(&req, &arc.env.reverse_proxy).map(IpNetwork::from);
+    if let Some(client_ip) = client_ip
+        && arc.pool.is_ip_banned(client_ip.ip()).await?
+    {
+        return Err(Error::IpBanned);
+    }
+
+    // the username is only limited along with the address, so that nobody can lock out
+    // someone else's account. Without a client address, the username alone is limited
+    let username = user_login.username.to_lowercase();
+    let rate_limit_keys = match client_ip {
+        Some(client_ip) => vec![
+            format!("login:username:{username}:ip:{}", client_ip.ip()),
+            format!("login:ip:{}", client_ip.ip()),
+        ],
+        None => vec![format!("login:username:{username}")],
+    };
+    let keys: Vec<&str> = rate_limit_keys.iter().map(String::as_str).collect();
+
+    if let Err(error) = arc.rate_limiter.check(&keys).await {
+        arc.pool
+            .create_auth_attempt(
+                AuthAttemptAction::Login,
+                client_ip,
+                Some(&user_login.username),
+                false,
+                true,
+            )
+            .await?;
+        return Err(error);
+    }
+
+    let user = match arc.pool.find_user_with_password(&user_login).await {
+        Ok(user) => user,
+        Err(Error::WrongUsernameOrPassword) => {
+            for key in &keys {
+                arc.rate_limiter
+                    .hit(key, arc.rate_limiter.config.max_failures)
+                    .await?;
+            }
+            arc.pool
+                .create_auth_attempt(
+                    AuthAttemptAction::Login,
+                    client_ip,
+                    Some(&user_login.username),
+                    false,
+                    false,
+                )
+                .await?;
+            return Err(Error::WrongUsernameOrPassword);
+        }
+        Err(error) => return Err(error),
+    };
+
+    // the ip keeps its failures, it may have been used to guess other accounts
+    arc.rate_limiter.reset(keys[0]).await?;
+    arc.pool
+        .create_auth_attempt(
+            AuthAttemptAction::Login,
+            client_ip,
+            Some(&user_login.username),
+            true,
+            false,
+        )
+        .await?;
 
     if user.banned {
         return Err(Error::AccountBanned);
     }
 
+    if arc.pool.is_two_factor_enabled(user.id).await? {
+        let challenge_token = arc
+            .auth
+            .create_two_factor_challenge(user.id, user_login.remember_me)
+            .await?;
+
+        return Ok(HttpResponse::Accepted().json(TwoFactorChallenge { challenge_token }));
+    }
+
+    Ok(HttpResponse::Ok().json(issue_tokens(&arc, user.id, user_login.remember_me)?))
+}
+
+pub fn issue_tokens<R: RedisPoolInterface>(
+    arc: &Arcadia<R>,
+    user_id: i32,
+    remember_me: bool,
+) -> Result<LoginResponse> {
     let mut token_expiration_date = Utc::now();
     let mut refresh_token = String::from("");
     let now = Utc::now();
 
-    if !user_login.remember_me {
+    if !remember_me {
         token_expiration_date += *AUTH_TOKEN_SHORT_DURATION;
     } else {
         token_expiration_date += *AUTH_TOKEN_LONG_DURATION;
 
         let refresh_token_expiration_date = Utc::now() + *REFRESH_TOKEN_DURATION;
         let refresh_token_claims = Claims {
-            sub: user.id,
+            sub: user_id,
             exp: refresh_token_expiration_date.timestamp(),
             iat: now.timestamp(),
         };
@@ -54,7 +147,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     }
 
     let token_claims = Claims {
-        sub: user.id,
+        sub: user_id,
         exp: token_expiration_date.timestamp(),
         iat: now.timestamp(),
     };
@@ -66,8 +159,8 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     )
     .map_err(Error::JwtError)?;
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "token": token,
-        "refresh_token": refresh_token
-    })))
+    Ok(LoginResponse {
+        token,
+        refresh_token,
+    })
 }
@@ -0,0 +1,46 @@
+use crate::{
+    handlers::auth::login::issue_tokens, services::two_factor_service::verify_second_factor,
+    Arcadia,
+};
+use actix_web::{web, HttpResponse};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::{two_factor::TwoFactorLogin, user::LoginResponse},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Login with second factor",
+    tag = "Auth",
+    path = "/api/auth/2fa/login",
+    responses(
+        (status = 200, description = "Successfully logged in", body=LoginResponse),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: web::Data<Arcadia<R>>,
+    form: web::Json<TwoFactorLogin>,
+) -> Result<HttpResponse> {
+    let challenge = arc
+        .auth
+        .consume_two_factor_challenge(&form.challenge_token)
+        .await?
+        .ok_or(Error::InvalidOrExpiredTwoFactorChallenge)?;
+
+    if !verify_second_factor(&arc.pool, challenge.user_id, &form.code).await? {
+        return Err(Error::InvalidTwoFactorCode);
+    }
+
+    // the user could have been banned since the password step
+    let user = arc.pool.find_user_with_id(challenge.user_id).await?;
+    if user.banned {
+        return Err(Error::AccountBanned);
+    }
+
+    Ok(HttpResponse::Ok().json(issue_tokens(
+        &arc,
+        challenge.user_id,
+        challenge.remember_me,
+    )?))
+}
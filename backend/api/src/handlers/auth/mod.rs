@@ -1,9 +1,15 @@
 pub mod create_user_application;
+pub mod disable_two_factor;
+pub mod enable_two_factor;
+pub mod forgot_password;
 pub mod irc_auth;
 pub mod login;
+pub mod login_two_factor;
 pub mod logout;
 pub mod refresh_token;
 pub mod register;
+pub mod reset_password;
+pub mod setup_two_factor;
 
 use actix_web::web::{post, resource, ServiceConfig};
 use arcadia_storage::redis::RedisPoolInterface;
@@ -15,4 +21,10 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
     cfg.service(resource("/refresh-token").route(post().to(self::refresh_token::exec::<R>)));
     cfg.service(resource("/apply").route(post().to(self::create_user_application::exec::<R>)));
     cfg.service(resource("/irc").route(post().to(self::irc_auth::exec::<R>)));
+    cfg.service(resource("/forgot-password").route(post().to(self::forgot_password::exec::<R>)));
+    cfg.service(resource("/reset-password").route(post().to(self::reset_password::exec::<R>)));
+    cfg.service(resource("/2fa/login").route(post().to(self::login_two_factor::exec::<R>)));
+    cfg.service(resource("/2fa/setup").route(post().to(self::setup_two_factor::exec::<R>)));
+    cfg.service(resource("/2fa/enable").route(post().to(self::enable_two_factor::exec::<R>)));
+    cfg.service(resource("/2fa/disable").route(post().to(self::disable_two_factor::exec::<R>)));
 }
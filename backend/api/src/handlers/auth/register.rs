@@ -3,15 +3,16 @@ use crate::{
         auth_service::{
             validate_email, validate_password, validate_password_verification, validate_username,
         },
+        client_ip_service,
         email_service::EmailService,
     },
     Arcadia,
 };
 use actix_web::{web, HttpRequest, HttpResponse};
 use arcadia_common::error::{Error, Result};
-use arcadia_shared::tracker::models::user::APIInsertUser;
 use arcadia_storage::{
     models::{
+        auth_attempt::AuthAttemptAction,
         invitation::Invitation,
         user::{Register, User},
     },
@@ -37,6 +38,8 @@ pub struct RegisterQuery {
     path = "/api/auth/register",
     responses(
         (status = 200, description = "Successfully registered the user", body = User),
+        (status = 403, description = "The ip address is banned"),
+        (status = 429, description = "Too many attempts from this ip, see the Retry-After header"),
     )
 )]
 pub async fn exec<R: RedisPoolInterface + 'static>(
@@ -44,6 +47,50 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     arc: web::Data<Arcadia<R>>,
     req: HttpRequest,
     query: web::Query<RegisterQuery>,
+) -> Result<HttpResponse> {
+    let client_ip = client_ip_service::client_ip(&req, &arc.env.reverse_proxy)
+        .map(IpNetwork::from)
+        .unwrap();
+    if arc.pool.is_ip_banned(client_ip.ip()).await? {
+        return Err(Error::IpBanned);
+    }
+    let ip_key = format!("register:ip:{}", client_ip.ip());
+
+    if let Err(error) = arc.rate_limiter.check(&[&ip_key]).await {
+        arc.pool
+            .create_auth_attempt(
+                AuthAttemptAction::Register,
+                Some(client_ip),
+                Some(&new_user.username),
+                false,
+                true,
+            )
+            .await?;
+        return Err(error);
+    }
+    arc.rate_limiter
+        .hit(&ip_key, arc.rate_limiter.config.max_attempts)
+        .await?;
+
+    let result = register(&new_user, &arc, client_ip, &query).await;
+    arc.pool
+        .create_auth_attempt(
+            AuthAttemptAction::Register,
+            Some(client_ip),
+            Some(&new_user.username),
+            result.is_ok(),
+            false,
+        )
+        .await?;
+
+    result
+}
+
+async fn register<R: RedisPoolInterface + 'static>(
+    new_user: &Register,
+    arc: &Arcadia<R>,
+    client_ip: IpNetwork,
+    query: &RegisterQuery,
 ) -> Result<HttpResponse> {
     let mut invitation: Option<Invitation> = None;
     if !arc.settings.lock().unwrap().open_signups {
@@ -69,12 +116,6 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     validate_password(&new_user.password)?;
     validate_password_verification(&new_user.password, &new_user.password_verify)?;
 
-    let client_ip = req
-        .connection_info()
-        .realip_remote_addr()
-        .and_then(|ip| ip.parse::<IpNetwork>().ok())
-        .unwrap();
-
     let salt = SaltString::generate(&mut OsRng);
 
     // Argon2 with default params (Argon2id v19)
@@ -90,7 +131,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     let user = arc
         .pool
         .create_user(
-            &new_user,
+            new_user,
             client_ip,
             &password_hash,
             &invitation,
@@ -107,33 +148,8 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         );
     }
 
-    // Inform tracker about the new user so it can accept announces immediately
-    {
-        let mut url = arc.env.tracker.url_internal.clone();
-        url.path_segments_mut().unwrap().push("api").push("users");
-
-        let payload = APIInsertUser {
-            id: user.id as u32,
-            passkey: user.passkey.parse().expect("invalid passkey format"),
-            max_snatches_per_day: user.max_snatches_per_day.map(|x| x as u32),
-        };
-
-        // Fire and log; don't fail registration if tracker call fails
-        let res = arc
-            .internal_http_client
-            .put(url)
-            .header("x-api-key", arc.env.tracker.api_key.clone())
-            .json(&payload)
-            .send()
-            .await;
-
-        if res.is_err() {
-            log::warn!("Tried to upsert user in tracker and got: {:?}", res);
-        }
-    }
-
     // Send welcome email
-    if let Ok(email_service) = EmailService::new(&arc) {
+    if let Ok(email_service) = EmailService::new(arc) {
         if let Err(e) = email_service
             .send_registration_email(&new_user.email, &new_user.username)
             .await
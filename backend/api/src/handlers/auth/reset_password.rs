@@ -0,0 +1,51 @@
+use crate::{
+    services::auth_service::{validate_password, validate_password_verification},
+    Arcadia,
+};
+use actix_web::{web, HttpResponse};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{models::user::ResetPassword, redis::RedisPoolInterface};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
+    Argon2,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Reset password",
+    tag = "Auth",
+    path = "/api/auth/reset-password",
+    responses(
+        (status = 200, description = "Successfully reset the password, existing sessions are revoked"),
+        (status = 400, description = "Invalid password or invalid/expired token"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: web::Data<Arcadia<R>>,
+    form: web::Json<ResetPassword>,
+) -> Result<HttpResponse> {
+    // validated before consuming the token so that a typo doesn't waste it
+    validate_password(&form.new_password)?;
+    validate_password_verification(&form.new_password, &form.new_password_verify)?;
+
+    let user_id = arc
+        .auth
+        .consume_password_reset_token(&form.token)
+        .await?
+        .ok_or(Error::InvalidOrExpiredPasswordResetToken)?;
+
+    let salt = SaltString::generate(&mut OsRng);
+    let password_hash = Argon2::default()
+        .hash_password(form.new_password.as_bytes(), &salt)
+        .unwrap()
+        .to_string();
+
+    arc.pool
+        .update_user_password_hash(user_id, &password_hash)
+        .await?;
+
+    // whoever had access to the account shouldn't keep it
+    arc.auth.invalidate(user_id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
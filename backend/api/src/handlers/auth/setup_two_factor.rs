@@ -0,0 +1,44 @@
+use crate::{
+    middlewares::auth_middleware::Authdata,
+    services::two_factor_service::{build_totp, generate_totp_secret},
+    Arcadia,
+};
+use actix_web::{web::Data, HttpResponse};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{models::two_factor::TwoFactorSetup, redis::RedisPoolInterface};
+
+#[utoipa::path(
+    post,
+    operation_id = "Set up two-factor authentication",
+    tag = "Auth",
+    path = "/api/auth/2fa/setup",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Generated a new TOTP secret, to confirm at /api/auth/2fa/enable", body=TwoFactorSetup),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    if arc.pool.is_two_factor_enabled(user.sub).await? {
+        return Err(Error::TwoFactorAlreadyEnabled);
+    }
+
+    let current_user = arc.pool.find_user_with_id(user.sub).await?;
+    let secret = generate_totp_secret();
+    let totp = build_totp(
+        &secret,
+        arc.env.frontend_url.host_str().map(str::to_string),
+        current_user.username,
+    )?;
+
+    arc.pool.create_pending_user_totp(user.sub, &secret).await?;
+
+    Ok(HttpResponse::Ok().json(TwoFactorSetup {
+        secret,
+        otpauth_uri: totp.get_url(),
+    }))
+}
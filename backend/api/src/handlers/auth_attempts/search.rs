@@ -0,0 +1,43 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        auth_attempt::{AuthAttempt, SearchAuthAttemptsQuery},
+        common::PaginatedResults,
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search auth attempts",
+    tag = "Auth Attempts",
+    path = "/api/auth-attempts",
+    params(SearchAuthAttemptsQuery),
+    responses(
+        (status = 200, description = "Paginated list of login, register and apply attempts, newest first", body = PaginatedResults<AuthAttempt>),
+        (status = 403, description = "Forbidden"),
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    query: Query<SearchAuthAttemptsQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::SearchAuthAttempts, req.path())
+        .await?;
+
+    let results = arc.pool.find_auth_attempts(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(results))
+}
@@ -0,0 +1,43 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        cheat_flag::{CheatFlag, SearchCheatFlagsQuery},
+        common::PaginatedResults,
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search cheat flags",
+    tag = "Cheat Flags",
+    path = "/api/cheat-flags",
+    params(SearchCheatFlagsQuery),
+    responses(
+        (status = 200, description = "Paginated list of the announces flagged by the tracker, newest first", body = PaginatedResults<CheatFlag>),
+        (status = 403, description = "Forbidden"),
+    ),
+    security(
+        ("bearer" = [])
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    query: Query<SearchCheatFlagsQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::SearchCheatFlags, req.path())
+        .await?;
+
+    let results = arc.pool.find_cheat_flags(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(results))
+}
@@ -0,0 +1,40 @@
+use crate::{
+    middlewares::auth_middleware::Authdata, services::image_service::validate_image_urls, Arcadia,
+};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::entity::{Entity, UserCreatedEntity},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create entities",
+    tag = "Entity",
+    path = "/api/entities",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 201, description = "Successfully created the entities, returned in the same order as the one sent.
+            In the case of a db conflict (duplicate), the existing entry is returned (can be seen with the created_at attribute).", body=Vec<Entity>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    entities: Json<Vec<UserCreatedEntity>>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
+    for entity in entities.iter() {
+        validate_image_urls(&entity.pictures, &approved_image_hosts)?;
+    }
+
+    let entities = arc.pool.create_entities(&entities, user.sub).await?;
+
+    Ok(HttpResponse::Created().json(entities))
+}
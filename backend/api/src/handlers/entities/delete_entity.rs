@@ -0,0 +1,40 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{web::Data, web::Query, HttpRequest, HttpResponse};
+use arcadia_common::error::Result;
+use arcadia_storage::models::user::UserPermission;
+use arcadia_storage::redis::RedisPoolInterface;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct DeleteEntityQuery {
+    pub entity_id: i64,
+}
+
+#[utoipa::path(
+    delete,
+    operation_id = "Delete entity",
+    tag = "Entity",
+    path = "/api/entities",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(DeleteEntityQuery),
+    responses(
+        (status = 200, description = "Successfully deleted the entity"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<DeleteEntityQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::DeleteEntity, req.path())
+        .await?;
+
+    arc.pool.delete_entity(query.entity_id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,65 @@
+use crate::{
+    middlewares::auth_middleware::Authdata, services::image_service::validate_image_urls, Arcadia,
+};
+use actix_web::{web::Data, web::Json, HttpResponse};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::models::entity::Entity;
+use arcadia_storage::models::user::UserPermission;
+use arcadia_storage::models::user_edit_change_log::NewUserEditChangeLog;
+use arcadia_storage::{models::entity::EditedEntity, redis::RedisPoolInterface};
+
+const GRACE_PERIOD_IN_DAYS: i64 = 7;
+
+#[utoipa::path(
+    put,
+    operation_id = "Edit entity",
+    tag = "Entity",
+    path = "/api/entities",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully edited the entity", body=Entity),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    form: Json<EditedEntity>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
+    validate_image_urls(&form.pictures, &approved_image_hosts)?;
+
+    let mut entity = arc.pool.find_entity_by_id(form.id).await?;
+
+    // users can edit their own entity for a grace period of
+    // 7 days after creation, to prevent e.g. hostile account takeovers.
+    let has_permission = arc
+        .pool
+        .user_has_permission(user.sub, &UserPermission::EditEntity)
+        .await?;
+
+    if !has_permission {
+        let grace_period = chrono::Utc::now() - chrono::Duration::days(GRACE_PERIOD_IN_DAYS);
+        if entity.created_by_id != user.sub || entity.created_at < grace_period {
+            return Err(Error::InsufficientPermissions(format!(
+                "{:?}",
+                UserPermission::EditEntity
+            )));
+        }
+    }
+
+    if let Some(edits) = entity.diff(&form) {
+        arc.pool
+            .create_user_edit_change_log(&NewUserEditChangeLog {
+                item_type: "entity".to_string(),
+                item_id: entity.id,
+                edited_by_id: user.sub,
+                edits,
+            })
+            .await?;
+        entity = arc.pool.update_entity_data(&form).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(entity))
+}
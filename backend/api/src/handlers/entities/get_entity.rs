@@ -0,0 +1,37 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::entity::EntityEnriched, redis::RedisPoolInterface};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct GetEntityQuery {
+    id: i64,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get entity",
+    tag = "Entity",
+    path = "/api/entities",
+    params (GetEntityQuery),
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully got the entity and the title groups it is affiliated to", body=EntityEnriched),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetEntityQuery>,
+    arc: Data<Arcadia<R>>,
+    _user: Authdata,
+) -> Result<HttpResponse> {
+    let enriched = arc.pool.find_entity_enriched(query.id).await?;
+
+    Ok(HttpResponse::Ok().json(enriched))
+}
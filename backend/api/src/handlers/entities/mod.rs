@@ -0,0 +1,17 @@
+pub mod create_entities;
+pub mod delete_entity;
+pub mod edit_entity;
+pub mod get_entity;
+
+use actix_web::web::{delete, get, post, put, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(
+        resource("")
+            .route(post().to(self::create_entities::exec::<R>))
+            .route(get().to(self::get_entity::exec::<R>))
+            .route(put().to(self::edit_entity::exec::<R>))
+            .route(delete().to(self::delete_entity::exec::<R>)),
+    );
+}
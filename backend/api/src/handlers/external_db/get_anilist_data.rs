@@ -0,0 +1,421 @@
+use crate::{
+    handlers::scrapers::ExternalDBData,
+    services::external_db_service::{
+        check_if_existing_title_group_with_link_exists, strip_html_tags,
+    },
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::{
+        edition_group::{create_default_edition_group, UserCreatedEditionGroup},
+        title_group::{
+            create_default_title_group, ContentType, TitleGroupCategory, UserCreatedTitleGroup,
+        },
+        torrent::Language,
+    },
+    redis::RedisPoolInterface,
+};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::IntoParams;
+
+const ANILIST_API_URL: &str = "https://graphql.anilist.co";
+
+const ANILIST_MEDIA_QUERY: &str = "
+query ($id: Int, $type: MediaType) {
+  Media(id: $id, type: $type) {
+    id
+    type
+    format
+    countryOfOrigin
+    title { romaji english native }
+    synonyms
+    description(asHtml: false)
+    startDate { year month day }
+    coverImage { extraLarge }
+    genres
+    tags { name isMediaSpoiler }
+    trailer { id site }
+  }
+}";
+
+#[derive(Debug, Deserialize)]
+struct AniListResponse {
+    data: Option<AniListData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AniListData {
+    media: Option<AniListMedia>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AniListTitle {
+    romaji: Option<String>,
+    english: Option<String>,
+    native: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AniListDate {
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListCoverImage {
+    extra_large: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListTag {
+    name: String,
+    is_media_spoiler: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct AniListTrailer {
+    id: String,
+    site: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AniListMedia {
+    id: i64,
+    #[serde(rename = "type")]
+    media_type: AniListMediaType,
+    format: Option<String>,
+    country_of_origin: Option<String>,
+    title: AniListTitle,
+    #[serde(default)]
+    synonyms: Vec<String>,
+    description: Option<String>,
+    start_date: Option<AniListDate>,
+    cover_image: Option<AniListCoverImage>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    tags: Vec<AniListTag>,
+    trailer: Option<AniListTrailer>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AniListMediaType {
+    Anime,
+    Manga,
+}
+
+impl AniListMediaType {
+    fn as_path(&self) -> &'static str {
+        match self {
+            AniListMediaType::Anime => "anime",
+            AniListMediaType::Manga => "manga",
+        }
+    }
+
+    fn as_graphql(&self) -> &'static str {
+        match self {
+            AniListMediaType::Anime => "ANIME",
+            AniListMediaType::Manga => "MANGA",
+        }
+    }
+}
+
+fn anilist_media_url(media_type: AniListMediaType, id: i64) -> String {
+    format!("https://anilist.co/{}/{id}", media_type.as_path())
+}
+
+fn map_anilist_format(
+    media_type: AniListMediaType,
+    format: Option<&str>,
+) -> (ContentType, Option<TitleGroupCategory>) {
+    match (media_type, format) {
+        (AniListMediaType::Anime, Some("MOVIE")) => {
+            (ContentType::Movie, Some(TitleGroupCategory::FeatureFilm))
+        }
+        (AniListMediaType::Anime, Some("MUSIC")) => (ContentType::Music, None),
+        (AniListMediaType::Anime, _) => (ContentType::TVShow, None),
+        (AniListMediaType::Manga, Some("NOVEL")) => {
+            (ContentType::Book, Some(TitleGroupCategory::Book))
+        }
+        (AniListMediaType::Manga, _) => (ContentType::Book, Some(TitleGroupCategory::Illustrated)),
+    }
+}
+
+fn map_anilist_country_to_language(country: &str) -> Option<Language> {
+    match country {
+        "JP" => Some(Language::Japanese),
+        "KR" => Some(Language::Korean),
+        "CN" | "TW" => Some(Language::Chinese),
+        _ => None,
+    }
+}
+
+impl From<AniListMedia> for UserCreatedTitleGroup {
+    fn from(media: AniListMedia) -> Self {
+        let (content_type, category) =
+            map_anilist_format(media.media_type, media.format.as_deref());
+        let name = media
+            .title
+            .romaji
+            .clone()
+            .or_else(|| media.title.english.clone())
+            .unwrap_or_default();
+        let mut name_aliases: Vec<String> = Vec::new();
+        for alias in [media.title.english, media.title.native]
+            .into_iter()
+            .flatten()
+            .chain(media.synonyms)
+        {
+            if alias != name && !name_aliases.contains(&alias) {
+                name_aliases.push(alias);
+            }
+        }
+        let start_date = media.start_date.as_ref();
+
+        UserCreatedTitleGroup {
+            name,
+            name_aliases,
+            description: media
+                .description
+                .as_deref()
+                .map(strip_html_tags)
+                .unwrap_or_default(),
+            original_language: media
+                .country_of_origin
+                .as_deref()
+                .and_then(map_anilist_country_to_language),
+            original_release_date: start_date.and_then(|date| {
+                NaiveDate::from_ymd_opt(date.year?, date.month.unwrap_or(1), date.day.unwrap_or(1))
+            }),
+            original_release_date_only_year_known: start_date
+                .is_some_and(|date| date.month.is_none()),
+            tags: media
+                .genres
+                .iter()
+                .chain(
+                    media
+                        .tags
+                        .iter()
+                        .filter(|tag| !tag.is_media_spoiler)
+                        .map(|tag| &tag.name),
+                )
+                .map(|tag| tag.to_lowercase().replace(" ", "."))
+                .collect(),
+            covers: media
+                .cover_image
+                .and_then(|cover| cover.extra_large)
+                .map_or_else(Vec::new, |cover| vec![cover]),
+            trailers: media
+                .trailer
+                .filter(|trailer| trailer.site == "youtube")
+                .map_or_else(Vec::new, |trailer| {
+                    vec![format!("https://www.youtube.com/watch?v={}", trailer.id)]
+                }),
+            external_links: vec![anilist_media_url(media.media_type, media.id)],
+            content_type,
+            category,
+            ..create_default_title_group()
+        }
+    }
+}
+
+async fn get_anilist_media_data(
+    client: &reqwest::Client,
+    media_type: AniListMediaType,
+    id: i64,
+) -> Result<AniListMedia> {
+    // unknown ids are answered with a 404 and a null media, not checking the status
+    // lets them end up as AniListMediaNotFound
+    client
+        .post(ANILIST_API_URL)
+        .json(&json!({
+            "query": ANILIST_MEDIA_QUERY,
+            "variables": { "id": id, "type": media_type.as_graphql() },
+        }))
+        .send()
+        .await?
+        .json::<AniListResponse>()
+        .await?
+        .data
+        .and_then(|data| data.media)
+        .ok_or(Error::AniListMediaNotFound)
+}
+
+fn extract_anilist_media(url: &str) -> Result<(AniListMediaType, i64)> {
+    Regex::new(r"anilist\.co/(anime|manga)/(\d+)")
+        .expect("Regex error for AniList URL")
+        .captures(url)
+        .and_then(|caps| {
+            Some((
+                match &caps[1] {
+                    "anime" => AniListMediaType::Anime,
+                    _ => AniListMediaType::Manga,
+                },
+                caps[2].parse().ok()?,
+            ))
+        })
+        .ok_or(Error::InvalidAniListUrl)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetAniListQuery {
+    url: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get AniList data",
+    tag = "External Source",
+    path = "/api/external-sources/anilist",
+    params(GetAniListQuery),
+    responses(
+        (status = 200, description = "", body=ExternalDBData),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetAniListQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let (media_type, id) = extract_anilist_media(&query.url)?;
+    if let Some(response) = check_if_existing_title_group_with_link_exists(
+        &arc.pool,
+        &anilist_media_url(media_type, id),
+    )
+    .await?
+    {
+        return Ok(response);
+    }
+
+    let media = get_anilist_media_data(&arc.http_client, media_type, id).await?;
+
+    let mut title_group = UserCreatedTitleGroup::from(media);
+    crate::services::image_host_service::rehost_image_urls(
+        &arc.image_host,
+        &mut title_group.covers,
+    )
+    .await;
+
+    let edition_group = UserCreatedEditionGroup {
+        release_date: title_group.original_release_date,
+        release_date_only_year_known: title_group.original_release_date_only_year_known,
+        ..create_default_edition_group()
+    };
+
+    Ok(HttpResponse::Ok().json(ExternalDBData {
+        title_group: Some(title_group),
+        edition_group: Some(edition_group),
+        affiliated_artists: vec![],
+        existing_title_group_id: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_media(response: &str) -> AniListMedia {
+        serde_json::from_str::<AniListResponse>(response)
+            .unwrap()
+            .data
+            .unwrap()
+            .media
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_anilist_media() {
+        assert_eq!(
+            extract_anilist_media("https://anilist.co/anime/1/Cowboy-Bebop/").unwrap(),
+            (AniListMediaType::Anime, 1)
+        );
+        assert_eq!(
+            extract_anilist_media("https://anilist.co/manga/30013").unwrap(),
+            (AniListMediaType::Manga, 30013)
+        );
+        assert!(extract_anilist_media("https://anilist.co/character/1").is_err());
+    }
+
+    #[test]
+    fn test_anime_cowboy_bebop() {
+        let title_group =
+            UserCreatedTitleGroup::from(read_media(include_str!("testdata/anilist_anime_1.json")));
+
+        assert_eq!(title_group.name, "Cowboy Bebop");
+        assert_eq!(title_group.name_aliases, vec!["カウボーイビバップ"]);
+        assert_eq!(title_group.content_type, ContentType::TVShow);
+        assert!(title_group.category.is_none());
+        assert!(matches!(
+            title_group.original_language,
+            Some(Language::Japanese)
+        ));
+        assert_eq!(
+            title_group.original_release_date,
+            NaiveDate::from_ymd_opt(1998, 4, 3)
+        );
+        assert!(!title_group.original_release_date_only_year_known);
+        assert_eq!(
+            title_group.description,
+            "Enter a world in the distant future.\nThe Bebop crew is just trying to make a living."
+        );
+        // the spoiler tag is left out
+        assert_eq!(
+            title_group.tags,
+            vec!["action", "sci-fi", "space", "episodic"]
+        );
+        assert_eq!(
+            title_group.external_links,
+            vec!["https://anilist.co/anime/1"]
+        );
+        assert_eq!(
+            title_group.trailers,
+            vec!["https://www.youtube.com/watch?v=qig4KOK2R2g"]
+        );
+    }
+
+    #[test]
+    fn test_manga_one_piece() {
+        let title_group = UserCreatedTitleGroup::from(read_media(include_str!(
+            "testdata/anilist_manga_30013.json"
+        )));
+
+        assert_eq!(title_group.name, "ONE PIECE");
+        assert_eq!(title_group.content_type, ContentType::Book);
+        assert!(matches!(
+            title_group.category,
+            Some(TitleGroupCategory::Illustrated)
+        ));
+        assert!(title_group.trailers.is_empty());
+        assert_eq!(
+            title_group.covers,
+            vec!["https://s4.anilist.co/file/anilistcdn/media/manga/cover/large/bx30013.jpg"]
+        );
+        assert_eq!(
+            title_group.external_links,
+            vec!["https://anilist.co/manga/30013"]
+        );
+    }
+
+    #[test]
+    fn test_media_not_found() {
+        let response = serde_json::from_str::<AniListResponse>(
+            r#"{"errors":[{"message":"Not Found.","status":404}],"data":{"Media":null}}"#,
+        )
+        .unwrap();
+
+        assert!(response.data.unwrap().media.is_none());
+    }
+}
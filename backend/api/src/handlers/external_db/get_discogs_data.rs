@@ -0,0 +1,675 @@
+use crate::{
+    handlers::scrapers::ExternalDBData,
+    middlewares::auth_middleware::Authdata,
+    services::external_db_service::{
+        check_if_existing_title_group_with_link_exists, resolve_credited_artists, CreditedArtist,
+    },
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{
+        artist::{ArtistRole, UserCreatedArtist},
+        edition_group::{create_default_edition_group, Source, UserCreatedEditionGroup},
+        entity::{
+            EntityRole, UserCreatedAffiliatedEntity, UserCreatedEntity,
+            UserCreatedNewAffiliatedEntity,
+        },
+        title_group::{
+            create_default_title_group, ContentType, TitleGroupCategory, UserCreatedTitleGroup,
+        },
+    },
+    redis::RedisPoolInterface,
+};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+use utoipa::IntoParams;
+
+const DISCOGS_API_BASE_URL: &str = "https://api.discogs.com";
+
+#[derive(Debug, Deserialize)]
+struct DiscogsArtistCredit {
+    id: u64,
+    name: String,
+    /// artist name variation, the name the artist is credited under
+    #[serde(default)]
+    anv: String,
+    #[serde(default)]
+    join: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsImage {
+    #[serde(rename = "type")]
+    image_type: String,
+    /// empty when the request isn't authenticated
+    #[serde(default)]
+    uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsMaster {
+    id: u64,
+    title: String,
+    #[serde(default)]
+    year: i32,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    styles: Vec<String>,
+    #[serde(default)]
+    images: Vec<DiscogsImage>,
+    #[serde(default)]
+    artists: Vec<DiscogsArtistCredit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsLabel {
+    name: String,
+    catno: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsFormat {
+    name: String,
+    #[serde(default)]
+    descriptions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsRelease {
+    id: u64,
+    title: String,
+    #[serde(default)]
+    year: i32,
+    released: Option<String>,
+    notes: Option<String>,
+    master_id: Option<u64>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    styles: Vec<String>,
+    #[serde(default)]
+    labels: Vec<DiscogsLabel>,
+    #[serde(default)]
+    formats: Vec<DiscogsFormat>,
+    #[serde(default)]
+    images: Vec<DiscogsImage>,
+    #[serde(default)]
+    artists: Vec<DiscogsArtistCredit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscogsArtist {
+    name: String,
+    #[serde(default)]
+    profile: String,
+    #[serde(default)]
+    namevariations: Vec<String>,
+    #[serde(default)]
+    images: Vec<DiscogsImage>,
+}
+
+fn discogs_master_url(id: u64) -> String {
+    format!("https://www.discogs.com/master/{id}")
+}
+
+fn discogs_release_url(id: u64) -> String {
+    format!("https://www.discogs.com/release/{id}")
+}
+
+/// Discogs suffixes homonyms with a number, e.g. "Nirvana (2)"
+fn strip_discogs_name_suffix(name: &str) -> String {
+    Regex::new(r" \(\d+\)$")
+        .expect("Regex error for Discogs name suffix")
+        .replace(name, "")
+        .to_string()
+}
+
+/// The primary image first, without the ones hidden to unauthenticated requests
+fn discogs_images_urls(images: &[DiscogsImage]) -> Vec<String> {
+    let mut images: Vec<&DiscogsImage> = images.iter().filter(|i| !i.uri.is_empty()).collect();
+    images.sort_by_key(|image| image.image_type != "primary");
+    images.iter().map(|image| image.uri.clone()).collect()
+}
+
+/// Discogs uses 0 when the year is unknown
+fn discogs_year(year: i32) -> Option<NaiveDate> {
+    (year > 0)
+        .then(|| NaiveDate::from_ymd_opt(year, 1, 1))
+        .flatten()
+}
+
+fn discogs_tags(genres: &[String], styles: &[String]) -> Vec<String> {
+    genres
+        .iter()
+        .chain(styles.iter())
+        .map(|tag| tag.to_lowercase().replace(" ", "."))
+        .collect()
+}
+
+/// Some releases only have a year, others use 00 for the unknown month or day
+fn parse_discogs_release_date(released: &str) -> Option<(NaiveDate, bool)> {
+    let mut parts = released.split('-').map(|part| part.parse::<u32>().ok());
+    let year = parts.next().flatten()? as i32;
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(month), Some(day)) if month > 0 && day > 0 => {
+            NaiveDate::from_ymd_opt(year, month, day).map(|date| (date, false))
+        }
+        _ => NaiveDate::from_ymd_opt(year, 1, 1).map(|date| (date, true)),
+    }
+}
+
+fn map_discogs_format_to_source(format: &str) -> Option<Source> {
+    match format {
+        "Vinyl" => Some(Source::Vinyl),
+        "CD" | "CDr" => Some(Source::Cd),
+        "SACD" => Some(Source::Sacd),
+        "Cassette" => Some(Source::Cassette),
+        "DAT" => Some(Source::Dat),
+        "File" => Some(Source::Web),
+        "Blu-ray" => Some(Source::BluRay),
+        "DVD" => Some(Source::Dvd),
+        "Laserdisc" => Some(Source::LaserDisc),
+        "VHS" => Some(Source::Vhs),
+        _ => None,
+    }
+}
+
+fn map_discogs_format_description_to_category(description: &str) -> Option<TitleGroupCategory> {
+    match description {
+        "Album" | "LP" => Some(TitleGroupCategory::Album),
+        "EP" => Some(TitleGroupCategory::Ep),
+        "Single" | "Maxi-Single" => Some(TitleGroupCategory::Single),
+        "Compilation" => Some(TitleGroupCategory::Compilation),
+        "Mixtape" => Some(TitleGroupCategory::Mixtape),
+        "Mixed" => Some(TitleGroupCategory::DjMix),
+        _ => None,
+    }
+}
+
+/// The artists credited on a master or release. Those listed after a "Feat." join are guests.
+fn credited_artists_from_discogs_credits(credits: &[DiscogsArtistCredit]) -> Vec<CreditedArtist> {
+    let mut featured = false;
+    let mut credited_artists = Vec::new();
+
+    for credit in credits {
+        credited_artists.push(CreditedArtist {
+            external_id: credit.id.to_string(),
+            external_link: format!("https://www.discogs.com/artist/{}", credit.id),
+            name: strip_discogs_name_suffix(&credit.name),
+            roles: vec![if featured {
+                ArtistRole::Guest
+            } else {
+                ArtistRole::Main
+            }],
+            nickname: (!credit.anv.is_empty()).then(|| credit.anv.clone()),
+        });
+        if credit.join.to_lowercase().contains("feat") {
+            featured = true;
+        }
+    }
+
+    credited_artists
+}
+
+impl From<DiscogsMaster> for UserCreatedTitleGroup {
+    fn from(master: DiscogsMaster) -> Self {
+        UserCreatedTitleGroup {
+            name: master.title,
+            tags: discogs_tags(&master.genres, &master.styles),
+            original_release_date: discogs_year(master.year),
+            original_release_date_only_year_known: true,
+            covers: discogs_images_urls(&master.images),
+            external_links: vec![discogs_master_url(master.id)],
+            trailers: vec![],
+            content_type: ContentType::Music,
+            category: None,
+            ..create_default_title_group()
+        }
+    }
+}
+
+impl DiscogsRelease {
+    fn category(&self) -> Option<TitleGroupCategory> {
+        self.formats
+            .iter()
+            .flat_map(|format| format.descriptions.iter())
+            .find_map(|description| map_discogs_format_description_to_category(description))
+    }
+
+    /// Used when the release doesn't belong to a master
+    fn title_group(&self) -> UserCreatedTitleGroup {
+        let release_date = self
+            .released
+            .as_deref()
+            .and_then(parse_discogs_release_date);
+        UserCreatedTitleGroup {
+            name: self.title.clone(),
+            tags: discogs_tags(&self.genres, &self.styles),
+            original_release_date: release_date
+                .map(|(date, _)| date)
+                .or_else(|| discogs_year(self.year)),
+            original_release_date_only_year_known: release_date
+                .is_none_or(|(_, only_year_known)| only_year_known),
+            covers: discogs_images_urls(&self.images),
+            external_links: vec![discogs_release_url(self.id)],
+            trailers: vec![],
+            content_type: ContentType::Music,
+            category: self.category(),
+            ..create_default_title_group()
+        }
+    }
+
+    /// The labels, once each. Self-released ones are credited to "Not On Label".
+    fn label_names(&self) -> Vec<String> {
+        let mut label_names: Vec<String> = Vec::new();
+        for label in &self.labels {
+            let name = strip_discogs_name_suffix(&label.name);
+            if !name.starts_with("Not On Label") && !label_names.contains(&name) {
+                label_names.push(name);
+            }
+        }
+        label_names
+    }
+
+    fn edition_group(&self) -> UserCreatedEditionGroup {
+        let release_date = self
+            .released
+            .as_deref()
+            .and_then(parse_discogs_release_date);
+        let label = self.labels.first();
+        UserCreatedEditionGroup {
+            additional_information: Some(json!({
+                "catalogue_number": label.map(|label| label.catno.clone()).unwrap_or_default(),
+                "label": label.map(|label| strip_discogs_name_suffix(&label.name)).unwrap_or_default(),
+            })),
+            release_date: release_date.map(|(date, _)| date),
+            release_date_only_year_known: release_date
+                .is_some_and(|(_, only_year_known)| only_year_known),
+            description: self.notes.clone().filter(|notes| !notes.is_empty()),
+            covers: discogs_images_urls(&self.images),
+            source: self
+                .formats
+                .iter()
+                .find_map(|format| map_discogs_format_to_source(&format.name)),
+            external_links: vec![discogs_release_url(self.id)],
+            ..create_default_edition_group()
+        }
+    }
+}
+
+impl DiscogsArtist {
+    fn into_user_created_artist(self, credit: &CreditedArtist) -> UserCreatedArtist {
+        UserCreatedArtist {
+            aliases: self
+                .namevariations
+                .into_iter()
+                .filter(|alias| *alias != credit.name)
+                .collect(),
+            name: strip_discogs_name_suffix(&self.name),
+            description: self.profile,
+            pictures: discogs_images_urls(&self.images),
+            external_links: vec![credit.external_link.clone()],
+        }
+    }
+}
+
+/// The labels that exist already are affiliated to the title group, the others
+/// are proposed to be created along with it
+async fn affiliate_discogs_labels(
+    pool: &ConnectionPool,
+    title_group: &mut UserCreatedTitleGroup,
+    label_names: Vec<String>,
+) -> Result<()> {
+    if label_names.is_empty() {
+        return Ok(());
+    }
+    let existing_entities = pool.find_entities_by_names(&label_names).await?;
+
+    for label_name in label_names {
+        match existing_entities
+            .iter()
+            .find(|entity| entity.name.to_lowercase() == label_name.to_lowercase())
+        {
+            Some(entity) => {
+                if !title_group
+                    .affiliated_entities
+                    .iter()
+                    .any(|affiliated| affiliated.entity_id == entity.id)
+                {
+                    title_group
+                        .affiliated_entities
+                        .push(UserCreatedAffiliatedEntity {
+                            title_group_id: 0,
+                            entity_id: entity.id,
+                            roles: vec![EntityRole::Label],
+                        });
+                }
+            }
+            None => title_group
+                .new_affiliated_entities
+                .push(UserCreatedNewAffiliatedEntity {
+                    entity: UserCreatedEntity {
+                        name: label_name,
+                        description: String::new(),
+                        pictures: vec![],
+                    },
+                    roles: vec![EntityRole::Label],
+                }),
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_discogs_data<T: DeserializeOwned>(
+    endpoint: &str,
+    client: &reqwest::Client,
+    user_agent: &str,
+    token: Option<&str>,
+) -> Result<T> {
+    let mut request = client
+        .get(format!("{DISCOGS_API_BASE_URL}/{endpoint}"))
+        .header(reqwest::header::USER_AGENT, user_agent);
+    if let Some(token) = token {
+        request = request.header(
+            reqwest::header::AUTHORIZATION,
+            format!("Discogs token={token}"),
+        );
+    }
+
+    Ok(request
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<T>()
+        .await?)
+}
+
+async fn get_discogs_artist_data(
+    client: &reqwest::Client,
+    user_agent: &str,
+    token: Option<&str>,
+    credit: CreditedArtist,
+) -> Result<UserCreatedArtist> {
+    let artist: DiscogsArtist = fetch_discogs_data(
+        &format!("artists/{}", credit.external_id),
+        client,
+        user_agent,
+        token,
+    )
+    .await?;
+
+    Ok(artist.into_user_created_artist(&credit))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiscogsResourceType {
+    Master,
+    Release,
+}
+
+fn extract_discogs_resource(url: &str) -> Result<(DiscogsResourceType, u64)> {
+    Regex::new(r"discogs\.com/(?:[a-z]{2}/)?(?:[^/]+/)?(master|release)/(\d+)")
+        .expect("Regex error for Discogs URL")
+        .captures(url)
+        .and_then(|caps| {
+            Some((
+                match &caps[1] {
+                    "master" => DiscogsResourceType::Master,
+                    _ => DiscogsResourceType::Release,
+                },
+                caps[2].parse().ok()?,
+            ))
+        })
+        .ok_or(Error::InvalidDiscogsUrl)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetDiscogsQuery {
+    url: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get Discogs data",
+    tag = "External Source",
+    path = "/api/external-sources/discogs",
+    params(GetDiscogsQuery),
+    responses(
+        (status = 200, description = "", body=ExternalDBData),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetDiscogsQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let (resource_type, id) = extract_discogs_resource(&query.url)?;
+    let user_agent = format!("{} ({})", arc.tracker.name, arc.frontend_url);
+    let token = arc.discogs_token.as_deref();
+
+    let release: Option<DiscogsRelease> = match resource_type {
+        DiscogsResourceType::Master => None,
+        DiscogsResourceType::Release => Some(
+            fetch_discogs_data(
+                &format!("releases/{id}"),
+                &arc.http_client,
+                &user_agent,
+                token,
+            )
+            .await?,
+        ),
+    };
+    let master_id = match &release {
+        Some(release) => release.master_id,
+        None => Some(id),
+    };
+
+    // the title group comes from the master, or from the release itself when it has none
+    let existing_link = master_id.map_or_else(|| discogs_release_url(id), discogs_master_url);
+    if let Some(response) =
+        check_if_existing_title_group_with_link_exists(&arc.pool, &existing_link).await?
+    {
+        return Ok(response);
+    }
+
+    let edition_group = release.as_ref().map(DiscogsRelease::edition_group);
+    let (mut title_group, credited_artists) = match (master_id, &release) {
+        (Some(master_id), _) => {
+            let master: DiscogsMaster = fetch_discogs_data(
+                &format!("masters/{master_id}"),
+                &arc.http_client,
+                &user_agent,
+                token,
+            )
+            .await?;
+            let credited_artists = credited_artists_from_discogs_credits(&master.artists);
+            let mut title_group = UserCreatedTitleGroup::from(master);
+            title_group.category = release.as_ref().and_then(DiscogsRelease::category);
+            (title_group, credited_artists)
+        }
+        (None, Some(release)) => (
+            release.title_group(),
+            credited_artists_from_discogs_credits(&release.artists),
+        ),
+        (None, None) => unreachable!("a master always has an id"),
+    };
+    crate::services::image_host_service::rehost_image_urls(
+        &arc.image_host,
+        &mut title_group.covers,
+    )
+    .await;
+    if let Some(release) = &release {
+        affiliate_discogs_labels(&arc.pool, &mut title_group, release.label_names()).await?;
+    }
+
+    let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
+    let affiliated_artists = resolve_credited_artists(
+        &arc.pool,
+        &arc.image_host,
+        &approved_image_hosts,
+        &credited_artists,
+        user.sub,
+        |credit| get_discogs_artist_data(&arc.http_client, &user_agent, token, credit),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ExternalDBData {
+        title_group: Some(title_group),
+        edition_group,
+        affiliated_artists,
+        existing_title_group_id: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_discogs_resource() {
+        assert_eq!(
+            extract_discogs_resource(
+                "https://www.discogs.com/release/249504-Rick-Astley-Never-Gonna-Give-You-Up"
+            )
+            .unwrap(),
+            (DiscogsResourceType::Release, 249504)
+        );
+        assert_eq!(
+            extract_discogs_resource(
+                "https://www.discogs.com/fr/master/96559-Rick-Astley-Never-Gonna-Give-You-Up"
+            )
+            .unwrap(),
+            (DiscogsResourceType::Master, 96559)
+        );
+        assert!(extract_discogs_resource("https://www.discogs.com/artist/72872").is_err());
+    }
+
+    #[test]
+    fn test_parse_discogs_release_date() {
+        assert_eq!(
+            parse_discogs_release_date("1987-07-27"),
+            Some((NaiveDate::from_ymd_opt(1987, 7, 27).unwrap(), false))
+        );
+        assert_eq!(
+            parse_discogs_release_date("1987-00-00"),
+            Some((NaiveDate::from_ymd_opt(1987, 1, 1).unwrap(), true))
+        );
+        assert_eq!(
+            parse_discogs_release_date("1987"),
+            Some((NaiveDate::from_ymd_opt(1987, 1, 1).unwrap(), true))
+        );
+        assert_eq!(parse_discogs_release_date(""), None);
+    }
+
+    #[test]
+    fn test_release_never_gonna_give_you_up() {
+        let release = serde_json::from_str::<DiscogsRelease>(include_str!(
+            "testdata/discogs_release_249504.json"
+        ))
+        .unwrap();
+
+        assert_eq!(release.master_id, Some(96559));
+        assert!(matches!(
+            release.category(),
+            Some(TitleGroupCategory::Single)
+        ));
+
+        let edition_group = release.edition_group();
+        assert_eq!(
+            edition_group.release_date,
+            NaiveDate::from_ymd_opt(1987, 7, 27)
+        );
+        assert!(!edition_group.release_date_only_year_known);
+        assert!(matches!(edition_group.source, Some(Source::Vinyl)));
+        assert_eq!(
+            edition_group.additional_information,
+            Some(json!({"catalogue_number": "PB 41447", "label": "RCA"}))
+        );
+        assert_eq!(release.label_names(), vec!["RCA"]);
+        assert_eq!(
+            edition_group.external_links,
+            vec!["https://www.discogs.com/release/249504"]
+        );
+
+        let credited_artists = credited_artists_from_discogs_credits(&release.artists);
+        assert_eq!(credited_artists.len(), 1);
+        assert_eq!(credited_artists[0].name, "Rick Astley");
+        assert_eq!(
+            credited_artists[0].external_link,
+            "https://www.discogs.com/artist/72872"
+        );
+        assert_eq!(credited_artists[0].roles, vec![ArtistRole::Main]);
+    }
+
+    #[test]
+    fn test_master_never_gonna_give_you_up() {
+        let master = serde_json::from_str::<DiscogsMaster>(include_str!(
+            "testdata/discogs_master_96559.json"
+        ))
+        .unwrap();
+
+        let title_group = UserCreatedTitleGroup::from(master);
+
+        assert_eq!(title_group.name, "Never Gonna Give You Up");
+        assert_eq!(
+            title_group.tags,
+            vec!["electronic", "pop", "synth-pop", "euro.house"]
+        );
+        assert_eq!(
+            title_group.original_release_date,
+            NaiveDate::from_ymd_opt(1987, 1, 1)
+        );
+        assert!(title_group.original_release_date_only_year_known);
+        assert_eq!(
+            title_group.covers,
+            vec![
+                "https://i.discogs.com/primary-249504.jpg",
+                "https://i.discogs.com/secondary-249504.jpg"
+            ]
+        );
+        assert_eq!(
+            title_group.external_links,
+            vec!["https://www.discogs.com/master/96559"]
+        );
+    }
+
+    #[test]
+    fn test_artist_rick_astley() {
+        let artist = serde_json::from_str::<DiscogsArtist>(include_str!(
+            "testdata/discogs_artist_72872.json"
+        ))
+        .unwrap();
+        let credit = CreditedArtist {
+            external_id: "72872".to_string(),
+            external_link: "https://www.discogs.com/artist/72872".to_string(),
+            name: "Rick Astley".to_string(),
+            roles: vec![ArtistRole::Main],
+            nickname: None,
+        };
+
+        let artist = artist.into_user_created_artist(&credit);
+
+        assert_eq!(artist.name, "Rick Astley");
+        assert_eq!(artist.aliases, vec!["Astley", "R. Astley"]);
+        assert!(artist.description.starts_with("English singer"));
+        // the image hidden to unauthenticated requests is skipped
+        assert_eq!(
+            artist.pictures,
+            vec!["https://i.discogs.com/rick-astley.jpg"]
+        );
+        assert_eq!(
+            artist.external_links,
+            vec!["https://www.discogs.com/artist/72872"]
+        );
+    }
+}
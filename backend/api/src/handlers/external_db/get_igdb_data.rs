@@ -0,0 +1,348 @@
+use crate::{
+    handlers::scrapers::ExternalDBData,
+    services::external_db_service::check_if_existing_title_group_with_link_exists, Arcadia,
+};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::{
+        edition_group::{create_default_edition_group, UserCreatedEditionGroup},
+        title_group::{
+            create_default_title_group, ContentType, Platform, TitleGroupCategory,
+            UserCreatedTitleGroup,
+        },
+    },
+    redis::{RedisInterface, RedisPoolInterface},
+};
+use chrono::DateTime;
+use regex::Regex;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+const IGDB_API_BASE_URL: &str = "https://api.igdb.com/v4";
+const TWITCH_TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+
+/// The app access token is shared by every lookup until it expires
+const IGDB_ACCESS_TOKEN_REDIS_KEY: &str = "igdb_access_token";
+/// Renewed that long before it expires, so that it doesn't while in use
+const IGDB_ACCESS_TOKEN_EXPIRY_MARGIN_SECONDS: u64 = 60;
+
+#[derive(Debug, Deserialize)]
+struct TwitchAccessToken {
+    access_token: String,
+    /// in seconds
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbNamed {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbImage {
+    image_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbPlatform {
+    slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbVideo {
+    video_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbGame {
+    name: String,
+    slug: String,
+    summary: Option<String>,
+    first_release_date: Option<i64>,
+    #[serde(default)]
+    alternative_names: Vec<IgdbNamed>,
+    #[serde(default)]
+    genres: Vec<IgdbNamed>,
+    #[serde(default)]
+    themes: Vec<IgdbNamed>,
+    #[serde(default)]
+    platforms: Vec<IgdbPlatform>,
+    cover: Option<IgdbImage>,
+    #[serde(default)]
+    screenshots: Vec<IgdbImage>,
+    #[serde(default)]
+    videos: Vec<IgdbVideo>,
+}
+
+fn map_igdb_platform(slug: &str) -> Option<Platform> {
+    match slug {
+        "win" => Some(Platform::Windows),
+        "linux" => Some(Platform::Linux),
+        "mac" => Some(Platform::MacOS),
+        "xbox" | "xbox360" | "xboxone" | "series-x-s" => Some(Platform::Xbox),
+        _ => None,
+    }
+}
+
+fn igdb_image_url(size: &str, image: &IgdbImage) -> String {
+    format!(
+        "https://images.igdb.com/igdb/image/upload/{size}/{}.jpg",
+        image.image_id
+    )
+}
+
+fn igdb_game_url(slug: &str) -> String {
+    format!("https://www.igdb.com/games/{slug}")
+}
+
+impl From<IgdbGame> for UserCreatedTitleGroup {
+    fn from(game: IgdbGame) -> Self {
+        UserCreatedTitleGroup {
+            external_links: vec![igdb_game_url(&game.slug)],
+            name: game.name,
+            name_aliases: game
+                .alternative_names
+                .into_iter()
+                .map(|alias| alias.name)
+                .collect(),
+            description: game.summary.unwrap_or_default(),
+            original_release_date: game
+                .first_release_date
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .map(|date| date.date_naive()),
+            tags: game
+                .genres
+                .iter()
+                .chain(game.themes.iter())
+                .map(|tag| tag.name.to_lowercase().replace(" ", "."))
+                .collect(),
+            // the first platform we know of, there is one title group per platform
+            platform: game
+                .platforms
+                .iter()
+                .find_map(|platform| map_igdb_platform(&platform.slug)),
+            covers: game
+                .cover
+                .map(|cover| vec![igdb_image_url("t_cover_big", &cover)])
+                .unwrap_or_default(),
+            screenshots: game
+                .screenshots
+                .iter()
+                .map(|screenshot| igdb_image_url("t_screenshot_big", screenshot))
+                .collect(),
+            trailers: game
+                .videos
+                .iter()
+                .map(|video| format!("https://www.youtube.com/watch?v={}", video.video_id))
+                .collect(),
+            content_type: ContentType::Software,
+            category: Some(TitleGroupCategory::Game),
+            ..create_default_title_group()
+        }
+    }
+}
+
+async fn get_igdb_access_token<R: RedisPoolInterface>(
+    arc: &Arcadia<R>,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<String> {
+    let mut redis = arc.redis_pool.connection().await?;
+    if let Some(access_token) = redis.get(IGDB_ACCESS_TOKEN_REDIS_KEY).await? {
+        return Ok(access_token);
+    }
+
+    let token = arc
+        .http_client
+        .post(TWITCH_TOKEN_URL)
+        .query(&[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("grant_type", "client_credentials"),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<TwitchAccessToken>()
+        .await?;
+
+    let cached_for = token
+        .expires_in
+        .saturating_sub(IGDB_ACCESS_TOKEN_EXPIRY_MARGIN_SECONDS);
+    if cached_for > 0 {
+        redis
+            .set_ex(
+                IGDB_ACCESS_TOKEN_REDIS_KEY,
+                &token.access_token,
+                cached_for as usize,
+            )
+            .await?;
+    }
+
+    Ok(token.access_token)
+}
+
+async fn get_igdb_game_data(
+    http_client: &reqwest::Client,
+    client_id: &str,
+    access_token: &str,
+    slug: &str,
+) -> Result<IgdbGame> {
+    // the slug only holds [a-z0-9-], it can't escape the string
+    let body = format!(
+        "fields name,slug,summary,first_release_date,alternative_names.name,genres.name,themes.name,\
+        platforms.slug,cover.image_id,screenshots.image_id,videos.video_id; \
+        where slug = \"{slug}\"; limit 1;"
+    );
+
+    http_client
+        .post(format!("{IGDB_API_BASE_URL}/games"))
+        .header("Client-ID", client_id)
+        .bearer_auth(access_token)
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<IgdbGame>>()
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(Error::IGDBGameNotFound)
+}
+
+fn extract_igdb_slug(url: &str) -> Result<String> {
+    // the game page can be followed by a sub-page, a query string or a fragment
+    Regex::new(r"igdb\.com/games/([a-z0-9-]+)(?:[/?#].*)?$")
+        .expect("Regex error for IGDB URL")
+        .captures(url)
+        .map(|caps| caps[1].to_string())
+        .ok_or(Error::InvalidIGDBUrl)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetIGDBQuery {
+    url: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get IGDB data",
+    tag = "External Source",
+    path = "/api/external-sources/igdb",
+    params(GetIGDBQuery),
+    responses(
+        (status = 200, description = "", body=ExternalDBData),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetIGDBQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let (Some(client_id), Some(client_secret)) = (&arc.igdb_client_id, &arc.igdb_client_secret)
+    else {
+        return Err(Error::IGDBDataFetchingNotAvailable);
+    };
+
+    let slug = extract_igdb_slug(&query.url)?;
+    if let Some(response) =
+        check_if_existing_title_group_with_link_exists(&arc.pool, &igdb_game_url(&slug)).await?
+    {
+        return Ok(response);
+    }
+
+    let access_token = get_igdb_access_token(&arc, client_id, client_secret).await?;
+    let game = get_igdb_game_data(&arc.http_client, client_id, &access_token, &slug).await?;
+
+    let mut title_group = UserCreatedTitleGroup::from(game);
+    crate::services::image_host_service::rehost_image_urls(
+        &arc.image_host,
+        &mut title_group.covers,
+    )
+    .await;
+
+    let edition_group = UserCreatedEditionGroup {
+        release_date: title_group.original_release_date,
+        ..create_default_edition_group()
+    };
+
+    Ok(HttpResponse::Ok().json(ExternalDBData {
+        title_group: Some(title_group),
+        edition_group: Some(edition_group),
+        affiliated_artists: vec![],
+        existing_title_group_id: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_igdb_slug() {
+        assert_eq!(
+            extract_igdb_slug("https://www.igdb.com/games/the-witcher-3-wild-hunt").unwrap(),
+            "the-witcher-3-wild-hunt"
+        );
+        assert_eq!(
+            extract_igdb_slug("https://www.igdb.com/games/the-witcher-3-wild-hunt/reviews?page=2")
+                .unwrap(),
+            "the-witcher-3-wild-hunt"
+        );
+        assert_eq!(
+            extract_igdb_slug("https://www.igdb.com/games/the-witcher-3-wild-hunt?utm_source=x")
+                .unwrap(),
+            "the-witcher-3-wild-hunt"
+        );
+        assert!(extract_igdb_slug("https://www.igdb.com/companies/cd-projekt-red").is_err());
+        assert!(extract_igdb_slug("https://www.igdb.com/games/The_Witcher").is_err());
+    }
+
+    #[test]
+    fn test_the_witcher_3() {
+        let games = serde_json::from_str::<Vec<IgdbGame>>(include_str!(
+            "testdata/igdb_game_the_witcher_3.json"
+        ))
+        .unwrap();
+
+        let title_group = UserCreatedTitleGroup::from(games.into_iter().next().unwrap());
+
+        assert_eq!(title_group.name, "The Witcher 3: Wild Hunt");
+        assert_eq!(title_group.name_aliases, vec!["Wiedźmin 3: Dziki Gon"]);
+        assert_eq!(
+            title_group.external_links,
+            vec!["https://www.igdb.com/games/the-witcher-3-wild-hunt"]
+        );
+        assert_eq!(
+            title_group.original_release_date,
+            chrono::NaiveDate::from_ymd_opt(2015, 5, 19)
+        );
+        assert_eq!(
+            title_group.tags,
+            vec![
+                "role-playing.(rpg)",
+                "adventure",
+                "action",
+                "fantasy",
+                "open.world"
+            ]
+        );
+        assert!(matches!(title_group.platform, Some(Platform::Windows)));
+        assert!(matches!(
+            title_group.category,
+            Some(TitleGroupCategory::Game)
+        ));
+        assert_eq!(
+            title_group.covers,
+            vec!["https://images.igdb.com/igdb/image/upload/t_cover_big/co1wyy.jpg"]
+        );
+        assert_eq!(title_group.screenshots.len(), 2);
+        assert_eq!(
+            title_group.trailers,
+            vec!["https://www.youtube.com/watch?v=c0i88t0Kacs"]
+        );
+    }
+}
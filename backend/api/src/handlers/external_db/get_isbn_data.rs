@@ -136,14 +136,14 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         .map(String::from)
         .unwrap_or_else(|| "".to_string());
 
-    let mut authors: Vec<Author> = vec![];
+    let mut authors: Vec<(String, Author)> = vec![];
 
     for link in book.authors {
         let author = reqwest::get(format!("https://openlibrary.org{}.json", link.key))
             .await?
             .json::<Author>()
             .await?;
-        authors.push(author);
+        authors.push((format!("https://openlibrary.org{}", link.key), author));
     }
 
     let artists = arc
@@ -151,7 +151,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         .create_artists(
             &authors
                 .iter()
-                .map(|author| UserCreatedArtist {
+                .map(|(author_link, author)| UserCreatedArtist {
                     name: author.name.clone(),
                     aliases: vec![],
                     description: author
@@ -162,6 +162,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
                         })
                         .value,
                     pictures: vec![],
+                    external_links: vec![author_link.clone()],
                 })
                 .collect::<Vec<UserCreatedArtist>>(),
             user.sub,
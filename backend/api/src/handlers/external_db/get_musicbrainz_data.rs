@@ -1,6 +1,10 @@
 use crate::{
     handlers::scrapers::ExternalDBData,
-    services::external_db_service::check_if_existing_title_group_with_link_exists, Arcadia,
+    middlewares::auth_middleware::Authdata,
+    services::external_db_service::{
+        check_if_existing_title_group_with_link_exists, resolve_credited_artists, CreditedArtist,
+    },
+    Arcadia,
 };
 use actix_web::{
     web::{Data, Query},
@@ -9,7 +13,7 @@ use actix_web::{
 use arcadia_common::error::{Error, Result};
 use arcadia_storage::{
     models::{
-        artist::AffiliatedArtistHierarchy,
+        artist::{ArtistRole, UserCreatedArtist},
         edition_group::{create_default_edition_group, UserCreatedEditionGroup},
         title_group::{create_default_title_group, ContentType, UserCreatedTitleGroup},
     },
@@ -18,6 +22,7 @@ use arcadia_storage::{
 use musicbrainz_rs::{
     client::MusicBrainzClient,
     entity::{
+        artist_credit::ArtistCredit,
         release::Release,
         release_group::{ReleaseGroup, ReleaseGroupPrimaryType},
         CoverartResponse,
@@ -27,18 +32,147 @@ use musicbrainz_rs::{
 use regex::Regex;
 use serde::Deserialize;
 use serde_json::json;
+use tokio::{
+    sync::Mutex,
+    time::{sleep_until, Duration, Instant},
+};
 use utoipa::IntoParams;
 
+/// MusicBrainz allows a single request per second from each application
+/// https://musicbrainz.org/doc/MusicBrainz_API/Rate_Limiting
+const MUSICBRAINZ_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+static MUSICBRAINZ_LAST_REQUEST_AT: Mutex<Option<Instant>> = Mutex::const_new(None);
+
+/// Waits for the turn of the next request to MusicBrainz, across all the concurrent lookups
+async fn wait_for_musicbrainz_rate_limit() {
+    let mut last_request_at = MUSICBRAINZ_LAST_REQUEST_AT.lock().await;
+    if let Some(last_request_at) = *last_request_at {
+        sleep_until(last_request_at + MUSICBRAINZ_REQUEST_INTERVAL).await;
+    }
+    *last_request_at = Some(Instant::now());
+}
+
+/// The artists credited on a release group. Those listed after a "feat." join phrase are guests,
+/// and the name they are credited under is kept as nickname when it isn't their usual one.
+fn credited_artists_from_artist_credit(artist_credit: &[ArtistCredit]) -> Vec<CreditedArtist> {
+    let mut featured = false;
+    let mut credited_artists = Vec::new();
+
+    for credit in artist_credit {
+        credited_artists.push(CreditedArtist {
+            external_id: credit.artist.id.clone(),
+            external_link: format!("https://musicbrainz.org/artist/{}", credit.artist.id),
+            name: credit.artist.name.clone(),
+            roles: vec![if featured {
+                ArtistRole::Guest
+            } else {
+                ArtistRole::Main
+            }],
+            nickname: (credit.name != credit.artist.name).then(|| credit.name.clone()),
+        });
+        if credit
+            .joinphrase
+            .as_ref()
+            .is_some_and(|joinphrase| joinphrase.to_lowercase().contains("feat"))
+        {
+            featured = true;
+        }
+    }
+
+    credited_artists
+}
+
+#[derive(Debug, Deserialize)]
+struct MusicBrainzArtist {
+    name: String,
+    #[serde(default)]
+    disambiguation: String,
+    annotation: Option<String>,
+    #[serde(default)]
+    aliases: Vec<MusicBrainzArtistAlias>,
+    #[serde(default)]
+    relations: Vec<MusicBrainzArtistRelation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MusicBrainzArtistAlias {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MusicBrainzArtistRelation {
+    #[serde(rename = "type")]
+    relation_type: String,
+    url: Option<MusicBrainzUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MusicBrainzUrl {
+    resource: String,
+}
+
+/// Wikimedia Commons links point to the file's page, this points to the image itself
+fn commons_file_url(url: &str) -> String {
+    url.replacen("/wiki/File:", "/wiki/Special:FilePath/", 1)
+}
+
+impl MusicBrainzArtist {
+    fn into_user_created_artist(self, credit: &CreditedArtist) -> UserCreatedArtist {
+        UserCreatedArtist {
+            aliases: self
+                .aliases
+                .into_iter()
+                .map(|alias| alias.name)
+                .filter(|alias| *alias != self.name)
+                .collect(),
+            name: self.name,
+            description: self
+                .annotation
+                .filter(|annotation| !annotation.is_empty())
+                .unwrap_or(self.disambiguation),
+            pictures: self
+                .relations
+                .into_iter()
+                .filter(|relation| relation.relation_type == "image")
+                .filter_map(|relation| relation.url)
+                .map(|url| commons_file_url(&url.resource))
+                .collect(),
+            external_links: vec![credit.external_link.clone()],
+        }
+    }
+}
+
+async fn get_musicbrainz_artist_data(
+    http_client: &reqwest::Client,
+    user_agent: &str,
+    credit: CreditedArtist,
+) -> Result<UserCreatedArtist> {
+    wait_for_musicbrainz_rate_limit().await;
+    let artist = http_client
+        .get(format!(
+            "https://musicbrainz.org/ws/2/artist/{}?inc=aliases+url-rels+annotation&fmt=json",
+            credit.external_id
+        ))
+        .header(reqwest::header::USER_AGENT, user_agent)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<MusicBrainzArtist>()
+        .await?;
+
+    Ok(artist.into_user_created_artist(&credit))
+}
+
 async fn get_musicbrainz_release_group_data(
     id: &str,
     client: &MusicBrainzClient,
-) -> Result<UserCreatedTitleGroup> {
-    // TODO: handle artists
+) -> Result<(UserCreatedTitleGroup, Vec<CreditedArtist>)> {
+    wait_for_musicbrainz_rate_limit().await;
     let musicbrainz_title_group = ReleaseGroup::fetch()
         .id(id)
         .with_tags()
         .with_aliases()
-        // .with_artists()
+        .with_artists()
         .execute_with_client(client)
         .await
         .map_err(Error::ErrorGettingMusicbrainzData)?;
@@ -57,7 +191,14 @@ async fn get_musicbrainz_release_group_data(
             },
         );
 
-    Ok(UserCreatedTitleGroup {
+    let credited_artists = credited_artists_from_artist_credit(
+        musicbrainz_title_group
+            .artist_credit
+            .as_deref()
+            .unwrap_or_default(),
+    );
+
+    let title_group = UserCreatedTitleGroup {
         name: musicbrainz_title_group.title,
         name_aliases: musicbrainz_title_group
             .aliases
@@ -82,13 +223,16 @@ async fn get_musicbrainz_release_group_data(
         external_links: vec![format!("https://musicbrainz.org/release-group/{}", id)],
         covers: vec![cover],
         ..create_default_title_group()
-    })
+    };
+
+    Ok((title_group, credited_artists))
 }
 
 async fn get_musicbrainz_release_data(
     id: &str,
     client: &MusicBrainzClient,
 ) -> Result<(UserCreatedEditionGroup, Option<String>)> {
+    wait_for_musicbrainz_rate_limit().await;
     let musicbrainz_edition_group = Release::fetch()
         .id(id)
         .with_release_groups()
@@ -137,18 +281,21 @@ pub struct GetMusicbrainzQuery {
 pub async fn exec<R: RedisPoolInterface + 'static>(
     query: Query<GetMusicbrainzQuery>,
     arc: Data<Arcadia<R>>,
+    user: Authdata,
 ) -> Result<HttpResponse> {
     let (entity_type, id) = Regex::new(r"musicbrainz.org/(release|release-group)/([0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12})")
         .expect("Regex error")
         .captures(&query.url).map(|caps| (match caps[1].as_ref() { "release" => MusicBrainzResourceType::Release, _ => MusicBrainzResourceType::ReleaseGroup }, caps[2].to_string()))
         .ok_or_else(|| Error::InvalidMusicbrainzUrl)?;
     // .expect("No MusicBrainz release/release-group match found in URL");
+    let user_agent = format!("{} ({})", arc.tracker.name, arc.frontend_url);
     let mut client = MusicBrainzClient::default();
     client
-        .set_user_agent(&format!("{} ({})", arc.tracker.name, arc.frontend_url))
+        .set_user_agent(&user_agent)
         .map_err(|_| Error::InvalidMusicbrainzUrl)?;
 
     let mut title_group: Option<UserCreatedTitleGroup> = None;
+    let mut credited_artists: Vec<CreditedArtist> = vec![];
     let mut edition_group: Option<UserCreatedEditionGroup> = None;
     match entity_type {
         MusicBrainzResourceType::ReleaseGroup => {
@@ -157,10 +304,11 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
             {
                 return Ok(response);
             }
-            let mut tg = get_musicbrainz_release_group_data(&id, &client).await?;
+            let (mut tg, credits) = get_musicbrainz_release_group_data(&id, &client).await?;
             crate::services::image_host_service::rehost_image_urls(&arc.image_host, &mut tg.covers)
                 .await;
             title_group = Some(tg);
+            credited_artists = credits;
         }
         MusicBrainzResourceType::Release => {
             let (eg, release_group_id) = get_musicbrainz_release_data(&id, &client).await?;
@@ -174,21 +322,87 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
                 {
                     return Ok(response);
                 }
-                let mut tg = get_musicbrainz_release_group_data(&rgid, &client).await?;
+                let (mut tg, credits) = get_musicbrainz_release_group_data(&rgid, &client).await?;
                 crate::services::image_host_service::rehost_image_urls(
                     &arc.image_host,
                     &mut tg.covers,
                 )
                 .await;
                 title_group = Some(tg);
+                credited_artists = credits;
             }
         }
     };
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "title_group": title_group,
-        "edition_group": edition_group,
-        "affiliated_artists": Vec::<AffiliatedArtistHierarchy>::new(),
-        "existing_title_group_id": null
-    })))
+    let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
+    let affiliated_artists = resolve_credited_artists(
+        &arc.pool,
+        &arc.image_host,
+        &approved_image_hosts,
+        &credited_artists,
+        user.sub,
+        |credit| get_musicbrainz_artist_data(&arc.http_client, &user_agent, credit),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ExternalDBData {
+        title_group,
+        edition_group,
+        affiliated_artists,
+        existing_title_group_id: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credited_artists_featuring() {
+        let artist_credit = serde_json::from_str::<Vec<ArtistCredit>>(include_str!(
+            "testdata/musicbrainz_artist_credit_love_the_way_you_lie.json"
+        ))
+        .unwrap();
+
+        let credited_artists = credited_artists_from_artist_credit(&artist_credit);
+
+        assert_eq!(credited_artists.len(), 2);
+        assert_eq!(credited_artists[0].name, "Eminem");
+        assert_eq!(credited_artists[0].roles, vec![ArtistRole::Main]);
+        assert_eq!(
+            credited_artists[0].external_link,
+            "https://musicbrainz.org/artist/b95ce3ff-3d05-4e87-9e01-c97b66af13d4"
+        );
+        assert_eq!(credited_artists[1].name, "Rihanna");
+        assert_eq!(credited_artists[1].roles, vec![ArtistRole::Guest]);
+        assert!(credited_artists.iter().all(|c| c.nickname.is_none()));
+    }
+
+    #[test]
+    fn test_artist_lookup_nirvana() {
+        let nirvana = serde_json::from_str::<MusicBrainzArtist>(include_str!(
+            "testdata/musicbrainz_artist_nirvana.json"
+        ))
+        .unwrap();
+        let credit = CreditedArtist {
+            external_id: "5b11f4ce-a62d-471e-81fc-a69a8278c7da".to_string(),
+            external_link: "https://musicbrainz.org/artist/5b11f4ce-a62d-471e-81fc-a69a8278c7da"
+                .to_string(),
+            name: "Nirvana".to_string(),
+            roles: vec![ArtistRole::Main],
+            nickname: None,
+        };
+
+        let artist = nirvana.into_user_created_artist(&credit);
+
+        assert_eq!(artist.name, "Nirvana");
+        assert_eq!(artist.aliases, vec!["Nirvana US", "ニルヴァーナ"]);
+        // no annotation, the disambiguation is used instead
+        assert_eq!(artist.description, "US rock band from Aberdeen, Washington");
+        assert_eq!(
+            artist.pictures,
+            vec!["https://commons.wikimedia.org/wiki/Special:FilePath/Nirvana_around_1992.jpg"]
+        );
+        assert_eq!(artist.external_links, vec![credit.external_link]);
+    }
 }
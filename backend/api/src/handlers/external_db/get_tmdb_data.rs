@@ -1,8 +1,12 @@
 use std::str::FromStr;
 
 use crate::{
-    handlers::scrapers::ExternalDBData, middlewares::auth_middleware::Authdata,
-    services::external_db_service::check_if_existing_title_group_with_link_exists, Arcadia,
+    handlers::scrapers::ExternalDBData,
+    middlewares::auth_middleware::Authdata,
+    services::external_db_service::{
+        check_if_existing_title_group_with_link_exists, resolve_credited_artists, CreditedArtist,
+    },
+    Arcadia,
 };
 use actix_web::{
     web::{Data, Query},
@@ -10,9 +14,8 @@ use actix_web::{
 };
 use arcadia_common::error::{Error, Result};
 use arcadia_storage::{
-    connection_pool::ConnectionPool,
     models::{
-        artist::{AffiliatedArtistHierarchy, ArtistRole, UserCreatedArtist},
+        artist::{ArtistRole, UserCreatedArtist},
         edition_group::{create_default_edition_group, UserCreatedEditionGroup},
         title_group::{
             create_default_title_group, ContentType, ExternalDB, PublicRating,
@@ -22,7 +25,6 @@ use arcadia_storage::{
     },
     redis::RedisPoolInterface,
 };
-use chrono::Utc;
 use regex::Regex;
 use serde::Deserialize;
 use tmdb_api::client::reqwest::Client as ReqwestClient;
@@ -30,6 +32,8 @@ use tmdb_api::client::Client;
 use tmdb_api::common::credits::{Cast, Crew};
 use utoipa::IntoParams;
 
+const MAX_CREDITED_CAST_MEMBERS: usize = 20;
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct GetTMDBQuery {
     url: String,
@@ -47,10 +51,6 @@ fn map_crew_job_to_role(job: &str) -> Option<ArtistRole> {
     }
 }
 
-fn map_crew_job_to_roles(job: &str) -> Vec<ArtistRole> {
-    map_crew_job_to_role(job).into_iter().collect()
-}
-
 fn tmdb_profile_picture_url(profile_path: &Option<String>) -> Vec<String> {
     profile_path
         .as_ref()
@@ -58,96 +58,82 @@ fn tmdb_profile_picture_url(profile_path: &Option<String>) -> Vec<String> {
         .unwrap_or_default()
 }
 
-async fn create_artists_from_credits(
-    pool: &ConnectionPool,
-    cast: &[Cast],
-    crew: &[Crew],
-    current_user_id: i32,
-) -> Result<Vec<AffiliatedArtistHierarchy>> {
-    let mut artists_to_create: Vec<UserCreatedArtist> = Vec::new();
-
-    // Collect cast members
-    for member in cast {
-        artists_to_create.push(UserCreatedArtist {
+/// The top billed cast members, and the crew members whose job maps to a role
+fn credited_artists_from_credits(cast: &[Cast], crew: &[Crew]) -> Vec<CreditedArtist> {
+    let cast = cast
+        .iter()
+        .take(MAX_CREDITED_CAST_MEMBERS)
+        .map(|member| CreditedArtist {
+            external_id: member.person.id.to_string(),
+            external_link: format!("https://www.themoviedb.org/person/{}", member.person.id),
             name: member.person.name.clone(),
-            aliases: vec![],
-            description: String::new(),
-            pictures: tmdb_profile_picture_url(&member.person.profile_path),
+            roles: vec![ArtistRole::Actor],
+            nickname: (!member.character.is_empty()).then(|| member.character.clone()),
         });
-    }
 
-    // Collect crew members
-    for member in crew {
-        artists_to_create.push(UserCreatedArtist {
+    let crew = crew.iter().filter_map(|member| {
+        map_crew_job_to_role(&member.job).map(|role| CreditedArtist {
+            external_id: member.person.id.to_string(),
+            external_link: format!("https://www.themoviedb.org/person/{}", member.person.id),
             name: member.person.name.clone(),
-            aliases: vec![],
-            description: String::new(),
-            pictures: tmdb_profile_picture_url(&member.person.profile_path),
-        });
-    }
+            roles: vec![role],
+            nickname: None,
+        })
+    });
 
-    if artists_to_create.is_empty() {
-        return Ok(vec![]);
-    }
-
-    let created_artists = pool
-        .create_artists(&artists_to_create, current_user_id)
-        .await?;
+    cast.chain(crew).collect()
+}
 
-    let mut affiliated_artists: Vec<AffiliatedArtistHierarchy> = Vec::new();
+#[derive(Debug, Deserialize)]
+struct TmdbPerson {
+    name: String,
+    #[serde(default)]
+    biography: String,
+    #[serde(default)]
+    also_known_as: Vec<String>,
+    profile_path: Option<String>,
+    imdb_id: Option<String>,
+}
 
-    // Map cast to affiliated artists
-    for member in cast {
-        if let Some(artist) = created_artists
-            .iter()
-            .find(|a| a.name == member.person.name)
-        {
-            affiliated_artists.push(AffiliatedArtistHierarchy {
-                id: 0,
-                title_group_id: 0,
-                artist_id: artist.id,
-                roles: vec![ArtistRole::Actor],
-                nickname: Some(member.character.clone()),
-                created_at: Utc::now(),
-                created_by_id: current_user_id,
-                artist: artist.clone(),
-            });
+impl TmdbPerson {
+    fn into_user_created_artist(self, credit: &CreditedArtist) -> UserCreatedArtist {
+        let mut external_links = vec![credit.external_link.clone()];
+        if let Some(imdb_id) = self.imdb_id.filter(|id| !id.is_empty()) {
+            external_links.push(format!("https://www.imdb.com/name/{imdb_id}"));
         }
-    }
 
-    // Map crew to affiliated artists
-    for member in crew {
-        if let Some(artist) = created_artists
-            .iter()
-            .find(|a| a.name == member.person.name)
-        {
-            let roles = map_crew_job_to_roles(&member.job);
-            // Check if this artist already has an entry, and if so merge the roles
-            if let Some(existing) = affiliated_artists
-                .iter_mut()
-                .find(|a| a.artist_id == artist.id)
-            {
-                for role in roles {
-                    if !existing.roles.contains(&role) {
-                        existing.roles.push(role);
-                    }
-                }
-            } else {
-                affiliated_artists.push(AffiliatedArtistHierarchy {
-                    id: 0,
-                    title_group_id: 0,
-                    artist_id: artist.id,
-                    roles,
-                    nickname: None,
-                    created_at: Utc::now(),
-                    created_by_id: current_user_id,
-                    artist: artist.clone(),
-                });
-            }
+        UserCreatedArtist {
+            aliases: self
+                .also_known_as
+                .into_iter()
+                .filter(|alias| *alias != self.name)
+                .collect(),
+            name: self.name,
+            description: self.biography,
+            pictures: tmdb_profile_picture_url(&self.profile_path),
+            external_links,
         }
     }
+}
 
-    Ok(affiliated_artists)
+async fn get_tmdb_person_data(
+    http_client: &reqwest::Client,
+    api_key: &str,
+    credit: CreditedArtist,
+) -> Result<UserCreatedArtist> {
+    let person = http_client
+        .get(format!(
+            "https://api.themoviedb.org/3/person/{}",
+            credit.external_id
+        ))
+        .query(&[("api_key", api_key)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<TmdbPerson>()
+        .await?;
+
+    Ok(person.into_user_created_artist(&credit))
 }
 
 async fn get_tmdb_movie_data(client: &Client<ReqwestClient>, id: u64) -> Result<ExternalDBData> {
@@ -237,7 +223,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         _ => return Err(Error::InvalidTMDBUrl),
     };
 
-    // Fetch credits and create artists
+    // Fetch credits and match or propose artists
     let credits = match media_type {
         ContentType::Movie => client
             .get_movie_credits(id, &Default::default())
@@ -246,8 +232,17 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         _ => unreachable!(),
     };
 
-    external_db_data.affiliated_artists =
-        create_artists_from_credits(&arc.pool, &credits.cast, &credits.crew, user.sub).await?;
+    let tmdb_api_key = arc.tmdb_api_key.clone().unwrap();
+    let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
+    external_db_data.affiliated_artists = resolve_credited_artists(
+        &arc.pool,
+        &arc.image_host,
+        &approved_image_hosts,
+        &credited_artists_from_credits(&credits.cast, &credits.crew),
+        user.sub,
+        |credit| get_tmdb_person_data(&arc.http_client, &tmdb_api_key, credit),
+    )
+    .await?;
 
     if let Some(title_group) = &mut external_db_data.title_group {
         title_group.external_links.push(query.url.clone());
@@ -258,30 +253,6 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         .await;
     }
 
-    // Rehost artist images in the background to avoid blocking the response
-    if arc.image_host.rehost_external_images {
-        let image_host_config = arc.image_host.clone();
-        let pool = arc.pool.clone();
-        let artists: Vec<_> = external_db_data
-            .affiliated_artists
-            .iter()
-            .map(|a| (a.artist.id, a.artist.pictures.clone()))
-            .collect();
-
-        tokio::spawn(async move {
-            for (artist_id, mut pictures) in artists {
-                crate::services::image_host_service::rehost_image_urls(
-                    &image_host_config,
-                    &mut pictures,
-                )
-                .await;
-                if let Err(e) = pool.update_artist_pictures(artist_id, &pictures).await {
-                    log::warn!("Failed to update rehosted pictures for artist {artist_id}: {e}");
-                }
-            }
-        });
-    }
-
     Ok(HttpResponse::Ok().json(external_db_data))
 }
 
@@ -342,3 +313,75 @@ fn extract_media_type_and_id(tmdb_url: &str) -> Result<(ContentType, u64)> {
 
     Ok((media_type, id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Credits {
+        cast: Vec<Cast>,
+        crew: Vec<Crew>,
+    }
+
+    #[test]
+    fn test_credited_artists_fight_club() {
+        let credits =
+            serde_json::from_str::<Credits>(include_str!("testdata/tmdb_movie_credits_550.json"))
+                .unwrap();
+
+        let credited_artists = credited_artists_from_credits(&credits.cast, &credits.crew);
+
+        // the novel's author has no matching role
+        assert_eq!(credited_artists.len(), 6);
+        assert_eq!(credited_artists[1].name, "Brad Pitt");
+        assert_eq!(credited_artists[1].roles, vec![ArtistRole::Actor]);
+        assert_eq!(
+            credited_artists[1].nickname,
+            Some("Tyler Durden".to_string())
+        );
+        assert_eq!(
+            credited_artists[1].external_link,
+            "https://www.themoviedb.org/person/287"
+        );
+        assert_eq!(credited_artists[2].nickname, None);
+        assert_eq!(credited_artists[3].name, "David Fincher");
+        assert_eq!(credited_artists[3].roles, vec![ArtistRole::Director]);
+        assert_eq!(credited_artists[4].roles, vec![ArtistRole::Writer]);
+        assert_eq!(credited_artists[5].roles, vec![ArtistRole::Producer]);
+    }
+
+    #[test]
+    fn test_person_brad_pitt() {
+        let person =
+            serde_json::from_str::<TmdbPerson>(include_str!("testdata/tmdb_person_287.json"))
+                .unwrap();
+        let credit = CreditedArtist {
+            external_id: "287".to_string(),
+            external_link: "https://www.themoviedb.org/person/287".to_string(),
+            name: "Brad Pitt".to_string(),
+            roles: vec![ArtistRole::Actor],
+            nickname: Some("Tyler Durden".to_string()),
+        };
+
+        let artist = person.into_user_created_artist(&credit);
+
+        assert_eq!(artist.name, "Brad Pitt");
+        assert_eq!(
+            artist.aliases,
+            vec!["William Bradley Pitt", "브래드 피트", "ブラッド・ピット"]
+        );
+        assert!(artist.description.starts_with("William Bradley Pitt is"));
+        assert_eq!(
+            artist.pictures,
+            vec!["https://image.tmdb.org/t/p/w500/cckcYc2v0yh1tc9QjRelptcOBko.jpg"]
+        );
+        assert_eq!(
+            artist.external_links,
+            vec![
+                "https://www.themoviedb.org/person/287",
+                "https://www.imdb.com/name/nm0000093"
+            ]
+        );
+    }
+}
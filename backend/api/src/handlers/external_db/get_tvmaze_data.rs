@@ -0,0 +1,328 @@
+use std::str::FromStr;
+
+use crate::{
+    handlers::scrapers::ExternalDBData,
+    services::external_db_service::{
+        check_if_existing_title_group_with_link_exists, strip_html_tags,
+    },
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::{
+        edition_group::{create_default_edition_group, UserCreatedEditionGroup},
+        title_group::{create_default_title_group, ContentType, UserCreatedTitleGroup},
+        torrent::Language,
+    },
+    redis::RedisPoolInterface,
+};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::json;
+use utoipa::IntoParams;
+
+const TVMAZE_API_BASE_URL: &str = "https://api.tvmaze.com";
+
+#[derive(Debug, Deserialize)]
+struct TVmazeImage {
+    original: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TVmazeExternals {
+    imdb: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TVmazeShow {
+    id: u64,
+    name: String,
+    language: Option<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+    premiered: Option<NaiveDate>,
+    summary: Option<String>,
+    image: Option<TVmazeImage>,
+    externals: Option<TVmazeExternals>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TVmazeEmbeddedShow {
+    show: TVmazeShow,
+}
+
+#[derive(Debug, Deserialize)]
+struct TVmazeEpisode {
+    id: u64,
+    name: String,
+    season: u32,
+    /// specials have no number
+    number: Option<u32>,
+    airdate: Option<NaiveDate>,
+    summary: Option<String>,
+    image: Option<TVmazeImage>,
+    #[serde(rename = "_embedded")]
+    embedded: TVmazeEmbeddedShow,
+}
+
+fn tvmaze_show_url(id: u64) -> String {
+    format!("https://www.tvmaze.com/shows/{id}")
+}
+
+fn tvmaze_episode_url(id: u64) -> String {
+    format!("https://www.tvmaze.com/episodes/{id}")
+}
+
+/// TVmaze gives the language's English name, which matches the enum's
+/// variants except for the ones serialized as language codes
+fn map_tvmaze_language(language: &str) -> Language {
+    match language {
+        "English" => Language::English,
+        "French" => Language::French,
+        _ => Language::from_str(language).unwrap_or(Language::Other),
+    }
+}
+
+fn tvmaze_image_urls(image: Option<&TVmazeImage>) -> Vec<String> {
+    image
+        .and_then(|image| image.original.clone())
+        .map_or_else(Vec::new, |url| vec![url])
+}
+
+impl From<TVmazeShow> for UserCreatedTitleGroup {
+    fn from(show: TVmazeShow) -> Self {
+        let mut external_links = vec![tvmaze_show_url(show.id)];
+        if let Some(imdb_id) = show.externals.and_then(|externals| externals.imdb) {
+            external_links.push(format!("https://www.imdb.com/title/{imdb_id}"));
+        }
+
+        UserCreatedTitleGroup {
+            name: show.name,
+            description: show
+                .summary
+                .as_deref()
+                .map(strip_html_tags)
+                .unwrap_or_default(),
+            original_language: show.language.as_deref().map(map_tvmaze_language),
+            original_release_date: show.premiered,
+            tags: show
+                .genres
+                .iter()
+                .map(|genre| genre.to_lowercase().replace(" ", "."))
+                .collect(),
+            covers: tvmaze_image_urls(show.image.as_ref()),
+            external_links,
+            trailers: vec![],
+            content_type: ContentType::TVShow,
+            ..create_default_title_group()
+        }
+    }
+}
+
+impl TVmazeEpisode {
+    fn edition_group(&self) -> UserCreatedEditionGroup {
+        let episode_code = match self.number {
+            Some(number) => format!("S{:02}E{number:02}", self.season),
+            None => format!("S{:02} Special", self.season),
+        };
+
+        UserCreatedEditionGroup {
+            name: Some(format!("{episode_code} - {}", self.name)),
+            release_date: self.airdate,
+            description: self.summary.as_deref().map(strip_html_tags),
+            covers: tvmaze_image_urls(self.image.as_ref()),
+            external_links: vec![tvmaze_episode_url(self.id)],
+            additional_information: Some(json!({
+                "season": self.season.to_string(),
+                "episode": self.number.map(|number| number.to_string()).unwrap_or_default(),
+            })),
+            ..create_default_edition_group()
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TVmazeResourceType {
+    Show,
+    Episode,
+}
+
+fn extract_tvmaze_resource(url: &str) -> Result<(TVmazeResourceType, u64)> {
+    Regex::new(r"tvmaze\.com/(shows|episodes)/(\d+)")
+        .expect("Regex error for TVmaze URL")
+        .captures(url)
+        .and_then(|caps| {
+            Some((
+                match &caps[1] {
+                    "shows" => TVmazeResourceType::Show,
+                    _ => TVmazeResourceType::Episode,
+                },
+                caps[2].parse().ok()?,
+            ))
+        })
+        .ok_or(Error::InvalidTVmazeUrl)
+}
+
+async fn fetch_tvmaze_data<T: for<'de> Deserialize<'de>>(
+    endpoint: &str,
+    client: &reqwest::Client,
+) -> Result<T> {
+    Ok(client
+        .get(format!("{TVMAZE_API_BASE_URL}/{endpoint}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<T>()
+        .await?)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetTVmazeQuery {
+    url: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get TVmaze data",
+    tag = "External Source",
+    path = "/api/external-sources/tvmaze",
+    params(GetTVmazeQuery),
+    responses(
+        (status = 200, description = "", body=ExternalDBData),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetTVmazeQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let (resource_type, id) = extract_tvmaze_resource(&query.url)?;
+
+    // an episode url gives the episode as edition group, and its show as title group
+    let (show, edition_group) = match resource_type {
+        TVmazeResourceType::Show => {
+            if let Some(response) =
+                check_if_existing_title_group_with_link_exists(&arc.pool, &tvmaze_show_url(id))
+                    .await?
+            {
+                return Ok(response);
+            }
+            let show: TVmazeShow =
+                fetch_tvmaze_data(&format!("shows/{id}"), &arc.http_client).await?;
+            (show, None)
+        }
+        TVmazeResourceType::Episode => {
+            let episode: TVmazeEpisode =
+                fetch_tvmaze_data(&format!("episodes/{id}?embed=show"), &arc.http_client).await?;
+            if let Some(response) = check_if_existing_title_group_with_link_exists(
+                &arc.pool,
+                &tvmaze_show_url(episode.embedded.show.id),
+            )
+            .await?
+            {
+                return Ok(response);
+            }
+            let edition_group = episode.edition_group();
+            (episode.embedded.show, Some(edition_group))
+        }
+    };
+
+    let mut title_group = UserCreatedTitleGroup::from(show);
+    crate::services::image_host_service::rehost_image_urls(
+        &arc.image_host,
+        &mut title_group.covers,
+    )
+    .await;
+
+    Ok(HttpResponse::Ok().json(ExternalDBData {
+        title_group: Some(title_group),
+        edition_group,
+        affiliated_artists: vec![],
+        existing_title_group_id: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tvmaze_resource() {
+        assert_eq!(
+            extract_tvmaze_resource("https://www.tvmaze.com/shows/82/game-of-thrones").unwrap(),
+            (TVmazeResourceType::Show, 82)
+        );
+        assert_eq!(
+            extract_tvmaze_resource(
+                "https://www.tvmaze.com/episodes/4952/game-of-thrones-1x01-winter-is-coming"
+            )
+            .unwrap(),
+            (TVmazeResourceType::Episode, 4952)
+        );
+        assert!(extract_tvmaze_resource("https://www.tvmaze.com/people/14075").is_err());
+    }
+
+    #[test]
+    fn test_map_tvmaze_language() {
+        assert!(matches!(map_tvmaze_language("English"), Language::English));
+        assert!(matches!(map_tvmaze_language("French"), Language::French));
+        assert!(matches!(
+            map_tvmaze_language("Japanese"),
+            Language::Japanese
+        ));
+        assert!(matches!(map_tvmaze_language("Klingon"), Language::Other));
+    }
+
+    #[test]
+    fn test_episode_winter_is_coming() {
+        let episode = serde_json::from_str::<TVmazeEpisode>(include_str!(
+            "testdata/tvmaze_episode_4952.json"
+        ))
+        .unwrap();
+        let edition_group = episode.edition_group();
+        let title_group = UserCreatedTitleGroup::from(episode.embedded.show);
+
+        assert_eq!(title_group.name, "Game of Thrones");
+        assert!(matches!(
+            title_group.original_language,
+            Some(Language::English)
+        ));
+        assert_eq!(
+            title_group.original_release_date,
+            NaiveDate::from_ymd_opt(2011, 4, 17)
+        );
+        assert_eq!(title_group.tags, vec!["drama", "adventure", "fantasy"]);
+        assert_eq!(
+            title_group.description,
+            "Based on the bestselling book series A Song of Ice and Fire by George R.R. Martin."
+        );
+        assert_eq!(
+            title_group.external_links,
+            vec![
+                "https://www.tvmaze.com/shows/82",
+                "https://www.imdb.com/title/tt0944947"
+            ]
+        );
+
+        assert_eq!(
+            edition_group.name,
+            Some("S01E01 - Winter is Coming".to_string())
+        );
+        assert_eq!(
+            edition_group.release_date,
+            NaiveDate::from_ymd_opt(2011, 4, 17)
+        );
+        assert_eq!(
+            edition_group.additional_information,
+            Some(json!({"season": "1", "episode": "1"}))
+        );
+        assert_eq!(
+            edition_group.external_links,
+            vec!["https://www.tvmaze.com/episodes/4952"]
+        );
+    }
+}
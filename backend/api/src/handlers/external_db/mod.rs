@@ -1,7 +1,11 @@
+pub mod get_anilist_data;
 pub mod get_comic_vine_data;
+pub mod get_discogs_data;
+pub mod get_igdb_data;
 pub mod get_isbn_data;
 pub mod get_musicbrainz_data;
 pub mod get_tmdb_data;
+pub mod get_tvmaze_data;
 
 use actix_web::web::{get, resource, ServiceConfig};
 use arcadia_storage::redis::RedisPoolInterface;
@@ -11,4 +15,8 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
     cfg.service(resource("/tmdb").route(get().to(self::get_tmdb_data::exec::<R>)));
     cfg.service(resource("/comic-vine").route(get().to(self::get_comic_vine_data::exec::<R>)));
     cfg.service(resource("/musicbrainz").route(get().to(self::get_musicbrainz_data::exec::<R>)));
+    cfg.service(resource("/igdb").route(get().to(self::get_igdb_data::exec::<R>)));
+    cfg.service(resource("/discogs").route(get().to(self::get_discogs_data::exec::<R>)));
+    cfg.service(resource("/anilist").route(get().to(self::get_anilist_data::exec::<R>)));
+    cfg.service(resource("/tvmaze").route(get().to(self::get_tvmaze_data::exec::<R>)));
 }
@@ -0,0 +1,32 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::feed::{FeedFilter, UserCreatedFeedFilter},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create feed filter",
+    tag = "Feed",
+    path = "/api/feeds/filters",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 201, description = "Successfully created the feed filter", body=FeedFilter),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    feed_filter: Json<UserCreatedFeedFilter>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let feed_filter = arc.pool.create_feed_filter(&feed_filter, user.sub).await?;
+
+    Ok(HttpResponse::Created().json(feed_filter))
+}
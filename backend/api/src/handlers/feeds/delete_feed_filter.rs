@@ -0,0 +1,38 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::redis::RedisPoolInterface;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct DeleteFeedFilterQuery {
+    pub id: i64,
+}
+
+#[utoipa::path(
+    delete,
+    operation_id = "Delete feed filter",
+    tag = "Feed",
+    path = "/api/feeds/filters",
+    params (DeleteFeedFilterQuery),
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully deleted the feed filter"),
+        (status = 404, description = "Feed filter not found"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<DeleteFeedFilterQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    arc.pool.delete_feed_filter(query.id, user.sub).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({"result": "success"})))
+}
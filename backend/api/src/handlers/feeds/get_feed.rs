@@ -0,0 +1,98 @@
+use crate::{
+    middlewares::auth_middleware::Authdata,
+    services::feed_service::{render_atom, render_rss, FeedChannel},
+    Arcadia,
+};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::user::UserPermission, redis::RedisPoolInterface};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+const FEED_ITEM_AMOUNT: i64 = 50;
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Rss,
+    Atom,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetFeedQuery {
+    pub filter_id: i64,
+    #[serde(default)]
+    #[param(inline)]
+    pub format: FeedFormat,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get feed",
+    tag = "Feed",
+    path = "/api/feeds",
+    params (
+        GetFeedQuery,
+        ("passkey" = Option<String>, Query, description = "Authenticates feed readers that can't send headers"),
+    ),
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "RSS 2.0 or Atom feed of the latest torrents matching the filter"),
+        (status = 404, description = "Feed filter not found"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetFeedQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    // every item is a download link
+    arc.pool
+        .require_permission(user.sub, &UserPermission::DownloadTorrent, req.path())
+        .await?;
+
+    let feed_filter = arc.pool.find_feed_filter(query.filter_id, user.sub).await?;
+    let items = arc
+        .pool
+        .find_feed_items(
+            &feed_filter.search,
+            feed_filter.tag_expression.as_deref(),
+            FEED_ITEM_AMOUNT,
+        )
+        .await?;
+    let passkey = arc.pool.find_user_with_id(user.sub).await?.passkey;
+
+    let (format_name, content_type) = match query.format {
+        FeedFormat::Rss => ("rss", "application/rss+xml; charset=utf-8"),
+        FeedFormat::Atom => ("atom", "application/atom+xml; charset=utf-8"),
+    };
+    // the api is served under the frontend's origin
+    let origin = arc.frontend_url.as_str().trim_end_matches('/');
+    let title = format!("{} - {}", arc.tracker.name, feed_filter.name);
+    let self_url = format!(
+        "{origin}/api/feeds?filter_id={}&format={format_name}",
+        feed_filter.id
+    );
+    let download_url = format!("{origin}/api/torrents");
+
+    let channel = FeedChannel {
+        title: &title,
+        self_url: &self_url,
+        frontend_url: arc.frontend_url.as_ref(),
+        download_url: &download_url,
+        passkey: &passkey,
+    };
+    let body = match query.format {
+        FeedFormat::Rss => render_rss(&channel, &items),
+        FeedFormat::Atom => render_atom(&channel, &items),
+    };
+
+    Ok(HttpResponse::Ok().content_type(content_type).body(body))
+}
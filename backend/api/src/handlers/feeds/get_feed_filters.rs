@@ -0,0 +1,25 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{web::Data, HttpResponse};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::feed::FeedFilter, redis::RedisPoolInterface};
+
+#[utoipa::path(
+    get,
+    operation_id = "Get feed filters",
+    tag = "Feed",
+    path = "/api/feeds/filters",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "The feed filters of the current user", body=Vec<FeedFilter>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let feed_filters = arc.pool.find_user_feed_filters(user.sub).await?;
+
+    Ok(HttpResponse::Ok().json(feed_filters))
+}
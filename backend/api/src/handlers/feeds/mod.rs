@@ -0,0 +1,17 @@
+pub mod create_feed_filter;
+pub mod delete_feed_filter;
+pub mod get_feed;
+pub mod get_feed_filters;
+
+use actix_web::web::{delete, get, post, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(resource("").route(get().to(self::get_feed::exec::<R>)));
+    cfg.service(
+        resource("/filters")
+            .route(post().to(self::create_feed_filter::exec::<R>))
+            .route(get().to(self::get_feed_filters::exec::<R>))
+            .route(delete().to(self::delete_feed_filter::exec::<R>)),
+    );
+}
@@ -0,0 +1,41 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        ip_ban::{IpBan, UserCreatedIpBan},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create IP ban",
+    tag = "IP Bans",
+    path = "/api/ip-bans",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    request_body = UserCreatedIpBan,
+    responses(
+        (status = 201, description = "Successfully banned the address or range", body=IpBan),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    payload: Json<UserCreatedIpBan>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageIpBans, req.path())
+        .await?;
+
+    let ip_ban = arc.pool.create_ip_ban(&payload, user.sub).await?;
+
+    Ok(HttpResponse::Created().json(ip_ban))
+}
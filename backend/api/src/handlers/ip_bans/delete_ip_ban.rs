@@ -0,0 +1,36 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Path},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::user::UserPermission, redis::RedisPoolInterface};
+
+#[utoipa::path(
+    delete,
+    operation_id = "Delete IP ban",
+    tag = "IP Bans",
+    path = "/api/ip-bans/{id}",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(("id" = i64, Path, description = "IP ban id")),
+    responses(
+        (status = 200, description = "Successfully lifted the ban"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    path: Path<i64>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageIpBans, req.path())
+        .await?;
+
+    let id = path.into_inner();
+    arc.pool.delete_ip_ban(id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,15 @@
+pub mod create_ip_ban;
+pub mod delete_ip_ban;
+pub mod search_ip_bans;
+
+use actix_web::web::{delete, get, post, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(
+        resource("")
+            .route(get().to(self::search_ip_bans::exec::<R>))
+            .route(post().to(self::create_ip_ban::exec::<R>)),
+    );
+    cfg.service(resource("/{id}").route(delete().to(self::delete_ip_ban::exec::<R>)));
+}
@@ -0,0 +1,42 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        common::PaginatedResults,
+        ip_ban::{IpBan, SearchIpBansQuery},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search IP bans",
+    tag = "IP Bans",
+    path = "/api/ip-bans",
+    params(SearchIpBansQuery),
+    security(
+        ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Paginated list of ip bans, newest first", body=PaginatedResults<IpBan>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<SearchIpBansQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageIpBans, req.path())
+        .await?;
+
+    let ip_bans = arc.pool.find_ip_bans(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(ip_bans))
+}
@@ -1,21 +1,28 @@
 pub mod affiliated_artists;
+pub mod affiliated_entities;
 pub mod arcadia_settings;
 pub mod artists;
 pub mod auth;
+pub mod auth_attempts;
+pub mod cheat_flags;
 pub mod collages;
 pub mod conversations;
 pub mod css_sheets;
 pub mod donations;
 pub mod edition_groups;
+pub mod entities;
 pub mod external_db;
+pub mod feeds;
 pub mod forum;
 pub mod gifts;
 pub mod health;
 pub mod home;
 pub mod image_host;
 pub mod invitations;
+pub mod ip_bans;
 pub mod master_groups;
 pub mod notifications;
+pub mod promotion_events;
 pub mod related_forum_threads;
 pub mod search;
 pub mod series;
@@ -35,6 +42,7 @@ pub mod user_badges;
 pub mod user_classes;
 pub mod user_edit_change_logs;
 pub mod users;
+pub mod webhooks;
 pub mod wiki;
 
 pub mod peers_handler;
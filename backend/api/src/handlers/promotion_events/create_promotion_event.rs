@@ -0,0 +1,41 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        promotion_event::{PromotionEvent, UserCreatedPromotionEvent},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create promotion event",
+    tag = "Promotion Events",
+    path = "/api/promotion-events",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    request_body = UserCreatedPromotionEvent,
+    responses(
+        (status = 201, description = "Successfully scheduled the promotion event", body=PromotionEvent),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    payload: Json<UserCreatedPromotionEvent>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManagePromotionEvents, req.path())
+        .await?;
+
+    let promotion_event = arc.pool.create_promotion_event(&payload, user.sub).await?;
+
+    Ok(HttpResponse::Created().json(promotion_event))
+}
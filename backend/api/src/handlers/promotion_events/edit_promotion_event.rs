@@ -0,0 +1,46 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        promotion_event::{PromotionEvent, UserCreatedPromotionEvent},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    put,
+    operation_id = "Edit promotion event",
+    tag = "Promotion Events",
+    path = "/api/promotion-events/{id}",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(("id" = i64, Path, description = "Promotion event id")),
+    request_body = UserCreatedPromotionEvent,
+    responses(
+        (status = 200, description = "Successfully edited the promotion event", body=PromotionEvent),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    path: Path<i64>,
+    payload: Json<UserCreatedPromotionEvent>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManagePromotionEvents, req.path())
+        .await?;
+
+    let promotion_event = arc
+        .pool
+        .edit_promotion_event(path.into_inner(), &payload)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(promotion_event))
+}
@@ -0,0 +1,20 @@
+pub mod create_promotion_event;
+pub mod delete_promotion_event;
+pub mod edit_promotion_event;
+pub mod search_promotion_events;
+
+use actix_web::web::{delete, get, post, put, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(
+        resource("")
+            .route(get().to(self::search_promotion_events::exec::<R>))
+            .route(post().to(self::create_promotion_event::exec::<R>)),
+    );
+    cfg.service(
+        resource("/{id}")
+            .route(put().to(self::edit_promotion_event::exec::<R>))
+            .route(delete().to(self::delete_promotion_event::exec::<R>)),
+    );
+}
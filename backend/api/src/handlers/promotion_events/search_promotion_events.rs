@@ -0,0 +1,42 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        common::PaginatedResults,
+        promotion_event::{PromotionEvent, SearchPromotionEventsQuery},
+        user::UserPermission,
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search promotion events",
+    tag = "Promotion Events",
+    path = "/api/promotion-events",
+    params(SearchPromotionEventsQuery),
+    security(
+        ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Paginated list of promotion events, latest start first", body=PaginatedResults<PromotionEvent>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<SearchPromotionEventsQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManagePromotionEvents, req.path())
+        .await?;
+
+    let promotion_events = arc.pool.find_promotion_events(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(promotion_events))
+}
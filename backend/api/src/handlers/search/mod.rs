@@ -3,6 +3,8 @@ pub mod search_artists_lite;
 pub mod search_collages;
 pub mod search_collages_lite;
 pub mod search_conversations;
+pub mod search_entities;
+pub mod search_entities_lite;
 pub mod search_forum;
 pub mod search_series;
 pub mod search_series_lite;
@@ -11,6 +13,7 @@ pub mod search_title_group_info_lite;
 pub mod search_title_group_tags;
 pub mod search_title_group_tags_lite;
 pub mod search_torrent_requests;
+pub mod search_torrent_suggestions;
 pub mod search_torrents;
 pub mod search_users;
 pub mod search_users_lite;
@@ -33,8 +36,14 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
             .route(get().to(self::search_title_group_tags_lite::exec::<R>)),
     );
     cfg.service(resource("/torrents/lite").route(get().to(self::search_torrents::exec::<R>)));
+    cfg.service(
+        resource("/torrents/suggestions")
+            .route(get().to(self::search_torrent_suggestions::exec::<R>)),
+    );
     cfg.service(resource("/artists").route(get().to(self::search_artists::exec::<R>)));
     cfg.service(resource("/artists/lite").route(get().to(self::search_artists_lite::exec::<R>)));
+    cfg.service(resource("/entities").route(get().to(self::search_entities::exec::<R>)));
+    cfg.service(resource("/entities/lite").route(get().to(self::search_entities_lite::exec::<R>)));
     cfg.service(
         resource("/torrent-requests").route(get().to(self::search_torrent_requests::exec::<R>)),
     );
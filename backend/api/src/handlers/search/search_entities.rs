@@ -0,0 +1,32 @@
+use crate::Arcadia;
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        common::PaginatedResults,
+        entity::{EntitySearchResult, SearchEntitiesQuery},
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search entities",
+    tag = "Search",
+    path = "/api/search/entities",
+    params(SearchEntitiesQuery),
+    description = "Case insensitive",
+    responses(
+        (status = 200, description = "Successfully got the entities", body = PaginatedResults<EntitySearchResult>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<SearchEntitiesQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let entities = arc.pool.search_entities(&query).await?;
+    Ok(HttpResponse::Ok().json(entities))
+}
@@ -0,0 +1,34 @@
+use crate::Arcadia;
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::entity::EntityLite, redis::RedisPoolInterface};
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct GetEntityLiteQuery {
+    name: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Search entities lite",
+    tag = "Search",
+    path = "/api/search/entities/lite",
+    params (GetEntityLiteQuery),
+    description = "Case insensitive",
+    responses(
+        (status = 200, description = "Successfully got the entities and some data about them (limits to 10 results)", body=Vec<EntityLite>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<GetEntityLiteQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let entities = arc.pool.find_entities_lite(&query.name, 10).await?;
+
+    Ok(HttpResponse::Ok().json(entities))
+}
@@ -0,0 +1,39 @@
+use crate::Arcadia;
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse,
+};
+use arcadia_common::{error::Result, services::torrent_service::looks_like_url};
+use arcadia_storage::redis::RedisPoolInterface;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SearchTorrentSuggestionsQuery {
+    name: String,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Search torrent suggestions",
+    tag = "Search",
+    path = "/api/search/torrents/suggestions",
+    params (SearchTorrentSuggestionsQuery),
+    description = "Title group, artist and series names close to the searched one, meant to be shown when a torrent search found nothing",
+    responses(
+        (status = 200, description = "Successfully got the suggestions (limits to 5 results)", body=Vec<String>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<SearchTorrentSuggestionsQuery>,
+    arc: Data<Arcadia<R>>,
+) -> Result<HttpResponse> {
+    let name = query.name.trim();
+    if name.is_empty() || looks_like_url(name) {
+        return Ok(HttpResponse::Ok().json(Vec::<String>::new()));
+    }
+
+    let suggestions = arc.pool.find_search_suggestions(name, 5).await?;
+
+    Ok(HttpResponse::Ok().json(suggestions))
+}
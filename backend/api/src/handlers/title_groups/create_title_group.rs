@@ -3,7 +3,10 @@ use actix_web::{
     HttpResponse,
 };
 use arcadia_storage::{
-    models::title_group::{PublicRating, TitleGroup, UserCreatedTitleGroup},
+    models::{
+        entity::UserCreatedAffiliatedEntity,
+        title_group::{PublicRating, TitleGroup, UserCreatedTitleGroup},
+    },
     redis::RedisPoolInterface,
 };
 use futures::future::join_all;
@@ -34,6 +37,9 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
     let approved_image_hosts = arc.settings.lock().unwrap().approved_image_hosts.clone();
     validate_image_urls(&form.covers, &approved_image_hosts)?;
     validate_image_urls(&form.screenshots, &approved_image_hosts)?;
+    for new_entity in &form.new_affiliated_entities {
+        validate_image_urls(&new_entity.entity.pictures, &approved_image_hosts)?;
+    }
 
     let rating_futures: Vec<_> = form
         .external_links
@@ -68,5 +74,40 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
             .await?;
     }
 
+    // an entity with the same name may have been created since it was proposed,
+    // it is then the one returned
+    if !form.new_affiliated_entities.is_empty() {
+        let new_affiliated_entities = std::mem::take(&mut form.new_affiliated_entities);
+        let (new_entities, roles): (Vec<_>, Vec<_>) = new_affiliated_entities
+            .into_iter()
+            .map(|new_entity| (new_entity.entity, new_entity.roles))
+            .unzip();
+        let created_entities = arc.pool.create_entities(&new_entities, user.sub).await?;
+        for (entity, roles) in created_entities.into_iter().zip(roles) {
+            if !form
+                .affiliated_entities
+                .iter()
+                .any(|affiliated| affiliated.entity_id == entity.id)
+            {
+                form.affiliated_entities.push(UserCreatedAffiliatedEntity {
+                    title_group_id: 0,
+                    entity_id: entity.id,
+                    roles,
+                });
+            }
+        }
+    }
+
+    if !form.affiliated_entities.is_empty() {
+        for entity in &mut form.affiliated_entities {
+            entity.title_group_id = created_title_group.id
+        }
+
+        let _ = arc
+            .pool
+            .create_entities_affiliation(&form.affiliated_entities, user.sub)
+            .await?;
+    }
+
     Ok(HttpResponse::Created().json(created_title_group))
 }
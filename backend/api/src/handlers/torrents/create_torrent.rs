@@ -1,6 +1,5 @@
 use actix_multipart::form::MultipartForm;
 use actix_web::{web::Data, HttpRequest, HttpResponse};
-use arcadia_shared::tracker::models::torrent::APIInsertTorrent;
 
 use crate::{middlewares::auth_middleware::Authdata, Arcadia};
 use arcadia_common::error::Result;
@@ -86,37 +85,5 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         )
         .await?;
 
-    let mut url = arc.env.tracker.url_internal.clone();
-    url.path_segments_mut()
-        .unwrap()
-        .push("api")
-        .push("torrents");
-
-    let payload = APIInsertTorrent {
-        id: torrent.id as u32,
-        info_hash: torrent.info_hash,
-        is_deleted: false,
-        seeders: 0,
-        leechers: 0,
-        times_completed: 0,
-        download_factor: torrent.upload_factor as u8,
-        upload_factor: torrent.download_factor as u8,
-    };
-
-    let res = arc
-        .internal_http_client
-        .put(url)
-        .header("x-api-key", arc.env.tracker.api_key.clone())
-        .json(&payload)
-        .send()
-        .await;
-
-    if res.is_err() {
-        log::warn!(
-            "Tried to insert new torrent into tracker's db and got: {:?}",
-            res
-        );
-    }
-
     Ok(HttpResponse::Created().json(torrent))
 }
@@ -54,27 +54,5 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         .remove_torrent(&form, user.sub, &arc.notification_sender)
         .await?;
 
-    let torrent_id = form.id;
-    let mut url = arc.env.tracker.url_internal.clone();
-    url.path_segments_mut()
-        .unwrap()
-        .push("api")
-        .push("torrents")
-        .push(&torrent_id.to_string());
-
-    let res = arc
-        .internal_http_client
-        .delete(url)
-        .header("x-api-key", arc.env.tracker.api_key.clone())
-        .send()
-        .await;
-
-    if res.is_err() {
-        log::warn!(
-            "Tried to mark torrent as deleted in tracker and got: {:?}",
-            res
-        );
-    }
-
     Ok(HttpResponse::Ok().finish())
 }
@@ -22,7 +22,10 @@ pub struct DownloadTorrentQuery {
     operation_id = "Download torrent file",
     tag = "Torrent",
     path = "/api/torrents",
-    params (DownloadTorrentQuery),
+    params (
+        DownloadTorrentQuery,
+        ("passkey" = Option<String>, Query, description = "Authenticates torrent clients following feed links"),
+    ),
     security(
       ("http" = ["Bearer"])
     ),
@@ -2,7 +2,6 @@ use actix_web::{
     web::{Data, Json},
     HttpRequest, HttpResponse,
 };
-use arcadia_shared::tracker::models::torrent::APIUpdateTorrentFactors;
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -49,34 +48,5 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         .update_torrent_up_down_factors(form.torrent_id, form.upload_factor, form.download_factor)
         .await?;
 
-    // Notify tracker to update its in-memory state
-    let mut url = arc.env.tracker.url_internal.clone();
-    url.path_segments_mut()
-        .unwrap()
-        .push("api")
-        .push("torrents")
-        .push(&form.torrent_id.to_string())
-        .push("up-down-factors");
-
-    let payload = APIUpdateTorrentFactors {
-        upload_factor: form.upload_factor,
-        download_factor: form.download_factor,
-    };
-
-    let res = arc
-        .internal_http_client
-        .put(url)
-        .header("x-api-key", arc.env.tracker.api_key.clone())
-        .json(&payload)
-        .send()
-        .await;
-
-    if res.is_err() {
-        log::warn!(
-            "Tried to update torrent factors in tracker and got: {:?}",
-            res
-        );
-    }
-
     Ok(HttpResponse::Ok().json(json!({"result": "success"})))
 }
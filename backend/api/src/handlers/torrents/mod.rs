@@ -11,6 +11,7 @@ pub mod get_torrent_title_group;
 pub mod get_upload_information;
 pub mod move_torrent_to_edition_group;
 pub mod set_torrent_staff_checked;
+pub mod use_freeleech_token;
 
 use actix_web::web::{delete, get, post, put, resource, ServiceConfig};
 use arcadia_storage::redis::RedisPoolInterface;
@@ -40,6 +41,9 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
     cfg.service(
         resource("/up-down-factors").route(put().to(self::edit_torrent_up_down_factors::exec::<R>)),
     );
+    cfg.service(
+        resource("/freeleech-token").route(post().to(self::use_freeleech_token::exec::<R>)),
+    );
     cfg.service(
         resource("/move-to-edition-group")
             .route(put().to(self::move_torrent_to_edition_group::exec::<R>)),
@@ -0,0 +1,45 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::personal_freeleech::{PersonalFreeleech, UserCreatedPersonalFreeleech},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Use freeleech token",
+    tag = "Torrent",
+    path = "/api/torrents/freeleech-token",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 201, description = "Spent a freeleech token on the torrent", body=PersonalFreeleech),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    form: Json<UserCreatedPersonalFreeleech>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    // make sure the torrent exists and isn't deleted
+    arc.pool.find_torrent(form.torrent_id).await?;
+
+    let current_user = arc.pool.find_user_with_id(user.sub).await?;
+    if current_user.freeleech_tokens < 1 {
+        return Err(Error::NotEnoughFreeleechTokensAvailable);
+    }
+
+    let duration_hours = arc.settings.lock().unwrap().freeleech_token_duration_hours;
+
+    let personal_freeleech = arc
+        .pool
+        .create_personal_freeleech(user.sub, form.torrent_id, duration_hours)
+        .await?;
+
+    Ok(HttpResponse::Created().json(personal_freeleech))
+}
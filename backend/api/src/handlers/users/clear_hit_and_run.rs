@@ -0,0 +1,44 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::{
+    models::hit_and_run::{HitAndRun, UserClearedHitAndRun},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Clear hit and run",
+    tag = "User",
+    path = "/api/users/hit-and-runs/clear",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Cleared the hit and run with bonus points", body = HitAndRun),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    form: Json<UserClearedHitAndRun>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let (cost, warning_threshold) = {
+        let settings = arc.settings.lock().unwrap();
+        (
+            settings.hit_and_run_clear_bonus_points_cost,
+            settings.hit_and_run_warning_threshold,
+        )
+    };
+    let cost = cost.ok_or(Error::HitAndRunClearingDisabled)?;
+
+    let hit_and_run = arc
+        .pool
+        .clear_hit_and_run_with_bonus_points(form.hit_and_run_id, user.sub, cost, warning_threshold)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(hit_and_run))
+}
@@ -58,6 +58,7 @@ pub async fn exec<R: RedisPoolInterface + 'static>(
         order_by_column: TorrentSearchOrderByColumn::TorrentCreatedAt,
         order_by_direction: OrderByDirection::Desc,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         series_id: None,
         user_id_bookmarks: None,
@@ -0,0 +1,50 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{hit_and_run::HitAndRun, user::UserPermission},
+    redis::RedisPoolInterface,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetUserHitAndRunsQuery {
+    /// Defaults to the current user. Looking at someone else's hit and runs requires the `warn_user` permission
+    user_id: Option<i32>,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get user hit and runs",
+    tag = "User",
+    path = "/api/users/hit-and-runs",
+    params(GetUserHitAndRunsQuery),
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Successfully got the user's hit and runs", body = Vec<HitAndRun>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    query: Query<GetUserHitAndRunsQuery>,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    let user_id = query.user_id.unwrap_or(user.sub);
+
+    if user_id != user.sub {
+        arc.pool
+            .require_permission(user.sub, &UserPermission::WarnUser, req.path())
+            .await?;
+    }
+
+    let hit_and_runs = arc.pool.find_user_hit_and_runs(user_id).await?;
+
+    Ok(HttpResponse::Ok().json(hit_and_runs))
+}
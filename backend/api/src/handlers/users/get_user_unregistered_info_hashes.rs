@@ -0,0 +1,30 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{web::Data, HttpResponse};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::unregistered_info_hash::UnregisteredInfoHash, redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Get user unregistered info hashes",
+    tag = "User",
+    path = "/api/users/unregistered-info-hashes",
+    security(
+      ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "The torrents the user still announces although they were deleted or never uploaded", body = Vec<UnregisteredInfoHash>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+) -> Result<HttpResponse> {
+    let unregistered_info_hashes = arc
+        .pool
+        .find_user_unregistered_info_hashes(user.sub)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(unregistered_info_hashes))
+}
@@ -1,15 +1,18 @@
 pub mod change_user_class;
 pub mod change_user_password;
+pub mod clear_hit_and_run;
 pub mod create_api_key;
 pub mod create_irc_account;
 pub mod edit_user;
 pub mod edit_user_permissions;
 pub mod get_me;
 pub mod get_user;
+pub mod get_user_hit_and_runs;
 pub mod get_user_permissions;
 pub mod get_user_settings;
 pub mod get_user_torrent_activities;
 pub mod get_user_torrent_activities_overview;
+pub mod get_user_unregistered_info_hashes;
 pub mod lock_user_class;
 pub mod remove_user_warnings;
 pub mod reset_irc_password;
@@ -37,6 +40,14 @@ pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
         resource("/torrent-activities")
             .route(get().to(self::get_user_torrent_activities::exec::<R>)),
     );
+    cfg.service(resource("/hit-and-runs").route(get().to(self::get_user_hit_and_runs::exec::<R>)));
+    cfg.service(
+        resource("/unregistered-info-hashes")
+            .route(get().to(self::get_user_unregistered_info_hashes::exec::<R>)),
+    );
+    cfg.service(
+        resource("/hit-and-runs/clear").route(post().to(self::clear_hit_and_run::exec::<R>)),
+    );
     cfg.service(
         resource("/bonus-points-logs").route(get().to(self::search_bonus_points_logs::exec::<R>)),
     );
@@ -0,0 +1,41 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        user::UserPermission,
+        webhook::{UserCreatedWebhook, Webhook},
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    post,
+    operation_id = "Create webhook",
+    tag = "Webhooks",
+    path = "/api/webhooks",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    request_body = UserCreatedWebhook,
+    responses(
+        (status = 201, description = "Successfully created the webhook", body=Webhook),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    payload: Json<UserCreatedWebhook>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageWebhooks, req.path())
+        .await?;
+
+    let webhook = arc.pool.create_webhook(&payload, user.sub).await?;
+
+    Ok(HttpResponse::Created().json(webhook))
+}
@@ -0,0 +1,35 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Path},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{models::user::UserPermission, redis::RedisPoolInterface};
+
+#[utoipa::path(
+    delete,
+    operation_id = "Delete webhook",
+    tag = "Webhooks",
+    path = "/api/webhooks/{id}",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(("id" = i32, Path, description = "Webhook id")),
+    responses(
+        (status = 200, description = "Successfully deleted the webhook and its delivery log"),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    path: Path<i32>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageWebhooks, req.path())
+        .await?;
+
+    arc.pool.delete_webhook(path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
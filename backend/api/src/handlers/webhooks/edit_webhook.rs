@@ -0,0 +1,43 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        user::UserPermission,
+        webhook::{EditedWebhook, Webhook},
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    put,
+    operation_id = "Edit webhook",
+    tag = "Webhooks",
+    path = "/api/webhooks/{id}",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    params(("id" = i32, Path, description = "Webhook id")),
+    request_body = EditedWebhook,
+    responses(
+        (status = 200, description = "Successfully edited the webhook", body=Webhook),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    path: Path<i32>,
+    payload: Json<EditedWebhook>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageWebhooks, req.path())
+        .await?;
+
+    let webhook = arc.pool.edit_webhook(path.into_inner(), &payload).await?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
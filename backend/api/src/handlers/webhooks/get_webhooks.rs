@@ -0,0 +1,33 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{web::Data, HttpRequest, HttpResponse};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{user::UserPermission, webhook::Webhook},
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Get webhooks",
+    tag = "Webhooks",
+    path = "/api/webhooks",
+    security(
+        ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "All the webhooks, their secrets are not returned", body=Vec<Webhook>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageWebhooks, req.path())
+        .await?;
+
+    let webhooks = arc.pool.find_webhooks().await?;
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
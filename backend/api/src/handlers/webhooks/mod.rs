@@ -0,0 +1,24 @@
+pub mod create_webhook;
+pub mod delete_webhook;
+pub mod edit_webhook;
+pub mod get_webhooks;
+pub mod search_webhook_deliveries;
+
+use actix_web::web::{delete, get, post, put, resource, ServiceConfig};
+use arcadia_storage::redis::RedisPoolInterface;
+
+pub fn config<R: RedisPoolInterface + 'static>(cfg: &mut ServiceConfig) {
+    cfg.service(
+        resource("")
+            .route(get().to(self::get_webhooks::exec::<R>))
+            .route(post().to(self::create_webhook::exec::<R>)),
+    );
+    cfg.service(
+        resource("/deliveries").route(get().to(self::search_webhook_deliveries::exec::<R>)),
+    );
+    cfg.service(
+        resource("/{id}")
+            .route(put().to(self::edit_webhook::exec::<R>))
+            .route(delete().to(self::delete_webhook::exec::<R>)),
+    );
+}
@@ -0,0 +1,42 @@
+use crate::{middlewares::auth_middleware::Authdata, Arcadia};
+use actix_web::{
+    web::{Data, Query},
+    HttpRequest, HttpResponse,
+};
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    models::{
+        common::PaginatedResults,
+        user::UserPermission,
+        webhook::{SearchWebhookDeliveriesQuery, WebhookDelivery},
+    },
+    redis::RedisPoolInterface,
+};
+
+#[utoipa::path(
+    get,
+    operation_id = "Search webhook deliveries",
+    tag = "Webhooks",
+    path = "/api/webhooks/deliveries",
+    params(SearchWebhookDeliveriesQuery),
+    security(
+        ("http" = ["Bearer"])
+    ),
+    responses(
+        (status = 200, description = "Paginated delivery log, newest first", body=PaginatedResults<WebhookDelivery>),
+    )
+)]
+pub async fn exec<R: RedisPoolInterface + 'static>(
+    query: Query<SearchWebhookDeliveriesQuery>,
+    arc: Data<Arcadia<R>>,
+    user: Authdata,
+    req: HttpRequest,
+) -> Result<HttpResponse> {
+    arc.pool
+        .require_permission(user.sub, &UserPermission::ManageWebhooks, req.path())
+        .await?;
+
+    let deliveries = arc.pool.find_webhook_deliveries(query.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
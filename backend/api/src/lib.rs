@@ -9,7 +9,10 @@ use std::{
 };
 use tokio::sync::broadcast;
 
-use crate::{env::Env, services::auth::Auth};
+use crate::{
+    env::Env,
+    services::{auth::Auth, rate_limiter::RateLimiter},
+};
 
 pub mod api_doc;
 pub mod env;
@@ -22,6 +25,7 @@ pub struct Arcadia<R: RedisPoolInterface> {
     pub pool: Arc<ConnectionPool>,
     pub redis_pool: Arc<R>,
     pub auth: Auth<R>,
+    pub rate_limiter: RateLimiter<R>,
     pub settings: Arc<Mutex<ArcadiaSettings>>,
     pub notification_sender: broadcast::Sender<NotificationEvent>,
     /// HTTP client for external requests (scrapers, external APIs), optionally proxied.
@@ -76,6 +80,7 @@ impl<R: RedisPoolInterface> Arcadia<R> {
             pool,
             redis_pool: Arc::clone(&redis_pool),
             auth: Auth::new(Arc::clone(&redis_pool)),
+            rate_limiter: RateLimiter::new(Arc::clone(&redis_pool), env.auth_rate_limit.clone()),
             settings: Arc::new(Mutex::new(settings)),
             notification_sender,
             http_client,
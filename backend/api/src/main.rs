@@ -2,6 +2,7 @@ use actix_cors::Cors;
 use actix_web::{web::Data, App, HttpServer};
 use arcadia_api::routes::init;
 use arcadia_api::{api_doc::ApiDoc, env::Env, Arcadia};
+use arcadia_common::services::tracker_client::TrackerClient;
 use arcadia_periodic_tasks::periodic_tasks::scheduler::run_periodic_tasks;
 use arcadia_storage::connection_pool::ConnectionPool;
 use arcadia_storage::redis::RedisPool;
@@ -34,6 +35,14 @@ async fn main() -> std::io::Result<()> {
         println!("TMDB_API_KEY env var is not set. TMDB data fetching won't be available")
     }
 
+    if env.igdb_client_id.is_none() || env.igdb_client_secret.is_none() {
+        println!("IGDB_CLIENT_ID or IGDB_CLIENT_SECRET env var is not set. IGDB data fetching won't be available")
+    }
+
+    if env.discogs_token.is_none() {
+        println!("DISCOGS_TOKEN env var is not set. Discogs data will be fetched without images")
+    }
+
     if env.smtp.host.is_some()
         && env.smtp.port.is_some()
         && env.smtp.username.is_some()
@@ -53,18 +62,18 @@ async fn main() -> std::io::Result<()> {
         println!("Ergo IRC integration not configured - IRC account provisioning will be skipped");
     }
 
-    let tracker_config = arcadia_storage::connection_pool::TrackerConfig {
-        url_internal: env.tracker.url_internal.clone(),
-        api_key: env.tracker.api_key.clone(),
-    };
-
     // Initialize and start periodic tasks before starting the web server
     // This ensures that if periodic tasks fail to initialize (e.g., missing env var),
     // the entire application fails to start
     let internal_http_client = arcadia_api::build_no_proxy_http_client();
+    let tracker_client = TrackerClient::new(
+        env.tracker.url_internal.clone(),
+        env.tracker.api_key.clone(),
+        internal_http_client.clone(),
+    );
     let store = Arc::new(
         arcadia_periodic_tasks::store::Store::new(
-            tracker_config.clone(),
+            tracker_client.clone(),
             internal_http_client.clone(),
         )
         .await,
@@ -78,7 +87,7 @@ async fn main() -> std::io::Result<()> {
     }
 
     let pool = Arc::new(
-        ConnectionPool::try_new(&env.database_url, tracker_config, internal_http_client)
+        ConnectionPool::try_new(&env.database_url, tracker_client, internal_http_client)
             .await
             .expect("db connection"),
     );
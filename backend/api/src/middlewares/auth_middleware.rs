@@ -2,13 +2,24 @@ use crate::Arcadia;
 use actix_web::{
     dev::{Payload, ServiceRequest},
     error::ErrorUnauthorized,
-    web::Data,
+    http::Method,
+    web::{Data, Query},
     Error, FromRequest, HttpMessage as _, HttpRequest,
 };
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use arcadia_storage::{models::user::Claims, redis::RedisPoolInterface};
 use futures_util::future::{err, ok, Ready};
 use jsonwebtoken::{decode, errors::ErrorKind, DecodingKey, Validation};
+use serde::Deserialize;
+
+// Feed readers and torrent clients can't log in, so these GET routes also accept
+// the user's passkey as a query parameter
+const PASSKEY_AUTHENTICATED_PATHS: [&str; 2] = ["/api/feeds", "/api/torrents"];
+
+#[derive(Deserialize)]
+struct PasskeyQuery {
+    passkey: String,
+}
 
 #[derive(Debug, Clone)]
 pub struct Authdata {
@@ -36,8 +47,11 @@ pub async fn authenticate_user<R: RedisPoolInterface + 'static>(
     if matches!(
         req.path(),
         "/api/auth/login"
+            | "/api/auth/2fa/login"
             | "/api/auth/register"
             | "/api/auth/refresh-token"
+            | "/api/auth/forgot-password"
+            | "/api/auth/reset-password"
             | "/api/auth/apply"
             | "/api/auth/irc"
             // SSE streams cannot send Bearer headers, auth is via query parameter
@@ -60,6 +74,8 @@ pub async fn authenticate_user<R: RedisPoolInterface + 'static>(
         } else {
             validate_user_api_key::<R>(req, &api_key).await
         }
+    } else if let Some(passkey) = passkey_from_query(&req) {
+        validate_passkey::<R>(req, &passkey).await
     } else {
         Err((
             ErrorUnauthorized("authentication error, missing jwt token or API key"),
@@ -129,6 +145,32 @@ async fn validate_user_api_key<R: RedisPoolInterface + 'static>(
     Ok(req)
 }
 
+fn passkey_from_query(req: &ServiceRequest) -> Option<String> {
+    if req.method() != Method::GET || !PASSKEY_AUTHENTICATED_PATHS.contains(&req.path()) {
+        return None;
+    }
+
+    Query::<PasskeyQuery>::from_query(req.query_string())
+        .ok()
+        .map(|query| query.into_inner().passkey)
+}
+
+async fn validate_passkey<R: RedisPoolInterface + 'static>(
+    req: ServiceRequest,
+    passkey: &str,
+) -> std::result::Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
+    let arc = req.app_data::<Data<Arcadia<R>>>().expect("app data set");
+
+    let user_id = match arc.pool.find_user_id_with_passkey(passkey).await {
+        Ok(user_id) => user_id,
+        Err(e) => return Err((actix_web::error::ErrorUnauthorized(e.to_string()), req)),
+    };
+
+    req.extensions_mut().insert(Authdata { sub: user_id });
+
+    Ok(req)
+}
+
 fn validate_tracker_api_key<R: RedisPoolInterface + 'static>(
     req: ServiceRequest,
     api_key: &str,
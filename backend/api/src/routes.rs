@@ -3,9 +3,12 @@ use actix_web_httpauth::middleware::HttpAuthentication;
 use arcadia_storage::redis::RedisPoolInterface;
 
 use crate::handlers::affiliated_artists::config as AffiliatedArtistsConfig;
+use crate::handlers::affiliated_entities::config as AffiliatedEntitiesConfig;
 use crate::handlers::arcadia_settings::config as ArcadiaSettingsConfig;
 use crate::handlers::artists::config as ArtistsConfig;
 use crate::handlers::auth::config as AuthConfig;
+use crate::handlers::auth_attempts::config as AuthAttemptsConfig;
+use crate::handlers::cheat_flags::config as CheatFlagsConfig;
 use crate::handlers::collages::config as CollagesConfig;
 use crate::handlers::conversations::config as ConversationsConfig;
 use crate::handlers::css_sheets::{
@@ -13,15 +16,19 @@ use crate::handlers::css_sheets::{
 };
 use crate::handlers::donations::config as DonationsConfig;
 use crate::handlers::edition_groups::config as EditionGroupsConfig;
+use crate::handlers::entities::config as EntitiesConfig;
 use crate::handlers::external_db::config as ExternalDbConfig;
+use crate::handlers::feeds::config as FeedsConfig;
 use crate::handlers::forum::config as ForumConfig;
 use crate::handlers::gifts::config as GiftsConfig;
 use crate::handlers::health::health_check;
 use crate::handlers::home::config as HomeConfig;
 use crate::handlers::image_host::config as ImageHostConfig;
 use crate::handlers::invitations::config as InvitationsConfig;
+use crate::handlers::ip_bans::config as IpBansConfig;
 use crate::handlers::master_groups::config as MasterGroupsConfig;
 use crate::handlers::notifications::config as NotificationsConfig;
+use crate::handlers::promotion_events::config as PromotionEventsConfig;
 use crate::handlers::related_forum_threads::config as RelatedForumThreadsConfig;
 use crate::handlers::search::config as SearchConfig;
 use crate::handlers::series::config as SeriesConfig;
@@ -42,6 +49,7 @@ use crate::handlers::user_badges::config_categories as UserBadgeCategoriesConfig
 use crate::handlers::user_classes::config as UserClassesConfig;
 use crate::handlers::user_edit_change_logs::config as UserEditChangeLogsConfig;
 use crate::handlers::users::config as UsersConfig;
+use crate::handlers::webhooks::config as WebhooksConfig;
 use crate::handlers::wiki::config as WikiConfig;
 use crate::middlewares::auth_middleware::authenticate_user;
 use crate::middlewares::side_effects::side_effects_middleware;
@@ -65,11 +73,19 @@ pub fn init<R: RedisPoolInterface + 'static>(cfg: &mut web::ServiceConfig) {
             .service(scope("/edition-groups").configure(EditionGroupsConfig::<R>))
             .service(scope("/search").configure(SearchConfig::<R>))
             .service(scope("/torrents").configure(TorrentsConfig::<R>))
+            .service(scope("/feeds").configure(FeedsConfig::<R>))
             .service(scope("/torrent-requests").configure(TorrentRequestsConfig::<R>))
             .service(scope("/unauthorized-access").configure(UnauthorizedAccessConfig::<R>))
+            .service(scope("/auth-attempts").configure(AuthAttemptsConfig::<R>))
+            .service(scope("/cheat-flags").configure(CheatFlagsConfig::<R>))
+            .service(scope("/ip-bans").configure(IpBansConfig::<R>))
+            .service(scope("/webhooks").configure(WebhooksConfig::<R>))
+            .service(scope("/promotion-events").configure(PromotionEventsConfig::<R>))
             .service(scope("/user-edit-change-logs").configure(UserEditChangeLogsConfig::<R>))
             .service(scope("/artists").configure(ArtistsConfig::<R>))
             .service(scope("/affiliated-artists").configure(AffiliatedArtistsConfig::<R>))
+            .service(scope("/entities").configure(EntitiesConfig::<R>))
+            .service(scope("/affiliated-entities").configure(AffiliatedEntitiesConfig::<R>))
             .service(scope("/conversations").configure(ConversationsConfig::<R>))
             .service(scope("/subscriptions").configure(SubscriptionsConfig::<R>))
             .service(scope("/notifications").configure(NotificationsConfig::<R>))
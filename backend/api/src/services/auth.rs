@@ -1,6 +1,7 @@
 use arcadia_common::error::Result;
 use arcadia_storage::redis::{RedisInterface, RedisPool, RedisPoolInterface};
 use chrono::{Duration, Utc};
+use rand::{distr::Alphanumeric, Rng};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_str, to_string};
 use std::sync::{Arc, LazyLock};
@@ -8,6 +9,9 @@ use std::sync::{Arc, LazyLock};
 pub static REFRESH_TOKEN_DURATION: LazyLock<Duration> = LazyLock::new(|| Duration::days(90));
 pub static AUTH_TOKEN_SHORT_DURATION: LazyLock<Duration> = LazyLock::new(|| Duration::hours(1));
 pub static AUTH_TOKEN_LONG_DURATION: LazyLock<Duration> = LazyLock::new(|| Duration::days(1));
+pub static TWO_FACTOR_CHALLENGE_DURATION: LazyLock<Duration> =
+    LazyLock::new(|| Duration::minutes(5));
+pub static PASSWORD_RESET_TOKEN_DURATION: LazyLock<Duration> = LazyLock::new(|| Duration::hours(1));
 
 #[derive(Serialize, Deserialize)]
 pub struct InvalidationEntry {
@@ -26,6 +30,13 @@ impl InvalidationEntry {
     }
 }
 
+/// Stored between the password step and the second factor step of a login
+#[derive(Serialize, Deserialize)]
+pub struct TwoFactorChallengeEntry {
+    pub user_id: i32,
+    pub remember_me: bool,
+}
+
 pub struct Auth<R: RedisPoolInterface = RedisPool> {
     redis_pool: Arc<R>,
 }
@@ -67,4 +78,83 @@ impl<R: RedisPoolInterface> Auth<R> {
 
         Ok(true)
     }
+
+    pub async fn create_two_factor_challenge(
+        &self,
+        user_id: i32,
+        remember_me: bool,
+    ) -> Result<String> {
+        let challenge_token = generate_token();
+
+        let entry = TwoFactorChallengeEntry {
+            user_id,
+            remember_me,
+        };
+        let mut redis = self.redis_pool.connection().await?;
+        redis
+            .set_ex(
+                two_factor_challenge_key(&challenge_token),
+                to_string(&entry)?,
+                (*TWO_FACTOR_CHALLENGE_DURATION).as_seconds_f64() as usize,
+            )
+            .await?;
+
+        Ok(challenge_token)
+    }
+
+    /// Challenges can only be used once, whether the code that comes with them is valid or not
+    pub async fn consume_two_factor_challenge(
+        &self,
+        challenge_token: &str,
+    ) -> Result<Option<TwoFactorChallengeEntry>> {
+        let key = two_factor_challenge_key(challenge_token);
+        let mut redis = self.redis_pool.connection().await?;
+        let Some(entry) = redis.get_del(&key).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(from_str(&entry)?))
+    }
+
+    pub async fn create_password_reset_token(&self, user_id: i32) -> Result<String> {
+        let token = generate_token();
+
+        let mut redis = self.redis_pool.connection().await?;
+        redis
+            .set_ex(
+                password_reset_key(&token),
+                user_id,
+                (*PASSWORD_RESET_TOKEN_DURATION).as_seconds_f64() as usize,
+            )
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Returns the id of the user the token was issued for, the token can't be used again
+    pub async fn consume_password_reset_token(&self, token: &str) -> Result<Option<i32>> {
+        let key = password_reset_key(token);
+        let mut redis = self.redis_pool.connection().await?;
+        let Some(user_id) = redis.get_del(&key).await? else {
+            return Ok(None);
+        };
+
+        Ok(user_id.parse().ok())
+    }
+}
+
+fn generate_token() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect()
+}
+
+fn two_factor_challenge_key(challenge_token: &str) -> String {
+    format!("two_factor_challenge:{challenge_token}")
+}
+
+fn password_reset_key(token: &str) -> String {
+    format!("password_reset:{token}")
 }
@@ -0,0 +1,24 @@
+use crate::env::ReverseProxyConfig;
+use actix_web::HttpRequest;
+use std::net::IpAddr;
+
+/// The address of the client, the connecting one unless it is a trusted reverse
+/// proxy, which passes it in the configured header. Only the last address of the
+/// header is used, the ones before it are sent by the client and can be anything.
+pub fn client_ip(req: &HttpRequest, reverse_proxy: &ReverseProxyConfig) -> Option<IpAddr> {
+    let peer_ip = req.peer_addr()?.ip();
+
+    let Some(header_name) = &reverse_proxy.client_ip_header_name else {
+        return Some(peer_ip);
+    };
+    if !reverse_proxy.trusted_ips.contains(peer_ip) {
+        return Some(peer_ip);
+    }
+
+    req.headers()
+        .get(header_name)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.split(',').next_back())
+        .and_then(|s| s.trim().parse::<IpAddr>().ok())
+        .or(Some(peer_ip))
+}
@@ -102,6 +102,34 @@ impl EmailService {
         self.send_email(recipient_email, &subject, &body).await
     }
 
+    pub async fn send_password_reset_email(
+        &self,
+        user_email: &str,
+        username: &str,
+        reset_token: &str,
+    ) -> Result<()> {
+        let subject = format!("Reset your {} password", self.tracker_name);
+        let reset_url = format!(
+            "{}/reset-password?token={}",
+            self.frontend_url.trim_end_matches('/'),
+            reset_token
+        );
+
+        let body = format!(
+            "Hello {},\n\n\
+            A password reset was requested for your {} account.\n\n\
+            To choose a new password, please click the link below:\n\
+            {}\n\n\
+            This link will expire in 1 hour and can only be used once. \
+            If you didn't request this, you can ignore this email.\n\n\
+            Best regards,\n\
+            The {} Team",
+            username, self.tracker_name, reset_url, self.tracker_name
+        );
+
+        self.send_email(user_email, &subject, &body).await
+    }
+
     async fn send_email(&self, to_email: &str, subject: &str, body: &str) -> Result<()> {
         let from_mailbox = Mailbox::new(
             Some(self.from_name.clone()),
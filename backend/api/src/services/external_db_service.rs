@@ -1,8 +1,19 @@
+use std::future::Future;
+
 use actix_web::HttpResponse;
 use arcadia_common::error::Result;
-use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::artist::{AffiliatedArtistHierarchy, Artist, ArtistRole, UserCreatedArtist},
+};
+use chrono::Utc;
+use regex::Regex;
 
-use crate::handlers::scrapers::ExternalDBData;
+use crate::{
+    env::ImageHostConfig,
+    handlers::scrapers::ExternalDBData,
+    services::{image_host_service::rehost_image_urls, image_service::validate_image_url},
+};
 
 pub async fn check_if_existing_title_group_with_link_exists(
     pool: &ConnectionPool,
@@ -19,3 +30,216 @@ pub async fn check_if_existing_title_group_with_link_exists(
     }
     Ok(None)
 }
+
+/// Some external databases keep html tags in their descriptions, line breaks are kept as such
+pub fn strip_html_tags(text: &str) -> String {
+    let text = Regex::new(r"<br\s*/?>")
+        .expect("Regex error for html line breaks")
+        .replace_all(text, "\n");
+    Regex::new(r"<[^>]+>")
+        .expect("Regex error for html tags")
+        .replace_all(&text, "")
+        .trim()
+        .to_string()
+}
+
+/// An artist credited by an external database, before being matched against ours
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditedArtist {
+    /// id of the artist on the external database
+    pub external_id: String,
+    pub external_link: String,
+    pub name: String,
+    pub roles: Vec<ArtistRole>,
+    pub nickname: Option<String>,
+}
+
+/// The existing artist with the same external link, or else the one
+/// named (or aliased) like the credited artist
+pub fn match_credited_artist<'a>(
+    credited: &CreditedArtist,
+    candidates: &'a [Artist],
+) -> Option<&'a Artist> {
+    let name = credited.name.to_lowercase();
+    candidates
+        .iter()
+        .find(|artist| artist.external_links.contains(&credited.external_link))
+        .or_else(|| {
+            candidates.iter().find(|artist| {
+                artist.name.to_lowercase() == name
+                    || artist
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.to_lowercase() == name)
+            })
+        })
+}
+
+/// The artist proposed for a credit that matches none of ours, it only gets an id
+/// once created when the upload form is submitted
+fn proposed_artist(details: UserCreatedArtist, current_user_id: i32) -> Artist {
+    Artist {
+        id: 0,
+        name: details.name,
+        aliases: details.aliases,
+        created_at: Utc::now(),
+        created_by_id: current_user_id,
+        description: details.description,
+        pictures: details.pictures,
+        external_links: details.external_links,
+        title_groups_amount: 0,
+        edition_groups_amount: 0,
+        torrents_amount: 0,
+        seeders_amount: 0,
+        leechers_amount: 0,
+        snatches_amount: 0,
+    }
+}
+
+/// Matches the credited artists against the existing ones, and proposes the others
+/// (with an `artist_id` of 0) with the details fetched from the external database.
+/// Nothing is written: the proposed artists are created, and the affiliations saved,
+/// when the upload form is submitted. An artist credited several times gets a single
+/// affiliation holding all the roles.
+pub async fn resolve_credited_artists<F, Fut>(
+    pool: &ConnectionPool,
+    image_host_config: &ImageHostConfig,
+    approved_image_hosts: &[String],
+    credited: &[CreditedArtist],
+    current_user_id: i32,
+    fetch_details: F,
+) -> Result<Vec<AffiliatedArtistHierarchy>>
+where
+    F: Fn(CreditedArtist) -> Fut,
+    Fut: Future<Output = Result<UserCreatedArtist>>,
+{
+    if credited.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let external_links: Vec<String> = credited.iter().map(|c| c.external_link.clone()).collect();
+    let names: Vec<String> = credited.iter().map(|c| c.name.clone()).collect();
+    let candidates = pool
+        .find_artists_by_external_links_or_names(&external_links, &names)
+        .await?;
+
+    let mut affiliated_artists: Vec<AffiliatedArtistHierarchy> = Vec::new();
+
+    for credit in credited {
+        let matched = match_credited_artist(credit, &candidates);
+        let already_affiliated = affiliated_artists.iter().position(|a| match matched {
+            Some(artist) => a.artist_id == artist.id,
+            None => a.artist_id == 0 && a.artist.external_links.contains(&credit.external_link),
+        });
+        if let Some(index) = already_affiliated {
+            let existing = &mut affiliated_artists[index];
+            for role in &credit.roles {
+                if !existing.roles.contains(role) {
+                    existing.roles.push(role.clone());
+                }
+            }
+            if existing.nickname.is_none() {
+                existing.nickname = credit.nickname.clone();
+            }
+            continue;
+        }
+
+        let artist = match matched {
+            Some(artist) => artist.clone(),
+            None => {
+                // the artist is still worth proposing without its details
+                let mut details = fetch_details(credit.clone()).await.unwrap_or_else(|e| {
+                    log::warn!("Failed to fetch details of artist {}: {e}", credit.name);
+                    UserCreatedArtist {
+                        name: credit.name.clone(),
+                        aliases: vec![],
+                        description: String::new(),
+                        pictures: vec![],
+                        external_links: vec![credit.external_link.clone()],
+                    }
+                });
+                rehost_image_urls(image_host_config, &mut details.pictures).await;
+                // the artist could not be created with them
+                details
+                    .pictures
+                    .retain(|picture| validate_image_url(picture, approved_image_hosts).is_ok());
+                proposed_artist(details, current_user_id)
+            }
+        };
+
+        affiliated_artists.push(AffiliatedArtistHierarchy {
+            id: 0,
+            title_group_id: 0,
+            artist_id: artist.id,
+            roles: credit.roles.clone(),
+            nickname: credit.nickname.clone(),
+            created_at: Utc::now(),
+            created_by_id: current_user_id,
+            artist,
+        });
+    }
+
+    Ok(affiliated_artists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artist(id: i64, name: &str, aliases: &[&str], external_links: &[&str]) -> Artist {
+        Artist {
+            id,
+            name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            created_at: Utc::now(),
+            created_by_id: 1,
+            description: String::new(),
+            pictures: vec![],
+            external_links: external_links.iter().map(|l| l.to_string()).collect(),
+            title_groups_amount: 0,
+            edition_groups_amount: 0,
+            torrents_amount: 0,
+            seeders_amount: 0,
+            leechers_amount: 0,
+            snatches_amount: 0,
+        }
+    }
+
+    fn credit(name: &str, external_link: &str) -> CreditedArtist {
+        CreditedArtist {
+            external_id: String::new(),
+            external_link: external_link.to_string(),
+            name: name.to_string(),
+            roles: vec![ArtistRole::Main],
+            nickname: None,
+        }
+    }
+
+    #[test]
+    fn test_match_credited_artist() {
+        let candidates = vec![
+            artist(1, "Prince", &[], &[]),
+            artist(
+                2,
+                "Prince Rogers Nelson",
+                &["The Artist"],
+                &["https://musicbrainz.org/artist/prince"],
+            ),
+        ];
+
+        // the external link wins over the name
+        let matched = match_credited_artist(
+            &credit("Prince", "https://musicbrainz.org/artist/prince"),
+            &candidates,
+        );
+        assert_eq!(matched.map(|a| a.id), Some(2));
+
+        // name and aliases are case insensitive
+        let matched = match_credited_artist(&credit("the artist", "https://other"), &candidates);
+        assert_eq!(matched.map(|a| a.id), Some(2));
+        let matched = match_credited_artist(&credit("PRINCE", "https://other"), &candidates);
+        assert_eq!(matched.map(|a| a.id), Some(1));
+
+        assert!(match_credited_artist(&credit("Madonna", "https://other"), &candidates).is_none());
+    }
+}
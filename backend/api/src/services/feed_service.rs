@@ -0,0 +1,142 @@
+use arcadia_storage::models::feed::FeedItem;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// What a feed needs to know besides its items
+pub struct FeedChannel<'a> {
+    pub title: &'a str,
+    /// where the feed can be fetched again, used as the atom feed id
+    pub self_url: &'a str,
+    pub frontend_url: &'a str,
+    /// url of the torrent download endpoint, the torrent id and passkey are appended to it
+    pub download_url: &'a str,
+    pub passkey: &'a str,
+}
+
+impl FeedChannel<'_> {
+    fn item_download_url(&self, item: &FeedItem) -> String {
+        format!(
+            "{}?id={}&passkey={}",
+            self.download_url, item.torrent_id, self.passkey
+        )
+    }
+
+    fn item_page_url(&self, item: &FeedItem) -> String {
+        format!(
+            "{}/torrent/{}",
+            self.frontend_url.trim_end_matches('/'),
+            item.torrent_id
+        )
+    }
+}
+
+fn item_title(item: &FeedItem) -> String {
+    match &item.release_name {
+        Some(release_name) if !release_name.is_empty() => {
+            format!("{} - {}", item.title_group_name, release_name)
+        }
+        _ => item.title_group_name.clone(),
+    }
+}
+
+fn escape_xml(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for character in input.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+pub fn render_rss(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<rss version="2.0"><channel>"#);
+    let _ = write!(
+        xml,
+        "<title>{}</title><link>{}</link><description>{}</description>",
+        escape_xml(channel.title),
+        escape_xml(channel.frontend_url),
+        escape_xml(channel.title),
+    );
+    if let Some(latest) = items.first() {
+        let _ = write!(
+            xml,
+            "<lastBuildDate>{}</lastBuildDate>",
+            latest.created_at.to_rfc2822()
+        );
+    }
+
+    for item in items {
+        let download_url = escape_xml(&channel.item_download_url(item));
+        let _ = write!(
+            xml,
+            "<item><title>{}</title><link>{}</link><guid isPermaLink=\"false\">{}</guid>\
+            <comments>{}</comments><pubDate>{}</pubDate>",
+            escape_xml(&item_title(item)),
+            download_url,
+            item.torrent_id,
+            escape_xml(&channel.item_page_url(item)),
+            item.created_at.to_rfc2822(),
+        );
+        for tag in &item.tags {
+            let _ = write!(xml, "<category>{}</category>", escape_xml(tag));
+        }
+        let _ = write!(
+            xml,
+            "<enclosure url=\"{}\" length=\"{}\" type=\"application/x-bittorrent\"/></item>",
+            download_url, item.size
+        );
+    }
+
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+pub fn render_atom(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    // atom requires an update date, even for an empty feed
+    let updated: DateTime<Utc> = items.first().map_or_else(Utc::now, |item| item.created_at);
+
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    let _ = write!(
+        xml,
+        "<title>{}</title><id>{}</id><updated>{}</updated>\
+        <link rel=\"self\" href=\"{}\"/><link href=\"{}\"/><author><name>{}</name></author>",
+        escape_xml(channel.title),
+        escape_xml(channel.self_url),
+        updated.to_rfc3339(),
+        escape_xml(channel.self_url),
+        escape_xml(channel.frontend_url),
+        escape_xml(channel.title),
+    );
+
+    for item in items {
+        let download_url = escape_xml(&channel.item_download_url(item));
+        let _ = write!(
+            xml,
+            "<entry><title>{}</title><id>{}</id><updated>{}</updated><link href=\"{}\"/>\
+            <link rel=\"enclosure\" type=\"application/x-bittorrent\" length=\"{}\" href=\"{}\"/>\
+            <link rel=\"related\" href=\"{}\"/>",
+            escape_xml(&item_title(item)),
+            escape_xml(&channel.item_page_url(item)),
+            item.created_at.to_rfc3339(),
+            download_url,
+            item.size,
+            download_url,
+            escape_xml(&channel.item_page_url(item)),
+        );
+        for tag in &item.tags {
+            let _ = write!(xml, "<category term=\"{}\"/>", escape_xml(tag));
+        }
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</feed>");
+    xml
+}
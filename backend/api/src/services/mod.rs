@@ -1,9 +1,13 @@
 pub mod announce_service;
 pub mod auth;
 pub mod auth_service;
+pub mod client_ip_service;
 pub mod common_service;
 pub mod email_service;
 pub mod external_db_service;
+pub mod feed_service;
 pub mod image_host_service;
 pub mod image_service;
 pub mod irc_service;
+pub mod rate_limiter;
+pub mod two_factor_service;
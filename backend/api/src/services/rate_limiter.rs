@@ -0,0 +1,95 @@
+use crate::env::AuthRateLimitConfig;
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::redis::{RedisInterface, RedisPool, RedisPoolInterface};
+use chrono::Utc;
+use rand::{distr::Alphanumeric, Rng};
+use std::sync::Arc;
+
+/// Sliding window limiter for the unauthenticated auth routes.
+///
+/// The attempts of a key (e.g. an ip or a username) are kept in a redis sorted set, only the ones
+/// made within the last `window_seconds` are counted. Once the maximum is reached within the
+/// window, the key is locked out for a while
+pub struct RateLimiter<R: RedisPoolInterface = RedisPool> {
+    redis_pool: Arc<R>,
+    pub config: AuthRateLimitConfig,
+}
+
+impl<R: RedisPoolInterface> RateLimiter<R> {
+    pub fn new(redis_pool: Arc<R>, config: AuthRateLimitConfig) -> Self {
+        Self { redis_pool, config }
+    }
+
+    /// Fails with the remaining lockout duration if any of the keys is locked out
+    pub async fn check(&self, keys: &[&str]) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let mut redis = self.redis_pool.connection().await?;
+
+        for key in keys {
+            let Some(locked_until) = redis.get(lockout_key(key)).await? else {
+                continue;
+            };
+            let remaining = locked_until.parse::<i64>().unwrap_or(now) - now;
+            if remaining > 0 {
+                return Err(Error::TooManyAttempts(remaining));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts an attempt for the key, and locks it out when `max_attempts` is reached
+    /// within the window
+    pub async fn hit(&self, key: &str, max_attempts: usize) -> Result<()> {
+        let now = Utc::now();
+        let mut redis = self.redis_pool.connection().await?;
+
+        // counted atomically, so that concurrent attempts can't get past the limit
+        let attempts = redis
+            .add_to_sliding_window(
+                window_key(key),
+                attempt_id(),
+                now.timestamp_millis(),
+                self.config.window_seconds * 1000,
+            )
+            .await?;
+
+        if attempts >= max_attempts as i64 {
+            redis
+                .set_ex(
+                    lockout_key(key),
+                    now.timestamp() + self.config.lockout_seconds,
+                    self.config.lockout_seconds as usize,
+                )
+                .await?;
+            redis.delete(window_key(key)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Forgets the attempts of the key, the lockout (if any) still applies
+    pub async fn reset(&self, key: &str) -> Result<()> {
+        let mut redis = self.redis_pool.connection().await?;
+        redis.delete(window_key(key)).await?;
+
+        Ok(())
+    }
+}
+
+/// Tells apart the attempts made in the same millisecond
+fn attempt_id() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+fn window_key(key: &str) -> String {
+    format!("rate_limit:{key}")
+}
+
+fn lockout_key(key: &str) -> String {
+    format!("rate_limit_lockout:{key}")
+}
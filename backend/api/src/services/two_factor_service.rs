@@ -0,0 +1,91 @@
+use arcadia_common::error::{Error, Result};
+use arcadia_storage::connection_pool::ConnectionPool;
+use chrono::Utc;
+use rand::{distr::Alphanumeric, Rng, RngCore};
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const RECOVERY_CODES_AMOUNT: usize = 10;
+
+/// Returns a new random 160 bits secret, base32 encoded
+pub fn generate_totp_secret() -> String {
+    let mut secret = [0u8; 20];
+    rand::rng().fill_bytes(&mut secret);
+    Secret::Raw(secret.to_vec()).to_encoded().to_string()
+}
+
+const TOTP_STEP_SECONDS: u64 = 30;
+
+/// RFC 6238 defaults (SHA1, 6 digits, 30 seconds steps), which is what authenticator apps
+/// expect
+pub fn build_totp(secret: &str, issuer: Option<String>, account_name: String) -> Result<TOTP> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| Error::TotpError(format!("{e:?}")))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP_SECONDS,
+        secret,
+        issuer,
+        account_name,
+    )
+    .map_err(|e| Error::TotpError(e.to_string()))
+}
+
+/// Returns the time step the code belongs to, if it is valid. One step of clock skew is
+/// tolerated in each direction.
+pub fn check_totp_code(secret: &str, code: &str) -> Result<Option<i64>> {
+    let totp = build_totp(secret, None, String::new())?;
+    let current_step = Utc::now().timestamp() as u64 / TOTP_STEP_SECONDS;
+
+    Ok((current_step - 1..=current_step + 1)
+        .find(|step| totp.check(code.trim(), step * TOTP_STEP_SECONDS))
+        .map(|step| step as i64))
+}
+
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES_AMOUNT)
+        .map(|_| {
+            let code: String = rand::rng()
+                .sample_iter(&Alphanumeric)
+                .take(10)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Recovery codes are random enough that a fast hash is fine
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| *c != '-')
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+/// Accepts either a code from the user's authenticator app or one of their unused recovery
+/// codes, which is then used up.
+pub async fn verify_second_factor(pool: &ConnectionPool, user_id: i32, code: &str) -> Result<bool> {
+    let Some(user_totp) = pool.find_user_totp(user_id).await? else {
+        return Err(Error::TwoFactorNotSetUp);
+    };
+
+    // a code can only be used once
+    if let Some(step) = check_totp_code(&user_totp.secret, code)? {
+        return pool.use_totp_step(user_id, step).await;
+    }
+
+    if user_totp.enabled_at.is_none() {
+        return Ok(false);
+    }
+
+    pool.use_totp_recovery_code(user_id, &hash_recovery_code(code))
+        .await
+}
@@ -17,6 +17,7 @@ use arcadia_storage::{
 };
 use envconfig::Envconfig;
 use serde::{de::DeserializeOwned, Deserialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 
 #[derive(Deserialize)]
@@ -111,10 +112,18 @@ pub enum TestUser {
     ReadAllConversationsMember,
     ManageSiteHighlights,
     ManageRelatedForumThread,
+    ManageIpBans,
+    ManageWebhooks,
+    SearchCheatFlags,
+    ManagePromotionEvents,
+    EditEntity,
+    DeleteEntity,
+    MergeArtist,
+    EditTitleGroup,
 }
 
 impl TestUser {
-    fn get_login_payload(&self) -> Login {
+    pub fn get_login_payload(&self) -> Login {
         let username = match self {
             TestUser::Standard => "user_basic",
             TestUser::EditArtist => "user_edit_art",
@@ -178,6 +187,14 @@ impl TestUser {
             TestUser::ReadAllConversationsMember => "user_ra_membr",
             TestUser::ManageSiteHighlights => "user_site_high",
             TestUser::ManageRelatedForumThread => "user_rel_thr",
+            TestUser::ManageIpBans => "user_ip_ban",
+            TestUser::ManageWebhooks => "user_webhook",
+            TestUser::SearchCheatFlags => "user_cheat_fl",
+            TestUser::ManagePromotionEvents => "user_promo_ev",
+            TestUser::EditEntity => "user_edit_ent",
+            TestUser::DeleteEntity => "user_ent_del",
+            TestUser::MergeArtist => "user_art_merge",
+            TestUser::EditTitleGroup => "user_edit_tg",
         };
 
         Login {
@@ -220,6 +237,10 @@ where
     user
 }
 
+/// The address of the reverse proxy the tests' requests come through, which
+/// passes the client's address in the `X-Forwarded-For` header
+pub const REVERSE_PROXY_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080);
+
 pub fn auth_header(token: &str) -> impl TryIntoHeaderPair {
     (AUTHORIZATION, format!("Bearer {}", token))
 }
@@ -0,0 +1,22 @@
+-- The same person created twice: source (id=21) is merged into target (id=20)
+INSERT INTO artists (id, name, aliases, description, pictures, created_by_id, created_at,
+                     title_groups_amount, edition_groups_amount, torrents_amount,
+                     seeders_amount, leechers_amount, snatches_amount)
+VALUES
+(20, 'Prince', '{"The Artist"}', 'American singer-songwriter', '{https://example.com/prince.jpg}', 1, NOW(), 1, 1, 1, 0, 0, 0),
+(21, 'Prince Rogers Nelson', '{"Jamie Starr","The Artist","Camille"}', '', '{https://example.com/prince-rogers-nelson.jpg,https://example.com/prince.jpg}', 1, NOW(), 2, 2, 2, 0, 0, 0),
+(22, 'The Revolution', '{}', 'American band', '{}', 1, NOW(), 0, 0, 0, 0, 0, 0);
+
+-- both are affiliated to title group 2, only the source to title group 1
+INSERT INTO affiliated_artists (title_group_id, artist_id, roles, nickname, created_by_id, created_at)
+VALUES
+(1, 21, '{main}', NULL, 1, NOW()),
+(2, 21, '{producer,main}', 'Jamie Starr', 1, NOW()),
+(2, 20, '{main}', NULL, 1, NOW());
+
+INSERT INTO similar_artists (artist_1_id, artist_2_id)
+VALUES (21, 22);
+
+-- an artist previously merged into the source
+INSERT INTO artist_redirects (old_artist_id, artist_id, created_by_id)
+VALUES (30, 21, 1);
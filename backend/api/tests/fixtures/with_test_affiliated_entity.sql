@@ -0,0 +1,6 @@
+INSERT INTO affiliated_entities (id, title_group_id, entity_id, roles, created_by_id, created_at)
+VALUES (1, 1, 1, '{label}', 1, '2025-03-30 17:39:13.568689+00');
+UPDATE entities SET title_groups_amount = 1 WHERE id = 1;
+
+-- Reset sequence
+SELECT setval('affiliated_entities_id_seq', 1);
@@ -0,0 +1,30 @@
+INSERT INTO entities (id, name, description, pictures, created_by_id, created_at,
+                      title_groups_amount, edition_groups_amount, torrents_amount,
+                      seeders_amount, leechers_amount, snatches_amount)
+VALUES (1,
+        'Parlophone',
+        'Parlophone is a German-British record label founded in Germany in 1896.',
+        '{}',
+        1,
+        '2025-03-30 17:39:13.568689+00',
+        0,
+        0,
+        0,
+        0,
+        0,
+        0),
+       (2,
+        'Hasbro Interactive',
+        'Hasbro Interactive was an American video game publisher.',
+        '{}',
+        1,
+        '2025-03-30 17:39:13.568689+00',
+        0,
+        0,
+        0,
+        0,
+        0,
+        0);
+
+-- Reset sequence
+SELECT setval('entities_id_seq', (SELECT MAX(id) FROM entities));
@@ -0,0 +1,22 @@
+-- Both torrents are 100MB
+UPDATE torrents SET size = 100000000 WHERE id IN (1, 2);
+
+-- Rules: 72 hours of seeding or a ratio of 1 within 14 days, warned at 1 hit and run,
+-- clearing one costs 300 bonus points
+UPDATE arcadia_settings SET
+    hit_and_run_min_seed_time_hours = 72,
+    hit_and_run_min_ratio = 1,
+    hit_and_run_grace_period_days = 14,
+    hit_and_run_warning_threshold = 1,
+    hit_and_run_clear_bonus_points_cost = 300;
+
+-- User 100 snatched torrent 1 a month ago and seeded it for an hour: hit and run
+-- User 100 snatched torrent 2 a month ago and seeded it for 100 hours
+-- User 101 snatched torrent 1 a month ago and uploaded its size
+-- User 101 snatched torrent 2 yesterday: still within the grace period
+INSERT INTO torrent_activities (torrent_id, user_id, completed_at, total_seed_time, real_uploaded)
+VALUES
+    (1, 100, NOW() - INTERVAL '30 days', 3600, 0),
+    (2, 100, NOW() - INTERVAL '30 days', 360000, 0),
+    (1, 101, NOW() - INTERVAL '30 days', 0, 100000000),
+    (2, 101, NOW() - INTERVAL '1 day', 0, 0);
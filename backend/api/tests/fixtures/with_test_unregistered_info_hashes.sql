@@ -0,0 +1,19 @@
+-- Torrent 1 was trumped by torrent 2
+UPDATE torrents SET deleted_at = NOW() WHERE id = 1;
+INSERT INTO torrent_deletions (torrent_id, title_group_name, deletion_reason, replacement_torrent_id)
+VALUES (1, 'Test Title Group', 'trumped', 2);
+
+-- Torrent 2 is a hybrid torrent, v2 clients announce the truncated v2 info hash
+UPDATE torrents
+SET info_hash_v2 = '\xdeadbeef00112233445566778899aabbccddeeff00112233445566778899aabb'
+WHERE id = 2;
+
+-- User 100 still announces torrent 1 and an info hash that was never uploaded,
+-- as well as torrent 2 from before it was uploaded, with both of its info hashes
+INSERT INTO unregistered_info_hashes (user_id, info_hash, torrent_id, created_at, updated_at)
+VALUES
+    (100, '\x112233445566778899aabbccddeeff0011223344', 1, NOW() - INTERVAL '2 days', NOW() - INTERVAL '1 hour'),
+    (100, '\xffffffffffffffffffffffffffffffffffffffff', NULL, NOW() - INTERVAL '1 day', NOW()),
+    (100, '\xcafebabefeedface0123456789abcdef11223344', NULL, NOW() - INTERVAL '3 days', NOW() - INTERVAL '3 days'),
+    (100, '\xdeadbeef00112233445566778899aabbccddeeff', NULL, NOW() - INTERVAL '3 days', NOW() - INTERVAL '3 days'),
+    (101, '\x112233445566778899aabbccddeeff0011223344', 1, NOW(), NOW());
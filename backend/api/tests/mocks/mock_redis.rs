@@ -21,9 +21,26 @@ impl RedisPoolInterface for MockRedisPool {
     }
 }
 
+/// The members of a sorted set with their scores
+type SortedSet = Vec<(i64, String)>;
+
 #[derive(Clone, Default)]
 pub struct MockRedis {
     inner: Arc<RwLock<HashMap<Vec<u8>, Vec<u8>>>>,
+    sorted_sets: Arc<RwLock<HashMap<Vec<u8>, SortedSet>>>,
+}
+
+impl MockRedis {
+    /// Lets tests find values stored under keys they can't know in advance, like random tokens
+    pub fn keys_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.inner
+            .read()
+            .unwrap()
+            .keys()
+            .map(|k| String::from_utf8_lossy(k).to_string())
+            .filter(|k| k.starts_with(prefix))
+            .collect()
+    }
 }
 
 impl RedisInterface for MockRedis {
@@ -60,6 +77,32 @@ impl RedisInterface for MockRedis {
     async fn delete<K: ToRedisArgs + Send>(&mut self, key: K) -> Result<()> {
         let key = key.to_redis_args()[0].clone();
         self.inner.write().unwrap().remove(&key);
+        self.sorted_sets.write().unwrap().remove(&key);
         Ok(())
     }
+
+    async fn get_del<K: ToRedisArgs + Send>(&mut self, key: K) -> Result<Option<String>> {
+        let key = key.to_redis_args()[0].clone();
+        Ok(self
+            .inner
+            .write()
+            .unwrap()
+            .remove(&key)
+            .map(|v| str::from_utf8(&v).unwrap().to_string()))
+    }
+
+    async fn add_to_sliding_window<K: ToRedisArgs + Send>(
+        &mut self,
+        key: K,
+        member: String,
+        now_millis: i64,
+        window_millis: i64,
+    ) -> Result<i64> {
+        let key = key.to_redis_args()[0].clone();
+        let mut sorted_sets = self.sorted_sets.write().unwrap();
+        let members = sorted_sets.entry(key).or_default();
+        members.retain(|(score, _)| *score > now_millis - window_millis);
+        members.push((now_millis, member));
+        Ok(members.len() as i64)
+    }
 }
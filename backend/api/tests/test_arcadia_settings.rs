@@ -78,6 +78,7 @@ async fn test_staff_can_update_arcadia_settings(pool: PgPool) {
         shop_upload_base_price_per_gb: 100,
         shop_freeleech_token_base_price: 500,
         bonus_points_alias: "bonus points".to_string(),
+        freeleech_token_duration_hours: 24,
         ..Default::default()
     };
 
@@ -115,6 +116,7 @@ async fn test_regular_user_cannot_update_arcadia_settings(pool: PgPool) {
         shop_upload_base_price_per_gb: 100,
         shop_freeleech_token_base_price: 500,
         bonus_points_alias: "bonus points".to_string(),
+        freeleech_token_duration_hours: 24,
         ..Default::default()
     };
 
@@ -142,6 +144,7 @@ async fn test_update_arcadia_settings_requires_auth(pool: PgPool) {
         shop_upload_base_price_per_gb: 100,
         shop_freeleech_token_base_price: 500,
         bonus_points_alias: "bonus points".to_string(),
+        freeleech_token_duration_hours: 24,
         ..Default::default()
     };
 
@@ -182,6 +185,7 @@ async fn test_update_arcadia_settings_updates_in_memory_cache(pool: PgPool) {
         shop_upload_base_price_per_gb: 100,
         shop_freeleech_token_base_price: 500,
         bonus_points_alias: "bonus points".to_string(),
+        freeleech_token_duration_hours: 24,
         ..Default::default()
     };
 
@@ -228,6 +232,7 @@ async fn test_update_arcadia_settings_requires_all_automated_message_fields(pool
         shop_upload_base_price_per_gb: 100,
         shop_freeleech_token_base_price: 500,
         bonus_points_alias: "bonus points".to_string(),
+        freeleech_token_duration_hours: 24,
         ..Default::default()
     };
 
@@ -4,9 +4,12 @@ pub mod mocks;
 use crate::common::TestUser;
 use actix_web::http::StatusCode;
 use actix_web::test;
+use arcadia_api::env::ImageHostConfig;
+use arcadia_api::services::external_db_service::{resolve_credited_artists, CreditedArtist};
 use arcadia_storage::connection_pool::ConnectionPool;
 use arcadia_storage::models::artist::{
     Artist, ArtistRole, ArtistSearchResult, EditedArtist, UserCreatedAffiliatedArtist,
+    UserCreatedArtist,
 };
 use arcadia_storage::models::common::PaginatedResults;
 use common::auth_header;
@@ -167,6 +170,51 @@ async fn test_search_artists_pagination(pool: PgPool) {
     assert_eq!(response.page_size, 2);
 }
 
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_artists_for_search"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_artists_orders_by_relevance(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    // "the" matches the name of The Beatles and an alias of Pink Floyd,
+    // the name is the closest match even though it sorts last by name
+    let req = test::TestRequest::get()
+        .uri("/api/search/artists?name=the&page=1&page_size=10&order_by_column=relevance&order_by_direction=desc")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<ArtistSearchResult> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 2);
+    assert_eq!(response.results[0].name, "The Beatles");
+    assert_eq!(response.results[1].name, "Pink Floyd");
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_artists_for_search"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_artists_tolerates_typos(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/artists?name=zepelin&page=1&page_size=10&order_by_column=relevance&order_by_direction=desc")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<ArtistSearchResult> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 1);
+    assert_eq!(response.results[0].name, "Led Zeppelin");
+}
+
 #[sqlx::test(
     fixtures("with_test_users", "with_test_artist"),
     migrations = "../storage/migrations"
@@ -246,3 +294,77 @@ async fn test_duplicate_artist_affiliation_returns_conflict(pool: PgPool) {
     let resp = test::call_service(&service, req).await;
     assert_eq!(resp.status(), StatusCode::CONFLICT);
 }
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_artist"),
+    migrations = "../storage/migrations"
+)]
+async fn test_credited_artists_are_proposed_without_being_saved(pg_pool: PgPool) {
+    let pool = ConnectionPool::with_pg_pool(pg_pool.clone());
+    let image_host_config = ImageHostConfig {
+        chevereto_api_url: None,
+        chevereto_api_key: None,
+        rehost_external_images: false,
+    };
+    let credit = |name: &str, external_id: &str, role: ArtistRole| CreditedArtist {
+        external_id: external_id.to_string(),
+        external_link: format!("https://musicbrainz.org/artist/{external_id}"),
+        name: name.to_string(),
+        roles: vec![role],
+        nickname: None,
+    };
+    let credited = vec![
+        credit("the beatles", "b10bbbfc", ArtistRole::Main),
+        credit("Ringo Starr", "300c4c73", ArtistRole::Main),
+        credit("Ringo Starr", "300c4c73", ArtistRole::Guest),
+    ];
+
+    let affiliated_artists = resolve_credited_artists(
+        &pool,
+        &image_host_config,
+        &["i.imgur.com".to_string()],
+        &credited,
+        100,
+        |credit| async move {
+            Ok(UserCreatedArtist {
+                name: credit.name.clone(),
+                aliases: vec![],
+                description: "Drummer".into(),
+                pictures: vec![
+                    "https://i.imgur.com/ringo.jpg".into(),
+                    "https://evil.example.com/ringo.jpg".into(),
+                ],
+                external_links: vec![credit.external_link.clone()],
+            })
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(affiliated_artists.len(), 2);
+    // matched by name
+    assert_eq!(affiliated_artists[0].artist_id, 1);
+    // proposed with its details, pictures from unapproved hosts are left out
+    let proposed = &affiliated_artists[1];
+    assert_eq!(proposed.artist_id, 0);
+    assert_eq!(proposed.roles, vec![ArtistRole::Main, ArtistRole::Guest]);
+    assert_eq!(proposed.artist.description, "Drummer");
+    assert_eq!(
+        proposed.artist.pictures,
+        vec!["https://i.imgur.com/ringo.jpg".to_string()]
+    );
+    assert_eq!(
+        proposed.artist.external_links,
+        vec!["https://musicbrainz.org/artist/300c4c73".to_string()]
+    );
+
+    // nothing is written until the upload is submitted, and a name alone
+    // is not enough to link the external artist to ours
+    let artists_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM artists")
+        .fetch_one(&pg_pool)
+        .await
+        .unwrap();
+    assert_eq!(artists_count, 1);
+    let matched = pool.find_artist_by_id(1).await.unwrap();
+    assert!(matched.external_links.is_empty());
+}
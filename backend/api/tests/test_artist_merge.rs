@@ -0,0 +1,143 @@
+pub mod common;
+pub mod mocks;
+
+use crate::common::TestUser;
+use actix_web::http::StatusCode;
+use actix_web::test;
+use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::models::artist::{Artist, ArtistEnriched};
+use common::{auth_header, create_test_app_and_login};
+use mocks::mock_redis::MockRedisPool;
+use sqlx::PgPool;
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_artist_peer_stats",
+        "with_merge_artists"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_merge_artists_moves_all_related_data(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        pool.clone(),
+        MockRedisPool::default(),
+        TestUser::MergeArtist,
+    )
+    .await;
+
+    let pg_pool: &PgPool = (*pool).borrow();
+
+    // Perform merge: source=21 into target=20
+    let req = test::TestRequest::post()
+        .uri("/api/artists/merge?source_artist_id=21&target_artist_id=20")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let artist =
+        common::call_and_read_body_json_with_status::<Artist, _>(&service, req, StatusCode::OK)
+            .await;
+
+    assert_eq!(artist.id, 20);
+    // the names of the source are added after the aliases of the target, in order
+    assert_eq!(
+        artist.aliases,
+        vec![
+            "The Artist".to_string(),
+            "Prince Rogers Nelson".to_string(),
+            "Jamie Starr".to_string(),
+            "Camille".to_string()
+        ]
+    );
+    // pictures are moved, without duplicates
+    assert_eq!(
+        artist.pictures,
+        vec![
+            "https://example.com/prince.jpg".to_string(),
+            "https://example.com/prince-rogers-nelson.jpg".to_string()
+        ]
+    );
+    // title group 2 was affiliated to both
+    assert_eq!(artist.title_groups_amount, 2);
+    assert_eq!(artist.edition_groups_amount, 2);
+    assert_eq!(artist.torrents_amount, 2);
+    // torrents 1 and 2, the deleted torrent 100 is not counted
+    assert_eq!(artist.seeders_amount, 16);
+    assert_eq!(artist.leechers_amount, 5);
+    assert_eq!(artist.snatches_amount, 11);
+
+    assert!(pool.find_artist_by_id(21).await.is_err());
+
+    // the affiliation of the source to title group 2 is merged into the target's
+    let affiliation: (Vec<String>, Option<String>) = sqlx::query_as(
+        "SELECT roles::TEXT[], nickname FROM affiliated_artists WHERE title_group_id = 2 AND artist_id = 20",
+    )
+    .fetch_one(pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        affiliation,
+        (
+            vec!["main".to_string(), "producer".to_string()],
+            Some("Jamie Starr".to_string())
+        )
+    );
+
+    let similar: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM similar_artists WHERE artist_1_id = 20 AND artist_2_id = 22",
+    )
+    .fetch_one(pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(similar.0, 1);
+
+    let merge_logs: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM user_edit_change_logs WHERE item_type = 'artist' AND item_id = 20 AND edits->'merged_artist'->>'id' = '21'",
+    )
+    .fetch_one(pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(merge_logs.0, 1);
+
+    // the merged artist and the one previously merged into it redirect to the target
+    for old_id in [21, 30] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/artists?id={old_id}"))
+            .insert_header(auth_header(&user.token))
+            .to_request();
+
+        let enriched = common::call_and_read_body_json_with_status::<ArtistEnriched, _>(
+            &service,
+            req,
+            StatusCode::OK,
+        )
+        .await;
+        assert_eq!(enriched.artist.id, 20);
+    }
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_title_group", "with_merge_artists"),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_without_permission_cannot_merge_artists(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/artists/merge?source_artist_id=21&target_artist_id=20")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    assert!(pool.find_artist_by_id(21).await.is_ok());
+}
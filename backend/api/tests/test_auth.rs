@@ -19,7 +19,7 @@ use crate::common::TestUser;
 use crate::{
     common::{
         auth_header, call_and_read_body_json, create_test_app, create_test_app_and_login,
-        read_body_json_data, Profile,
+        read_body_json_data, Profile, REVERSE_PROXY_ADDR,
     },
     mocks::mock_redis::MockRedis,
 };
@@ -47,6 +47,7 @@ async fn test_open_registration(pool: PgPool) {
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "test_user",
@@ -80,6 +81,7 @@ async fn test_duplicate_username_registration(pool: PgPool) {
     // Register first user
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "duplicate_user",
@@ -95,6 +97,7 @@ async fn test_duplicate_username_registration(pool: PgPool) {
     // Try to register second user with same username
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.89"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "duplicate_user",
@@ -126,6 +129,7 @@ async fn test_closed_registration_failures(pool: PgPool) {
     // No key specified.  Should fail.
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "test_user",
@@ -147,6 +151,7 @@ async fn test_closed_registration_failures(pool: PgPool) {
     // Invalid key specified.  Should fail.
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register?invitation_key=invalid")
         .set_json(RegisterRequest {
             username: "test_user",
@@ -176,6 +181,7 @@ async fn test_closed_registration_success(pool: PgPool) {
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register?invitation_key=valid_key")
         .set_json(RegisterRequest {
             username: "test_user2",
@@ -203,6 +209,7 @@ async fn test_closed_registration_success(pool: PgPool) {
     // Try again with same key.  Should fail.
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register?invitation_key=valid_key")
         .set_json(RegisterRequest {
             username: "test_user3",
@@ -228,6 +235,7 @@ async fn test_closed_registration_expired_failure(pool: PgPool) {
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register?invitation_key=valid_key")
         .set_json(RegisterRequest {
             username: "test_user2",
@@ -433,6 +441,7 @@ async fn test_registration_assigns_class_permissions(pool: PgPool) {
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "test_user_perms",
@@ -477,6 +486,7 @@ async fn test_registration_sends_automated_message_when_configured(pool: PgPool)
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "new_user_msg",
@@ -538,6 +548,7 @@ async fn test_registration_no_automated_message_when_not_configured(pool: PgPool
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "new_user_no_msg",
@@ -588,6 +599,7 @@ async fn test_registration_applies_default_values_from_settings(pool: PgPool) {
 
     let req = TestRequest::post()
         .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
         .uri("/api/auth/register")
         .set_json(RegisterRequest {
             username: "test_defaults",
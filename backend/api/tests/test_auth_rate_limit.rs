@@ -0,0 +1,262 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::{header::RETRY_AFTER, StatusCode},
+    test::{call_service, TestRequest},
+};
+use arcadia_api::{env::AuthRateLimitConfig, services::rate_limiter::RateLimiter};
+use arcadia_common::error::Error;
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{
+        auth_attempt::{AuthAttempt, AuthAttemptAction},
+        common::PaginatedResults,
+    },
+};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    common::{
+        auth_header, call_and_read_body_json, create_test_app, create_test_app_and_login, login_as,
+        TestUser, REVERSE_PROXY_ADDR,
+    },
+    mocks::mock_redis::MockRedisPool,
+};
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_login_lockout_after_failures(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let service = create_test_app(pool, MockRedisPool::default()).await;
+
+    let mut login = TestUser::Standard.get_login_payload();
+    let correct_password = login.password.clone();
+    login.password = "wrong_password".into();
+
+    // the username is matched case insensitively
+    for attempt in 0..5 {
+        if attempt % 2 == 1 {
+            login.username = login.username.to_uppercase();
+        } else {
+            login.username = login.username.to_lowercase();
+        }
+        let req = TestRequest::post()
+            .insert_header(("X-Forwarded-For", "10.10.4.88"))
+            .peer_addr(REVERSE_PROXY_ADDR)
+            .uri("/api/auth/login")
+            .set_json(&login)
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // locked out, even with the right password
+    login.username = login.username.to_lowercase();
+    login.password = correct_password;
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(&login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = resp
+        .headers()
+        .get(RETRY_AFTER)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 900);
+
+    // the ip is locked out for other accounts too
+    let mut other_login = TestUser::EditArtist.get_login_payload();
+    other_login.password = "wrong_password".into();
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(&other_login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // but the failures from that ip don't lock the account out for its owner
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.89"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(&login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_forwarded_header_is_ignored_from_untrusted_clients(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let service = create_test_app(pool, MockRedisPool::default()).await;
+
+    let mut login = TestUser::Standard.get_login_payload();
+    login.password = "wrong_password".into();
+
+    // a client connecting directly can't get a fresh window by changing the header
+    let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(203, 0, 113, 5)), 50000);
+    for attempt in 0..6 {
+        let req = TestRequest::post()
+            .insert_header(("X-Forwarded-For", format!("10.10.5.{attempt}")))
+            .peer_addr(client_addr)
+            .uri("/api/auth/login")
+            .set_json(&login)
+            .to_request();
+        let resp = call_service(&service, req).await;
+        let expected_status = if attempt < 5 {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::TOO_MANY_REQUESTS
+        };
+        assert_eq!(resp.status(), expected_status);
+    }
+}
+
+#[actix_web::test]
+async fn test_attempts_are_counted_over_a_sliding_window() {
+    let rate_limiter = RateLimiter::new(
+        Arc::new(MockRedisPool::default()),
+        AuthRateLimitConfig {
+            window_seconds: 1,
+            max_failures: 3,
+            max_attempts: 3,
+            max_password_resets: 3,
+            lockout_seconds: 60,
+        },
+    );
+
+    rate_limiter.hit("ip:10.10.4.89", 3).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    rate_limiter.hit("ip:10.10.4.89", 3).await.unwrap();
+    rate_limiter.check(&["ip:10.10.4.89"]).await.unwrap();
+
+    // the first attempt left the window, the two others are still counted
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    rate_limiter.hit("ip:10.10.4.89", 3).await.unwrap();
+    rate_limiter.check(&["ip:10.10.4.89"]).await.unwrap();
+
+    rate_limiter.hit("ip:10.10.4.89", 3).await.unwrap();
+    assert!(matches!(
+        rate_limiter.check(&["ip:10.10.4.89"]).await,
+        Err(Error::TooManyAttempts(_))
+    ));
+}
+
+#[sqlx::test(migrations = "../storage/migrations")]
+async fn test_register_rate_limit(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let service = create_test_app(pool, MockRedisPool::default()).await;
+
+    // invalid registrations count as well
+    for _ in 0..20 {
+        let req = TestRequest::post()
+            .insert_header(("X-Forwarded-For", "10.10.4.88"))
+            .peer_addr(REVERSE_PROXY_ADDR)
+            .uri("/api/auth/register")
+            .set_json(json!({
+                "username": "flood",
+                "password": "short",
+                "password_verify": "short",
+                "email": "flood@testdomain.com",
+            }))
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/register")
+        .set_json(json!({
+            "username": "flood",
+            "password": "TestPassword123",
+            "password_verify": "TestPassword123",
+            "email": "flood@testdomain.com",
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(resp.headers().contains_key(RETRY_AFTER));
+
+    // other ips are not affected
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.89"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/register")
+        .set_json(json!({
+            "username": "not_flood",
+            "password": "TestPassword123",
+            "password_verify": "TestPassword123",
+            "email": "not_flood@testdomain.com",
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_search_auth_attempts(pool: PgPool) {
+    sqlx::query(
+        "UPDATE users SET permissions = array_append(permissions, 'search_auth_attempts') WHERE id = 127",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) = create_test_app_and_login(
+        pool,
+        MockRedisPool::default(),
+        TestUser::SearchUnauthorizedAccess,
+    )
+    .await;
+
+    let mut login = TestUser::Standard.get_login_payload();
+    login.password = "wrong_password".into();
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.88"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(&login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/auth-attempts?ip=10.10.4.0/24&successful=false&page=1&page_size=10")
+        .to_request();
+    let attempts = call_and_read_body_json::<PaginatedResults<AuthAttempt>, _>(&service, req).await;
+    assert_eq!(attempts.total_items, 1);
+    assert_eq!(attempts.results[0].action, AuthAttemptAction::Login);
+    assert_eq!(
+        attempts.results[0].username.as_deref(),
+        Some(login.username.as_str())
+    );
+    assert!(!attempts.results[0].locked_out);
+
+    // staff only
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/auth-attempts?page=1&page_size=10")
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
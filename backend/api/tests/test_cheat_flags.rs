@@ -0,0 +1,83 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{
+        cheat_flag::{CheatFlag, CheatFlagReason, CheatFlagSeverity},
+        common::PaginatedResults,
+    },
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    common::{auth_header, call_and_read_body_json, create_test_app_and_login, login_as, TestUser},
+    mocks::mock_redis::MockRedisPool,
+};
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_cheat_flags(pool: PgPool) {
+    // as flushed by the tracker
+    sqlx::query(
+        r#"
+        INSERT INTO cheat_flags (created_at, user_id, torrent_id, peer_id, ip, reason, severity,
+                                 uploaded_delta, seconds_since_last_announce, swarm_leechers,
+                                 swarm_downloaded, credit_withheld)
+        VALUES
+            (NOW(), 100, 1, '\x2d6c743046303131313131313131313131313131', '10.10.4.88',
+             'impossible_upload_speed', 'high', 1073741824, 1, 0, 0, TRUE),
+            (NOW(), 100, 1, '\x2d6c743046303131313131313131313131313131', '10.10.4.88',
+             'upload_exceeds_swarm_download', 'low', 1073741824, 1, 0, 0, TRUE)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::SearchCheatFlags).await;
+
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/cheat-flags?user_id=100&min_severity=medium&page=1&page_size=10")
+        .to_request();
+    let flags = call_and_read_body_json::<PaginatedResults<CheatFlag>, _>(&service, req).await;
+    assert_eq!(flags.total_items, 1);
+    assert_eq!(flags.results[0].user.id, 100);
+    assert_eq!(
+        flags.results[0].reason,
+        CheatFlagReason::ImpossibleUploadSpeed
+    );
+    assert_eq!(flags.results[0].severity, CheatFlagSeverity::High);
+    assert!(flags.results[0].credit_withheld);
+
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/cheat-flags?torrent_id=1&page=1&page_size=10")
+        .to_request();
+    let flags = call_and_read_body_json::<PaginatedResults<CheatFlag>, _>(&service, req).await;
+    assert_eq!(flags.total_items, 2);
+
+    // staff only
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/cheat-flags?page=1&page_size=10")
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
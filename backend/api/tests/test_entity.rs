@@ -0,0 +1,436 @@
+pub mod common;
+pub mod mocks;
+
+use crate::common::TestUser;
+use actix_web::http::StatusCode;
+use actix_web::test;
+use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::models::common::{OrderByDirection, PaginatedResults};
+use arcadia_storage::models::entity::{
+    EditedEntity, Entity, EntityEnriched, EntityRole, EntitySearchResult,
+    UserCreatedAffiliatedEntity, UserCreatedEntity,
+};
+use arcadia_storage::models::title_group::TitleGroupHierarchyLite;
+use arcadia_storage::models::torrent::{TorrentSearch, TorrentSearchOrderByColumn};
+use common::auth_header;
+use common::create_test_app_and_login;
+use mocks::mock_redis::MockRedisPool;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_create_entities_returns_existing_on_duplicate_name(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req_body = vec![
+        UserCreatedEntity {
+            name: "Toho".into(),
+            description: "Japanese film studio".into(),
+            pictures: vec![],
+        },
+        UserCreatedEntity {
+            name: "Toho".into(),
+            description: "Duplicate".into(),
+            pictures: vec![],
+        },
+    ];
+
+    let req = test::TestRequest::post()
+        .uri("/api/entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let response = common::call_and_read_body_json_with_status::<Vec<Entity>, _>(
+        &service,
+        req,
+        StatusCode::CREATED,
+    )
+    .await;
+
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].id, response[1].id);
+    assert_eq!(response[1].description, "Japanese film studio");
+    assert_eq!(response[0].created_by_id, 100);
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_staff_can_edit_entity_and_edit_is_logged(pool: PgPool) {
+    let pg_pool = pool.clone();
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::EditEntity).await;
+
+    let req_body = EditedEntity {
+        id: 1,
+        name: "Parlophone Records".into(),
+        description: "Parlophone is a German-British record label founded in Germany in 1896."
+            .into(),
+        pictures: vec![],
+    };
+
+    let req = test::TestRequest::put()
+        .uri("/api/entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let response =
+        common::call_and_read_body_json_with_status::<Entity, _>(&service, req, StatusCode::OK)
+            .await;
+
+    assert_eq!(response.name, req_body.name);
+
+    let edits: serde_json::Value = sqlx::query_scalar(
+        "SELECT edits FROM user_edit_change_logs WHERE item_type = 'entity' AND item_id = 1",
+    )
+    .fetch_one(&pg_pool)
+    .await
+    .unwrap();
+
+    // only the changed field is recorded
+    assert_eq!(
+        edits,
+        serde_json::json!({"name": {"old": "Parlophone", "new": "Parlophone Records"}})
+    );
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_cannot_edit_entity_created_by_someone_else(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req_body = EditedEntity {
+        id: 1,
+        name: "Vandalized".into(),
+        description: "".into(),
+        pictures: vec![],
+    };
+
+    let req = test::TestRequest::put()
+        .uri("/api/entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_entities_filters_by_name(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/entities?name=hasbro&page=1&page_size=10&order_by_column=name&order_by_direction=asc")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<EntitySearchResult> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 1);
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].name, "Hasbro Interactive");
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_entities_matches_wildcards_literally(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/entities?name=%25&page=1&page_size=10&order_by_column=name&order_by_direction=asc")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<EntitySearchResult> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 0);
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_with_permission_can_delete_entity(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        pool.clone(),
+        MockRedisPool::default(),
+        TestUser::DeleteEntity,
+    )
+    .await;
+
+    let req = test::TestRequest::delete()
+        .uri("/api/entities?entity_id=1")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    assert!(pool.find_entity_by_id(1).await.is_err());
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_entity"),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_without_permission_cannot_delete_entity(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::delete()
+        .uri("/api/entities?entity_id=1")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    assert!(pool.find_entity_by_id(1).await.is_ok());
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_entity",
+        "with_test_title_group",
+        "with_test_affiliated_entity"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_entity_affiliation_updates_title_groups_amount(pool: PgPool) {
+    let pg_pool = pool.clone();
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        pool.clone(),
+        MockRedisPool::default(),
+        TestUser::EditTitleGroup,
+    )
+    .await;
+
+    let req_body = vec![UserCreatedAffiliatedEntity {
+        title_group_id: 2,
+        entity_id: 2,
+        roles: vec![EntityRole::Publisher],
+    }];
+
+    let req = test::TestRequest::post()
+        .uri("/api/affiliated-entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(
+        pool.find_entity_by_id(2).await.unwrap().title_groups_amount,
+        1
+    );
+
+    let edits: serde_json::Value = sqlx::query_scalar(
+        "SELECT edits FROM user_edit_change_logs WHERE item_type = 'title_group' AND item_id = 2",
+    )
+    .fetch_one(&pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        edits,
+        serde_json::json!({"affiliated_entities": {
+            "old": [],
+            "new": [{"entity_id": 2, "roles": ["publisher"]}]
+        }})
+    );
+
+    // entity 1 is already affiliated to title_group 1 via fixture
+    let req_body = vec![UserCreatedAffiliatedEntity {
+        title_group_id: 1,
+        entity_id: 1,
+        roles: vec![EntityRole::Label],
+    }];
+
+    let req = test::TestRequest::post()
+        .uri("/api/affiliated-entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    pool.delete_entities_affiliation(&[1], 170).await.unwrap();
+    assert_eq!(
+        pool.find_entity_by_id(1).await.unwrap().title_groups_amount,
+        0
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_entity",
+        "with_test_title_group",
+        "with_test_affiliated_entity"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_cannot_edit_affiliations_of_someone_elses_title_group(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let req_body = vec![UserCreatedAffiliatedEntity {
+        title_group_id: 2,
+        entity_id: 2,
+        roles: vec![EntityRole::Publisher],
+    }];
+
+    let req = test::TestRequest::post()
+        .uri("/api/affiliated-entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(&req_body)
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let req = test::TestRequest::delete()
+        .uri("/api/affiliated-entities")
+        .insert_header(auth_header(&user.token))
+        .set_json(serde_json::json!({"affiliation_ids": [1]}))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        pool.find_entity_by_id(1).await.unwrap().title_groups_amount,
+        1
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_entity",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_affiliated_entity",
+        "with_artist_peer_stats"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_get_entity_lists_title_groups_with_peer_stats(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/entities?id=1")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: EntityEnriched =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.entity.name, "Parlophone");
+    assert_eq!(response.title_groups.len(), 1);
+    let title_group = &response.title_groups[0];
+    assert_eq!(title_group.id, 1);
+    assert_eq!(title_group.roles, vec![EntityRole::Label]);
+    // the deleted torrent 100 is not counted
+    assert_eq!(title_group.seeders_amount, 5);
+    assert_eq!(title_group.leechers_amount, 2);
+    assert_eq!(title_group.snatches_amount, 7);
+
+    let updated = pool.update_entity_peer_stats().await.unwrap();
+    assert_eq!(updated, 1);
+
+    let entity = pool.find_entity_by_id(1).await.unwrap();
+    assert_eq!(entity.edition_groups_amount, 1);
+    assert_eq!(entity.torrents_amount, 1);
+    assert_eq!(entity.seeders_amount, 5);
+    assert_eq!(entity.leechers_amount, 2);
+    assert_eq!(entity.snatches_amount, 7);
+
+    assert_eq!(pool.update_entity_peer_stats().await.unwrap(), 0);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_entity",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_affiliated_entity",
+        "with_refreshed_title_group_hierarchy_lite"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_torrents_filters_by_entity(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let query = TorrentSearch {
+        title_group_name: None,
+        title_group_content_type: vec![],
+        title_group_category: vec![],
+        title_group_tags: None,
+        title_group_include_empty_groups: true,
+        edition_group_source: vec![],
+        torrent_video_resolution: vec![],
+        torrent_language: vec![],
+        torrent_reported: None,
+        torrent_staff_checked: None,
+        torrent_created_by_id: None,
+        torrent_snatched_by_id: None,
+        artist_id: None,
+        entity_id: Some(1),
+        collage_id: None,
+        page: 1,
+        page_size: 50,
+        order_by_column: TorrentSearchOrderByColumn::TorrentCreatedAt,
+        order_by_direction: OrderByDirection::Desc,
+        series_id: None,
+        user_id_bookmarks: None,
+    };
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/search/torrents/lite?{}",
+            serde_qs::to_string(&query).unwrap()
+        ))
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let results: PaginatedResults<TitleGroupHierarchyLite> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(results.total_items, 1);
+    assert_eq!(results.results.len(), 1);
+    assert_eq!(results.results[0].id, 1);
+}
@@ -0,0 +1,282 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::{
+        header::{CONTENT_TYPE, HOST},
+        StatusCode,
+    },
+    test,
+};
+use arcadia_api::services::feed_service::{render_rss, FeedChannel};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::feed::{FeedFilter, FeedItem},
+};
+use chrono::Utc;
+use common::{
+    auth_header, call_and_read_body_json, call_and_read_body_json_with_status,
+    create_test_app_and_login, TestUser,
+};
+use mocks::mock_redis::MockRedisPool;
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+// passkey of the standard test user
+const PASSKEY: &str = "d2037c66dd3e13044e0d2f9b891c3837";
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_feed_filter_lifecycle(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .set_json(json!({
+            "name": " flac ",
+            "search": { "torrent_staff_checked": true },
+            "tag_expression": "rock & !pop",
+        }))
+        .to_request();
+    let feed_filter =
+        call_and_read_body_json_with_status::<FeedFilter, _>(&service, req, StatusCode::CREATED)
+            .await;
+    assert_eq!(feed_filter.name, "flac");
+    assert_eq!(feed_filter.search.torrent_staff_checked, Some(true));
+
+    for invalid in [
+        json!({ "name": "", "search": {} }),
+        json!({ "name": "broken", "search": {}, "tag_expression": "rock & (pop" }),
+    ] {
+        let req = test::TestRequest::post()
+            .insert_header(auth_header(&user.token))
+            .uri("/api/feeds/filters")
+            .set_json(invalid)
+            .to_request();
+        let resp = test::call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let req = test::TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .to_request();
+    let feed_filters = call_and_read_body_json::<Vec<FeedFilter>, _>(&service, req).await;
+    assert_eq!(feed_filters.len(), 1);
+
+    for expected_status in [StatusCode::OK, StatusCode::NOT_FOUND] {
+        let req = test::TestRequest::delete()
+            .insert_header(auth_header(&user.token))
+            .uri(&format!("/api/feeds/filters?id={}", feed_filter.id))
+            .to_request();
+        let resp = test::call_service(&service, req).await;
+        assert_eq!(resp.status(), expected_status);
+    }
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_refreshed_title_group_hierarchy_lite"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_rss_feed_with_passkey(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .set_json(json!({ "name": "everything", "search": {} }))
+        .to_request();
+    let feed_filter =
+        call_and_read_body_json_with_status::<FeedFilter, _>(&service, req, StatusCode::CREATED)
+            .await;
+
+    // no authorization header, feed readers only have the url
+    let req = test::TestRequest::get()
+        .insert_header((HOST, "attacker.example"))
+        .uri(&format!(
+            "/api/feeds?filter_id={}&passkey={PASSKEY}",
+            feed_filter.id
+        ))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).unwrap(),
+        "application/rss+xml; charset=utf-8"
+    );
+
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><rss version="2.0">"#));
+    // links are built from the configured url, not from the request headers
+    assert!(body.contains(&format!(
+        "https://site.com/api/torrents?id=1&amp;passkey={PASSKEY}"
+    )));
+    assert!(body.contains("<comments>https://site.com/torrent/1</comments>"));
+    assert!(!body.contains("attacker.example"));
+
+    // newest torrents first
+    let newest = body.find("RollerCoaster Tycoon").unwrap();
+    let oldest = body.find("Love Me Do").unwrap();
+    assert!(newest < oldest);
+
+    // the item links work with the passkey alone
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/torrents?id=1&passkey={PASSKEY}"))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).unwrap(),
+        "application/octet-stream"
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_title_group_tag",
+        "with_test_title_group_tag_applied",
+        "with_refreshed_title_group_hierarchy_lite"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_atom_feed_with_tag_expression(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .set_json(json!({ "name": "action", "search": {}, "tag_expression": "action" }))
+        .to_request();
+    let feed_filter =
+        call_and_read_body_json_with_status::<FeedFilter, _>(&service, req, StatusCode::CREATED)
+            .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/feeds?filter_id={}&format=atom&passkey={PASSKEY}",
+            feed_filter.id
+        ))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers().get(CONTENT_TYPE).unwrap(),
+        "application/atom+xml; charset=utf-8"
+    );
+
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#));
+    assert_eq!(body.matches("<entry>").count(), 1);
+    assert!(body.contains("Love Me Do"));
+    assert!(body.contains(r#"<category term="action"/>"#));
+    assert!(!body.contains("RollerCoaster Tycoon"));
+
+    // wildcards in the name are matched literally
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .set_json(json!({ "name": "wildcard", "search": { "title_group_name": "%" } }))
+        .to_request();
+    let feed_filter =
+        call_and_read_body_json_with_status::<FeedFilter, _>(&service, req, StatusCode::CREATED)
+            .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/feeds?filter_id={}&format=atom&passkey={PASSKEY}",
+            feed_filter.id
+        ))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(body.matches("<entry>").count(), 0);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_feed_access(pool: PgPool) {
+    sqlx::query(
+        "INSERT INTO feed_filters (id, user_id, name, search) VALUES (1000, 1, 'theirs', '{}')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/feeds/filters")
+        .set_json(json!({ "name": "mine", "search": {} }))
+        .to_request();
+    let feed_filter =
+        call_and_read_body_json_with_status::<FeedFilter, _>(&service, req, StatusCode::CREATED)
+            .await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/feeds?filter_id={}&passkey=00000000000000000000000000000000",
+            feed_filter.id
+        ))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // the passkey is only accepted on the feed and download routes
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/users/me?passkey={PASSKEY}"))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // other users' filters can't be read
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/feeds?filter_id=1000&passkey={PASSKEY}"))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_item_links_with_a_frontend_url_path() {
+    let items = [FeedItem {
+        torrent_id: 1,
+        title_group_id: 1,
+        title_group_name: "Love Me Do".into(),
+        release_name: None,
+        size: 1,
+        created_at: Utc::now(),
+        tags: Vec::new(),
+    }];
+
+    for frontend_url in ["https://site.com/app", "https://site.com/app/"] {
+        let channel = FeedChannel {
+            title: "feed",
+            self_url: "https://site.com/app/api/feeds",
+            frontend_url,
+            download_url: "https://site.com/app/api/torrents",
+            passkey: PASSKEY,
+        };
+        let body = render_rss(&channel, &items);
+        assert!(body.contains("<comments>https://site.com/app/torrent/1</comments>"));
+    }
+}
@@ -6,8 +6,8 @@ use actix_web::test;
 use arcadia_storage::connection_pool::ConnectionPool;
 use arcadia_storage::models::common::PaginatedResults;
 use arcadia_storage::models::forum::{
-    EditedForumThread, ForumPost, ForumPostHierarchy, ForumSubCategoryHierarchy, ForumThread,
-    ForumThreadEnriched, UserCreatedForumPost, UserCreatedForumThread,
+    EditedForumThread, ForumPost, ForumPostHierarchy, ForumSearchResult, ForumSubCategoryHierarchy,
+    ForumThread, ForumThreadEnriched, UserCreatedForumPost, UserCreatedForumThread,
 };
 use common::{auth_header, create_test_app_and_login, TestUser};
 use mocks::mock_redis::MockRedisPool;
@@ -1047,3 +1047,34 @@ async fn test_get_nonexistent_sub_category_threads(pool: PgPool) {
     let resp = test::call_service(&service, req).await;
     assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 }
+
+// ============================================================================
+// SEARCH THREADS TESTS
+// ============================================================================
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_forum_category",
+        "with_test_forum_sub_category",
+        "with_test_forum_thread",
+        "with_test_forum_post"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_threads_tolerates_typos(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/forum?thread_name=pined&page=1&page_size=10")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<ForumSearchResult> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 1);
+    assert_eq!(response.results[0].thread_name, "Pinned Thread");
+}
@@ -3,6 +3,7 @@ pub mod mocks;
 
 use actix_web::http::StatusCode;
 use actix_web::test;
+use arcadia_common::error::Error;
 use arcadia_storage::connection_pool::ConnectionPool;
 use arcadia_storage::models::gift::{Gift, UserCreatedGift};
 use common::{
@@ -94,3 +95,30 @@ async fn test_gift_updates_sender_and_receiver_balances_and_sends_message(pool:
     assert_eq!(gift_conversation.sender_id, 1);
     assert_eq!(gift_conversation.receiver_id, 101);
 }
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_gift_balance"),
+    migrations = "../storage/migrations"
+)]
+async fn test_gift_cannot_overdraw_freeleech_tokens(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+
+    let gift = UserCreatedGift {
+        message: "Too generous".into(),
+        receiver_id: 101,
+        bonus_points: 0,
+        freeleech_tokens: 11,
+    };
+
+    // the balance is checked by the update itself, not only by the handler
+    let result = pool.create_gift(&gift, 100).await;
+    assert!(matches!(
+        result,
+        Err(Error::NotEnoughFreeleechTokensAvailable)
+    ));
+
+    let sender = pool.find_user_with_id(100).await.unwrap();
+    assert_eq!(sender.freeleech_tokens, 10);
+    let receiver = pool.find_user_with_id(101).await.unwrap();
+    assert_eq!(receiver.freeleech_tokens, 5);
+}
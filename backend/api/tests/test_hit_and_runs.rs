@@ -0,0 +1,260 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use arcadia_common::error::Error;
+use arcadia_periodic_tasks::periodic_tasks::hit_and_runs::process_hit_and_runs;
+use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::models::hit_and_run::{HitAndRun, HitAndRunClearedBy, UserClearedHitAndRun};
+use arcadia_storage::models::user::UserCreatedUserWarning;
+use common::{auth_header, call_and_read_body_json, create_test_app_and_login, TestUser};
+use mocks::mock_redis::MockRedisPool;
+use sqlx::PgPool;
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_hit_and_runs_are_flagged_and_warned(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let hit_and_runs = pool.find_user_hit_and_runs(100).await.unwrap();
+    assert_eq!(hit_and_runs.len(), 1);
+    assert_eq!(hit_and_runs[0].torrent_id, 1);
+    assert!(hit_and_runs[0].cleared_at.is_none());
+
+    assert!(pool.find_user_hit_and_runs(101).await.unwrap().is_empty());
+
+    // the threshold is 1 hit and run
+    assert!(pool.is_user_warned(100).await.unwrap());
+    assert!(!pool.is_user_warned(101).await.unwrap());
+    let warnings = pool.find_user_warnings(100).await;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].reason, "Hit and runs: 1");
+    assert_eq!(warnings[0].created_by_id, 1);
+    assert!(!warnings[0].ban);
+
+    // running the task again neither flags nor warns twice
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+    assert_eq!(pool.find_user_hit_and_runs(100).await.unwrap().len(), 1);
+    assert_eq!(pool.find_user_warnings(100).await.len(), 1);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_hit_and_run_cleared_by_seeding(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let pg_pool: &PgPool = (*pool).borrow();
+    sqlx::query(
+        "UPDATE torrent_activities SET total_seed_time = 72 * 3600 WHERE torrent_id = 1 AND user_id = 100",
+    )
+    .execute(pg_pool)
+    .await
+    .unwrap();
+
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let hit_and_runs = pool.find_user_hit_and_runs(100).await.unwrap();
+    assert_eq!(hit_and_runs.len(), 1);
+    assert!(hit_and_runs[0].cleared_at.is_some());
+    assert_eq!(
+        hit_and_runs[0].cleared_by,
+        Some(HitAndRunClearedBy::Seeding)
+    );
+
+    // back under the threshold, the warning is lifted
+    assert!(!pool.is_user_warned(100).await.unwrap());
+    let warnings = pool.find_user_warnings(100).await;
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].removed_at.is_some());
+    assert_eq!(warnings[0].removed_by_id, Some(1));
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_already_warned_user_is_warned_for_hit_and_runs(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+
+    pool.create_user_warning(
+        101,
+        &UserCreatedUserWarning {
+            user_id: 100,
+            expires_at: None,
+            reason: "Spam".to_string(),
+            ban: false,
+        },
+    )
+    .await
+    .unwrap();
+
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let warnings = pool.find_user_warnings(100).await;
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.reason == "Hit and runs: 1" && warning.created_by_id == 1));
+
+    let pg_pool: &PgPool = (*pool).borrow();
+    sqlx::query(
+        "UPDATE torrent_activities SET total_seed_time = 72 * 3600 WHERE torrent_id = 1 AND user_id = 100",
+    )
+    .execute(pg_pool)
+    .await
+    .unwrap();
+
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    // only the hit and run warning is lifted
+    assert!(pool.is_user_warned(100).await.unwrap());
+    let warnings = pool.find_user_warnings(100).await;
+    for warning in warnings {
+        assert_eq!(warning.removed_at.is_some(), warning.reason != "Spam");
+    }
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_gift_balance",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_clear_hit_and_run_with_bonus_points(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let (service, user) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::Standard,
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/hit-and-runs")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let hit_and_runs: Vec<HitAndRun> = call_and_read_body_json(&service, req).await;
+    assert_eq!(hit_and_runs.len(), 1);
+
+    let req = test::TestRequest::post()
+        .uri("/api/users/hit-and-runs/clear")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserClearedHitAndRun {
+            hit_and_run_id: hit_and_runs[0].id,
+        })
+        .to_request();
+
+    let cleared: HitAndRun = call_and_read_body_json(&service, req).await;
+    assert_eq!(cleared.cleared_by, Some(HitAndRunClearedBy::BonusPoints));
+
+    let user_after = pool.find_user_with_id(100).await.unwrap();
+    assert_eq!(user_after.bonus_points, 700);
+    assert!(!pool.is_user_warned(100).await.unwrap());
+    assert!(pool.find_user_warnings(100).await[0].removed_at.is_some());
+
+    // it can't be cleared twice
+    let req = test::TestRequest::post()
+        .uri("/api/users/hit-and-runs/clear")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserClearedHitAndRun {
+            hit_and_run_id: hit_and_runs[0].id,
+        })
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_clear_hit_and_run_without_enough_bonus_points(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    process_hit_and_runs(Arc::clone(&pool)).await.unwrap();
+
+    let pg_pool: &PgPool = (*pool).borrow();
+    sqlx::query("UPDATE users SET bonus_points = 299 WHERE id = 100")
+        .execute(pg_pool)
+        .await
+        .unwrap();
+
+    let hit_and_runs = pool.find_user_hit_and_runs(100).await.unwrap();
+    let result = pool
+        .clear_hit_and_run_with_bonus_points(hit_and_runs[0].id, 100, 300, Some(1))
+        .await;
+    assert!(matches!(result, Err(Error::NotEnoughBonusPointsAvailable)));
+
+    // nothing was spent nor cleared
+    assert_eq!(pool.find_user_with_id(100).await.unwrap().bonus_points, 299);
+    let hit_and_runs = pool.find_user_hit_and_runs(100).await.unwrap();
+    assert_eq!(hit_and_runs[0].cleared_at, None);
+    assert!(pool.is_user_warned(100).await.unwrap());
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_hit_and_runs"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_regular_user_cannot_see_other_users_hit_and_runs(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/hit-and-runs?user_id=101")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
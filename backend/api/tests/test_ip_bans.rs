@@ -0,0 +1,202 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{common::PaginatedResults, ip_ban::IpBan},
+};
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use crate::{
+    common::{
+        auth_header, call_and_read_body_json, call_and_read_body_json_with_status,
+        create_test_app_and_login, login_as, TestUser, REVERSE_PROXY_ADDR,
+    },
+    mocks::mock_redis::MockRedisPool,
+};
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_ip_ban_lifecycle(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::ManageIpBans).await;
+
+    // host bits are dropped from ranges
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({ "ip_range": "10.20.30.40/16", "reason": " proxy network " }))
+        .to_request();
+    let ip_ban =
+        call_and_read_body_json_with_status::<IpBan, _>(&service, req, StatusCode::CREATED).await;
+    assert_eq!(ip_ban.ip_range.to_string(), "10.20.0.0/16");
+    assert_eq!(ip_ban.reason, "proxy network");
+    assert_eq!(ip_ban.expires_at, None);
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({ "ip_range": "10.30.0.1", "reason": "" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // expired bans are hidden unless asked for
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({
+            "ip_range": "10.20.1.1",
+            "reason": "old",
+            "expires_at": "2020-01-01T00:00:00Z",
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    for (query, expected_count) in [
+        ("ip=10.20.1.1", 1),
+        ("ip=10.20.1.1&include_expired=true", 2),
+        ("ip=10.21.0.1", 0),
+    ] {
+        let req = TestRequest::get()
+            .insert_header(auth_header(&staff.token))
+            .uri(&format!("/api/ip-bans?{query}&page=1&page_size=10"))
+            .to_request();
+        let ip_bans = call_and_read_body_json::<PaginatedResults<IpBan>, _>(&service, req).await;
+        assert_eq!(ip_bans.total_items, expected_count, "{query}");
+    }
+
+    for expected_status in [StatusCode::OK, StatusCode::NOT_FOUND] {
+        let req = TestRequest::delete()
+            .insert_header(auth_header(&staff.token))
+            .uri(&format!("/api/ip-bans/{}", ip_ban.id))
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), expected_status);
+    }
+
+    // staff only
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/ip-bans?page=1&page_size=10")
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_banned_ip_cannot_login_or_register(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::ManageIpBans).await;
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({ "ip_range": "10.40.0.0/24", "reason": "ban evasion" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.40.0.7"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(TestUser::Standard.get_login_payload())
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // the same client seen as an ipv4-mapped ipv6 address
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "::ffff:10.40.0.7"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/login")
+        .set_json(TestUser::Standard.get_login_payload())
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let register = json!({
+        "username": "evader",
+        "password": "TestPassword123",
+        "password_verify": "TestPassword123",
+        "email": "evader@testdomain.com",
+    });
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.40.0.8"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/register")
+        .set_json(&register)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.40.0.9"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/apply")
+        .set_json(json!({ "body": "let me in", "email": "evader@testdomain.com", "referral": "" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // addresses outside of the range are not affected
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.40.1.1"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/register")
+        .set_json(&register)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_forged_forwarded_header_does_not_get_around_a_ban(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::ManageIpBans).await;
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({ "ip_range": "10.40.0.0/24", "reason": "ban evasion" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // connecting directly, the header is not trusted
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "1.2.3.4"))
+        .peer_addr(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(10, 40, 0, 7)),
+            50000,
+        ))
+        .uri("/api/auth/login")
+        .set_json(TestUser::Standard.get_login_payload())
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // through the reverse proxy, which appends the address it sees to the forged one
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "1.2.3.4, 10.40.0.7"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/apply")
+        .set_json(json!({ "body": "let me in", "email": "evader@testdomain.com", "referral": "" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,204 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_api::services::auth::Auth;
+use arcadia_storage::connection_pool::ConnectionPool;
+use serde_json::json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    common::{
+        auth_header, create_test_app, create_test_app_and_login, TestUser, REVERSE_PROXY_ADDR,
+    },
+    mocks::mock_redis::{MockRedis, MockRedisPool},
+};
+
+const NEW_PASSWORD: &str = "NewTestPassword123";
+
+fn reset_tokens(redis: &MockRedis) -> Vec<String> {
+    redis
+        .keys_with_prefix("password_reset:")
+        .into_iter()
+        .map(|key| key.trim_start_matches("password_reset:").to_string())
+        .collect()
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_password_reset_revokes_sessions(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let redis = MockRedis::default();
+    let (service, user) = create_test_app_and_login(
+        pool,
+        MockRedisPool::with_conn(redis.clone()),
+        TestUser::Standard,
+    )
+    .await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/forgot-password")
+        .set_json(json!({ "email": "test_user@testdomain.com" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let tokens = reset_tokens(&redis);
+    assert_eq!(tokens.len(), 1);
+
+    // the new password is validated before the token is used up
+    let req = TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({
+            "token": tokens[0],
+            "new_password": "short",
+            "new_password_verify": "short",
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({
+            "token": tokens[0],
+            "new_password": NEW_PASSWORD,
+            "new_password_verify": NEW_PASSWORD,
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // existing sessions are revoked
+    let req = TestRequest::get()
+        .uri("/api/users/me")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // the token can only be used once
+    let req = TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({
+            "token": tokens[0],
+            "new_password": NEW_PASSWORD,
+            "new_password_verify": NEW_PASSWORD,
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // only the new password works
+    let mut login = TestUser::Standard.get_login_payload();
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(&login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    login.password = NEW_PASSWORD.into();
+    let req = TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(&login)
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_forgot_password_unknown_email(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let redis = MockRedis::default();
+    let service = create_test_app(pool, MockRedisPool::with_conn(redis.clone())).await;
+
+    // same answer as for a registered email
+    let req = TestRequest::post()
+        .uri("/api/auth/forgot-password")
+        .set_json(json!({ "email": "nobody@testdomain.com" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    assert!(reset_tokens(&redis).is_empty());
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_forgot_password_ignores_email_case(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let redis = MockRedis::default();
+    let service = create_test_app(pool, MockRedisPool::with_conn(redis.clone())).await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/forgot-password")
+        .set_json(json!({ "email": "Test_User@TestDomain.com" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    assert_eq!(reset_tokens(&redis).len(), 1);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_reset_password_invalid_token(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let service = create_test_app(pool, MockRedisPool::default()).await;
+
+    let req = TestRequest::post()
+        .uri("/api/auth/reset-password")
+        .set_json(json!({
+            "token": "not_a_token",
+            "new_password": NEW_PASSWORD,
+            "new_password_verify": NEW_PASSWORD,
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn test_password_reset_token_is_consumed_once() {
+    let auth = Auth::new(Arc::new(MockRedisPool::default()));
+    let token = auth.create_password_reset_token(100).await.unwrap();
+
+    let (first, second) = tokio::join!(
+        auth.consume_password_reset_token(&token),
+        auth.consume_password_reset_token(&token)
+    );
+    assert_eq!(first.unwrap(), Some(100));
+    assert_eq!(second.unwrap(), None);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_forgot_password_rate_limit(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let redis = MockRedis::default();
+    let service = create_test_app(pool, MockRedisPool::with_conn(redis.clone())).await;
+
+    // the email is limited whatever the ip and the case
+    for attempt in 0..3 {
+        let req = TestRequest::post()
+            .insert_header(("X-Forwarded-For", format!("10.10.4.{attempt}")))
+            .peer_addr(REVERSE_PROXY_ADDR)
+            .uri("/api/auth/forgot-password")
+            .set_json(json!({ "email": "test_user@testdomain.com" }))
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let req = TestRequest::post()
+        .insert_header(("X-Forwarded-For", "10.10.4.3"))
+        .peer_addr(REVERSE_PROXY_ADDR)
+        .uri("/api/auth/forgot-password")
+        .set_json(json!({ "email": "Test_User@TestDomain.com" }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    assert_eq!(reset_tokens(&redis).len(), 3);
+}
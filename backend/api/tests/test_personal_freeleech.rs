@@ -0,0 +1,122 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::http::StatusCode;
+use actix_web::test;
+use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::models::personal_freeleech::{
+    PersonalFreeleech, UserCreatedPersonalFreeleech,
+};
+use chrono::{Duration, Utc};
+use common::{
+    auth_header, call_and_read_body_json_with_status, create_test_app_and_login, TestUser,
+};
+use mocks::mock_redis::MockRedisPool;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_gift_balance",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_use_freeleech_token(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::Standard,
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/torrents/freeleech-token")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserCreatedPersonalFreeleech { torrent_id: 1 })
+        .to_request();
+
+    let personal_freeleech: PersonalFreeleech =
+        call_and_read_body_json_with_status(&service, req, StatusCode::CREATED).await;
+
+    assert_eq!(personal_freeleech.user_id, 100);
+    assert_eq!(personal_freeleech.torrent_id, 1);
+    // default duration is 24 hours
+    let expected_expiry = Utc::now() + Duration::hours(24);
+    assert!((personal_freeleech.expires_at - expected_expiry).abs() < Duration::minutes(1));
+
+    let user_after = pool.find_user_with_id(100).await.unwrap();
+    assert_eq!(user_after.freeleech_tokens, 9);
+
+    // spending another token on the same torrent extends the window
+    let req = test::TestRequest::post()
+        .uri("/api/torrents/freeleech-token")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserCreatedPersonalFreeleech { torrent_id: 1 })
+        .to_request();
+
+    let extended: PersonalFreeleech =
+        call_and_read_body_json_with_status(&service, req, StatusCode::CREATED).await;
+
+    assert_eq!(
+        extended.expires_at,
+        personal_freeleech.expires_at + Duration::hours(24)
+    );
+
+    let user_after = pool.find_user_with_id(100).await.unwrap();
+    assert_eq!(user_after.freeleech_tokens, 8);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_use_freeleech_token_without_tokens(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/torrents/freeleech-token")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserCreatedPersonalFreeleech { torrent_id: 1 })
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_gift_balance"),
+    migrations = "../storage/migrations"
+)]
+async fn test_use_freeleech_token_on_unknown_torrent(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::Standard,
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/torrents/freeleech-token")
+        .insert_header(auth_header(&user.token))
+        .set_json(UserCreatedPersonalFreeleech { torrent_id: 999 })
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let user_after = pool.find_user_with_id(100).await.unwrap();
+    assert_eq!(user_after.freeleech_tokens, 10);
+}
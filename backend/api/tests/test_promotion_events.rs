@@ -0,0 +1,206 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_common::services::tracker_client::TrackerMutation;
+use arcadia_shared::tracker::models::promotion_event::{PromotionEventScope, TagClause};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{arcadia_settings::PublicArcadiaSettings, promotion_event::PromotionEvent},
+};
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::{types::Json, PgPool};
+use std::sync::Arc;
+
+use crate::{
+    common::{
+        auth_header, call_and_read_body_json, call_and_read_body_json_with_status,
+        create_test_app_and_login, login_as, TestUser,
+    },
+    mocks::mock_redis::MockRedisPool,
+};
+
+async fn queued_tracker_mutation(pool: &PgPool, key: &str) -> TrackerMutation {
+    let (mutation,): (Json<TrackerMutation>,) =
+        sqlx::query_as("SELECT mutation FROM tracker_outbox WHERE key = $1")
+            .bind(key)
+            .fetch_one(pool)
+            .await
+            .expect("Failed to query the tracker outbox");
+    mutation.0
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_title_group_tag",
+        "with_test_title_group_tag_applied"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_scheduled_promotion_events(pg_pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pg_pool.clone()));
+    let (service, staff) = create_test_app_and_login(
+        pool,
+        MockRedisPool::default(),
+        TestUser::ManagePromotionEvents,
+    )
+    .await;
+
+    let starts_at = Utc::now() + Duration::days(1);
+    let ends_at = starts_at + Duration::days(2);
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/promotion-events")
+        .set_json(json!({
+            "name": "Weekend freeleech",
+            "starts_at": ends_at,
+            "ends_at": starts_at,
+            "upload_factor": 100,
+            "download_factor": 0,
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/promotion-events")
+        .set_json(json!({
+            "name": "Weekend hundredfold upload",
+            "starts_at": starts_at,
+            "ends_at": ends_at,
+            "upload_factor": 10000,
+            "download_factor": 100,
+        }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/promotion-events")
+        .set_json(json!({
+            "name": "Action music double upload",
+            "starts_at": starts_at,
+            "ends_at": ends_at,
+            "upload_factor": 200,
+            "download_factor": 100,
+            "content_type": "music",
+            "tag_expression": "action",
+        }))
+        .to_request();
+    let scoped = call_and_read_body_json_with_status::<PromotionEvent, _>(
+        &service,
+        req,
+        StatusCode::CREATED,
+    )
+    .await;
+    assert_eq!(scoped.tag_expression.as_deref(), Some("action"));
+
+    // the tracker matches the scope against the torrents when they are announced
+    let TrackerMutation::UpsertPromotionEvent(tracked) =
+        queued_tracker_mutation(&pg_pool, &format!("promotion_event:{}", scoped.id)).await
+    else {
+        panic!("the event should be sent to the tracker");
+    };
+    assert_eq!(tracked.upload_factor, 200);
+    assert_eq!(
+        tracked.scope,
+        PromotionEventScope {
+            content_type: Some("music".to_owned()),
+            tag_filter: Some(vec![TagClause {
+                include: vec!["action".to_owned()],
+                exclude: vec![],
+            }]),
+            ..Default::default()
+        }
+    );
+
+    let req = TestRequest::put()
+        .insert_header(auth_header(&staff.token))
+        .uri(&format!("/api/promotion-events/{}", scoped.id))
+        .set_json(json!({
+            "name": "Non-action music double upload",
+            "starts_at": starts_at,
+            "ends_at": ends_at,
+            "upload_factor": 200,
+            "download_factor": 100,
+            "content_type": "music",
+            "tag_expression": "!action",
+        }))
+        .to_request();
+    call_and_read_body_json::<PromotionEvent, _>(&service, req).await;
+    let TrackerMutation::UpsertPromotionEvent(tracked) =
+        queued_tracker_mutation(&pg_pool, &format!("promotion_event:{}", scoped.id)).await
+    else {
+        panic!("the event should be sent to the tracker");
+    };
+    assert_eq!(
+        tracked.scope.tag_filter,
+        Some(vec![TagClause {
+            include: vec![],
+            exclude: vec!["action".to_owned()],
+        }])
+    );
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/promotion-events")
+        .set_json(json!({
+            "name": "Weekend freeleech",
+            "starts_at": starts_at,
+            "ends_at": ends_at,
+            "upload_factor": 100,
+            "download_factor": 0,
+        }))
+        .to_request();
+    let site_wide = call_and_read_body_json_with_status::<PromotionEvent, _>(
+        &service,
+        req,
+        StatusCode::CREATED,
+    )
+    .await;
+    let TrackerMutation::UpsertPromotionEvent(tracked) =
+        queued_tracker_mutation(&pg_pool, &format!("promotion_event:{}", site_wide.id)).await
+    else {
+        panic!("the event should be sent to the tracker");
+    };
+    assert_eq!(tracked.scope, PromotionEventScope::default());
+
+    // upcoming events are announced to everybody
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/arcadia-settings/public")
+        .to_request();
+    let settings = call_and_read_body_json::<PublicArcadiaSettings, _>(&service, req).await;
+    assert_eq!(settings.promotion_events.len(), 2);
+
+    let req = TestRequest::delete()
+        .insert_header(auth_header(&staff.token))
+        .uri(&format!("/api/promotion-events/{}", site_wide.id))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        queued_tracker_mutation(&pg_pool, &format!("promotion_event:{}", site_wide.id)).await,
+        TrackerMutation::DeletePromotionEvent { id: site_wide.id }
+    );
+
+    // staff only
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/promotion-events?page=1&page_size=10")
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
@@ -76,3 +76,47 @@ async fn test_search_wiki_includes_body(pool: sqlx::PgPool) {
     assert_eq!(response.total_items, 1);
     assert_eq!(response.results[0].title, "Upload Guidelines");
 }
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_wiki_articles"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_wiki_orders_by_relevance(pool: sqlx::PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    // "guide" is a whole word of "Formatting Guide" but only a part of "Upload Guidelines"
+    let req = test::TestRequest::get()
+        .uri("/api/search/wiki?search_string=guide&title_only=true&page=1&page_size=10")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<WikiSearchResult> =
+        call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 2);
+    assert_eq!(response.results[0].title, "Formatting Guide");
+    assert_eq!(response.results[1].title, "Upload Guidelines");
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_wiki_articles"),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_wiki_tolerates_typos(pool: sqlx::PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/wiki?search_string=guidlines&title_only=true&page=1&page_size=10")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let response: PaginatedResults<WikiSearchResult> =
+        call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(response.total_items, 1);
+    assert_eq!(response.results[0].title, "Upload Guidelines");
+}
@@ -1,7 +1,7 @@
 pub mod common;
 pub mod mocks;
 
-use std::sync::Arc;
+use std::{path::Path, str::FromStr, sync::Arc};
 
 use actix_http::Request;
 use actix_web::{
@@ -9,6 +9,7 @@ use actix_web::{
     http::StatusCode,
     test, Error,
 };
+use arcadia_shared::tracker::models::torrent::InfoHash;
 use arcadia_storage::{
     connection_pool::ConnectionPool,
     models::{
@@ -18,6 +19,7 @@ use arcadia_storage::{
         torrent::{TorrentSearch, TorrentSearchOrderByColumn},
     },
 };
+use bip_metainfo::{Info, Metainfo};
 use mocks::mock_redis::MockRedisPool;
 use serde::{de::DeserializeOwned, Deserialize};
 use sqlx::PgPool;
@@ -25,6 +27,25 @@ use sqlx::PgPool;
 use crate::common::{auth_header, TestUser};
 
 async fn upload_test_torrent<S, T>(service: &S, token: &str, release_group: &str) -> T
+where
+    S: Service<Request, Response = ServiceResponse, Error = Error>,
+    T: DeserializeOwned,
+{
+    upload_torrent_file(
+        service,
+        token,
+        release_group,
+        include_bytes!("data/debian-12.10.0-i386-netinst.iso.torrent"),
+    )
+    .await
+}
+
+async fn upload_torrent_file<S, T>(
+    service: &S,
+    token: &str,
+    release_group: &str,
+    torrent_file: &'static [u8],
+) -> T
 where
     S: Service<Request, Response = ServiceResponse, Error = Error>,
     T: DeserializeOwned,
@@ -53,9 +74,7 @@ where
     form.add_text("extras", "");
     form.add_text("bonus_points_snatch_cost", "0");
 
-    let torrent_data = bytes::Bytes::from_static(include_bytes!(
-        "data/debian-12.10.0-i386-netinst.iso.torrent"
-    ));
+    let torrent_data = bytes::Bytes::from_static(torrent_file);
 
     form.add_reader_file(
         "torrent_file",
@@ -183,6 +202,108 @@ async fn test_upload_torrent(pool: PgPool) {
     );
 }
 
+const HYBRID_TORRENT: &[u8] = include_bytes!("data/arcadia-hybrid-test.torrent");
+
+#[actix_web::test]
+async fn test_hybrid_torrent_metainfo_round_trip() {
+    let metainfo = Metainfo::from_bytes(HYBRID_TORRENT).unwrap();
+    let info = metainfo.info();
+
+    assert!(info.is_hybrid());
+    assert_eq!(
+        info.info_hash().as_ref(),
+        InfoHash::from_str("08f33445d4c0d0ac64b9034b437ad1372c6d49a5")
+            .unwrap()
+            .0
+    );
+    // v2 clients announce with the info hash v2 truncated to 20 bytes
+    assert_eq!(
+        info.info_hash_v2().unwrap().truncated().as_ref(),
+        InfoHash::from_str("8224aa2d2cabe58a2bcbc6cddf1e9a76a62c3272")
+            .unwrap()
+            .0
+    );
+    // the padding file aligning cover.jpg on a piece is only part of the v1 files
+    assert_eq!(info.files().count(), 3);
+    let files: Vec<(u64, &Path)> = info
+        .files()
+        .filter(|file| !file.is_padding())
+        .map(|file| (file.length(), file.path()))
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            (40000, Path::new("a.flac")),
+            (10000, Path::new("cover.jpg"))
+        ]
+    );
+    // only a.flac spans more than one piece
+    assert_eq!(metainfo.piece_layers().unwrap().iter().count(), 1);
+
+    // building the file again gives the exact same bytes, so the info hashes are kept
+    assert_eq!(metainfo.to_bytes(), HYBRID_TORRENT);
+    assert_eq!(
+        Info::from_bytes(info.to_bytes()).unwrap().info_hash_v2(),
+        info.info_hash_v2()
+    );
+}
+
+#[sqlx::test(
+    fixtures("with_test_users", "with_test_title_group", "with_test_edition_group"),
+    migrations = "../storage/migrations"
+)]
+async fn test_upload_and_download_hybrid_torrent(pool: PgPool) {
+    let pg_pool = pool.clone();
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        common::create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    #[derive(Debug, Deserialize)]
+    struct Torrent {
+        id: i32,
+        size: i64,
+    }
+
+    let torrent: Torrent =
+        upload_torrent_file(&service, &user.token, "TESTGRoUP", HYBRID_TORRENT).await;
+
+    // padding files are not counted in the size
+    assert_eq!(torrent.size, 50000);
+
+    let (info_hash, info_hash_v2): (Vec<u8>, Option<Vec<u8>>) =
+        sqlx::query_as("SELECT info_hash, info_hash_v2 FROM torrents WHERE id = $1")
+            .bind(torrent.id)
+            .fetch_one(&pg_pool)
+            .await
+            .unwrap();
+
+    let req = test::TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri(&format!("/api/torrents?id={}", torrent.id))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let downloaded = Metainfo::from_bytes(test::read_body(resp).await).unwrap();
+    let uploaded = Metainfo::from_bytes(HYBRID_TORRENT).unwrap();
+
+    // the downloaded file is still hybrid, and announces with the stored hashes
+    assert!(downloaded.info().is_hybrid());
+    assert_eq!(downloaded.info().is_private(), Some(true));
+    assert_eq!(downloaded.info().info_hash().as_ref(), &info_hash[..]);
+    assert_eq!(
+        downloaded.info().info_hash_v2().as_ref().map(AsRef::as_ref),
+        info_hash_v2.as_deref()
+    );
+    assert_eq!(downloaded.piece_layers(), uploaded.piece_layers());
+    assert_eq!(downloaded.info().file_tree(), uploaded.info().file_tree());
+    assert_eq!(
+        downloaded.info().pieces().collect::<Vec<_>>(),
+        uploaded.info().pieces().collect::<Vec<_>>()
+    );
+}
+
 #[sqlx::test(
     fixtures("with_test_users", "with_test_title_group", "with_test_edition_group"),
     migrations = "../storage/migrations"
@@ -438,6 +559,7 @@ async fn test_find_torrents_by_external_link(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -496,6 +618,7 @@ async fn test_find_torrents_by_external_link_with_trailing_slash(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -554,6 +677,7 @@ async fn test_find_torrents_by_name(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -612,6 +736,7 @@ async fn test_find_torrents_no_link_or_name_provided(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -1134,6 +1259,7 @@ async fn test_search_torrents_by_own_bookmarks(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -1195,6 +1321,7 @@ async fn test_create_bookmark_then_search(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -1287,6 +1414,7 @@ async fn test_search_torrents_by_other_user_bookmarks_forbidden(pool: PgPool) {
         torrent_created_by_id: None,
         torrent_snatched_by_id: None,
         artist_id: None,
+        entity_id: None,
         collage_id: None,
         page: 1,
         page_size: 50,
@@ -1311,3 +1439,85 @@ async fn test_search_torrents_by_other_user_bookmarks_forbidden(pool: PgPool) {
     )
     .await;
 }
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_refreshed_title_group_hierarchy_lite"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_search_torrents_suggests_close_names_when_nothing_found(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        common::create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let mut query = TorrentSearch {
+        title_group_name: Some("Rolercoster Ticon".to_string()),
+        title_group_content_type: vec![],
+        title_group_category: vec![],
+        title_group_tags: None,
+        title_group_include_empty_groups: true,
+        edition_group_source: vec![],
+        torrent_video_resolution: vec![],
+        torrent_language: vec![],
+        torrent_reported: None,
+        torrent_staff_checked: None,
+        torrent_created_by_id: None,
+        torrent_snatched_by_id: None,
+        artist_id: None,
+        entity_id: None,
+        collage_id: None,
+        page: 1,
+        page_size: 50,
+        order_by_column: TorrentSearchOrderByColumn::Relevance,
+        order_by_direction: OrderByDirection::Desc,
+        series_id: None,
+        user_id_bookmarks: None,
+    };
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/api/search/torrents/lite?{}",
+            serde_qs::to_string(&query).unwrap()
+        ))
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let results: PaginatedResults<TitleGroupHierarchyLite> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(results.total_items, 0);
+
+    let req = test::TestRequest::get()
+        .uri("/api/search/torrents/suggestions?name=Rolercoster%20Ticon")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let suggestions: Vec<String> =
+        common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+    assert_eq!(suggestions, vec!["RollerCoaster Tycoon".to_string()]);
+
+    // small typos are tolerated, and words are matched regardless of their order
+    for name in ["Rollercoster Tycon", "tycoon rollercoaster"] {
+        query.title_group_name = Some(name.to_string());
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/api/search/torrents/lite?{}",
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .insert_header(auth_header(&user.token))
+            .to_request();
+
+        let results: PaginatedResults<TitleGroupHierarchyLite> =
+            common::call_and_read_body_json_with_status(&service, req, StatusCode::OK).await;
+
+        assert_eq!(results.total_items, 1);
+        assert_eq!(results.results[0].id, 2);
+    }
+}
@@ -0,0 +1,126 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_common::services::tracker_client::TrackerMutation;
+use arcadia_periodic_tasks::periodic_tasks::tracker_sync::{
+    deliver_tracker_outbox, reconcile_tracker_state,
+};
+use arcadia_shared::tracker::models::state_digest::{bucket_of, DIGEST_BUCKETS};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{ip_ban::IpBan, tracker_outbox::TrackerStateBuckets},
+};
+use serde_json::json;
+use sqlx::{types::Json, PgPool};
+use std::sync::Arc;
+
+use crate::{
+    common::{
+        auth_header, call_and_read_body_json_with_status, create_test_app_and_login, TestUser,
+    },
+    mocks::mock_redis::MockRedisPool,
+};
+
+async fn tracker_outbox(pool: &PgPool) -> Vec<(String, Json<TrackerMutation>, i64, i32)> {
+    sqlx::query_as("SELECT key, mutation, revision, attempts FROM tracker_outbox ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .expect("Failed to query the tracker outbox")
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_tracker_mutations_are_queued_and_retried(pg_pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pg_pool.clone()));
+    let (service, staff) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::ManageIpBans,
+    )
+    .await;
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/ip-bans")
+        .set_json(json!({ "ip_range": "10.20.0.0/16", "reason": "proxy network" }))
+        .to_request();
+    let ip_ban =
+        call_and_read_body_json_with_status::<IpBan, _>(&service, req, StatusCode::CREATED).await;
+
+    let outbox = tracker_outbox(&pg_pool).await;
+    assert_eq!(outbox.len(), 1);
+    assert_eq!(outbox[0].0, format!("ip_ban:{}", ip_ban.id));
+    assert!(matches!(*outbox[0].1, TrackerMutation::UpsertIpBan(_)));
+
+    // no tracker is listening, the mutation stays queued
+    assert_eq!(deliver_tracker_outbox(Arc::clone(&pool)).await.unwrap(), 1);
+    let outbox = tracker_outbox(&pg_pool).await;
+    assert_eq!(outbox[0].3, 1);
+    // not due yet
+    assert_eq!(deliver_tracker_outbox(Arc::clone(&pool)).await.unwrap(), 0);
+
+    // lifting the ban replaces the pending insertion
+    let req = TestRequest::delete()
+        .insert_header(auth_header(&staff.token))
+        .uri(&format!("/api/ip-bans/{}", ip_ban.id))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let outbox = tracker_outbox(&pg_pool).await;
+    assert_eq!(outbox.len(), 1);
+    assert_eq!(*outbox[0].1, TrackerMutation::DeleteIpBan { id: ip_ban.id });
+    assert_eq!((outbox[0].2, outbox[0].3), (1, 0));
+
+    // nothing can be compared without the tracker
+    assert_eq!(reconcile_tracker_state(Arc::clone(&pool)).await.unwrap(), 0);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_tracker_state_digest(pg_pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pg_pool.clone()));
+
+    let digest = pool.find_tracker_state_digest().await.unwrap();
+    assert_eq!(digest, pool.find_tracker_state_digest().await.unwrap());
+
+    let every_user = TrackerStateBuckets {
+        users: (0..DIGEST_BUCKETS as i32).collect(),
+        torrents: Vec::new(),
+    };
+    let state = pool.find_tracker_state(&every_user).await.unwrap();
+    assert_eq!(digest.users.count, state.users.len() as u64);
+    assert!(state.torrents.is_empty());
+
+    // banned users can't announce anymore
+    let user_id = state.users.iter().find(|user| !user.banned).unwrap().id;
+    sqlx::query("UPDATE users SET banned = TRUE WHERE id = $1")
+        .bind(user_id as i32)
+        .execute(&pg_pool)
+        .await
+        .unwrap();
+    let banned_digest = pool.find_tracker_state_digest().await.unwrap();
+    let bucket = bucket_of(user_id as u64);
+    assert_eq!(
+        banned_digest.users.mismatched_buckets(&digest.users),
+        [bucket].into()
+    );
+    assert_eq!(banned_digest.torrents, digest.torrents);
+
+    // only the users of that bucket are read to be sent again
+    let buckets = TrackerStateBuckets {
+        users: vec![bucket as i32],
+        torrents: Vec::new(),
+    };
+    let state = pool.find_tracker_state(&buckets).await.unwrap();
+    assert!(state
+        .users
+        .iter()
+        .all(|user| bucket_of(user.id as u64) == bucket));
+    assert!(state
+        .users
+        .iter()
+        .any(|user| user.id == user_id && user.banned));
+}
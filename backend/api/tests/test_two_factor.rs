@@ -0,0 +1,251 @@
+pub mod common;
+pub mod mocks;
+
+use actix_http::Request;
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, Error,
+};
+use arcadia_api::services::{auth::Auth, two_factor_service::build_totp};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{
+        two_factor::{TwoFactorChallenge, TwoFactorRecoveryCodes, TwoFactorSetup},
+        user::LoginResponse,
+    },
+};
+use common::{
+    auth_header, call_and_read_body_json, call_and_read_body_json_with_status,
+    create_test_app_and_login, TestUser,
+};
+use mocks::mock_redis::MockRedisPool;
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn current_code(secret: &str) -> String {
+    build_totp(secret, None, String::new())
+        .unwrap()
+        .generate_current()
+        .unwrap()
+}
+
+/// The code of the next time step, which is still accepted, for when the current one was used
+fn next_code(secret: &str) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    build_totp(secret, None, String::new())
+        .unwrap()
+        .generate(now.as_secs() + 30)
+}
+
+/// Sets up and enables 2FA for the logged in user, returning the secret and recovery codes
+async fn enable_two_factor<S>(service: &S, token: &str) -> (String, Vec<String>)
+where
+    S: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(token))
+        .uri("/api/auth/2fa/setup")
+        .to_request();
+    let setup = call_and_read_body_json::<TwoFactorSetup, _>(service, req).await;
+    assert!(setup.otpauth_uri.starts_with("otpauth://totp/"));
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(token))
+        .uri("/api/auth/2fa/enable")
+        .set_json(json!({ "code": current_code(&setup.secret) }))
+        .to_request();
+    let codes = call_and_read_body_json::<TwoFactorRecoveryCodes, _>(service, req).await;
+
+    (setup.secret, codes.recovery_codes)
+}
+
+async fn login_challenge<S>(service: &S, test_user: TestUser) -> String
+where
+    S: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(test_user.get_login_payload())
+        .to_request();
+    let challenge = call_and_read_body_json_with_status::<TwoFactorChallenge, _>(
+        service,
+        req,
+        StatusCode::ACCEPTED,
+    )
+    .await;
+    assert!(!challenge.challenge_token.is_empty());
+
+    challenge.challenge_token
+}
+
+async fn login_second_step<S>(service: &S, challenge_token: &str, code: &str) -> ServiceResponse
+where
+    S: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    let req = test::TestRequest::post()
+        .uri("/api/auth/2fa/login")
+        .set_json(json!({ "challenge_token": challenge_token, "code": code }))
+        .to_request();
+    test::call_service(service, req).await
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_enable_two_factor_and_login(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/auth/2fa/setup")
+        .to_request();
+    let setup = call_and_read_body_json::<TwoFactorSetup, _>(&service, req).await;
+
+    // a wrong code does not enable 2FA
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/auth/2fa/enable")
+        .set_json(json!({ "code": "000000x" }))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(!pool.is_two_factor_enabled(100).await.unwrap());
+
+    let enable_code = current_code(&setup.secret);
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/auth/2fa/enable")
+        .set_json(json!({ "code": enable_code }))
+        .to_request();
+    let codes = call_and_read_body_json::<TwoFactorRecoveryCodes, _>(&service, req).await;
+    assert_eq!(codes.recovery_codes.len(), 10);
+    assert!(pool.is_two_factor_enabled(100).await.unwrap());
+
+    // the code used to enable 2FA can't be used to log in
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+    let resp = login_second_step(&service, &challenge_token, &enable_code).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // the password alone no longer logs the user in
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+
+    let code = next_code(&setup.secret);
+    let resp = login_second_step(&service, &challenge_token, &code).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let login = common::read_body_json_data::<LoginResponse, _>(resp).await;
+    assert!(!login.token.is_empty());
+    assert!(!login.refresh_token.is_empty());
+
+    // challenges are single use
+    let resp = login_second_step(&service, &challenge_token, &code).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // and so are codes
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+    let resp = login_second_step(&service, &challenge_token, &code).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_two_factor_login_with_wrong_and_recovery_codes(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let (_, recovery_codes) = enable_two_factor(&service, &user.token).await;
+
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+    let resp = login_second_step(&service, &challenge_token, "not a code").await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // the failed attempt consumed the challenge
+    let resp = login_second_step(&service, &challenge_token, &recovery_codes[0]).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+    let resp = login_second_step(
+        &service,
+        &challenge_token,
+        &recovery_codes[0].to_uppercase(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // recovery codes only work once
+    let challenge_token = login_challenge(&service, TestUser::Standard).await;
+    let resp = login_second_step(&service, &challenge_token, &recovery_codes[0]).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_disable_two_factor(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool.clone(), MockRedisPool::default(), TestUser::Standard).await;
+
+    let (secret, _) = enable_two_factor(&service, &user.token).await;
+
+    let req = test::TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/auth/2fa/disable")
+        .set_json(json!({ "code": next_code(&secret) }))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert!(!pool.is_two_factor_enabled(100).await.unwrap());
+
+    // back to a single step login
+    common::login_as(&service, TestUser::Standard).await;
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_permission_requiring_two_factor(pool: PgPool) {
+    sqlx::query(
+        "UPDATE arcadia_settings SET two_factor_required_permissions = '{edit_arcadia_settings}'",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) = create_test_app_and_login(
+        pool,
+        MockRedisPool::default(),
+        TestUser::EditArcadiaSettings,
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/arcadia-settings")
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    enable_two_factor(&service, &user.token).await;
+
+    let req = test::TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/arcadia-settings")
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_two_factor_challenge_is_consumed_once() {
+    let auth = Auth::new(Arc::new(MockRedisPool::default()));
+    let challenge_token = auth.create_two_factor_challenge(100, false).await.unwrap();
+
+    let (first, second) = tokio::join!(
+        auth.consume_two_factor_challenge(&challenge_token),
+        auth.consume_two_factor_challenge(&challenge_token)
+    );
+    assert_eq!(first.unwrap().map(|entry| entry.user_id), Some(100));
+    assert!(second.unwrap().is_none());
+}
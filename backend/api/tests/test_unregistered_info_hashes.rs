@@ -0,0 +1,60 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::test;
+use arcadia_storage::connection_pool::ConnectionPool;
+use arcadia_storage::models::torrent::TorrentDeletionReason;
+use arcadia_storage::models::unregistered_info_hash::UnregisteredInfoHash;
+use common::{auth_header, call_and_read_body_json, create_test_app_and_login, TestUser};
+use mocks::mock_redis::MockRedisPool;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_unregistered_info_hashes"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_user_sees_their_unregistered_info_hashes(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, user) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::Standard).await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/users/unregistered-info-hashes")
+        .insert_header(auth_header(&user.token))
+        .to_request();
+
+    let unregistered_info_hashes: Vec<UnregisteredInfoHash> =
+        call_and_read_body_json(&service, req).await;
+
+    // both info hashes of torrent 2 were registered since then
+    assert_eq!(unregistered_info_hashes.len(), 2);
+
+    assert_eq!(
+        unregistered_info_hashes[0].info_hash,
+        "ffffffffffffffffffffffffffffffffffffffff"
+    );
+    assert_eq!(unregistered_info_hashes[0].torrent_id, None);
+    assert!(unregistered_info_hashes[0].deletion_reason.is_none());
+
+    assert_eq!(
+        unregistered_info_hashes[1].info_hash,
+        "112233445566778899aabbccddeeff0011223344"
+    );
+    assert_eq!(unregistered_info_hashes[1].torrent_id, Some(1));
+    assert_eq!(
+        unregistered_info_hashes[1].deletion_reason,
+        Some(TorrentDeletionReason::Trumped)
+    );
+    assert_eq!(unregistered_info_hashes[1].replacement_torrent_id, Some(2));
+    assert_eq!(
+        unregistered_info_hashes[1].title_group_name.as_deref(),
+        Some("Test Title Group")
+    );
+}
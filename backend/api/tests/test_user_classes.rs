@@ -2,6 +2,7 @@ pub mod common;
 pub mod mocks;
 
 use actix_web::{http::StatusCode, test};
+use arcadia_common::services::tracker_client::TrackerMutation;
 use arcadia_storage::{
     connection_pool::ConnectionPool,
     models::user::{
@@ -14,7 +15,7 @@ use common::{
     TestUser,
 };
 use mocks::mock_redis::MockRedisPool;
-use sqlx::PgPool;
+use sqlx::{types::Json, PgPool};
 use std::sync::Arc;
 
 // ============================================================================
@@ -50,6 +51,8 @@ async fn test_staff_can_create_user_class(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::post()
@@ -91,6 +94,8 @@ async fn test_regular_user_cannot_create_user_class(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::post()
@@ -128,6 +133,8 @@ async fn test_create_user_class_requires_auth(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::post()
@@ -166,6 +173,8 @@ async fn test_create_user_class_with_invalid_name(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::post()
@@ -211,6 +220,8 @@ async fn test_staff_can_edit_user_class(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::put()
@@ -253,6 +264,8 @@ async fn test_edit_nonexistent_user_class(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     let req = test::TestRequest::put()
@@ -527,7 +540,7 @@ async fn test_one_hop_promotion(pool: PgPool) {
     // Promote user from basic_class to advanced_class (1-hop promotion)
     // User 1000 is created in fixture with basic_class and download_torrent permission
     pool_arc
-        .change_user_class(1000, "advanced_class", false)
+        .change_user_class(1000, "advanced_class")
         .await
         .expect("Failed to change user class");
 
@@ -553,7 +566,7 @@ async fn test_one_hop_demotion(pool: PgPool) {
     // Demote user from advanced_class to basic_class (1-hop demotion)
     // User 1001 is created in fixture with advanced_class and both permissions
     pool_arc
-        .change_user_class(1001, "basic_class", false)
+        .change_user_class(1001, "basic_class")
         .await
         .expect("Failed to change user class");
 
@@ -579,7 +592,7 @@ async fn test_lateral_class_change_keeps_permissions(pool: PgPool) {
     // Change to a class that is not in the hierarchy (lateral move to newbie)
     // User 1002 is created in fixture with basic_class and download_torrent permission
     pool_arc
-        .change_user_class(1002, "newbie", false)
+        .change_user_class(1002, "newbie")
         .await
         .expect("Failed to change user class");
 
@@ -626,6 +639,8 @@ async fn test_edit_class_permissions_propagate_to_classes_above(pool: PgPool) {
         required_title_group_comments: 0,
         required_seeding_size: 0,
         promotion_cost_bonus_points: 0,
+        max_leeching: None,
+        max_leeching_scaled_by_ratio: false,
     };
 
     pool_arc
@@ -677,6 +692,50 @@ async fn test_edit_class_permissions_propagate_to_classes_above(pool: PgPool) {
         .contains(&UserPermission::EditWikiArticle));
 }
 
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_edit_class_leeching_slots_are_sent_to_tracker(pool: PgPool) {
+    let pool_arc = Arc::new(ConnectionPool::with_pg_pool(pool.clone()));
+
+    let edited = EditedUserClass {
+        name: "newbie".into(),
+        new_permissions: vec![],
+        max_snatches_per_day: None,
+        automatic_promotion: true,
+        automatic_demotion: true,
+        promotion_allowed_while_warned: false,
+        previous_user_class: None,
+        required_account_age_in_days: 0,
+        required_ratio: 0.0,
+        required_torrent_uploads: 0,
+        required_torrent_uploads_in_unique_title_groups: 0,
+        required_uploaded: 0,
+        required_torrent_snatched: 0,
+        required_downloaded: 0,
+        required_forum_posts: 0,
+        required_forum_posts_in_unique_threads: 0,
+        required_title_group_comments: 0,
+        required_seeding_size: 0,
+        promotion_cost_bonus_points: 0,
+        max_leeching: Some(4),
+        max_leeching_scaled_by_ratio: true,
+    };
+    pool_arc
+        .update_user_class("newbie", &edited)
+        .await
+        .expect("Failed to update user class");
+
+    let (mutation,): (Json<TrackerMutation>,) =
+        sqlx::query_as("SELECT mutation FROM tracker_outbox WHERE key = 'user:100'")
+            .fetch_one(&pool)
+            .await
+            .expect("the user should be sent to the tracker");
+    let TrackerMutation::UpsertUser(user) = mutation.0 else {
+        panic!("expected the user to be upserted, got {:?}", mutation.0);
+    };
+    // nothing uploaded yet, a single slot is kept
+    assert_eq!(user.max_leeching, Some(1));
+}
+
 #[sqlx::test(
     fixtures("with_test_users", "with_hierarchy_user_classes"),
     migrations = "../storage/migrations"
@@ -687,7 +746,7 @@ async fn test_deduplicates_permissions(pool: PgPool) {
     // Change to advanced_class (should deduplicate during the update)
     // User 1003 is created in fixture with basic_class and duplicate permissions
     pool_arc
-        .change_user_class(1003, "advanced_class", false)
+        .change_user_class(1003, "advanced_class")
         .await
         .expect("Failed to change user class");
 
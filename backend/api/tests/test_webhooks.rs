@@ -0,0 +1,309 @@
+pub mod common;
+pub mod mocks;
+
+use actix_web::{
+    http::StatusCode,
+    test::{call_service, TestRequest},
+};
+use arcadia_periodic_tasks::periodic_tasks::webhooks::{
+    build_webhooks_http_client, deliver_webhooks, sign_payload,
+};
+use arcadia_storage::{
+    connection_pool::ConnectionPool,
+    models::{
+        common::PaginatedResults,
+        torrent::TorrentDeletionReason,
+        webhook::{Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent, WebhookTrigger},
+    },
+};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use crate::{
+    common::{
+        auth_header, call_and_read_body_json, call_and_read_body_json_with_status,
+        create_test_app_and_login, login_as, TestUser,
+    },
+    mocks::mock_redis::MockRedisPool,
+};
+
+#[test]
+fn test_sign_payload() {
+    assert_eq!(
+        sign_payload("key", b"The quick brown fox jumps over the lazy dog"),
+        "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+}
+
+#[sqlx::test(fixtures("with_test_users"), migrations = "../storage/migrations")]
+async fn test_webhook_lifecycle(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) =
+        create_test_app_and_login(pool, MockRedisPool::default(), TestUser::ManageWebhooks).await;
+
+    for invalid in [
+        json!({ "name": "bot", "url": "not a url", "secret": "s", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "ftp://bots.example", "secret": "s", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "https://bots.example", "secret": " ", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "https://bots.example", "secret": "s", "events": [], "enabled": true }),
+        // internal services cannot be targeted
+        json!({ "name": "bot", "url": "http://127.0.0.1:8080/hook", "secret": "s", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "http://169.254.169.254/latest", "secret": "s", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "http://[::1]/hook", "secret": "s", "events": ["forum_post"], "enabled": true }),
+        json!({ "name": "bot", "url": "http://localhost/hook", "secret": "s", "events": ["forum_post"], "enabled": true }),
+    ] {
+        let req = TestRequest::post()
+            .insert_header(auth_header(&staff.token))
+            .uri("/api/webhooks")
+            .set_json(&invalid)
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{invalid}");
+    }
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/webhooks")
+        .set_json(json!({
+            "name": "irc announcer",
+            "url": "https://bots.example/hook",
+            "secret": "very secret",
+            "events": ["torrent_uploaded", "torrent_deleted"],
+            "enabled": true,
+        }))
+        .to_request();
+    let webhook =
+        call_and_read_body_json_with_status::<Webhook, _>(&service, req, StatusCode::CREATED).await;
+    assert_eq!(
+        webhook.events,
+        vec![WebhookEvent::TorrentUploaded, WebhookEvent::TorrentDeleted]
+    );
+
+    // secrets are never sent back
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/webhooks")
+        .to_request();
+    let webhooks = call_and_read_body_json::<Vec<Value>, _>(&service, req).await;
+    assert_eq!(webhooks.len(), 1);
+    assert!(webhooks[0].get("secret").is_none());
+
+    let req = TestRequest::put()
+        .insert_header(auth_header(&staff.token))
+        .uri(&format!("/api/webhooks/{}", webhook.id))
+        .set_json(json!({
+            "name": "irc announcer",
+            "url": "https://bots.example/hook",
+            "events": ["torrent_uploaded"],
+            "enabled": false,
+        }))
+        .to_request();
+    let edited = call_and_read_body_json::<Webhook, _>(&service, req).await;
+    assert_eq!(edited.events, vec![WebhookEvent::TorrentUploaded]);
+    assert!(!edited.enabled);
+
+    for expected_status in [StatusCode::OK, StatusCode::NOT_FOUND] {
+        let req = TestRequest::delete()
+            .insert_header(auth_header(&staff.token))
+            .uri(&format!("/api/webhooks/{}", webhook.id))
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), expected_status);
+    }
+
+    // staff only
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::get()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/webhooks")
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_forum_category",
+        "with_test_forum_sub_category",
+        "with_test_forum_thread"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_forum_posts_are_queued_and_retried(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::ManageWebhooks,
+    )
+    .await;
+
+    // the reserved .invalid domain never resolves, so the delivery fails
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/webhooks")
+        .set_json(json!({
+            "name": "matrix bot",
+            "url": "http://bots.invalid/hook",
+            "secret": "very secret",
+            "events": ["forum_post"],
+            "forum_sub_category_ids": [100],
+            "enabled": true,
+        }))
+        .to_request();
+    let webhook =
+        call_and_read_body_json_with_status::<Webhook, _>(&service, req, StatusCode::CREATED).await;
+
+    // only the thread in the chosen sub-category is delivered
+    let user = login_as(&service, TestUser::Standard).await;
+    for forum_thread_id in [100, 103] {
+        let req = TestRequest::post()
+            .insert_header(auth_header(&user.token))
+            .uri("/api/forum/post")
+            .set_json(json!({ "content": "hello", "forum_thread_id": forum_thread_id }))
+            .to_request();
+        let resp = call_service(&service, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    let search_uri = format!(
+        "/api/webhooks/deliveries?webhook_id={}&page=1&page_size=10",
+        webhook.id
+    );
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri(&search_uri)
+        .to_request();
+    let deliveries =
+        call_and_read_body_json::<PaginatedResults<WebhookDelivery>, _>(&service, req).await;
+    assert_eq!(deliveries.total_items, 1);
+    let delivery = &deliveries.results[0];
+    assert_eq!(delivery.event, WebhookEvent::ForumPost);
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.payload["event"], "forum_post");
+    assert_eq!(delivery.payload["data"]["forum_thread_id"], 100);
+    assert_eq!(delivery.payload["data"]["author"], "user_basic");
+
+    let sent_count = deliver_webhooks(Arc::clone(&pool), build_webhooks_http_client().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(sent_count, 1);
+
+    // the delivery stays pending until the next retry
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri(&search_uri)
+        .to_request();
+    let deliveries =
+        call_and_read_body_json::<PaginatedResults<WebhookDelivery>, _>(&service, req).await;
+    let delivery = &deliveries.results[0];
+    assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.response_status, None);
+    assert!(delivery.error.is_some());
+    assert!(delivery.next_attempt_at > delivery.last_attempt_at.unwrap());
+
+    // not due yet
+    let sent_count = deliver_webhooks(Arc::clone(&pool), build_webhooks_http_client().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(sent_count, 0);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_users",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_torrent_request",
+        "with_test_torrent_request_vote"
+    ),
+    migrations = "../storage/migrations"
+)]
+async fn test_torrent_events_are_queued(pool: PgPool) {
+    let pool = Arc::new(ConnectionPool::with_pg_pool(pool));
+    let (service, staff) = create_test_app_and_login(
+        Arc::clone(&pool),
+        MockRedisPool::default(),
+        TestUser::ManageWebhooks,
+    )
+    .await;
+
+    let req = TestRequest::post()
+        .insert_header(auth_header(&staff.token))
+        .uri("/api/webhooks")
+        .set_json(json!({
+            "name": "irc announcer",
+            "url": "https://bots.example/hook",
+            "secret": "very secret",
+            "events": ["torrent_uploaded", "torrent_deleted", "torrent_request_filled"],
+            "enabled": true,
+        }))
+        .to_request();
+    let webhook =
+        call_and_read_body_json_with_status::<Webhook, _>(&service, req, StatusCode::CREATED).await;
+
+    pool.enqueue_webhooks(WebhookTrigger::TorrentUploaded { torrent_id: 1 })
+        .await;
+    pool.enqueue_webhooks(WebhookTrigger::TorrentDeleted {
+        torrent_id: 1,
+        deletion_reason: TorrentDeletionReason::Trumped,
+    })
+    .await;
+
+    let user = login_as(&service, TestUser::Standard).await;
+    let req = TestRequest::post()
+        .insert_header(auth_header(&user.token))
+        .uri("/api/torrent-requests/fill")
+        .set_json(json!({ "torrent_request_id": 1, "torrent_id": 1 }))
+        .to_request();
+    let resp = call_service(&service, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let req = TestRequest::get()
+        .insert_header(auth_header(&staff.token))
+        .uri(&format!(
+            "/api/webhooks/deliveries?webhook_id={}&page=1&page_size=10",
+            webhook.id
+        ))
+        .to_request();
+    let mut deliveries =
+        call_and_read_body_json::<PaginatedResults<WebhookDelivery>, _>(&service, req)
+            .await
+            .results;
+    deliveries.sort_by_key(|delivery| delivery.id);
+    assert_eq!(
+        deliveries
+            .iter()
+            .map(|delivery| delivery.event)
+            .collect::<Vec<_>>(),
+        vec![
+            WebhookEvent::TorrentUploaded,
+            WebhookEvent::TorrentDeleted,
+            WebhookEvent::TorrentRequestFilled
+        ]
+    );
+
+    let uploaded = &deliveries[0].payload;
+    assert_eq!(uploaded["event"], "torrent_uploaded");
+    assert_eq!(uploaded["data"]["torrent_id"], 1);
+    assert_eq!(uploaded["data"]["title_group_id"], 1);
+    assert_eq!(
+        uploaded["data"]["title_group_name"],
+        "Love Me Do / P.S. I Love You"
+    );
+
+    let deleted = &deliveries[1].payload;
+    assert_eq!(deleted["event"], "torrent_deleted");
+    assert_eq!(deleted["data"]["torrent_id"], 1);
+    assert_eq!(deleted["data"]["deletion_reason"], "trumped");
+
+    let filled = &deliveries[2].payload;
+    assert_eq!(filled["event"], "torrent_request_filled");
+    assert_eq!(filled["data"]["torrent_request_id"], 1);
+    assert_eq!(filled["data"]["torrent_id"], 1);
+    assert_eq!(filled["data"]["filled_by"], "user_basic");
+}
@@ -5,6 +5,8 @@ use std::path::{Path, PathBuf};
 use bip_util::sha::ShaHash;
 use walkdir::{self, WalkDir, DirEntry};
 
+use metainfo::TreeFile;
+
 /// Trait for types convertible as a Result into some Accessor.
 pub trait IntoAccessor {
     /// Concrete Accessor type that will be converted into.
@@ -25,6 +27,25 @@ pub trait Accessor {
     /// Access the sequential pieces that make up all of the files.
     fn access_pieces<C>(&self, callback: C) -> io::Result<()>
         where C: for<'a> FnMut(PieceAccess<'a>) -> io::Result<()>;
+
+    /// Access the attributes (BEP 47) for all files, in the same order as `access_metadata`.
+    fn access_attributes<C>(&self, _callback: C) -> io::Result<()>
+        where C: FnMut(Option<&str>)
+    {
+        Ok(())
+    }
+
+    /// Access the pre computed meta version 2 (BEP 52) file tree, if there is one.
+    fn access_file_tree(&self) -> Option<&[TreeFile]> {
+        None
+    }
+
+    /// Whether or not the v1 pieces and file list should be built.
+    ///
+    /// Only accessors for meta version 2 data without a v1 part return false.
+    fn access_v1(&self) -> bool {
+        true
+    }
 }
 
 impl<'a, T> Accessor for &'a T
@@ -45,6 +66,20 @@ impl<'a, T> Accessor for &'a T
     {
         Accessor::access_pieces(*self, callback)
     }
+
+    fn access_attributes<C>(&self, callback: C) -> io::Result<()>
+        where C: FnMut(Option<&str>)
+    {
+        Accessor::access_attributes(*self, callback)
+    }
+
+    fn access_file_tree(&self) -> Option<&[TreeFile]> {
+        Accessor::access_file_tree(*self)
+    }
+
+    fn access_v1(&self) -> bool {
+        Accessor::access_v1(*self)
+    }
 }
 
 // ----------------------------------------------------------------------------//
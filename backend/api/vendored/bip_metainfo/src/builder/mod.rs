@@ -5,6 +5,7 @@ use bip_util::sha::{self, ShaHash};
 
 use accessor::{Accessor, IntoAccessor};
 use error::ParseResult;
+use metainfo::{PieceLayers, TreeFile};
 use parse;
 
 mod buffer;
@@ -159,6 +160,31 @@ impl<'a> MetainfoBuilder<'a> {
         self
     }
 
+    /// Set or unset the piece layers for a meta version 2 torrent file.
+    pub fn set_piece_layers(mut self, opt_piece_layers: Option<&'a PieceLayers>) -> MetainfoBuilder<'a> {
+        {
+            let dict_access = self.root.dict_mut().unwrap();
+
+            if let Some(piece_layers) = opt_piece_layers {
+                let mut layers = BencodeMut::new_dict();
+
+                {
+                    let layers_access = layers.dict_mut().unwrap();
+
+                    for (root, layer) in piece_layers.iter() {
+                        layers_access.insert((&root[..]).into(), ben_bytes!(&layer[..]));
+                    }
+                }
+
+                dict_access.insert(parse::PIECE_LAYERS_KEY.into(), layers);
+            } else {
+                dict_access.remove(parse::PIECE_LAYERS_KEY);
+            }
+        }
+
+        self
+    }
+
     /// Set or unset the private flag for the torrent file.
     pub fn set_private_flag(mut self, opt_is_private: Option<bool>) -> MetainfoBuilder<'a> {
         self.info = self.info.set_private_flag(opt_is_private);
@@ -295,16 +321,28 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
             files_info.push((len, path_list));
         }));
 
+        let mut files_attr = Vec::new();
+        try!(accessor.access_attributes(|attr| files_attr.push(attr.map(String::from))));
+
+        // Meta version 2 data is pre computed, so there is nothing to hash for it
+        let opt_file_tree = accessor.access_file_tree().map(build_file_tree);
+        let is_v1 = accessor.access_v1();
+
         // Build the pieces for the data our accessor is pointing at
         let total_files_len = files_info.iter().fold(0, |acc, nex| acc + nex.0);
         let piece_length = determine_piece_length(total_files_len, piece_length);
         let total_num_pieces = ((total_files_len as f64) / (piece_length as f64)).ceil() as u64;
-        let pieces_list = try!(worker::start_hasher_workers(&accessor,
-                                                            piece_length,
-                                                            total_num_pieces,
-                                                            threads,
-                                                            progress));
-        let pieces = map_pieces_list(pieces_list.into_iter().map(|(_, piece)| piece));
+        let pieces = if is_v1 {
+            let pieces_list = try!(worker::start_hasher_workers(&accessor,
+                                                                piece_length,
+                                                                total_num_pieces,
+                                                                threads,
+                                                                progress));
+
+            map_pieces_list(pieces_list.into_iter().map(|(_, piece)| piece))
+        } else {
+            Vec::new()
+        };
 
         let mut single_file_name = String::new();
         let access_directory = accessor.access_directory().map(|path| path.to_string_lossy());
@@ -318,7 +356,14 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
             let info_access = info.dict_mut().unwrap();
 
             info_access.insert(parse::PIECE_LENGTH_KEY.into(), ben_int!(piece_length as i64));
-            info_access.insert(parse::PIECES_KEY.into(), ben_bytes!(&pieces[..]));
+            if is_v1 {
+                info_access.insert(parse::PIECES_KEY.into(), ben_bytes!(&pieces[..]));
+            }
+
+            if let Some(file_tree) = opt_file_tree {
+                info_access.insert(parse::META_VERSION_KEY.into(), ben_int!(2));
+                info_access.insert(parse::FILE_TREE_KEY.into(), file_tree);
+            }
 
             // If the accessor specifies a directory OR there are mutliple files, we will build a multi file torrent
             // If the directory is not present but there are multiple files, the direcotry field will be set to empty
@@ -330,7 +375,7 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
                         let bencode_files_access = bencode_files.list_mut().unwrap();
 
                         // Multi File
-                        for &(len, ref path) in files_info.iter() {
+                        for (index, &(len, ref path)) in files_info.iter().enumerate() {
                             let mut bencode_path = BencodeMut::new_list();
 
                             {
@@ -341,15 +386,23 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
                                 }
                             }
 
-                            bencode_files_access.push(ben_map!{
+                            let mut bencode_file = ben_map!{
                                 parse::LENGTH_KEY => ben_int!(len as i64),
                                 parse::PATH_KEY   => bencode_path
-                            });
+                            };
+
+                            if let Some(&Some(ref attr)) = files_attr.get(index) {
+                                bencode_file.dict_mut().unwrap().insert(parse::ATTR_KEY.into(), ben_bytes!(&attr[..]));
+                            }
+
+                            bencode_files_access.push(bencode_file);
                         }
                     }
 
                     info_access.insert(parse::NAME_KEY.into(), ben_bytes!(directory.as_ref()));
-                    info_access.insert(parse::FILES_KEY.into(), bencode_files);
+                    if is_v1 {
+                        info_access.insert(parse::FILES_KEY.into(), bencode_files);
+                    }
                 }
                 (&None, true) => {
                     let mut bencode_files = BencodeMut::new_list();
@@ -358,7 +411,7 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
                         let bencode_files_access = bencode_files.list_mut().unwrap();
 
                         // Multi File
-                        for &(len, ref path) in files_info.iter() {
+                        for (index, &(len, ref path)) in files_info.iter().enumerate() {
                             let mut bencode_path = BencodeMut::new_list();
 
                             {
@@ -369,15 +422,23 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
                                 }
                             }
 
-                            bencode_files_access.push(ben_map!{
+                            let mut bencode_file = ben_map!{
                                 parse::LENGTH_KEY => ben_int!(len as i64),
                                 parse::PATH_KEY   => bencode_path
-                            });
+                            };
+
+                            if let Some(&Some(ref attr)) = files_attr.get(index) {
+                                bencode_file.dict_mut().unwrap().insert(parse::ATTR_KEY.into(), ben_bytes!(&attr[..]));
+                            }
+
+                            bencode_files_access.push(bencode_file);
                         }
                     }
 
                     info_access.insert(parse::NAME_KEY.into(), ben_bytes!(""));
-                    info_access.insert(parse::FILES_KEY.into(), bencode_files);
+                    if is_v1 {
+                        info_access.insert(parse::FILES_KEY.into(), bencode_files);
+                    }
                 }
                 (&None, false) => {
                     // Single File
@@ -385,7 +446,9 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
                         single_file_name.push_str(name_component);
                     }
 
-                    info_access.insert(parse::LENGTH_KEY.into(), ben_int!(files_info[0].0 as i64));
+                    if is_v1 {
+                        info_access.insert(parse::LENGTH_KEY.into(), ben_int!(files_info[0].0 as i64));
+                    }
                     info_access.insert(parse::NAME_KEY.into(), ben_bytes!(&single_file_name[..]));
                 }
             }
@@ -400,6 +463,38 @@ fn build_with_accessor<'a, A, C>(threads:       usize,
         }
 }
 
+/// Build the meta version 2 file tree, nesting each file under its path elements.
+fn build_file_tree<'a>(files: &[TreeFile]) -> BencodeMut<'a> {
+    let mut file_tree = BencodeMut::new_dict();
+
+    for file in files.iter() {
+        let mut file_entry = ben_map!{
+            parse::LENGTH_KEY => ben_int!(file.length() as i64)
+        };
+
+        if let Some(pieces_root) = file.pieces_root() {
+            file_entry.dict_mut().unwrap().insert(parse::PIECES_ROOT_KEY.into(),
+                                                  BencodeMut::new_bytes(pieces_root.to_vec().into()));
+        }
+
+        let mut node = &mut file_tree;
+        for path_element in file.path().iter() {
+            let key = path_element.to_string_lossy().into_owned().into_bytes();
+            let node_access = node.dict_mut().unwrap();
+
+            if node_access.lookup(&key).is_none() {
+                node_access.insert(key.clone().into(), BencodeMut::new_dict());
+            }
+
+            node = node_access.lookup_mut(&key).unwrap();
+        }
+
+        node.dict_mut().unwrap().insert(parse::FILE_TREE_LEAF_KEY.into(), file_entry);
+    }
+
+    file_tree
+}
+
 /// Calculate the final piece length given the total file size and piece length strategy.
 ///
 /// Lower piece length will result in a bigger file but better transfer reliability and vice versa.
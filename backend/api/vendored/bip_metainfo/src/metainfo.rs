@@ -1,10 +1,14 @@
 //! Accessing the fields of a Metainfo file.
+use std::collections::BTreeMap;
+use std::collections::btree_map;
 use std::path::{Path, PathBuf};
 use std::io;
+use std::str;
 
-use bip_bencode::{BencodeRef, BDictAccess, BDecodeOpt, BRefAccess};
+use bip_bencode::{BencodeMut, BencodeRef, BDictAccess, BDecodeOpt, BMutAccess, BRefAccess};
 use bip_util::bt::InfoHash;
 use bip_util::sha::{self, ShaHash};
+use sha2::{Digest, Sha256};
 
 use accessor::{Accessor, PieceAccess, IntoAccessor};
 use builder::{MetainfoBuilder, InfoBuilder, PieceLength};
@@ -22,6 +26,7 @@ pub struct Metainfo {
     created_by: Option<String>,
     creation_date: Option<i64>,
     info: Info,
+    piece_layers: Option<PieceLayers>,
 }
 
 impl Metainfo {
@@ -69,6 +74,11 @@ impl Metainfo {
         &self.info
     }
 
+    /// Piece layers for the metainfo file, present only for meta version 2 torrents.
+    pub fn piece_layers(&self) -> Option<&PieceLayers> {
+        self.piece_layers.as_ref()
+    }
+
     /// Retrieve the bencoded bytes for the `Metainfo` file.
     pub fn to_bytes(&self) -> Vec<u8> {
         // Since there are no file system accesses here, should be fine to unwrap
@@ -80,6 +90,7 @@ impl Metainfo {
             .set_private_flag(self.info().is_private())
             // TODO: Revisit this cast...
             .set_piece_length(PieceLength::Custom(self.info().piece_length() as usize))
+            .set_piece_layers(self.piece_layers())
             .build(1, &self.info, |_| ())
             .unwrap()
     }
@@ -94,7 +105,8 @@ impl From<Info> for Metainfo {
             encoding: None,
             created_by: None,
             creation_date: None,
-            info: info
+            info: info,
+            piece_layers: None
         }
     }
 }
@@ -120,6 +132,12 @@ fn parse_meta_bytes(bytes: &[u8]) -> ParseResult<Metainfo> {
     let info_bencode = try!(parse::parse_info_bencode(root_dict));
     let info = try!(parse_info_dictionary(info_bencode));
 
+    let opt_piece_layers = match parse::parse_piece_layers(root_dict) {
+        Some(layers_dict) => Some(try!(PieceLayers::from_dictionary(layers_dict))),
+        None => None
+    };
+    try!(validate_piece_layers(&info, opt_piece_layers.as_ref()));
+
     Ok(Metainfo {
         comment: opt_comment,
         announce: announce,
@@ -127,10 +145,143 @@ fn parse_meta_bytes(bytes: &[u8]) -> ParseResult<Metainfo> {
         encoding: opt_encoding,
         created_by: opt_created_by,
         creation_date: opt_creation_date,
-        info: info
+        info: info,
+        piece_layers: opt_piece_layers
     })
 }
 
+/// Validates that every file of a meta version 2 torrent spanning more than one piece has its piece layer.
+fn validate_piece_layers(info: &Info, opt_piece_layers: Option<&PieceLayers>) -> ParseResult<()> {
+    let file_tree = match info.file_tree() {
+        Some(file_tree) => file_tree,
+        None => return Ok(())
+    };
+
+    for file in file_tree.iter().filter(|file| file.length() > info.piece_length()) {
+        let num_pieces = (file.length() + info.piece_length() - 1) / info.piece_length();
+
+        let layer_len = file.pieces_root()
+            .and_then(|root| opt_piece_layers.and_then(|layers| layers.layer(root)))
+            .map(|layer| layer.len() as u64);
+
+        if layer_len != Some(num_pieces * SHA256_HASH_LEN as u64) {
+            let error_msg = format!("Piece Layer For File {:?} Is Missing Or Invalid", file.path());
+            return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+        }
+    }
+
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------//
+
+/// Length in bytes of the SHA-256 hashes used by meta version 2 (BEP 52) torrents.
+pub const SHA256_HASH_LEN: usize = 32;
+
+/// Smallest piece length allowed for meta version 2 torrents.
+const V2_MIN_PIECE_LENGTH: u64 = 16 * 1024;
+
+/// SHA-256 hash of the info dictionary, identifying a meta version 2 torrent.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct InfoHashV2([u8; SHA256_HASH_LEN]);
+
+impl InfoHashV2 {
+    /// Create an `InfoHashV2` by hashing the given info dictionary bytes.
+    pub fn from_bytes(bytes: &[u8]) -> InfoHashV2 {
+        let mut hash = [0u8; SHA256_HASH_LEN];
+        hash.copy_from_slice(&Sha256::digest(bytes));
+
+        InfoHashV2(hash)
+    }
+
+    /// Hash truncated to 20 bytes, which is what peers and trackers use to refer to the torrent.
+    pub fn truncated(&self) -> InfoHash {
+        InfoHash::from_hash(&self.0[..sha::SHA_HASH_LEN]).unwrap()
+    }
+}
+
+impl AsRef<[u8]> for InfoHashV2 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Piece layers of a meta version 2 torrent, keyed by the pieces root of each file.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct PieceLayers {
+    layers: BTreeMap<[u8; SHA256_HASH_LEN], Vec<u8>>,
+}
+
+impl PieceLayers {
+    /// Read `PieceLayers` from the bencoded piece layers dictionary.
+    pub fn from_bytes<B>(bytes: B) -> ParseResult<PieceLayers>
+        where B: AsRef<[u8]>
+    {
+        let layers_bencode = try!(BencodeRef::decode(bytes.as_ref(), BDecodeOpt::default()));
+        let layers_dict = try!(parse::parse_root_dict(&layers_bencode));
+
+        PieceLayers::from_dictionary(layers_dict)
+    }
+
+    /// Parses the piece layers dictionary found in the root of a metainfo file.
+    fn from_dictionary<'a>(layers_dict: &BDictAccess<&'a [u8], BencodeRef<'a>>) -> ParseResult<PieceLayers> {
+        let mut layers = BTreeMap::new();
+
+        for (root, layer_bencode) in layers_dict.to_list() {
+            let layer = try!(parse::parse_piece_layer(layer_bencode));
+
+            if root.len() != SHA256_HASH_LEN || layer.len() % SHA256_HASH_LEN != 0 {
+                let error_msg = format!("Piece Layer Of Length {} Is Invalid", layer.len());
+                return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+            }
+
+            let mut root_buffer = [0u8; SHA256_HASH_LEN];
+            root_buffer.copy_from_slice(root);
+
+            layers.insert(root_buffer, layer.to_owned());
+        }
+
+        Ok(PieceLayers { layers: layers })
+    }
+
+    /// Concatenated piece hashes of the file with the given pieces root.
+    pub fn layer(&self, pieces_root: &[u8]) -> Option<&[u8]> {
+        if pieces_root.len() != SHA256_HASH_LEN {
+            return None;
+        }
+
+        let mut root_buffer = [0u8; SHA256_HASH_LEN];
+        root_buffer.copy_from_slice(pieces_root);
+
+        self.layers.get(&root_buffer).map(|layer| &layer[..])
+    }
+
+    /// Iterator over each pieces root and its piece layer.
+    pub fn iter<'a>(&'a self) -> btree_map::Iter<'a, [u8; SHA256_HASH_LEN], Vec<u8>> {
+        self.layers.iter()
+    }
+
+    /// Whether or not there are any piece layers.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Retrieve the bencoded bytes for the piece layers dictionary.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut layers_dict = BencodeMut::new_dict();
+
+        {
+            let layers_dict_access = layers_dict.dict_mut().unwrap();
+
+            for (root, layer) in self.iter() {
+                layers_dict_access.insert((&root[..]).into(), ben_bytes!(&layer[..]));
+            }
+        }
+
+        layers_dict.encode()
+    }
+}
+
 // ----------------------------------------------------------------------------//
 
 /// Contains directory and checksum data for a torrent file.
@@ -143,6 +294,11 @@ pub struct Info {
     is_private:     Option<bool>,
     // Present only for multi file torrents.
     file_directory: Option<PathBuf>,
+    // Present only for meta version 2 (and hybrid) torrents.
+    info_hash_v2:   Option<InfoHashV2>,
+    file_tree:      Option<Vec<TreeFile>>,
+    // False for meta version 2 torrents without v1 pieces, whose files are derived from the file tree.
+    has_v1:         bool,
 }
 
 impl Info {
@@ -156,10 +312,37 @@ impl Info {
     }
 
     /// Hash to uniquely identify this torrent.
+    ///
+    /// For meta version 2 only torrents, peers use the truncated `info_hash_v2` instead.
     pub fn info_hash(&self) -> InfoHash {
         self.info_hash
     }
 
+    /// SHA-256 hash to uniquely identify this torrent, if it is a meta version 2 torrent.
+    pub fn info_hash_v2(&self) -> Option<InfoHashV2> {
+        self.info_hash_v2
+    }
+
+    /// Whether or not the torrent carries v1 pieces and files.
+    pub fn is_v1(&self) -> bool {
+        self.has_v1
+    }
+
+    /// Whether or not the torrent carries a meta version 2 file tree.
+    pub fn is_v2(&self) -> bool {
+        self.file_tree.is_some()
+    }
+
+    /// Whether or not the torrent can be downloaded by both v1 and v2 clients.
+    pub fn is_hybrid(&self) -> bool {
+        self.is_v1() && self.is_v2()
+    }
+
+    /// Files of the meta version 2 file tree, in the order they are found in the torrent file.
+    pub fn file_tree(&self) -> Option<&[TreeFile]> {
+        self.file_tree.as_ref().map(|f| &f[..])
+    }
+
     /// Some file directory if this is a multi-file torrent, otherwise None.
     ///
     /// If you want to check to see if this is a multi-file torrent, you should
@@ -192,7 +375,8 @@ impl Info {
     ///
     /// Ordering of files yielded in the iterator is guaranteed to be the order in
     /// which they are found in the torrent file as this is necessary to reconstruct
-    /// pieces received from peers.
+    /// pieces received from peers. For meta version 2 only torrents, these are the
+    /// files of the file tree.
     pub fn files<'a>(&'a self) -> Files<'a> {
         Files::new(&self.files)
     }
@@ -247,6 +431,23 @@ impl Accessor for Info {
         
         Ok(())
     }
+
+    fn access_attributes<C>(&self, mut callback: C) -> io::Result<()>
+        where C: FnMut(Option<&str>) {
+        for file in self.files() {
+            callback(file.attr());
+        }
+
+        Ok(())
+    }
+
+    fn access_file_tree(&self) -> Option<&[TreeFile]> {
+        self.file_tree()
+    }
+
+    fn access_v1(&self) -> bool {
+        self.is_v1()
+    }
 }
 
 /// Parses the given info dictionary bytes and builds a Metainfo from them.
@@ -264,6 +465,38 @@ fn parse_info_dictionary<'a>(info_bencode: &BencodeRef<'a>) -> ParseResult<Info>
     let piece_len = try!(parse::parse_piece_length(info_dict));
     let is_private = parse::parse_private(info_dict);
 
+    let (info_hash_v2, file_tree) = match parse::parse_meta_version(info_dict) {
+        None => (None, None),
+        Some(2) => {
+            let file_tree = try!(parse_file_tree(info_dict, piece_len));
+
+            (Some(InfoHashV2::from_bytes(info_bencode.buffer())), Some(file_tree))
+        }
+        Some(meta_version) => {
+            let error_msg = format!("Meta Version {} Is Not Supported", meta_version);
+            return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+        }
+    };
+
+    // Meta version 2 only torrents have no v1 pieces, their files come from the file tree
+    if file_tree.is_some() && parse::parse_pieces(info_dict).is_err() {
+        let file_tree = file_tree.unwrap();
+        let name = try!(parse::parse_name(info_dict));
+        let is_single_file = file_tree.len() == 1 && file_tree[0].path().iter().count() == 1;
+
+        return Ok(Info {
+            info_hash: info_hash,
+            files: file_tree.iter().map(File::from_tree_file).collect(),
+            pieces: Vec::new(),
+            piece_len: piece_len,
+            is_private: is_private,
+            file_directory: if is_single_file { None } else { Some(name.into()) },
+            info_hash_v2: info_hash_v2,
+            file_tree: Some(file_tree),
+            has_v1: false,
+        });
+    }
+
     let pieces = try!(parse::parse_pieces(info_dict));
     let piece_buffers = try!(allocate_pieces(pieces));
 
@@ -289,6 +522,9 @@ fn parse_info_dictionary<'a>(info_bencode: &BencodeRef<'a>) -> ParseResult<Info>
             piece_len: piece_len,
             is_private: is_private,
             file_directory: Some(file_directory_path),
+            info_hash_v2: info_hash_v2,
+            file_tree: file_tree,
+            has_v1: true,
         })
     } else {
         let file = try!(File::as_single_file(info_dict));
@@ -300,10 +536,58 @@ fn parse_info_dictionary<'a>(info_bencode: &BencodeRef<'a>) -> ParseResult<Info>
             piece_len: piece_len,
             is_private: is_private,
             file_directory: None,
+            info_hash_v2: info_hash_v2,
+            file_tree: file_tree,
+            has_v1: true,
         })
     }
 }
 
+/// Parses the meta version 2 file tree of the info dictionary into a list of files.
+fn parse_file_tree<'a>(info_dict: &BDictAccess<&'a [u8], BencodeRef<'a>>, piece_len: u64) -> ParseResult<Vec<TreeFile>> {
+    if piece_len < V2_MIN_PIECE_LENGTH || !piece_len.is_power_of_two() {
+        let error_msg = format!("Piece Length Of {} Is Invalid For Meta Version 2", piece_len);
+        return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+    }
+
+    let file_tree_dict = try!(parse::parse_file_tree(info_dict));
+
+    let mut files = Vec::new();
+    try!(parse_file_tree_directory(file_tree_dict, &mut PathBuf::new(), &mut files));
+
+    if files.is_empty() {
+        let error_msg = "File Tree Is Empty".to_owned();
+        return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+    }
+
+    Ok(files)
+}
+
+/// Recursively parses a directory of the file tree, pushing every file found into files.
+fn parse_file_tree_directory<'a>(directory_dict: &BDictAccess<&'a [u8], BencodeRef<'a>>,
+                                 path: &mut PathBuf,
+                                 files: &mut Vec<TreeFile>) -> ParseResult<()> {
+    for (name, node_bencode) in directory_dict.to_list() {
+        let name = match str::from_utf8(name) {
+            Ok(name) if !name.is_empty() && name != "." && name != ".." && !name.contains('/') => name,
+            _ => {
+                let error_msg = format!("File Tree Path Element {:?} Is Invalid", String::from_utf8_lossy(name));
+                return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+            }
+        };
+        let node_dict = try!(parse::parse_file_tree_node(node_bencode));
+
+        path.push(name);
+        match parse::parse_file_tree_leaf(node_dict) {
+            Some(file_dict) => files.push(try!(TreeFile::from_file_tree_entry(file_dict, path.clone()))),
+            None => try!(parse_file_tree_directory(node_dict, path, files))
+        }
+        path.pop();
+    }
+
+    Ok(())
+}
+
 /// Returns whether or not this is a multi file torrent.
 fn is_multi_file_torrent<B>(info_dict: &BDictAccess<B::BKey, B>) -> bool
     where B: BRefAccess {
@@ -339,6 +623,7 @@ pub struct File {
     len:    u64,
     path:   PathBuf,
     md5sum: Option<Vec<u8>>,
+    attr:   Option<String>,
 }
 
 impl File {
@@ -347,12 +632,14 @@ impl File {
         where B: BRefAccess {
         let length = try!(parse::parse_length(info_dict));
         let md5sum = parse::parse_md5sum(info_dict).map(|m| m.to_owned());
+        let attr = parse::parse_attr(info_dict).map(|a| a.to_owned());
         let name = try!(parse::parse_name(info_dict));
 
         Ok(File {
             len: length,
             path: name.to_owned().into(),
             md5sum: md5sum,
+            attr: attr,
         })
     }
 
@@ -361,6 +648,7 @@ impl File {
         where B: BRefAccess<BType=B> {
         let length = try!(parse::parse_length(file_dict));
         let md5sum = parse::parse_md5sum(file_dict).map(|m| m.to_owned());
+        let attr = parse::parse_attr(file_dict).map(|a| a.to_owned());
 
         let path_list_bencode = try!(parse::parse_path_list(file_dict));
 
@@ -375,9 +663,20 @@ impl File {
             len: length,
             path: path_buf,
             md5sum: md5sum,
+            attr: attr,
         })
     }
 
+    /// Generate a File from a file of the meta version 2 file tree.
+    fn from_tree_file(tree_file: &TreeFile) -> File {
+        File {
+            len: tree_file.length(),
+            path: tree_file.path().to_owned(),
+            md5sum: None,
+            attr: None,
+        }
+    }
+
     /// Length of the file in bytes.
     pub fn length(&self) -> u64 {
         self.len
@@ -394,6 +693,67 @@ impl File {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Optional attributes (BEP 47) of the file.
+    pub fn attr(&self) -> Option<&str> {
+        self.attr.as_ref().map(|a| &a[..])
+    }
+
+    /// Whether or not this is a padding file, aligning the next file of a hybrid torrent to a piece boundary.
+    pub fn is_padding(&self) -> bool {
+        self.attr().map(|a| a.contains('p')).unwrap_or(false)
+    }
+}
+
+/// Contains information for a single file of a meta version 2 file tree.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TreeFile {
+    len:         u64,
+    path:        PathBuf,
+    pieces_root: Option<[u8; SHA256_HASH_LEN]>,
+}
+
+impl TreeFile {
+    /// Parse the file tree entry of a file at the given path.
+    fn from_file_tree_entry<B>(file_dict: &BDictAccess<B::BKey, B>, path: PathBuf) -> ParseResult<TreeFile>
+        where B: BRefAccess {
+        let length = try!(parse::parse_length(file_dict));
+
+        let pieces_root = match parse::parse_pieces_root(file_dict) {
+            Some(root) if root.len() == SHA256_HASH_LEN => {
+                let mut root_buffer = [0u8; SHA256_HASH_LEN];
+                root_buffer.copy_from_slice(root);
+
+                Some(root_buffer)
+            }
+            None if length == 0 => None,
+            _ => {
+                let error_msg = format!("Pieces Root For File {:?} Is Missing Or Invalid", path);
+                return Err(ParseError::from_kind(ParseErrorKind::MissingData { details: error_msg }));
+            }
+        };
+
+        Ok(TreeFile {
+            len: length,
+            path: path,
+            pieces_root: pieces_root,
+        })
+    }
+
+    /// Length of the file in bytes.
+    pub fn length(&self) -> u64 {
+        self.len
+    }
+
+    /// Path of the file, relative to the torrent directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Root of the merkle tree of the file, absent for empty files.
+    pub fn pieces_root(&self) -> Option<&[u8]> {
+        self.pieces_root.as_ref().map(|r| &r[..])
+    }
 }
 
 #[cfg(test)]
@@ -404,7 +764,7 @@ mod tests {
     use bip_util::sha;
     use bip_util::bt::InfoHash;
 
-    use metainfo::Metainfo;
+    use metainfo::{Info, InfoHashV2, Metainfo, PieceLayers, SHA256_HASH_LEN};
     use parse;
 
     /// Helper function for manually constructing a metainfo file based on the parameters given.
@@ -818,6 +1178,148 @@ mod tests {
                                    Some(vec![(Some(file_len), None, Some(file_paths))]));
     }
 
+    /// Builds a meta version 2 info dictionary with a directory and an empty file, plus a v1 part if hybrid.
+    fn v2_info_dict(root: &[u8], hybrid: bool) -> BencodeMut<'static> {
+        let mut info_dict = ben_map!{
+            parse::FILE_TREE_KEY => ben_map!{
+                "a.flac" => ben_map!{
+                    parse::FILE_TREE_LEAF_KEY => ben_map!{
+                        parse::LENGTH_KEY      => ben_int!(20000),
+                        parse::PIECES_ROOT_KEY => BencodeMut::new_bytes(root.to_vec().into())
+                    }
+                },
+                "scans" => ben_map!{
+                    "empty.txt" => ben_map!{
+                        parse::FILE_TREE_LEAF_KEY => ben_map!{
+                            parse::LENGTH_KEY => ben_int!(0)
+                        }
+                    }
+                }
+            },
+            parse::META_VERSION_KEY => ben_int!(2),
+            parse::NAME_KEY         => ben_bytes!("dummy_file_directory"),
+            parse::PIECE_LENGTH_KEY => ben_int!(16384)
+        };
+
+        if hybrid {
+            let info_dict_access = info_dict.dict_mut().unwrap();
+
+            info_dict_access.insert(parse::PIECES_KEY.into(), BencodeMut::new_bytes(vec![0u8; 2 * sha::SHA_HASH_LEN].into()));
+            info_dict_access.insert(parse::FILES_KEY.into(), ben_list!(
+                ben_map!{
+                    parse::LENGTH_KEY => ben_int!(20000),
+                    parse::PATH_KEY   => ben_list!(ben_bytes!("a.flac"))
+                },
+                ben_map!{
+                    parse::ATTR_KEY   => ben_bytes!("p"),
+                    parse::LENGTH_KEY => ben_int!(12768),
+                    parse::PATH_KEY   => ben_list!(ben_bytes!(".pad"), ben_bytes!("12768"))
+                },
+                ben_map!{
+                    parse::LENGTH_KEY => ben_int!(0),
+                    parse::PATH_KEY   => ben_list!(ben_bytes!("scans"), ben_bytes!("empty.txt"))
+                }
+            ));
+        }
+
+        info_dict
+    }
+
+    #[test]
+    fn positive_parse_v2_only() {
+        let root = [1u8; SHA256_HASH_LEN];
+        let info_bytes = v2_info_dict(&root, false).encode();
+
+        let info = Info::from_bytes(&info_bytes).unwrap();
+
+        assert!(!info.is_v1());
+        assert!(info.is_v2());
+        assert_eq!(info.info_hash_v2(), Some(InfoHashV2::from_bytes(&info_bytes)));
+        assert_eq!(info.info_hash_v2().unwrap().truncated().as_ref(), &info.info_hash_v2().unwrap().as_ref()[..sha::SHA_HASH_LEN]);
+        assert_eq!(info.directory(), Some(Path::new("dummy_file_directory")));
+        assert_eq!(info.pieces().count(), 0);
+
+        let file_tree = info.file_tree().unwrap();
+        assert_eq!(file_tree.len(), 2);
+        assert_eq!(file_tree[0].path(), Path::new("a.flac"));
+        assert_eq!(file_tree[0].pieces_root(), Some(&root[..]));
+        assert_eq!(file_tree[1].path(), Path::new("scans/empty.txt"));
+        assert_eq!(file_tree[1].pieces_root(), None);
+
+        let files: Vec<(u64, &Path)> = info.files().map(|f| (f.length(), f.path())).collect();
+        assert_eq!(files, vec![(20000, Path::new("a.flac")), (0, Path::new("scans/empty.txt"))]);
+    }
+
+    #[test]
+    fn positive_parse_hybrid() {
+        let info_bytes = v2_info_dict(&[1u8; SHA256_HASH_LEN], true).encode();
+
+        let info = Info::from_bytes(&info_bytes).unwrap();
+
+        assert!(info.is_hybrid());
+        assert_eq!(info.info_hash(), InfoHash::from_bytes(&info_bytes));
+        assert_eq!(info.pieces().count(), 2);
+        assert_eq!(info.files().count(), 3);
+        assert_eq!(info.files().filter(|f| f.is_padding()).count(), 1);
+        assert_eq!(info.file_tree().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn positive_build_v2_only_round_trip() {
+        let info_bytes = v2_info_dict(&[1u8; SHA256_HASH_LEN], false).encode();
+
+        assert_eq!(Info::from_bytes(&info_bytes).unwrap().to_bytes(), info_bytes);
+    }
+
+    #[test]
+    fn positive_build_hybrid_round_trip() {
+        let info_bytes = v2_info_dict(&[1u8; SHA256_HASH_LEN], true).encode();
+
+        assert_eq!(Info::from_bytes(&info_bytes).unwrap().to_bytes(), info_bytes);
+    }
+
+    #[test]
+    fn positive_parse_with_piece_layers() {
+        let root = [1u8; SHA256_HASH_LEN];
+        let layer = [2u8; 2 * SHA256_HASH_LEN];
+
+        let mut root_dict = BencodeMut::new_dict();
+        {
+            let root_dict_access = root_dict.dict_mut().unwrap();
+
+            root_dict_access.insert(parse::INFO_KEY.into(), v2_info_dict(&root, false));
+            root_dict_access.insert(parse::PIECE_LAYERS_KEY.into(), ben_map!{
+                &root[..] => ben_bytes!(&layer[..])
+            });
+        }
+        let metainfo_bytes = root_dict.encode();
+
+        let metainfo_file = Metainfo::from_bytes(&metainfo_bytes).unwrap();
+        let piece_layers = metainfo_file.piece_layers().unwrap();
+
+        assert_eq!(piece_layers.layer(&root), Some(&layer[..]));
+        assert_eq!(PieceLayers::from_bytes(piece_layers.to_bytes()).unwrap(), *piece_layers);
+        assert_eq!(metainfo_file.to_bytes(), metainfo_bytes);
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_parse_v2_with_no_piece_layers() {
+        let mut root_dict = BencodeMut::new_dict();
+        root_dict.dict_mut().unwrap().insert(parse::INFO_KEY.into(), v2_info_dict(&[1u8; SHA256_HASH_LEN], false));
+
+        Metainfo::from_bytes(root_dict.encode()).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_parse_v2_with_unsupported_meta_version() {
+        let mut info_dict = v2_info_dict(&[1u8; SHA256_HASH_LEN], false);
+        info_dict.dict_mut().unwrap().insert(parse::META_VERSION_KEY.into(), ben_int!(3));
+
+        Info::from_bytes(info_dict.encode()).unwrap();
+    }
+
     #[test]
     #[should_panic]
     fn negative_parse_from_empty_bytes() {
@@ -28,6 +28,7 @@ pub const COMMENT_KEY:       &'static [u8] = b"comment";
 pub const CREATED_BY_KEY:    &'static [u8] = b"created by";
 pub const ENCODING_KEY:      &'static [u8] = b"encoding";
 pub const INFO_KEY:          &'static [u8] = b"info";
+pub const PIECE_LAYERS_KEY:  &'static [u8] = b"piece layers";
 
 /// Keys found within the info dictionary of a metainfo file.
 pub const PIECE_LENGTH_KEY: &'static [u8] = b"piece length";
@@ -35,11 +36,18 @@ pub const PIECES_KEY:       &'static [u8] = b"pieces";
 pub const PRIVATE_KEY:      &'static [u8] = b"private";
 pub const NAME_KEY:         &'static [u8] = b"name";
 pub const FILES_KEY:        &'static [u8] = b"files";
+pub const META_VERSION_KEY: &'static [u8] = b"meta version";
+pub const FILE_TREE_KEY:    &'static [u8] = b"file tree";
 
 /// Keys found within the files dictionary of a metainfo file.
 pub const LENGTH_KEY: &'static [u8] = b"length";
 pub const MD5SUM_KEY: &'static [u8] = b"md5sum";
 pub const PATH_KEY:   &'static [u8] = b"path";
+pub const ATTR_KEY:   &'static [u8] = b"attr";
+
+/// Keys found within the file tree of a (meta version 2) metainfo file.
+pub const FILE_TREE_LEAF_KEY: &'static [u8] = b"";
+pub const PIECES_ROOT_KEY:    &'static [u8] = b"pieces root";
 
 /// Parses the root bencode as a dictionary.
 pub fn parse_root_dict<B>(root_bencode: &B) -> ParseResult<&BDictAccess<B::BKey, B::BType>>
@@ -103,6 +111,18 @@ pub fn parse_info_bencode<'a, B>(root_dict: &'a BDictAccess<B::BKey, B>) -> Pars
     CONVERT.lookup(root_dict, INFO_KEY)
 }
 
+/// Parses the piece layers from the root dictionary.
+pub fn parse_piece_layers<B>(root_dict: &BDictAccess<B::BKey, B>) -> Option<&BDictAccess<B::BKey, B>>
+    where B: BRefAccess<BType=B> {
+    CONVERT.lookup_and_convert_dict(root_dict, PIECE_LAYERS_KEY).ok()
+}
+
+/// Parses a single piece layer from the piece layers bencode.
+pub fn parse_piece_layer<B>(layer_bencode: &B) -> ParseResult<&[u8]>
+    where B: BRefAccess {
+    CONVERT.convert_bytes(layer_bencode, PIECE_LAYERS_KEY)
+}
+
 // ----------------------------------------------------------------------------//
 
 /// Parses the piece length from the info dictionary.
@@ -135,6 +155,18 @@ pub fn parse_files_list<B>(info_dict: &BDictAccess<B::BKey, B>) -> ParseResult<&
     CONVERT.lookup_and_convert_list(info_dict, FILES_KEY)
 }
 
+/// Parses the meta version from the info dictionary.
+pub fn parse_meta_version<B>(info_dict: &BDictAccess<B::BKey, B>) -> Option<i64>
+    where B: BRefAccess {
+    CONVERT.lookup_and_convert_int(info_dict, META_VERSION_KEY).ok()
+}
+
+/// Parses the file tree from the info dictionary.
+pub fn parse_file_tree<B>(info_dict: &BDictAccess<B::BKey, B>) -> ParseResult<&BDictAccess<B::BKey, B>>
+    where B: BRefAccess<BType=B> {
+    CONVERT.lookup_and_convert_dict(info_dict, FILE_TREE_KEY)
+}
+
 // ----------------------------------------------------------------------------//
 
 /// Parses the file dictionary from the file bencode.
@@ -155,6 +187,12 @@ pub fn parse_md5sum<'a, B>(info_or_file_dict: &'a BDictAccess<B::BKey, B>) -> Op
     CONVERT.lookup_and_convert_bytes(info_or_file_dict, MD5SUM_KEY).ok()
 }
 
+/// Parses the attributes (BEP 47) from the info or file dictionary.
+pub fn parse_attr<'a, B>(info_or_file_dict: &'a BDictAccess<B::BKey, B>) -> Option<&'a str>
+    where B: BRefAccess + 'a {
+    CONVERT.lookup_and_convert_str(info_or_file_dict, ATTR_KEY).ok()
+}
+
 /// Parses the path list from the file dictionary.
 pub fn parse_path_list<B>(file_dict: &BDictAccess<B::BKey, B>) -> ParseResult<&BListAccess<B>>
     where B: BRefAccess<BType=B> {
@@ -166,3 +204,24 @@ pub fn parse_path_str<B>(path_bencode: &B) -> ParseResult<&str>
     where B: BRefAccess {
     CONVERT.convert_str(path_bencode, PATH_KEY)
 }
+
+
+// ----------------------------------------------------------------------------//
+
+/// Parses a file tree node (directory or file entry) from the node bencode.
+pub fn parse_file_tree_node<B>(node_bencode: &B) -> ParseResult<&BDictAccess<B::BKey, B::BType>>
+    where B: BRefAccess {
+    CONVERT.convert_dict(node_bencode, FILE_TREE_KEY)
+}
+
+/// Parses the file entry from a file tree node, if the node is a file.
+pub fn parse_file_tree_leaf<B>(node_dict: &BDictAccess<B::BKey, B>) -> Option<&BDictAccess<B::BKey, B>>
+    where B: BRefAccess<BType=B> {
+    CONVERT.lookup_and_convert_dict(node_dict, FILE_TREE_LEAF_KEY).ok()
+}
+
+/// Parses the pieces root from the file tree entry.
+pub fn parse_pieces_root<'a, B>(file_entry_dict: &'a BDictAccess<B::BKey, B>) -> Option<&'a [u8]>
+    where B: BRefAccess + 'a {
+    CONVERT.lookup_and_convert_bytes(file_entry_dict, PIECES_ROOT_KEY).ok()
+}
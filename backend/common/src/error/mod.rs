@@ -66,6 +66,33 @@ pub enum Error {
     #[error("could not search for artists")]
     CouldNotSearchForArtists(#[source] sqlx::Error),
 
+    #[error("could not merge artists")]
+    CouldNotMergeArtists(#[source] sqlx::Error),
+
+    #[error("cannot merge an artist into itself")]
+    CannotMergeArtistIntoItself,
+
+    #[error("could not create entity")]
+    CouldNotCreateEntity(#[source] sqlx::Error),
+
+    #[error("could not update entity")]
+    CouldNotUpdateEntity(#[source] sqlx::Error),
+
+    #[error("could not delete entity")]
+    CouldNotDeleteEntity(#[source] sqlx::Error),
+
+    #[error("could not find entity")]
+    CouldNotFindEntity(#[source] sqlx::Error),
+
+    #[error("could not create entity affiliation")]
+    CouldNotCreateEntityAffiliation(#[source] sqlx::Error),
+
+    #[error("entity is already affiliated to this title group")]
+    DuplicateEntityAffiliation,
+
+    #[error("could not search for entities")]
+    CouldNotSearchForEntities(#[source] sqlx::Error),
+
     #[error("could not search for users")]
     CouldNotSearchForUsers(#[source] sqlx::Error),
 
@@ -230,12 +257,57 @@ pub enum Error {
     #[error("invalid API key or banned")]
     InvalidAPIKeyOrBanned,
 
+    #[error("invalid passkey or banned")]
+    InvalidPasskeyOrBanned,
+
     #[error("invalid or expired refresh token")]
     InvalidOrExpiredRefreshToken,
 
     #[error("invalided token")]
     InvalidatedToken,
 
+    #[error("invalid or expired two-factor authentication challenge")]
+    InvalidOrExpiredTwoFactorChallenge,
+
+    #[error("invalid two-factor authentication code")]
+    InvalidTwoFactorCode,
+
+    #[error("invalid or expired password reset token")]
+    InvalidOrExpiredPasswordResetToken,
+
+    #[error("too many attempts, try again in {0} seconds")]
+    TooManyAttempts(i64),
+
+    #[error("this ip address is banned")]
+    IpBanned,
+
+    #[error("ip ban not found")]
+    IpBanNotFound,
+
+    #[error("ip ban reason cannot be empty")]
+    IpBanReasonEmpty,
+
+    #[error("webhook not found")]
+    WebhookNotFound,
+
+    #[error("{0}")]
+    InvalidWebhook(String),
+
+    #[error("promotion event not found")]
+    PromotionEventNotFound,
+
+    #[error("{0}")]
+    InvalidPromotionEvent(String),
+
+    #[error("two-factor authentication is already enabled")]
+    TwoFactorAlreadyEnabled,
+
+    #[error("two-factor authentication is not set up")]
+    TwoFactorNotSetUp,
+
+    #[error("totp error: {0}")]
+    TotpError(String),
+
     #[error("JWT error")]
     JwtError(#[source] jsonwebtoken::errors::Error),
 
@@ -320,6 +392,21 @@ pub enum Error {
     #[error("could not create gift")]
     CouldNotCreateGift(#[source] sqlx::Error),
 
+    #[error("could not create personal freeleech")]
+    CouldNotCreatePersonalFreeleech(#[source] sqlx::Error),
+
+    #[error("hit and run not found")]
+    HitAndRunNotFound,
+
+    #[error("clearing hit and runs with bonus points is disabled")]
+    HitAndRunClearingDisabled,
+
+    #[error("feed filter not found")]
+    FeedFilterNotFound,
+
+    #[error("feed filter name cannot be empty")]
+    FeedFilterNameEmpty,
+
     #[error("could not create forum post")]
     CouldNotCreateForumPost(#[source] sqlx::Error),
 
@@ -443,6 +530,9 @@ pub enum Error {
     #[error("insufficient permissions: missing {0}")]
     InsufficientPermissions(String),
 
+    #[error("two-factor authentication must be enabled to use the {0} permission")]
+    TwoFactorAuthenticationRequired(String),
+
     #[error("you can only delete your own torrents within 24 hours of uploading")]
     TorrentDeletionWindowExpired,
 
@@ -560,6 +650,27 @@ pub enum Error {
     #[error("invalid tmdb url")]
     InvalidTMDBUrl,
 
+    #[error("igdb data fetching not available")]
+    IGDBDataFetchingNotAvailable,
+
+    #[error("igdb game not found")]
+    IGDBGameNotFound,
+
+    #[error("invalid igdb url")]
+    InvalidIGDBUrl,
+
+    #[error("invalid discogs url")]
+    InvalidDiscogsUrl,
+
+    #[error("anilist media not found")]
+    AniListMediaNotFound,
+
+    #[error("invalid anilist url")]
+    InvalidAniListUrl,
+
+    #[error("invalid tvmaze url")]
+    InvalidTVmazeUrl,
+
     #[error("redis error '{0}'")]
     RedisError(String),
 
@@ -731,6 +842,9 @@ impl actix_web::ResponseError for Error {
             | Error::InvitationKeyRequired
             | Error::InvitationKeyAlreadyUsed
             | Error::WrongUsernameOrPassword
+            | Error::InvalidTwoFactorCode
+            | Error::InvalidOrExpiredPasswordResetToken
+            | Error::TwoFactorNotSetUp
             | Error::TorrentFileInvalid
             | Error::InvalidUserIdOrTorrentId
             | Error::ForumThreadNameEmpty
@@ -745,6 +859,7 @@ impl actix_web::ResponseError for Error {
             | Error::TitleGroupHasUndeletedTorrents
             | Error::EditionGroupHasUndeletedTorrents
             | Error::CannotMergeTitleGroupIntoItself
+            | Error::CannotMergeArtistIntoItself
             | Error::CannotMergeTitleGroupsWithDifferentContentTypes
             | Error::InvalidUserClassName
             | Error::ImageHostNotApproved { .. }
@@ -760,6 +875,10 @@ impl actix_web::ResponseError for Error {
             | Error::InvalidBonusPointsFormula(_)
             | Error::PromotionNotAvailable(_)
             | Error::InvalidTagExpression(_)
+            | Error::FeedFilterNameEmpty
+            | Error::IpBanReasonEmpty
+            | Error::InvalidWebhook(_)
+            | Error::InvalidPromotionEvent(_)
             | Error::TitleGroupTagDeleted(..)
             | Error::EditionGroupsNotInSameTitleGroup
             | Error::UserBadgeCategoryNameEmpty
@@ -767,16 +886,24 @@ impl actix_web::ResponseError for Error {
             | Error::UserBadgeNameEmpty
             | Error::UserBadgeCriteriaMismatch
             | Error::WikiArticleCannotBeLinkedToItself
-            | Error::InvalidSiteHighlight(_) => StatusCode::BAD_REQUEST,
+            | Error::InvalidSiteHighlight(_)
+            | Error::IGDBDataFetchingNotAvailable
+            | Error::InvalidIGDBUrl
+            | Error::InvalidDiscogsUrl
+            | Error::InvalidAniListUrl
+            | Error::InvalidTVmazeUrl => StatusCode::BAD_REQUEST,
 
             // 401 Unauthorized
-            Error::InvalidOrExpiredRefreshToken | Error::InvalidatedToken => {
-                StatusCode::UNAUTHORIZED
-            }
+            Error::InvalidOrExpiredRefreshToken
+            | Error::InvalidatedToken
+            | Error::InvalidPasskeyOrBanned
+            | Error::InvalidOrExpiredTwoFactorChallenge => StatusCode::UNAUTHORIZED,
 
             // 403 Forbidden
             Error::AccountBanned
+            | Error::IpBanned
             | Error::InsufficientPermissions(_)
+            | Error::TwoFactorAuthenticationRequired(_)
             | Error::TorrentDeletionWindowExpired
             | Error::TorrentMoveWindowExpired
             | Error::EditionGroupDeletionWindowExpired
@@ -785,7 +912,8 @@ impl actix_web::ResponseError for Error {
             | Error::ConversationLocked
             | Error::StaffPmResolved
             | Error::UserClassLocked
-            | Error::ShopItemNotAvailable => StatusCode::FORBIDDEN,
+            | Error::ShopItemNotAvailable
+            | Error::HitAndRunClearingDisabled => StatusCode::FORBIDDEN,
 
             // 404 Not Found
             Error::IrcAccountNotFound
@@ -795,6 +923,7 @@ impl actix_web::ResponseError for Error {
             | Error::DottorrentFileNotFound
             | Error::TorrentNotFound
             | Error::CouldNotFindArtist(_)
+            | Error::CouldNotFindEntity(_)
             | Error::TitleGroupTagNotFound
             | Error::CouldNotFindTitleGroupComment(_)
             | Error::CouldNotFindForumThread(_)
@@ -811,10 +940,18 @@ impl actix_web::ResponseError for Error {
             | Error::UserEarnedBadgeNotFound
             | Error::EditionGroupNotFound
             | Error::SiteHighlightNotFound
-            | Error::RelatedForumThreadNotFound => StatusCode::NOT_FOUND,
+            | Error::RelatedForumThreadNotFound
+            | Error::HitAndRunNotFound
+            | Error::FeedFilterNotFound
+            | Error::IpBanNotFound
+            | Error::WebhookNotFound
+            | Error::PromotionEventNotFound
+            | Error::IGDBGameNotFound
+            | Error::AniListMediaNotFound => StatusCode::NOT_FOUND,
 
             // 409 Conflict
             Error::IrcAccountAlreadyExists
+            | Error::TwoFactorAlreadyEnabled
             | Error::NoInvitationsAvailable
             | Error::NotEnoughBonusPointsAvailable
             | Error::NotEnoughFreeleechTokensAvailable
@@ -826,10 +963,14 @@ impl actix_web::ResponseError for Error {
             | Error::UserClassAlreadyExists
             | Error::UserAlreadyHasBadge
             | Error::DuplicateArtistAffiliation
+            | Error::DuplicateEntityAffiliation
             | Error::ForumThreadAlreadyHasPoll
             | Error::ForumPollAlreadyVoted
             | Error::SiteHighlightPositionTaken => StatusCode::CONFLICT,
 
+            // 429 Too Many Requests
+            Error::TooManyAttempts(_) => StatusCode::TOO_MANY_REQUESTS,
+
             // 503 Service Unavailable
             Error::IrcNotEnabled => StatusCode::SERVICE_UNAVAILABLE,
 
@@ -842,7 +983,11 @@ impl actix_web::ResponseError for Error {
         let status_code = self.status_code();
         log::error!("The request generated this error: {self}");
         crate::metrics::record_error(self.as_ref(), status_code.as_u16());
-        actix_web::HttpResponse::build(status_code).json(serde_json::json!({
+        let mut response = actix_web::HttpResponse::build(status_code);
+        if let Error::TooManyAttempts(retry_after) = self {
+            response.insert_header((actix_web::http::header::RETRY_AFTER, *retry_after));
+        }
+        response.json(serde_json::json!({
             "error": format!("{self}"),
         }))
     }
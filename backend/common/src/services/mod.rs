@@ -1 +1,2 @@
 pub mod torrent_service;
+pub mod tracker_client;
@@ -0,0 +1,182 @@
+use arcadia_shared::tracker::models::{
+    env::ArcadiaSettingsForTracker,
+    ip_ban::APIInsertIpBan,
+    personal_freeleech::APIInsertPersonalFreeleech,
+    promotion_event::APIInsertPromotionEvent,
+    state_digest::StateDigest,
+    torrent::{APIInsertTorrent, APIUpdateTorrentFactors},
+    user::{APIInsertUser, APIUpdateUserMaxSnatchesPerDay},
+};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A change of the state the tracker keeps in memory. The backend queues them
+/// in the tracker outbox, from which they are sent until the tracker accepts them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum TrackerMutation {
+    UpsertUser(APIInsertUser),
+    UpdateUserMaxSnatchesPerDay(APIUpdateUserMaxSnatchesPerDay),
+    UpsertTorrent(APIInsertTorrent),
+    DeleteTorrent {
+        id: u32,
+    },
+    UpdateTorrentFactors {
+        id: u32,
+        factors: APIUpdateTorrentFactors,
+    },
+    UpsertPersonalFreeleech(APIInsertPersonalFreeleech),
+    UpsertIpBan(APIInsertIpBan),
+    DeleteIpBan {
+        id: i64,
+    },
+    UpsertPromotionEvent(APIInsertPromotionEvent),
+    DeletePromotionEvent {
+        id: i64,
+    },
+    UpdateSettings(ArcadiaSettingsForTracker),
+}
+
+impl TrackerMutation {
+    /// What the mutation applies to. A mutation supersedes the pending one
+    /// with the same key, as the tracker only needs the latest state.
+    pub fn key(&self) -> String {
+        match self {
+            Self::UpsertUser(user) => format!("user:{}", user.id),
+            Self::UpdateUserMaxSnatchesPerDay(user) => {
+                format!("user_max_snatches_per_day:{}", user.id)
+            }
+            Self::UpsertTorrent(APIInsertTorrent { id, .. }) | Self::DeleteTorrent { id } => {
+                format!("torrent:{id}")
+            }
+            Self::UpdateTorrentFactors { id, .. } => format!("torrent_factors:{id}"),
+            Self::UpsertPersonalFreeleech(personal_freeleech) => format!(
+                "personal_freeleech:{}:{}",
+                personal_freeleech.user_id, personal_freeleech.torrent_id
+            ),
+            Self::UpsertIpBan(APIInsertIpBan { id, .. }) | Self::DeleteIpBan { id } => {
+                format!("ip_ban:{id}")
+            }
+            Self::UpsertPromotionEvent(APIInsertPromotionEvent { id, .. })
+            | Self::DeletePromotionEvent { id } => format!("promotion_event:{id}"),
+            Self::UpdateSettings(_) => "settings".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TrackerClientError {
+    #[error("request to the tracker failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("the tracker answered with {0}")]
+    UnexpectedStatus(StatusCode),
+}
+
+impl TrackerClientError {
+    /// Whether sending the same request again can't succeed
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            Self::Request(_) => false,
+            Self::UnexpectedStatus(status) => matches!(
+                *status,
+                StatusCode::BAD_REQUEST
+                    | StatusCode::METHOD_NOT_ALLOWED
+                    | StatusCode::PAYLOAD_TOO_LARGE
+                    | StatusCode::UNSUPPORTED_MEDIA_TYPE
+                    | StatusCode::UNPROCESSABLE_ENTITY
+            ),
+        }
+    }
+}
+
+/// Client of the tracker's internal api, authenticated with the shared api key
+#[derive(Debug, Clone)]
+pub struct TrackerClient {
+    url_internal: Url,
+    api_key: String,
+    http_client: reqwest::Client,
+}
+
+impl TrackerClient {
+    pub fn new(url_internal: Url, api_key: String, http_client: reqwest::Client) -> Self {
+        Self {
+            url_internal,
+            api_key,
+            http_client,
+        }
+    }
+
+    pub async fn send(&self, mutation: &TrackerMutation) -> Result<(), TrackerClientError> {
+        let request = match mutation {
+            TrackerMutation::UpsertUser(user) => self.request(Method::PUT, &["users"]).json(user),
+            TrackerMutation::UpdateUserMaxSnatchesPerDay(user) => self
+                .request(
+                    Method::PUT,
+                    &["users", &user.id.to_string(), "max-snatches-per-day"],
+                )
+                .json(user),
+            TrackerMutation::UpsertTorrent(torrent) => {
+                self.request(Method::PUT, &["torrents"]).json(torrent)
+            }
+            TrackerMutation::DeleteTorrent { id } => {
+                self.request(Method::DELETE, &["torrents", &id.to_string()])
+            }
+            TrackerMutation::UpdateTorrentFactors { id, factors } => self
+                .request(
+                    Method::PUT,
+                    &["torrents", &id.to_string(), "up-down-factors"],
+                )
+                .json(factors),
+            TrackerMutation::UpsertPersonalFreeleech(personal_freeleech) => self
+                .request(Method::PUT, &["personal-freeleeches"])
+                .json(personal_freeleech),
+            TrackerMutation::UpsertIpBan(ip_ban) => {
+                self.request(Method::PUT, &["ip-bans"]).json(ip_ban)
+            }
+            TrackerMutation::DeleteIpBan { id } => {
+                self.request(Method::DELETE, &["ip-bans", &id.to_string()])
+            }
+            TrackerMutation::UpsertPromotionEvent(promotion_event) => self
+                .request(Method::PUT, &["promotion-events"])
+                .json(promotion_event),
+            TrackerMutation::DeletePromotionEvent { id } => {
+                self.request(Method::DELETE, &["promotion-events", &id.to_string()])
+            }
+            TrackerMutation::UpdateSettings(settings) => {
+                self.request(Method::PUT, &["settings"]).json(settings)
+            }
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(TrackerClientError::UnexpectedStatus(response.status()));
+        }
+
+        Ok(())
+    }
+
+    /// Summary of the tracker's in-memory state, to compare with the database
+    pub async fn state_digest(&self) -> Result<StateDigest, TrackerClientError> {
+        let response = self.request(Method::GET, &["state-digest"]).send().await?;
+        if !response.status().is_success() {
+            return Err(TrackerClientError::UnexpectedStatus(response.status()));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    fn request(&self, method: Method, path: &[&str]) -> reqwest::RequestBuilder {
+        let mut url = self.url_internal.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .push("api")
+            .extend(path);
+
+        self.http_client
+            .request(method, url)
+            .header("x-api-key", self.api_key.clone())
+    }
+}
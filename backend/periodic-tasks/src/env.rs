@@ -26,8 +26,21 @@ pub struct PeriodicTasksConfig {
     pub expired_warnings_seconds: u64,
     #[envconfig(from = "TASK_INTERVAL_ARTIST_PEER_STATS_UPDATE_SECONDS")]
     pub artist_peer_stats_update_seconds: u64,
+    #[envconfig(
+        from = "TASK_INTERVAL_ENTITY_PEER_STATS_UPDATE_SECONDS",
+        default = "1800"
+    )]
+    pub entity_peer_stats_update_seconds: u64,
     #[envconfig(from = "TASK_INTERVAL_USER_BADGES_EVALUATION_SECONDS")]
     pub user_badges_evaluation_seconds: u64,
+    #[envconfig(from = "TASK_INTERVAL_HIT_AND_RUNS_SECONDS", default = "3600")]
+    pub hit_and_runs_seconds: u64,
+    #[envconfig(from = "TASK_INTERVAL_WEBHOOK_DELIVERIES_SECONDS", default = "30")]
+    pub webhook_deliveries_seconds: u64,
+    #[envconfig(from = "TASK_INTERVAL_TRACKER_OUTBOX_SECONDS", default = "5")]
+    pub tracker_outbox_seconds: u64,
+    #[envconfig(from = "TASK_INTERVAL_TRACKER_RECONCILIATION_SECONDS", default = "600")]
+    pub tracker_reconciliation_seconds: u64,
 }
 
 /// Validates and converts a formula string to SQL expression.
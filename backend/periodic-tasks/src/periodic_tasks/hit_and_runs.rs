@@ -0,0 +1,46 @@
+use arcadia_storage::connection_pool::ConnectionPool;
+use std::sync::Arc;
+
+/// Flags snatches that were not seeded enough within the grace period, clears
+/// the ones that have since met the requirements, and warns users who reached
+/// the configured amount of active hit and runs (lifting the warning of the
+/// ones back under it).
+pub async fn process_hit_and_runs(
+    pool: Arc<ConnectionPool>,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let settings = pool.get_arcadia_settings().await?;
+
+    let Some(min_seed_time_hours) = settings.hit_and_run_min_seed_time_hours else {
+        log::debug!("Hit and run detection is disabled, skipping");
+        return Ok(0);
+    };
+
+    let cleared_count = pool
+        .clear_seeded_hit_and_runs(
+            min_seed_time_hours,
+            settings.hit_and_run_min_ratio,
+            settings.hit_and_run_warning_threshold,
+        )
+        .await?;
+
+    let flagged_count = pool
+        .flag_hit_and_runs(
+            min_seed_time_hours,
+            settings.hit_and_run_min_ratio,
+            settings.hit_and_run_grace_period_days,
+        )
+        .await?;
+
+    let warned_count = match settings.hit_and_run_warning_threshold {
+        Some(threshold) => pool.warn_users_with_hit_and_runs(threshold).await?,
+        None => 0,
+    };
+
+    log::info!(
+        "Hit and runs: {} flagged, {} cleared by seeding, {} users warned",
+        flagged_count,
+        cleared_count,
+        warned_count
+    );
+    Ok(flagged_count + cleared_count + warned_count)
+}
@@ -1,10 +1,13 @@
 pub mod bonus_points;
 pub mod expired_warnings;
+pub mod hit_and_runs;
 pub mod inactive_users;
 pub mod materialized_views;
 pub mod peers;
 pub mod scheduler;
 pub mod seeding_size;
 pub mod torrents;
+pub mod tracker_sync;
 pub mod user_badges;
 pub mod user_classes;
+pub mod webhooks;
@@ -9,12 +9,15 @@ use crate::store::Store;
 
 use super::bonus_points::update_seedtime_and_bonus_points;
 use super::expired_warnings::clear_expired_warnings;
+use super::hit_and_runs::process_hit_and_runs;
 use super::inactive_users::ban_inactive_users;
 use super::materialized_views::refresh_title_group_hierarchy_lite;
-use super::peers::update_artist_peer_stats;
+use super::peers::{update_artist_peer_stats, update_entity_peer_stats};
 use super::seeding_size::update_user_torrent_stats;
+use super::tracker_sync::{deliver_tracker_outbox, reconcile_tracker_state};
 use super::user_badges::evaluate_user_badges;
 use super::user_classes::process_user_class_changes;
+use super::webhooks::{build_webhooks_http_client, deliver_webhooks};
 
 static INSTRUMENTS: OnceLock<PeriodicTaskInstruments> = OnceLock::new();
 
@@ -125,6 +128,20 @@ pub async fn run_periodic_tasks(
     )?;
     sched.add(artist_peer_stats_job).await?;
 
+    let pool_entity_peers = Arc::clone(&store.pool);
+    let entity_peer_stats_job = Job::new_repeated_async(
+        Duration::from_secs(store.env.periodic_tasks.entity_peer_stats_update_seconds),
+        move |_uuid, _l| {
+            let pool = Arc::clone(&pool_entity_peers);
+            Box::pin(instrument_periodic_task(
+                instruments(),
+                "entity_peer_stats",
+                move || update_entity_peer_stats(pool),
+            ))
+        },
+    )?;
+    sched.add(entity_peer_stats_job).await?;
+
     let pool_materialized_views = Arc::clone(&store.pool);
     let materialized_view_refresh_job = Job::new_repeated_async(
         Duration::from_secs(store.env.periodic_tasks.materialized_view_refresh_seconds),
@@ -153,6 +170,64 @@ pub async fn run_periodic_tasks(
     )?;
     sched.add(user_badges_job).await?;
 
+    let pool_hit_and_runs = Arc::clone(&store.pool);
+    let hit_and_runs_job = Job::new_repeated_async(
+        Duration::from_secs(store.env.periodic_tasks.hit_and_runs_seconds),
+        move |_uuid, _l| {
+            let pool = Arc::clone(&pool_hit_and_runs);
+            Box::pin(instrument_periodic_task(
+                instruments(),
+                "hit_and_runs",
+                move || process_hit_and_runs(pool),
+            ))
+        },
+    )?;
+    sched.add(hit_and_runs_job).await?;
+
+    let pool_webhooks = Arc::clone(&store.pool);
+    let webhooks_http_client = build_webhooks_http_client()?;
+    let webhook_deliveries_job = Job::new_repeated_async(
+        Duration::from_secs(store.env.periodic_tasks.webhook_deliveries_seconds),
+        move |_uuid, _l| {
+            let pool = Arc::clone(&pool_webhooks);
+            let http_client = webhooks_http_client.clone();
+            Box::pin(instrument_periodic_task(
+                instruments(),
+                "webhook_deliveries",
+                move || deliver_webhooks(pool, http_client),
+            ))
+        },
+    )?;
+    sched.add(webhook_deliveries_job).await?;
+
+    let pool_tracker_outbox = Arc::clone(&store.pool);
+    let tracker_outbox_job = Job::new_repeated_async(
+        Duration::from_secs(store.env.periodic_tasks.tracker_outbox_seconds),
+        move |_uuid, _l| {
+            let pool = Arc::clone(&pool_tracker_outbox);
+            Box::pin(instrument_periodic_task(
+                instruments(),
+                "tracker_outbox",
+                move || deliver_tracker_outbox(pool),
+            ))
+        },
+    )?;
+    sched.add(tracker_outbox_job).await?;
+
+    let pool_tracker_reconciliation = Arc::clone(&store.pool);
+    let tracker_reconciliation_job = Job::new_repeated_async(
+        Duration::from_secs(store.env.periodic_tasks.tracker_reconciliation_seconds),
+        move |_uuid, _l| {
+            let pool = Arc::clone(&pool_tracker_reconciliation);
+            Box::pin(instrument_periodic_task(
+                instruments(),
+                "tracker_reconciliation",
+                move || reconcile_tracker_state(pool),
+            ))
+        },
+    )?;
+    sched.add(tracker_reconciliation_job).await?;
+
     sched.start().await?;
 
     Ok(sched)
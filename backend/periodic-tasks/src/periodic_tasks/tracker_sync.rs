@@ -0,0 +1,169 @@
+use arcadia_common::{error::Result, services::tracker_client::TrackerMutation};
+use arcadia_shared::tracker::models::state_digest::bucket_of;
+use arcadia_storage::{
+    connection_pool::ConnectionPool, models::tracker_outbox::TrackerStateBuckets,
+};
+use chrono::{Duration, Utc};
+use std::{collections::HashSet, sync::Arc};
+
+/// Mutations sent per run
+const BATCH_SIZE: i64 = 500;
+/// A mutation is given up on after this many attempts, the reconciliation
+/// takes over from there
+const MAX_ATTEMPTS: i32 = 10;
+/// Delay before the first retry, doubled after every failed attempt
+const RETRY_BASE_DELAY_SECONDS: i64 = 5;
+
+/// Sends the queued mutations to the tracker, in the order they were queued,
+/// and schedules a retry with exponential backoff for the ones that failed.
+pub async fn deliver_tracker_outbox(pool: Arc<ConnectionPool>) -> Result<u64> {
+    let mutations = pool.claim_pending_tracker_mutations(BATCH_SIZE).await?;
+    if mutations.is_empty() {
+        return Ok(0);
+    }
+
+    let mut sent_count = 0;
+    let mut failed_count = 0;
+    for pending in &mutations {
+        match pool.tracker_client.send(&pending.mutation).await {
+            Ok(()) => {
+                pool.mark_tracker_mutation_sent(pending.id, pending.revision)
+                    .await?;
+                sent_count += 1;
+            }
+            Err(error) => {
+                let attempts = pending.attempts + 1;
+                let next_attempt_at =
+                    (!error.is_permanent() && attempts < MAX_ATTEMPTS).then(|| {
+                        Utc::now() + Duration::seconds(RETRY_BASE_DELAY_SECONDS << (attempts - 1))
+                    });
+                pool.mark_tracker_mutation_attempt_failed(
+                    pending.id,
+                    pending.revision,
+                    &error.to_string(),
+                    next_attempt_at,
+                )
+                .await?;
+                failed_count += 1;
+            }
+        }
+    }
+
+    log::info!(
+        "Tracker outbox: {} mutations sent, {} failed",
+        sent_count,
+        failed_count
+    );
+    Ok(mutations.len() as u64)
+}
+
+/// Compares the state in the database with the tracker's and queues the
+/// mutations that bring the tracker back in line. Returns how many were queued.
+pub async fn reconcile_tracker_state(pool: Arc<ConnectionPool>) -> Result<u64> {
+    let tracker_digest = match pool.tracker_client.state_digest().await {
+        Ok(digest) => digest,
+        Err(error) => {
+            log::warn!("Could not get the tracker's state digest: {error}");
+            return Ok(0);
+        }
+    };
+    let database_digest = pool.find_tracker_state_digest().await?;
+    if database_digest == tracker_digest {
+        return Ok(0);
+    }
+
+    // only the users and torrents of the buckets that differ are read, there
+    // are too many of them to hold them all in memory
+    let buckets = TrackerStateBuckets {
+        users: database_digest
+            .users
+            .mismatched_buckets(&tracker_digest.users)
+            .into_iter()
+            .map(|bucket| bucket as i32)
+            .collect(),
+        torrents: database_digest
+            .torrents
+            .mismatched_buckets(&tracker_digest.torrents)
+            .into_iter()
+            .map(|bucket| bucket as i32)
+            .collect(),
+    };
+    let state = pool.find_tracker_state(&buckets).await?;
+
+    let mut mutations = Vec::new();
+    mutations.extend(state.users.into_iter().map(TrackerMutation::UpsertUser));
+    mutations.extend(
+        state
+            .torrents
+            .into_iter()
+            .map(TrackerMutation::UpsertTorrent),
+    );
+
+    let buckets = database_digest
+        .personal_freeleeches
+        .mismatched_buckets(&tracker_digest.personal_freeleeches);
+    mutations.extend(
+        state
+            .personal_freeleeches
+            .into_iter()
+            .filter(|personal_freeleech| {
+                buckets.contains(&bucket_of(personal_freeleech.user_id as u64))
+            })
+            .map(TrackerMutation::UpsertPersonalFreeleech),
+    );
+
+    let buckets = database_digest
+        .ip_bans
+        .mismatched_buckets(&tracker_digest.ip_bans);
+    let ip_ban_ids: HashSet<i64> = state.ip_bans.iter().map(|ip_ban| ip_ban.id).collect();
+    mutations.extend(
+        tracker_digest
+            .ip_ban_ids
+            .iter()
+            .filter(|id| !ip_ban_ids.contains(id))
+            .map(|id| TrackerMutation::DeleteIpBan { id: *id }),
+    );
+    mutations.extend(
+        state
+            .ip_bans
+            .into_iter()
+            .filter(|ip_ban| buckets.contains(&bucket_of(ip_ban.id as u64)))
+            .map(TrackerMutation::UpsertIpBan),
+    );
+
+    let buckets = database_digest
+        .promotion_events
+        .mismatched_buckets(&tracker_digest.promotion_events);
+    let promotion_event_ids: HashSet<i64> = state
+        .promotion_events
+        .iter()
+        .map(|promotion_event| promotion_event.id)
+        .collect();
+    mutations.extend(
+        tracker_digest
+            .promotion_event_ids
+            .iter()
+            .filter(|id| !promotion_event_ids.contains(id))
+            .map(|id| TrackerMutation::DeletePromotionEvent { id: *id }),
+    );
+    mutations.extend(
+        state
+            .promotion_events
+            .into_iter()
+            .filter(|promotion_event| buckets.contains(&bucket_of(promotion_event.id as u64)))
+            .map(TrackerMutation::UpsertPromotionEvent),
+    );
+
+    if database_digest.settings != tracker_digest.settings {
+        mutations.push(TrackerMutation::UpdateSettings(state.settings));
+    }
+
+    let queued_count = mutations.len() as u64;
+    log::info!(
+        "Tracker reconciliation: the tracker is out of sync, queued {} mutations",
+        queued_count
+    );
+    pool.queue_tracker_mutations(mutations).await?;
+
+    Ok(queued_count)
+}
@@ -46,10 +46,7 @@ pub async fn process_user_class_changes(
                     user.class_name,
                     previous_class_name
                 );
-                match pool
-                    .change_user_class(user.id, previous_class_name, true)
-                    .await
-                {
+                match pool.change_user_class(user.id, previous_class_name).await {
                     Ok(_) => {
                         demotions += 1;
                     }
@@ -86,10 +83,7 @@ pub async fn process_user_class_changes(
                         user.class_name,
                         next_class.name
                     );
-                    match pool
-                        .change_user_class(user.id, &next_class.name, true)
-                        .await
-                    {
+                    match pool.change_user_class(user.id, &next_class.name).await {
                         Ok(_) => {
                             promotions += 1;
                             // Only promote one level at a time
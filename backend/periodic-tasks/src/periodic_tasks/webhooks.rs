@@ -0,0 +1,144 @@
+use arcadia_common::error::Result;
+use arcadia_storage::{
+    connection_pool::ConnectionPool, models::webhook::PendingWebhookDelivery, utils::is_global_ip,
+};
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::Policy,
+};
+use sha2::Sha256;
+use std::{net::SocketAddr, sync::Arc};
+
+/// Deliveries sent per run
+const BATCH_SIZE: i64 = 100;
+/// A delivery is marked as failed after this many attempts
+const MAX_ATTEMPTS: i32 = 8;
+/// Delay before the first retry, doubled after every failed attempt
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+
+/// Resolves hosts through the system resolver, leaving out the addresses that are not public
+/// so that a webhook cannot be pointed at internal services
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_global_ip(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(
+                    format!("{} does not resolve to a public address", name.as_str()).into(),
+                );
+            }
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Client used for the deliveries: it only connects to public addresses and does not follow
+/// redirects, which could lead anywhere
+pub fn build_webhooks_http_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .redirect(Policy::none())
+        .dns_resolver(Arc::new(PublicAddressResolver))
+        .build()
+}
+
+/// Sends the due webhook deliveries and schedules a retry with exponential
+/// backoff for the ones that failed.
+pub async fn deliver_webhooks(
+    pool: Arc<ConnectionPool>,
+    http_client: reqwest::Client,
+) -> Result<u64> {
+    let deliveries = pool.claim_pending_webhook_deliveries(BATCH_SIZE).await?;
+    if deliveries.is_empty() {
+        return Ok(0);
+    }
+
+    let mut delivered_count = 0;
+    let mut failed_count = 0;
+    for delivery in &deliveries {
+        match send_delivery(&http_client, delivery).await {
+            Ok(response_status) => {
+                pool.mark_webhook_delivery_delivered(delivery.id, response_status)
+                    .await?;
+                delivered_count += 1;
+            }
+            Err((response_status, error)) => {
+                let attempts = delivery.attempts + 1;
+                let next_attempt_at = (attempts < MAX_ATTEMPTS).then(|| {
+                    Utc::now() + Duration::seconds(RETRY_BASE_DELAY_SECONDS << (attempts - 1))
+                });
+                pool.mark_webhook_delivery_attempt_failed(
+                    delivery.id,
+                    response_status,
+                    &error,
+                    next_attempt_at,
+                )
+                .await?;
+                failed_count += 1;
+            }
+        }
+    }
+
+    log::info!(
+        "Webhooks: {} deliveries sent, {} failed",
+        delivered_count,
+        failed_count
+    );
+    Ok(deliveries.len() as u64)
+}
+
+/// Value of the X-Arcadia-Signature header: the HMAC-SHA256 of the body, keyed with the webhook secret
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any size");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Returns the http status, along with the error when the delivery failed
+async fn send_delivery(
+    http_client: &reqwest::Client,
+    delivery: &PendingWebhookDelivery,
+) -> std::result::Result<i32, (Option<i32>, String)> {
+    // urls with an ip address are not resolved, so they are checked here
+    let url = reqwest::Url::parse(&delivery.url).map_err(|error| (None, error.to_string()))?;
+    let literal_ip = match url.host() {
+        Some(url::Host::Ipv4(ip)) => Some(ip.into()),
+        Some(url::Host::Ipv6(ip)) => Some(ip.into()),
+        _ => None,
+    };
+    if literal_ip.is_some_and(|ip| !is_global_ip(ip)) {
+        return Err((None, "the url does not point to a public address".into()));
+    }
+
+    let body = serde_json::to_vec(&delivery.payload).expect("json values serialize");
+
+    let response = http_client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header("X-Arcadia-Event", delivery.event.to_string())
+        .header("X-Arcadia-Delivery", delivery.id)
+        .header("X-Arcadia-Signature", sign_payload(&delivery.secret, &body))
+        .body(body)
+        .send()
+        .await
+        .map_err(|error| (None, error.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16() as i32)
+    } else {
+        Err((
+            Some(status.as_u16() as i32),
+            format!("the receiver answered with {status}"),
+        ))
+    }
+}
@@ -1,5 +1,6 @@
 use crate::env::{formula_to_sql, Env};
-use arcadia_storage::connection_pool::{ConnectionPool, TrackerConfig};
+use arcadia_common::services::tracker_client::TrackerClient;
+use arcadia_storage::connection_pool::ConnectionPool;
 use envconfig::Envconfig;
 use std::sync::Arc;
 
@@ -9,10 +10,10 @@ pub struct Store {
 }
 
 impl Store {
-    pub async fn new(tracker_config: TrackerConfig, internal_http_client: reqwest::Client) -> Self {
+    pub async fn new(tracker_client: TrackerClient, internal_http_client: reqwest::Client) -> Self {
         let mut env = Env::init_from_env().unwrap();
         let pool = Arc::new(
-            ConnectionPool::try_new(&env.database_url, tracker_config, internal_http_client)
+            ConnectionPool::try_new(&env.database_url, tracker_client, internal_http_client)
                 .await
                 .expect("db connection"),
         );
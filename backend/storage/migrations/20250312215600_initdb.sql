@@ -1,4 +1,15 @@
 CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- unaccent() is only STABLE, this wrapper can be used in indexes
+CREATE FUNCTION f_unaccent(TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+AS $$ SELECT public.unaccent('public.unaccent', $1) $$;
+
+-- same for name lists (aliases), array_to_string() is only STABLE too
+CREATE FUNCTION f_unaccent_array(TEXT[]) RETURNS TEXT
+LANGUAGE sql IMMUTABLE PARALLEL SAFE STRICT
+AS $$ SELECT public.unaccent('public.unaccent', array_to_string($1, ' ')) $$;
 
 CREATE TYPE user_permissions_enum AS ENUM (
     'create_user_class',
@@ -17,6 +28,9 @@ CREATE TYPE user_permissions_enum AS ENUM (
     'edit_torrent',
     'edit_artist',
     'delete_artist',
+    'merge_artist',
+    'edit_entity',
+    'delete_entity',
     'delete_title_group',
     'edit_collage',
     'delete_collage',
@@ -62,6 +76,8 @@ CREATE TYPE user_permissions_enum AS ENUM (
     'search_donation',
     'search_users',
     'search_unauthorized_access',
+    'search_auth_attempts',
+    'search_cheat_flags',
     'search_user_edit_change_logs',
     'delete_user_edit_change_log',
     'delete_forum_category',
@@ -89,6 +105,9 @@ CREATE TYPE user_permissions_enum AS ENUM (
     'award_user_badge',
     'revoke_user_badge',
     'manage_site_highlights',
+    'manage_ip_bans',
+    'manage_webhooks',
+    'manage_promotion_events',
     'manage_related_forum_thread',
     'create_forum_poll_vote'
 );
@@ -116,7 +135,12 @@ CREATE TABLE user_classes (
     required_forum_posts_in_unique_threads INT NOT NULL DEFAULT 0,
     required_title_group_comments INT NOT NULL DEFAULT 0,
     required_seeding_size BIGINT NOT NULL DEFAULT 0,
-    promotion_cost_bonus_points BIGINT NOT NULL DEFAULT 0
+    promotion_cost_bonus_points BIGINT NOT NULL DEFAULT 0,
+    -- torrents that can be leeched at the same time, unlimited when NULL
+    max_leeching INT,
+    -- when true, the slots are scaled down by the ratio of users below 1
+    -- (at least one is kept), see user_max_leeching()
+    max_leeching_scaled_by_ratio BOOLEAN NOT NULL DEFAULT FALSE
 );
 INSERT INTO user_classes (name, new_permissions)
 VALUES ('newbie', '{}');
@@ -174,6 +198,23 @@ CREATE TABLE users (
 );
 INSERT INTO users (username, email, password_hash, registered_from_ip, passkey, class_name, css_sheet_name)
 VALUES ('creator', 'none@domain.com', 'none', '127.0.0.1', 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa', 'newbie', 'arcadia');
+-- leeching slots of a user, computed from their class as the ratio keeps changing
+CREATE FUNCTION user_max_leeching(
+    user_class_name VARCHAR(30),
+    user_uploaded BIGINT,
+    user_downloaded BIGINT
+) RETURNS INT AS $$
+    SELECT CASE
+        WHEN uc.max_leeching IS NULL
+            OR NOT uc.max_leeching_scaled_by_ratio
+            OR user_downloaded <= 0
+            OR user_uploaded >= user_downloaded
+        THEN uc.max_leeching
+        ELSE GREATEST(1, FLOOR(uc.max_leeching * user_uploaded::FLOAT / user_downloaded))::INT
+    END
+    FROM user_classes uc
+    WHERE uc.name = user_class_name
+$$ LANGUAGE SQL STABLE;
 CREATE TABLE css_sheets (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     created_by_id INT NOT NULL REFERENCES users(id),
@@ -198,7 +239,44 @@ CREATE TABLE unauthorized_accesses (
     missing_permission user_permissions_enum NOT NULL,
     path TEXT NOT NULL
 );
+CREATE TYPE auth_attempt_action_enum AS ENUM (
+    'login',
+    'register',
+    'apply'
+);
+-- attempts on the unauthenticated auth routes, which are rate limited by ip and username
+CREATE TABLE auth_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    action auth_attempt_action_enum NOT NULL,
+    -- unknown when the request doesn't come with a client address
+    ip INET,
+    -- as submitted, the user doesn't necessarily exist
+    username VARCHAR(255),
+    successful BOOLEAN NOT NULL,
+    -- refused right away because the ip or username was locked out
+    locked_out BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE INDEX idx_auth_attempts_created_at ON auth_attempts(created_at);
+CREATE INDEX idx_auth_attempts_ip ON auth_attempts(ip);
+-- addresses and ranges refused at registration, login and by the tracker
+CREATE TABLE ip_bans (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_by_id INT NOT NULL REFERENCES users(id),
+    -- a single address is stored as a /32 (or /128) network
+    ip_range CIDR NOT NULL,
+    reason TEXT NOT NULL,
+    -- the ban is permanent when null
+    expires_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX idx_ip_bans_ip_range ON ip_bans USING GIST (ip_range inet_ops);
 
+CREATE TYPE cheat_flag_severity_enum AS ENUM (
+    'low',
+    'medium',
+    'high'
+);
 CREATE TYPE snatched_torrent_bonus_points_transferred_to_enum AS ENUM (
     'uploader',
     'current_seeders'
@@ -295,7 +373,20 @@ CREATE TABLE arcadia_settings (
     irc_webchat_enabled BOOLEAN NOT NULL DEFAULT FALSE,
     irc_webchat_default_channels TEXT[] NOT NULL DEFAULT '{#general}',
     min_amount_tags_title_group INT NOT NULL DEFAULT 1,
-    custom_js_code TEXT DEFAULT NULL
+    custom_js_code TEXT DEFAULT NULL,
+    freeleech_token_duration_hours INT NOT NULL DEFAULT 24,
+    -- hit and run detection is disabled when NULL
+    hit_and_run_min_seed_time_hours INT DEFAULT NULL,
+    hit_and_run_min_ratio FLOAT NOT NULL DEFAULT 1,
+    hit_and_run_grace_period_days INT NOT NULL DEFAULT 14,
+    -- users are automatically warned once they reach this amount of active hit and runs, disabled when NULL
+    hit_and_run_warning_threshold INT DEFAULT NULL,
+    -- clearing a hit and run with bonus points is disabled when NULL
+    hit_and_run_clear_bonus_points_cost BIGINT DEFAULT NULL,
+    -- these permissions can only be used by users who enabled two-factor authentication
+    two_factor_required_permissions user_permissions_enum[] NOT NULL DEFAULT '{}',
+    -- announces flagged by the tracker with at least this severity are not credited any upload, disabled when NULL
+    cheat_flag_withhold_credit_min_severity cheat_flag_severity_enum DEFAULT NULL
 );
 INSERT INTO arcadia_settings (user_class_name_on_signup, default_css_sheet_name, open_signups, global_upload_factor, global_download_factor, bonus_points_given_on_upload, allow_uploader_set_torrent_bonus_points_cost, default_torrent_bonus_points_cost)
 VALUES ('newbie', 'arcadia', TRUE, 100, 100, 100, FALSE, 0);
@@ -306,6 +397,23 @@ CREATE TABLE api_keys (
     value VARCHAR(40) NOT NULL UNIQUE,
     user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE
 );
+CREATE TABLE user_totps (
+    user_id INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- base32 encoded
+    secret VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- NULL until the user confirms the enrolment with a valid code
+    enabled_at TIMESTAMP WITH TIME ZONE,
+    -- time step of the last accepted code, codes of this step or earlier are rejected
+    last_used_step BIGINT
+);
+CREATE TABLE user_totp_recovery_codes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- sha256 hex digest
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE
+);
 CREATE TYPE user_application_status_enum AS ENUM (
     'pending',
     'accepted',
@@ -341,6 +449,8 @@ CREATE TABLE user_warnings (
     expires_at TIMESTAMP WITH TIME ZONE,
     reason TEXT NOT NULL,
     ban boolean NOT NULL,
+    -- given for reaching the hit and run threshold, lifted once back under it
+    hit_and_run BOOLEAN NOT NULL DEFAULT FALSE,
     created_by_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
     removed_at TIMESTAMP WITH TIME ZONE,
     removed_by_id INT REFERENCES users(id) ON DELETE SET NULL
@@ -360,6 +470,7 @@ CREATE TABLE artists (
     aliases VARCHAR(255) [] NOT NULL DEFAULT '{}',
     description TEXT NOT NULL,
     pictures TEXT [] NOT NULL,
+    external_links TEXT [] NOT NULL DEFAULT '{}',
     created_by_id INT NOT NULL,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     title_groups_amount INT NOT NULL DEFAULT 0,
@@ -377,6 +488,13 @@ CREATE TABLE similar_artists (
     FOREIGN KEY (artist_1_id) REFERENCES artists(id) ON DELETE CASCADE,
     FOREIGN KEY (artist_2_id) REFERENCES artists(id) ON DELETE CASCADE
 );
+-- artists merged into another one, so their old ids keep resolving
+CREATE TABLE artist_redirects (
+    old_artist_id BIGINT PRIMARY KEY,
+    artist_id BIGINT NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_by_id INT NOT NULL REFERENCES users(id)
+);
 CREATE TABLE master_groups (
     id SERIAL PRIMARY KEY,
     name VARCHAR(255),
@@ -770,8 +888,13 @@ CREATE TABLE torrents (
     created_by_id INT NOT NULL,
     deleted_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
     deleted_by_id INT DEFAULT NULL,
+    -- for v2 only torrents, the truncated v2 info hash which clients announce with
     info_hash BYTEA NOT NULL CHECK(octet_length(info_hash) = 20),
+    -- SHA-256 info hash of BEP 52 (v2 and hybrid) torrents
+    info_hash_v2 BYTEA CHECK(octet_length(info_hash_v2) = 32),
     info_dict BYTEA NOT NULL,
+    -- bencoded piece layers of BEP 52 torrents, served along the info dict
+    piece_layers BYTEA,
     languages language_enum[] NOT NULL DEFAULT ARRAY[]::language_enum[],
     release_name TEXT NOT NULL,
     -- maybe change the size
@@ -814,7 +937,8 @@ CREATE TABLE torrents (
 
     FOREIGN KEY (edition_group_id) REFERENCES edition_groups(id) ON DELETE CASCADE,
     FOREIGN KEY (created_by_id) REFERENCES users(id) ON DELETE SET NULL,
-    UNIQUE (info_hash)
+    UNIQUE (info_hash),
+    UNIQUE (info_hash_v2)
 );
 CREATE TABLE title_group_comments (
     id BIGSERIAL PRIMARY KEY,
@@ -902,7 +1026,7 @@ CREATE TABLE peers (
     updated_at timestamp without time zone DEFAULT NULL,
     torrent_id integer NOT NULL,
     user_id integer NOT NULL,
-    -- connectable boolean NOT NULL DEFAULT FALSE,
+    connectable boolean NOT NULL DEFAULT FALSE,
     active boolean NOT NULL,
     -- visible boolean NOT NULL,
     PRIMARY KEY (user_id, torrent_id, peer_id)
@@ -914,6 +1038,32 @@ ALTER TABLE peers
 ADD CONSTRAINT peers_torrent_id_foreign FOREIGN KEY (torrent_id) REFERENCES torrents (id) ON DELETE CASCADE ON UPDATE CASCADE;
 ALTER TABLE peers
 ADD CONSTRAINT peers_user_id_foreign FOREIGN KEY (user_id) REFERENCES users (id) ON UPDATE CASCADE;
+CREATE TYPE cheat_flag_reason_enum AS ENUM (
+    'impossible_upload_speed',
+    'upload_without_leechers',
+    'upload_exceeds_swarm_download'
+);
+-- announces whose reported upload is implausible, flagged by the tracker
+CREATE TABLE cheat_flags (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    torrent_id INT NOT NULL REFERENCES torrents(id) ON DELETE CASCADE,
+    peer_id BYTEA NOT NULL,
+    ip INET NOT NULL,
+    reason cheat_flag_reason_enum NOT NULL,
+    severity cheat_flag_severity_enum NOT NULL,
+    -- reported by the client since its previous announce
+    uploaded_delta BIGINT NOT NULL,
+    seconds_since_last_announce BIGINT NOT NULL,
+    -- the other users' peers in the swarm at the time of the announce
+    swarm_leechers INT NOT NULL,
+    swarm_downloaded BIGINT NOT NULL,
+    -- the upload of the announce was not credited to the user
+    credit_withheld BOOLEAN NOT NULL
+);
+CREATE INDEX idx_cheat_flags_user_id ON cheat_flags(user_id);
+CREATE INDEX idx_cheat_flags_created_at ON cheat_flags(created_at);
 CREATE TABLE torrent_activities (
     id BIGSERIAL PRIMARY KEY,
     torrent_id INT NOT NULL,
@@ -936,9 +1086,74 @@ CREATE TABLE torrent_activities (
 
     UNIQUE (torrent_id, user_id)
 );
+-- freeleech windows bought by users with their freeleech tokens
+CREATE TABLE personal_freeleeches (
+    user_id INT NOT NULL,
+    torrent_id INT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrents(id) ON DELETE CASCADE,
+
+    PRIMARY KEY (user_id, torrent_id)
+);
+-- site-wide freeleech, double upload, etc. applied by the tracker while active
+CREATE TABLE promotion_events (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_by_id INT NOT NULL REFERENCES users(id),
+    name VARCHAR(100) NOT NULL,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    upload_factor SMALLINT NOT NULL,
+    download_factor SMALLINT NOT NULL,
+    -- scope, the event applies to every torrent when they are all null
+    content_type content_type_enum,
+    category title_group_category_enum,
+    -- same syntax as the tag filter of the torrent search
+    tag_expression TEXT,
+    -- tag_expression in disjunctive normal form, as matched by the queries
+    tag_filter JSONB,
+    min_size BIGINT,
+
+    CHECK (ends_at > starts_at)
+);
+CREATE INDEX idx_promotion_events_ends_at ON promotion_events(ends_at);
+CREATE TYPE hit_and_run_cleared_by_enum AS ENUM (
+    'seeding',
+    'bonus_points'
+);
+-- snatches that were not seeded enough within the grace period
+CREATE TABLE hit_and_runs (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    torrent_id INT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    cleared_at TIMESTAMP WITH TIME ZONE,
+    cleared_by hit_and_run_cleared_by_enum,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (torrent_id) REFERENCES torrents(id) ON DELETE CASCADE,
+
+    UNIQUE (user_id, torrent_id)
+);
+CREATE TABLE feed_filters (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- subset of the torrent search filters
+    search JSONB NOT NULL,
+    -- same syntax as the tag filter of the torrent search
+    tag_expression TEXT,
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_feed_filters_user_id ON feed_filters(user_id);
 CREATE TABLE entities (
     id BIGSERIAL PRIMARY KEY,
-    name VARCHAR(255) NOT NULL,
+    name VARCHAR(255) UNIQUE NOT NULL,
     description TEXT NOT NULL,
     pictures TEXT[] NOT NULL,
     created_by_id INT NOT NULL,
@@ -955,7 +1170,11 @@ CREATE TYPE entity_role_enum AS ENUM (
     'producer',
     'developer',
     'designer',
-    'label'
+    'label',
+    'network',
+    'studio',
+    'publisher',
+    'franchise'
 );
 CREATE TABLE affiliated_entities (
     id BIGSERIAL PRIMARY KEY,
@@ -966,7 +1185,8 @@ CREATE TABLE affiliated_entities (
     roles entity_role_enum[] NOT NULL,
     FOREIGN KEY (title_group_id) REFERENCES title_groups(id) ON DELETE CASCADE,
     FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE CASCADE,
-    FOREIGN KEY (created_by_id) REFERENCES users(id) ON DELETE SET NULL
+    FOREIGN KEY (created_by_id) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE(title_group_id, entity_id)
 );
 CREATE TYPE collage_category_enum AS ENUM (
     'Personal',
@@ -1309,6 +1529,20 @@ CREATE TABLE torrent_deletion_notifications (
     FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
     FOREIGN KEY (torrent_id) REFERENCES torrent_deletions(torrent_id) ON DELETE CASCADE
 );
+-- info hashes announced by users while unknown to the tracker, or after their
+-- torrent was deleted. torrent_id is intentionally not a foreign key, like in
+-- torrent_deletions, so that the tracker can always flush them.
+CREATE TABLE unregistered_info_hashes (
+    user_id INT NOT NULL,
+    info_hash BYTEA NOT NULL CHECK(octet_length(info_hash) = 20),
+    -- the deleted torrent, NULL when the info hash was never registered
+    torrent_id INT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- last announce
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, info_hash),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
 CREATE TABLE donations  (
     id BIGSERIAL PRIMARY KEY,
     donated_by_id INT NOT NULL,
@@ -1334,7 +1568,8 @@ CREATE TYPE bonus_points_log_action_enum AS ENUM (
     'side_effect_reward',
     'shop_purchase_upload',
     'shop_purchase_freeleech_tokens',
-    'shop_purchase_promotion'
+    'shop_purchase_promotion',
+    'hit_and_run_cleared'
 );
 CREATE TABLE bonus_points_logs (
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
@@ -1433,6 +1668,63 @@ CREATE TABLE artist_related_threads (
     created_by_id INT REFERENCES users(id),
     PRIMARY KEY (artist_id, forum_thread_id)
 );
+CREATE TYPE webhook_event_enum AS ENUM (
+    'torrent_uploaded',
+    'torrent_deleted',
+    'torrent_request_filled',
+    'forum_post'
+);
+CREATE TABLE webhooks (
+    id SERIAL PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_by_id INT NOT NULL REFERENCES users(id),
+    name VARCHAR(64) NOT NULL,
+    url TEXT NOT NULL,
+    -- key of the HMAC-SHA256 signature sent with every payload
+    secret TEXT NOT NULL,
+    events webhook_event_enum[] NOT NULL,
+    -- forum posts are only delivered for these sub-categories
+    forum_sub_category_ids INT[] NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT TRUE
+);
+CREATE TYPE webhook_delivery_status_enum AS ENUM (
+    'pending',
+    'delivered',
+    'failed'
+);
+-- queued when an event happens, sent and retried by the periodic tasks
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id INT NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    event webhook_event_enum NOT NULL,
+    payload JSONB NOT NULL,
+    status webhook_delivery_status_enum NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMP WITH TIME ZONE,
+    -- null when the receiver could not be reached
+    response_status INT,
+    error TEXT
+);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+
+-- Changes of the tracker's in-memory state, sent by the periodic tasks until the tracker accepts them
+CREATE TABLE tracker_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    -- what the mutation applies to, a newer mutation replaces the pending one
+    key TEXT NOT NULL UNIQUE,
+    mutation JSONB NOT NULL,
+    -- bumped when the mutation is replaced, so that an attempt on the previous one doesn't settle it
+    revision BIGINT NOT NULL DEFAULT 0,
+    queued_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    attempts INT NOT NULL DEFAULT 0,
+    -- null once given up on
+    next_attempt_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    last_error TEXT
+);
+CREATE INDEX idx_tracker_outbox_next_attempt_at ON tracker_outbox(next_attempt_at);
 
 
 -- Views
@@ -1570,7 +1862,14 @@ SELECT
         SELECT 1
         FROM torrent_reports tr
         WHERE tr.reported_torrent_id = torrents.id
-    )) AS torrent_reported
+    )) AS torrent_reported,
+
+    -- ranked full-text search: names first, then series and artists, then the description
+    setweight(to_tsvector('simple', f_unaccent(title_groups.name)), 'A') ||
+    setweight(to_tsvector('simple', f_unaccent(COALESCE(array_to_string(title_groups.name_aliases, ' '), ''))), 'A') ||
+    setweight(to_tsvector('simple', f_unaccent(COALESCE(series.name, ''))), 'B') ||
+    setweight(to_tsvector('simple', f_unaccent(COALESCE(tg_artists.names, ''))), 'B') ||
+    setweight(to_tsvector('simple', f_unaccent(title_groups.description)), 'D') AS title_group_search_vector
 FROM title_groups
 LEFT JOIN LATERAL (
     SELECT
@@ -1593,8 +1892,36 @@ LEFT JOIN LATERAL (
             ARRAY[]::text[]
         ) AS tag_names
 ) tg_tags ON TRUE
+LEFT JOIN LATERAL (
+    SELECT string_agg(a.name || ' ' || COALESCE(array_to_string(a.aliases, ' '), ''), ' ') AS names
+    FROM affiliated_artists aa
+    JOIN artists a ON a.id = aa.artist_id
+    WHERE aa.title_group_id = title_groups.id
+) tg_artists ON TRUE
 LEFT JOIN edition_groups ON edition_groups.title_group_id = title_groups.id
 LEFT JOIN torrents ON torrents.edition_group_id = edition_groups.id AND torrents.deleted_at IS NULL
 LEFT JOIN series ON series.id = title_groups.series_id;
 
+CREATE INDEX idx_title_group_hierarchy_lite_search_vector ON title_group_hierarchy_lite USING GIN (title_group_search_vector);
+-- fuzzy partial matching of the names (the <% operator)
+CREATE INDEX idx_title_group_hierarchy_lite_name_trgm ON title_group_hierarchy_lite USING GIN (f_unaccent(title_group_name) gin_trgm_ops);
+CREATE INDEX idx_title_group_hierarchy_lite_name_aliases_trgm ON title_group_hierarchy_lite USING GIN (f_unaccent_array(title_group_name_aliases) gin_trgm_ops);
+CREATE INDEX idx_title_group_hierarchy_lite_series_name_trgm ON title_group_hierarchy_lite USING GIN (f_unaccent(title_group_series_name) gin_trgm_ops);
+
+-- fuzzy matching, used for searches, "did you mean" suggestions and relevance ordering
+CREATE INDEX idx_title_groups_name_trgm ON title_groups USING GIN (f_unaccent(name) gin_trgm_ops);
+CREATE INDEX idx_artists_name_trgm ON artists USING GIN (f_unaccent(name) gin_trgm_ops);
+CREATE INDEX idx_artists_aliases_trgm ON artists USING GIN (f_unaccent_array(aliases) gin_trgm_ops);
+CREATE INDEX idx_artists_external_links ON artists USING GIN (external_links);
+CREATE INDEX idx_series_name_trgm ON series USING GIN (f_unaccent(name) gin_trgm_ops);
+
+CREATE INDEX idx_forum_threads_name_search ON forum_threads USING GIN (to_tsvector('simple', f_unaccent(name)));
+CREATE INDEX idx_forum_threads_name_trgm ON forum_threads USING GIN (f_unaccent(name) gin_trgm_ops);
+-- the title only search and the full search each have their own expression
+CREATE INDEX idx_wiki_articles_title_search ON wiki_articles USING GIN (to_tsvector('simple', f_unaccent(title)));
+CREATE INDEX idx_wiki_articles_search ON wiki_articles USING GIN (
+    (setweight(to_tsvector('simple', f_unaccent(title)), 'A') || setweight(to_tsvector('simple', f_unaccent(body)), 'D'))
+);
+CREATE INDEX idx_wiki_articles_title_trgm ON wiki_articles USING GIN (f_unaccent(title) gin_trgm_ops);
+
 -- the materialized view is refreshed periodically by the periodic tasks in the backend
@@ -1,23 +1,18 @@
+use arcadia_common::services::tracker_client::TrackerClient;
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use std::borrow::{Borrow, BorrowMut};
 use url::Url;
 
-#[derive(Clone)]
-pub struct TrackerConfig {
-    pub url_internal: Url,
-    pub api_key: String,
-}
-
 pub struct ConnectionPool {
     pool: PgPool,
-    pub tracker_config: TrackerConfig,
+    pub tracker_client: TrackerClient,
     pub internal_http_client: reqwest::Client,
 }
 
 impl ConnectionPool {
     pub async fn try_new(
         db_uri: &str,
-        tracker_config: TrackerConfig,
+        tracker_client: TrackerClient,
         internal_http_client: reqwest::Client,
     ) -> Result<Self, sqlx::Error> {
         let pool = PgPoolOptions::new()
@@ -28,25 +23,27 @@ impl ConnectionPool {
 
         Ok(Self {
             pool,
-            tracker_config,
+            tracker_client,
             internal_http_client,
         })
     }
 
-    /// Creates a ConnectionPool from an existing PgPool, without tracker config.
-    /// Used in tests where tracker notifications are disabled.
+    /// Creates a ConnectionPool from an existing PgPool, without a reachable tracker.
+    /// Used in tests, where the tracker mutations are only queued.
     pub fn with_pg_pool(pool: PgPool) -> Self {
+        let internal_http_client = reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .expect("Failed to build no-proxy HTTP client");
         Self {
             pool,
-            // Dummy config for tests - notifications are disabled via notify_tracker parameter
-            tracker_config: TrackerConfig {
-                url_internal: Url::parse("http://localhost").unwrap(),
-                api_key: String::new(),
-            },
-            internal_http_client: reqwest::Client::builder()
-                .no_proxy()
-                .build()
-                .expect("Failed to build no-proxy HTTP client"),
+            // Dummy client for tests
+            tracker_client: TrackerClient::new(
+                Url::parse("http://localhost").unwrap(),
+                String::new(),
+                internal_http_client.clone(),
+            ),
+            internal_http_client,
         }
     }
 }
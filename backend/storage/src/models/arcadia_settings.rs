@@ -5,6 +5,10 @@ use sqlx::prelude::FromRow;
 use sqlx::types::Json;
 use utoipa::ToSchema;
 
+use super::{
+    cheat_flag::CheatFlagSeverity, promotion_event::PublicPromotionEvent, user::UserPermission,
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type, ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "displayed_top_bar_stats_enum", rename_all = "snake_case")]
@@ -121,6 +125,14 @@ pub struct ArcadiaSettings {
     pub irc_webchat_default_channels: Vec<String>,
     pub min_amount_tags_title_group: i32,
     pub custom_js_code: Option<String>,
+    pub freeleech_token_duration_hours: i32,
+    pub hit_and_run_min_seed_time_hours: Option<i32>,
+    pub hit_and_run_min_ratio: f64,
+    pub hit_and_run_grace_period_days: i32,
+    pub hit_and_run_warning_threshold: Option<i32>,
+    pub hit_and_run_clear_bonus_points_cost: Option<i64>,
+    pub two_factor_required_permissions: Vec<UserPermission>,
+    pub cheat_flag_withhold_credit_min_severity: Option<CheatFlagSeverity>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -161,4 +173,7 @@ pub struct PublicArcadiaSettings {
     pub irc_webchat_default_channels: Vec<String>,
     pub min_amount_tags_title_group: i32,
     pub custom_js_code: Option<String>,
+    /// running and upcoming site-wide freeleech, double upload, etc.
+    #[sqlx(skip)]
+    pub promotion_events: Vec<PublicPromotionEvent>,
 }
@@ -19,6 +19,7 @@ pub struct Artist {
     pub created_by_id: i32,
     pub description: String,
     pub pictures: Vec<String>,
+    pub external_links: Vec<String>,
     pub title_groups_amount: i32,
     pub edition_groups_amount: i32,
     pub torrents_amount: i32,
@@ -39,6 +40,8 @@ pub struct UserCreatedArtist {
     pub aliases: Vec<String>,
     pub description: String,
     pub pictures: Vec<String>,
+    #[serde(default)]
+    pub external_links: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -69,6 +72,9 @@ pub enum ArtistSearchOrderByColumn {
     #[serde(rename = "title_groups_amount")]
     #[strum(serialize = "title_groups_amount")]
     TitleGroupsAmount,
+    #[serde(rename = "relevance")]
+    #[strum(serialize = "relevance")]
+    Relevance,
 }
 
 #[derive(Debug, Deserialize, ToSchema, IntoParams)]
@@ -92,7 +98,7 @@ pub struct ArtistSearchResult {
     pub title_groups_amount: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, ToSchema, PartialEq)]
 #[sqlx(type_name = "artist_role_enum")]
 pub enum ArtistRole {
     #[serde(rename = "main")]
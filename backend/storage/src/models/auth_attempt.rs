@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::ipnetwork::IpNetwork};
+use strum::Display;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, ToSchema, Display)]
+#[sqlx(type_name = "auth_attempt_action_enum", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AuthAttemptAction {
+    Login,
+    Register,
+    Apply,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AuthAttempt {
+    pub id: i64,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub action: AuthAttemptAction,
+    #[schema(value_type = Option<String>, format = "0.0.0.0")]
+    pub ip: Option<IpNetwork>,
+    pub username: Option<String>,
+    pub successful: bool,
+    pub locked_out: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchAuthAttemptsQuery {
+    /// matches the address and the ones in the network if it is a cidr
+    #[schema(value_type = Option<String>)]
+    #[param(value_type = Option<String>)]
+    pub ip: Option<IpNetwork>,
+    pub username: Option<String>,
+    pub action: Option<AuthAttemptAction>,
+    pub successful: Option<bool>,
+    pub page: i64,
+    pub page_size: i64,
+}
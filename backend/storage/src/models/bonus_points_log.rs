@@ -22,6 +22,7 @@ pub enum BonusPointsLogAction {
     ShopPurchaseUpload,
     ShopPurchaseFreeleechTokens,
     ShopPurchasePromotion,
+    HitAndRunCleared,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
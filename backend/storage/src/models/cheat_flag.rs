@@ -0,0 +1,42 @@
+pub use arcadia_shared::tracker::models::cheat_flag::{CheatFlagReason, CheatFlagSeverity};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
+use utoipa::ToSchema;
+
+use super::user::UserLiteAvatar;
+
+/// An announce whose reported upload is implausible, flagged by the tracker
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheatFlag {
+    pub id: i64,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub user: UserLiteAvatar,
+    pub torrent_id: i32,
+    #[schema(value_type = String, format = "0.0.0.0")]
+    pub ip: IpNetwork,
+    pub reason: CheatFlagReason,
+    pub severity: CheatFlagSeverity,
+    /// reported by the client since its previous announce
+    pub uploaded_delta: i64,
+    pub seconds_since_last_announce: i64,
+    /// leechers among the other users' peers
+    pub swarm_leechers: i32,
+    /// downloaded by the other users' peers during their current session
+    pub swarm_downloaded: i64,
+    /// the upload of the announce was not credited to the user
+    pub credit_withheld: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchCheatFlagsQuery {
+    pub user_id: Option<i32>,
+    pub torrent_id: Option<i32>,
+    pub reason: Option<CheatFlagReason>,
+    /// flags of this severity or higher
+    pub min_severity: Option<CheatFlagSeverity>,
+    pub page: i64,
+    pub page_size: i64,
+}
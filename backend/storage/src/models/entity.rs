@@ -1,9 +1,14 @@
-use chrono::{DateTime, Local};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sqlx::prelude::FromRow;
-use utoipa::ToSchema;
+use strum::Display;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema)]
+use super::{common::OrderByDirection, title_group::ContentType};
+use crate::utils::compute_diff;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::Type, ToSchema, PartialEq)]
 #[sqlx(type_name = "entity_role_enum")]
 pub enum EntityRole {
     #[serde(rename = "producer")]
@@ -21,23 +26,89 @@ pub enum EntityRole {
     #[serde(rename = "network")]
     #[sqlx(rename = "network")]
     Network,
+    #[serde(rename = "studio")]
+    #[sqlx(rename = "studio")]
+    Studio,
+    #[serde(rename = "publisher")]
+    #[sqlx(rename = "publisher")]
+    Publisher,
+    #[serde(rename = "franchise")]
+    #[sqlx(rename = "franchise")]
+    Franchise,
 }
-#[derive(Debug, Deserialize, Serialize, FromRow, ToSchema)]
+
+#[derive(Debug, Deserialize, Serialize, FromRow, ToSchema, Clone)]
 pub struct Entity {
     pub id: i64,
     pub name: String,
     #[schema(value_type = String, format = DateTime)]
-    pub created_at: DateTime<Local>,
+    pub created_at: DateTime<Utc>,
     pub created_by_id: i32,
     pub description: String,
     pub pictures: Vec<String>,
+    pub title_groups_amount: i32,
+    pub edition_groups_amount: i32,
+    pub torrents_amount: i32,
+    pub seeders_amount: i32,
+    pub leechers_amount: i32,
+    pub snatches_amount: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, FromRow, ToSchema)]
+pub struct UserCreatedEntity {
+    pub name: String,
+    pub description: String,
+    pub pictures: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct EditedEntity {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub pictures: Vec<String>,
 }
+
 #[derive(Debug, Deserialize, Serialize, FromRow, ToSchema)]
 pub struct EntityLite {
     pub id: i64,
     pub name: String,
     pub pictures: Vec<String>,
 }
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, Display)]
+pub enum EntitySearchOrderByColumn {
+    #[serde(rename = "name")]
+    #[strum(serialize = "name")]
+    Name,
+    #[serde(rename = "created_at")]
+    #[strum(serialize = "created_at")]
+    CreatedAt,
+    #[serde(rename = "title_groups_amount")]
+    #[strum(serialize = "title_groups_amount")]
+    TitleGroupsAmount,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct SearchEntitiesQuery {
+    pub name: Option<String>,
+    pub page: u32,
+    pub page_size: u32,
+    pub order_by_column: EntitySearchOrderByColumn,
+    pub order_by_direction: OrderByDirection,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EntitySearchResult {
+    pub id: i64,
+    pub name: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub created_by_id: i32,
+    pub pictures: Vec<String>,
+    pub title_groups_amount: i32,
+}
+
 #[derive(Debug, Deserialize, Serialize, FromRow, ToSchema)]
 pub struct AffiliatedEntity {
     pub id: i64,
@@ -45,9 +116,25 @@ pub struct AffiliatedEntity {
     pub entity_id: i64,
     pub created_by_id: i32,
     #[schema(value_type = String, format = DateTime)]
-    pub created_at: DateTime<Local>,
+    pub created_at: DateTime<Utc>,
+    pub roles: Vec<EntityRole>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedAffiliatedEntity {
+    pub title_group_id: i32,
+    pub entity_id: i64,
     pub roles: Vec<EntityRole>,
 }
+
+/// An entity that doesn't exist yet, e.g. proposed by an external database,
+/// created when the title group is
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedNewAffiliatedEntity {
+    pub entity: UserCreatedEntity,
+    pub roles: Vec<EntityRole>,
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct AffiliatedEntityHierarchy {
     pub id: i64,
@@ -55,7 +142,35 @@ pub struct AffiliatedEntityHierarchy {
     pub entity_id: i64,
     pub created_by_id: i32,
     #[schema(value_type = String, format = DateTime)]
-    pub created_at: DateTime<Local>,
+    pub created_at: DateTime<Utc>,
     pub roles: Vec<EntityRole>,
     pub entity: Entity,
 }
+
+/// A title group the entity is affiliated to, with the peers of its torrents
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct EntityTitleGroup {
+    pub id: i32,
+    pub name: String,
+    pub content_type: ContentType,
+    pub covers: Vec<String>,
+    #[schema(value_type = String, format = Date, nullable = true)]
+    pub original_release_date: Option<NaiveDate>,
+    pub original_release_date_only_year_known: bool,
+    pub roles: Vec<EntityRole>,
+    pub seeders_amount: i64,
+    pub leechers_amount: i64,
+    pub snatches_amount: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EntityEnriched {
+    pub entity: Entity,
+    pub title_groups: Vec<EntityTitleGroup>,
+}
+
+impl Entity {
+    pub fn diff(&self, edited: &EditedEntity) -> Option<Value> {
+        compute_diff(self, edited, &["id"])
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::Json};
+use utoipa::ToSchema;
+
+use super::{
+    common::OrderByDirection,
+    edition_group::Source,
+    title_group::{ContentType, TitleGroupCategory},
+    torrent::{Language, TorrentSearch, TorrentSearchOrderByColumn, VideoResolution},
+};
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct FeedFilter {
+    pub id: i64,
+    pub user_id: i32,
+    pub name: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = FeedSearch)]
+    pub search: Json<FeedSearch>,
+    pub tag_expression: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedFeedFilter {
+    pub name: String,
+    pub search: FeedSearch,
+    /// same syntax as `title_group_tags` in the torrent search
+    pub tag_expression: Option<String>,
+}
+
+/// The filters of the torrent search that make sense for a feed, which is always
+/// ordered by upload date
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct FeedSearch {
+    pub title_group_name: Option<String>,
+    #[serde(default)]
+    pub title_group_content_type: Vec<ContentType>,
+    #[serde(default)]
+    pub title_group_category: Vec<TitleGroupCategory>,
+    #[serde(default)]
+    pub edition_group_source: Vec<Source>,
+    #[serde(default)]
+    pub torrent_video_resolution: Vec<VideoResolution>,
+    #[serde(default)]
+    pub torrent_language: Vec<Language>,
+    pub torrent_staff_checked: Option<bool>,
+    pub artist_id: Option<i64>,
+    pub collage_id: Option<i32>,
+    pub series_id: Option<i64>,
+}
+
+impl FeedSearch {
+    /// First page of the torrent search, latest uploads first
+    pub fn to_torrent_search(&self, tag_expression: Option<&str>, page_size: i64) -> TorrentSearch {
+        TorrentSearch {
+            title_group_name: self.title_group_name.clone(),
+            title_group_content_type: self.title_group_content_type.clone(),
+            title_group_category: self.title_group_category.clone(),
+            title_group_tags: tag_expression.map(str::to_string),
+            title_group_include_empty_groups: false,
+            edition_group_source: self.edition_group_source.clone(),
+            torrent_video_resolution: self.torrent_video_resolution.clone(),
+            torrent_language: self.torrent_language.clone(),
+            torrent_reported: None,
+            torrent_staff_checked: self.torrent_staff_checked,
+            torrent_created_by_id: None,
+            torrent_snatched_by_id: None,
+            artist_id: self.artist_id,
+            entity_id: None,
+            collage_id: self.collage_id,
+            series_id: self.series_id,
+            user_id_bookmarks: None,
+            page: 1,
+            page_size,
+            order_by_column: TorrentSearchOrderByColumn::TorrentCreatedAt,
+            order_by_direction: OrderByDirection::Desc,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FeedItem {
+    pub torrent_id: i32,
+    pub title_group_id: i32,
+    pub title_group_name: String,
+    pub release_name: Option<String>,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "hit_and_run_cleared_by_enum", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum HitAndRunClearedBy {
+    Seeding,
+    BonusPoints,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct HitAndRun {
+    pub id: i64,
+    pub user_id: i32,
+    pub torrent_id: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub cleared_at: Option<DateTime<Utc>>,
+    pub cleared_by: Option<HitAndRunClearedBy>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserClearedHitAndRun {
+    pub hit_and_run_id: i64,
+}
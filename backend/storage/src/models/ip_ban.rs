@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::FromRow, types::ipnetwork::IpNetwork};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct IpBan {
+    pub id: i64,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub created_by_id: i32,
+    /// single addresses are returned as /32 (or /128) networks
+    #[schema(value_type = String, format = "0.0.0.0/0")]
+    pub ip_range: IpNetwork,
+    pub reason: String,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedIpBan {
+    /// a single address or a cidr range
+    #[schema(value_type = String, format = "0.0.0.0/0")]
+    pub ip_range: IpNetwork,
+    pub reason: String,
+    /// the ban is permanent when not set
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchIpBansQuery {
+    /// only return the bans covering this address
+    #[schema(value_type = Option<String>)]
+    #[param(value_type = Option<String>)]
+    pub ip: Option<IpNetwork>,
+    /// also return the bans that expired
+    #[serde(default)]
+    pub include_expired: bool,
+    pub page: i64,
+    pub page_size: i64,
+}
@@ -1,6 +1,8 @@
 pub mod arcadia_settings;
 pub mod artist;
+pub mod auth_attempt;
 pub mod bonus_points_log;
+pub mod cheat_flag;
 pub mod collage;
 pub mod common;
 pub mod conversation;
@@ -8,14 +10,19 @@ pub mod css_sheet;
 pub mod donation;
 pub mod edition_group;
 pub mod entity;
+pub mod feed;
 pub mod forum;
 pub mod forum_stats;
 pub mod gift;
+pub mod hit_and_run;
 pub mod home_stats;
 pub mod invitation;
+pub mod ip_ban;
 pub mod master_group;
 pub mod notification;
 pub mod peer;
+pub mod personal_freeleech;
+pub mod promotion_event;
 pub mod series;
 pub mod shop;
 pub mod site_highlight;
@@ -32,9 +39,13 @@ pub mod torrent_request;
 pub mod torrent_request_comment;
 pub mod torrent_request_vote;
 pub mod torrent_stats;
+pub mod tracker_outbox;
+pub mod two_factor;
 pub mod unauthorized_access;
+pub mod unregistered_info_hash;
 pub mod user;
 pub mod user_application;
 pub mod user_badge;
 pub mod user_edit_change_log;
+pub mod webhook;
 pub mod wiki;
@@ -38,6 +38,7 @@ pub struct PublicPeer {
     pub left: i64,
     pub seeder: bool,
     pub agent: String,
+    pub connectable: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -52,4 +53,6 @@ pub struct TorrentClient {
     pub real_uploaded: i64,
     pub real_downloaded: i64,
     pub agent: String,
+    /// Whether the tracker could connect to the client
+    pub connectable: bool,
 }
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use utoipa::{IntoParams, ToSchema};
+
+use super::title_group::{ContentType, TitleGroupCategory};
+
+/// Factors applied by the tracker to the torrents in the scope of the event while it runs
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PromotionEvent {
+    pub id: i64,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub created_by_id: i32,
+    pub name: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub starts_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub ends_at: DateTime<Utc>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    pub content_type: Option<ContentType>,
+    pub category: Option<TitleGroupCategory>,
+    pub tag_expression: Option<String>,
+    /// in bytes
+    pub min_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedPromotionEvent {
+    pub name: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub starts_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub ends_at: DateTime<Utc>,
+    /// in percent, like the torrents' factors
+    pub upload_factor: i16,
+    /// in percent, 0 for freeleech
+    pub download_factor: i16,
+    /// the event applies to every torrent when no scope is set
+    pub content_type: Option<ContentType>,
+    pub category: Option<TitleGroupCategory>,
+    /// same syntax as the tag filter of the torrent search
+    pub tag_expression: Option<String>,
+    /// in bytes
+    pub min_size: Option<i64>,
+}
+
+/// A running or upcoming event, as shown to the users
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PublicPromotionEvent {
+    pub name: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub starts_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub ends_at: DateTime<Utc>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    pub content_type: Option<ContentType>,
+    pub category: Option<TitleGroupCategory>,
+    pub tag_expression: Option<String>,
+    pub min_size: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchPromotionEventsQuery {
+    /// also return the events that ended
+    #[serde(default)]
+    pub include_ended: bool,
+    pub page: i64,
+    pub page_size: i64,
+}
@@ -12,8 +12,13 @@ use super::{
     title_group_comment::TitleGroupCommentHierarchy,
 };
 use crate::models::{
-    collage::CollageSearchResult, entity::AffiliatedEntityHierarchy, torrent::Language,
-    torrent_request::TorrentRequestHierarchyLite, user::UserLite,
+    collage::CollageSearchResult,
+    entity::{
+        AffiliatedEntityHierarchy, UserCreatedAffiliatedEntity, UserCreatedNewAffiliatedEntity,
+    },
+    torrent::Language,
+    torrent_request::TorrentRequestHierarchyLite,
+    user::UserLite,
 };
 use crate::utils::compute_diff;
 
@@ -158,7 +163,6 @@ pub struct UserCreatedTitleGroup {
     pub external_links: Vec<String>,
     pub trailers: Vec<String>,
     // pub artists_affiliated: //(multiple categories, multiple in each category) (composer, remixer, actors, developers, etc.)
-    // pub entities_affiliated (multiple categories, mutliple in each category) (publisher, record label, franchise, etc.)
     pub category: Option<TitleGroupCategory>, // ((movie: feature film, short film), (music: ep, album, compilation))
     pub content_type: ContentType,            // movies, tv shows, books, games, etc
     pub tags: Vec<String>,
@@ -168,6 +172,11 @@ pub struct UserCreatedTitleGroup {
     pub original_release_date: Option<NaiveDate>,
     pub original_release_date_only_year_known: bool,
     pub affiliated_artists: Vec<UserCreatedAffiliatedArtist>,
+    // publishers, record labels, studios, franchises, etc.
+    #[serde(default)]
+    pub affiliated_entities: Vec<UserCreatedAffiliatedEntity>,
+    #[serde(default)]
+    pub new_affiliated_entities: Vec<UserCreatedNewAffiliatedEntity>,
     pub series_id: Option<i64>,
     pub screenshots: Vec<String>,
     // one of them should be given, if master groups are required for this type of content
@@ -283,6 +292,8 @@ pub fn create_default_title_group() -> UserCreatedTitleGroup {
         original_release_date: Some(Utc::now().date_naive()),
         original_release_date_only_year_known: false,
         affiliated_artists: Vec::new(),
+        affiliated_entities: Vec::new(),
+        new_affiliated_entities: Vec::new(),
         series_id: None,
         screenshots: Vec::new(),
         master_group_id: None,
@@ -1,5 +1,6 @@
 use actix_multipart::form::{bytes::Bytes, text::Text, MultipartForm};
 use arcadia_shared::tracker::models::torrent::InfoHash;
+pub use arcadia_shared::tracker::models::torrent::TorrentDeletionReason;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -487,6 +488,9 @@ pub enum TorrentSearchOrderByColumn {
     #[serde(rename = "torrent_snatched")]
     #[strum(serialize = "torrent_snatched")]
     TorrentSnatched,
+    #[serde(rename = "relevance")]
+    #[strum(serialize = "relevance")]
+    Relevance,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema, IntoParams)]
@@ -514,6 +518,7 @@ pub struct TorrentSearch {
     pub torrent_snatched_by_id: Option<i32>,
     // link to other tables
     pub artist_id: Option<i64>,
+    pub entity_id: Option<i64>,
     pub collage_id: Option<i32>,
     pub series_id: Option<i64>,
     pub user_id_bookmarks: Option<i64>,
@@ -621,15 +626,6 @@ pub struct TorrentHierarchy {
     pub bonus_points_snatch_cost: i64,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, ToSchema)]
-#[sqlx(type_name = "torrent_deletion_reason_enum", rename_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
-pub enum TorrentDeletionReason {
-    Trumped,
-    Duplicate,
-    Other,
-}
-
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TorrentToDelete {
     pub id: i32,
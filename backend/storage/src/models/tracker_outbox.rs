@@ -0,0 +1,38 @@
+use arcadia_common::services::tracker_client::TrackerMutation;
+use arcadia_shared::tracker::models::{
+    env::ArcadiaSettingsForTracker, ip_ban::APIInsertIpBan,
+    personal_freeleech::APIInsertPersonalFreeleech, promotion_event::APIInsertPromotionEvent,
+    torrent::APIInsertTorrent, user::APIInsertUser,
+};
+use sqlx::{prelude::FromRow, types::Json};
+
+/// A mutation claimed by the periodic task, to be sent to the tracker
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingTrackerMutation {
+    pub id: i64,
+    pub revision: i64,
+    pub mutation: Json<TrackerMutation>,
+    pub attempts: i32,
+}
+
+/// What the tracker should hold in memory, according to the database
+#[derive(Debug, Clone)]
+pub struct TrackerState {
+    pub users: Vec<APIInsertUser>,
+    pub torrents: Vec<APIInsertTorrent>,
+    /// only the active ones
+    pub personal_freeleeches: Vec<APIInsertPersonalFreeleech>,
+    /// only the active ones
+    pub ip_bans: Vec<APIInsertIpBan>,
+    /// only the ones that haven't ended
+    pub promotion_events: Vec<APIInsertPromotionEvent>,
+    pub settings: ArcadiaSettingsForTracker,
+}
+
+/// Buckets of the state digest whose users and torrents are read, because
+/// they differ between the database and the tracker
+#[derive(Debug, Clone, Default)]
+pub struct TrackerStateBuckets {
+    pub users: Vec<i32>,
+    pub torrents: Vec<i32>,
+}
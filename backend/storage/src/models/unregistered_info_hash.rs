@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::torrent::TorrentDeletionReason;
+
+/// An info hash the user still announces while the tracker doesn't know it,
+/// usually because its torrent was deleted
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UnregisteredInfoHash {
+    /// In hex
+    pub info_hash: String,
+    /// The deleted torrent, `None` when the info hash was never registered
+    pub torrent_id: Option<i32>,
+    pub title_group_name: Option<String>,
+    pub deletion_reason: Option<TorrentDeletionReason>,
+    pub extra_information: Option<String>,
+    pub replacement_torrent_id: Option<i32>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// First announce
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    /// Last announce
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
@@ -79,6 +79,9 @@ pub enum UserPermission {
     EditTorrent,
     EditArtist,
     DeleteArtist,
+    MergeArtist,
+    EditEntity,
+    DeleteEntity,
     DeleteTitleGroup,
     EditCollage,
     DeleteCollage,
@@ -134,6 +137,8 @@ pub enum UserPermission {
     SearchDonation,
     SearchUsers,
     SearchUnauthorizedAccess,
+    SearchAuthAttempts,
+    SearchCheatFlags,
     SearchUserEditChangeLogs,
     DeleteUserEditChangeLog,
     ViewTorrentPeers,
@@ -157,6 +162,9 @@ pub enum UserPermission {
     AwardUserBadge,
     RevokeUserBadge,
     ManageSiteHighlights,
+    ManageIpBans,
+    ManageWebhooks,
+    ManagePromotionEvents,
     ManageRelatedForumThread,
     CreateForumPollVote,
 }
@@ -208,6 +216,19 @@ pub struct UserChangedPassword {
     pub new_password_verify: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForgotPassword {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetPassword {
+    /// token received by email
+    pub token: String,
+    pub new_password: String,
+    pub new_password_verify: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PublicUser {
     pub id: i32,
@@ -360,6 +381,10 @@ pub struct UserClass {
     pub required_title_group_comments: i32,
     pub required_seeding_size: i64,
     pub promotion_cost_bonus_points: i64,
+    /// torrents leeched at the same time, unlimited when `None`
+    pub max_leeching: Option<i32>,
+    /// scales `max_leeching` down by the ratio of the users below 1
+    pub max_leeching_scaled_by_ratio: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -383,6 +408,10 @@ pub struct UserCreatedUserClass {
     pub required_title_group_comments: i32,
     pub required_seeding_size: i64,
     pub promotion_cost_bonus_points: i64,
+    /// torrents leeched at the same time, unlimited when `None`
+    pub max_leeching: Option<i32>,
+    /// scales `max_leeching` down by the ratio of the users below 1
+    pub max_leeching_scaled_by_ratio: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -406,6 +435,10 @@ pub struct EditedUserClass {
     pub required_title_group_comments: i32,
     pub required_seeding_size: i64,
     pub promotion_cost_bonus_points: i64,
+    /// torrents leeched at the same time, unlimited when `None`
+    pub max_leeching: Option<i32>,
+    /// scales `max_leeching` down by the ratio of the users below 1
+    pub max_leeching_scaled_by_ratio: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use strum::Display;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::torrent::TorrentDeletionReason;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, ToSchema, Display)]
+#[sqlx(type_name = "webhook_event_enum", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookEvent {
+    TorrentUploaded,
+    TorrentDeleted,
+    TorrentRequestFilled,
+    ForumPost,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, ToSchema, Display)]
+#[sqlx(type_name = "webhook_delivery_status_enum", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// all the attempts failed
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Webhook {
+    pub id: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub created_by_id: i32,
+    pub name: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    /// forum posts are only delivered for these sub-categories
+    pub forum_sub_category_ids: Vec<i32>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserCreatedWebhook {
+    pub name: String,
+    pub url: String,
+    /// used to sign the payloads, the receiver checks the X-Arcadia-Signature header with it
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub forum_sub_category_ids: Vec<i32>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EditedWebhook {
+    pub name: String,
+    pub url: String,
+    /// the current secret is kept when not set
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub forum_sub_category_ids: Vec<i32>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    #[schema(value_type = String, format = DateTime)]
+    pub next_attempt_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// http status returned by the receiver on the last attempt
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchWebhookDeliveriesQuery {
+    pub webhook_id: Option<i32>,
+    pub status: Option<WebhookDeliveryStatus>,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+/// A delivery claimed by the periodic task, with what is needed to send it
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingWebhookDelivery {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+}
+
+/// Body sent to the receivers, `data` depends on the event
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum WebhookPayload {
+    TorrentUploaded(WebhookTorrent),
+    TorrentDeleted(WebhookDeletedTorrent),
+    TorrentRequestFilled(WebhookTorrentRequestFill),
+    ForumPost(WebhookForumPost),
+}
+
+impl WebhookPayload {
+    pub fn event(&self) -> WebhookEvent {
+        match self {
+            Self::TorrentUploaded(_) => WebhookEvent::TorrentUploaded,
+            Self::TorrentDeleted(_) => WebhookEvent::TorrentDeleted,
+            Self::TorrentRequestFilled(_) => WebhookEvent::TorrentRequestFilled,
+            Self::ForumPost(_) => WebhookEvent::ForumPost,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookTorrent {
+    pub torrent_id: i32,
+    pub title_group_id: i32,
+    pub title_group_name: String,
+    pub release_name: String,
+    pub size: i64,
+    /// not set when uploaded anonymously
+    pub uploader: Option<String>,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeletedTorrent {
+    #[serde(flatten)]
+    pub torrent: WebhookTorrent,
+    pub deletion_reason: TorrentDeletionReason,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookTorrentRequestFill {
+    pub torrent_request_id: i64,
+    pub title_group_id: i32,
+    pub title_group_name: String,
+    pub torrent_id: i32,
+    pub filled_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct WebhookForumPost {
+    pub forum_post_id: i64,
+    pub forum_thread_id: i64,
+    pub forum_thread_name: String,
+    pub forum_sub_category_id: i32,
+    pub forum_sub_category_name: String,
+    pub author: String,
+    pub content: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+}
+
+/// What happened, the payload is built from it when queuing the deliveries
+#[derive(Debug, Clone, Copy)]
+pub enum WebhookTrigger {
+    TorrentUploaded {
+        torrent_id: i32,
+    },
+    TorrentDeleted {
+        torrent_id: i32,
+        deletion_reason: TorrentDeletionReason,
+    },
+    TorrentRequestFilled {
+        torrent_request_id: i64,
+    },
+    ForumPost {
+        forum_post_id: i64,
+    },
+}
@@ -49,6 +49,23 @@ pub trait RedisInterface {
     ) -> impl Future<Output = Result<Option<String>>> + Send;
 
     fn delete<K: ToRedisArgs + Send>(&mut self, key: K) -> impl Future<Output = Result<()>> + Send;
+
+    /// Returns the value stored at the key and deletes the key, both are done atomically.
+    fn get_del<K: ToRedisArgs + Send>(
+        &mut self,
+        key: K,
+    ) -> impl Future<Output = Result<Option<String>>> + Send;
+
+    /// Drops the members of the sorted set at the key that are older than `window_millis`, adds
+    /// the member scored `now_millis` and returns how many members are left. The key expires
+    /// `window_millis` after its newest member, all of it is done atomically.
+    fn add_to_sliding_window<K: ToRedisArgs + Send>(
+        &mut self,
+        key: K,
+        member: String,
+        now_millis: i64,
+        window_millis: i64,
+    ) -> impl Future<Output = Result<i64>> + Send;
 }
 
 pub struct Redis(Connection);
@@ -102,4 +119,39 @@ impl RedisInterface for Redis {
             .await
             .map_err(RedisError::CmdError)
     }
+
+    async fn get_del<K: ToRedisArgs + Send>(&mut self, key: K) -> Result<Option<String>> {
+        cmd("GETDEL")
+            .arg(key)
+            .query_async(&mut self.0)
+            .await
+            .map_err(RedisError::CmdError)
+    }
+
+    async fn add_to_sliding_window<K: ToRedisArgs + Send>(
+        &mut self,
+        key: K,
+        member: String,
+        now_millis: i64,
+        window_millis: i64,
+    ) -> Result<i64> {
+        cmd("EVAL")
+            .arg(SLIDING_WINDOW_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(now_millis)
+            .arg(window_millis)
+            .arg(member)
+            .query_async(&mut self.0)
+            .await
+            .map_err(RedisError::CmdError)
+    }
 }
+
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+redis.call("ZREMRANGEBYSCORE", KEYS[1], "-inf", ARGV[1] - ARGV[2])
+redis.call("ZADD", KEYS[1], ARGV[1], ARGV[3])
+local count = redis.call("ZCARD", KEYS[1])
+redis.call("PEXPIRE", KEYS[1], ARGV[2])
+return count
+"#;
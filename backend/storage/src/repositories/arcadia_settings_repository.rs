@@ -4,9 +4,15 @@ use crate::{
         ArcadiaSettings, AvailableShopItem, BonusPointsEndpoint, DisplayableUserStats,
         DisplayedTopBarStats, SnatchedTorrentBonusPointsTransferredTo, TorrentRequestVoteCurrency,
     },
+    models::cheat_flag::CheatFlagSeverity,
+    models::user::UserPermission,
 };
-use arcadia_common::error::{Error, Result};
-use sqlx::types::Json;
+use arcadia_common::{
+    error::{Error, Result},
+    services::tracker_client::TrackerMutation,
+};
+use arcadia_shared::tracker::models::env::ArcadiaSettingsForTracker;
+use sqlx::{types::Json, PgPool};
 use std::borrow::Borrow;
 
 impl ConnectionPool {
@@ -54,7 +60,15 @@ impl ConnectionPool {
                     irc_webchat_enabled,
                     irc_webchat_default_channels,
                     min_amount_tags_title_group,
-                    custom_js_code
+                    custom_js_code,
+                    freeleech_token_duration_hours,
+                    hit_and_run_min_seed_time_hours,
+                    hit_and_run_min_ratio,
+                    hit_and_run_grace_period_days,
+                    hit_and_run_warning_threshold,
+                    hit_and_run_clear_bonus_points_cost,
+                    two_factor_required_permissions as "two_factor_required_permissions: Vec<UserPermission>",
+                    cheat_flag_withhold_credit_min_severity as "cheat_flag_withhold_credit_min_severity: _"
                 FROM arcadia_settings
                 LIMIT 1
             "#,
@@ -70,6 +84,10 @@ impl ConnectionPool {
         &self,
         settings: &ArcadiaSettings,
     ) -> Result<ArcadiaSettings> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
         let updated_settings = sqlx::query_as!(
             ArcadiaSettings,
             r#"
@@ -113,7 +131,15 @@ impl ConnectionPool {
                     irc_webchat_enabled = $37,
                     irc_webchat_default_channels = $38,
                     min_amount_tags_title_group = $39,
-                    custom_js_code = $40
+                    custom_js_code = $40,
+                    freeleech_token_duration_hours = $41,
+                    hit_and_run_min_seed_time_hours = $42,
+                    hit_and_run_min_ratio = $43,
+                    hit_and_run_grace_period_days = $44,
+                    hit_and_run_warning_threshold = $45,
+                    hit_and_run_clear_bonus_points_cost = $46,
+                    two_factor_required_permissions = $47,
+                    cheat_flag_withhold_credit_min_severity = $48
                 RETURNING
                     user_class_name_on_signup,
                     default_css_sheet_name,
@@ -154,7 +180,15 @@ impl ConnectionPool {
                     irc_webchat_enabled,
                     irc_webchat_default_channels,
                     min_amount_tags_title_group,
-                    custom_js_code
+                    custom_js_code,
+                    freeleech_token_duration_hours,
+                    hit_and_run_min_seed_time_hours,
+                    hit_and_run_min_ratio,
+                    hit_and_run_grace_period_days,
+                    hit_and_run_warning_threshold,
+                    hit_and_run_clear_bonus_points_cost,
+                    two_factor_required_permissions as "two_factor_required_permissions: Vec<UserPermission>",
+                    cheat_flag_withhold_credit_min_severity as "cheat_flag_withhold_credit_min_severity: _"
             "#,
             settings.user_class_name_on_signup,
             settings.default_css_sheet_name,
@@ -198,11 +232,35 @@ impl ConnectionPool {
             &settings.irc_webchat_default_channels,
             settings.min_amount_tags_title_group,
             settings.custom_js_code,
+            settings.freeleech_token_duration_hours,
+            settings.hit_and_run_min_seed_time_hours,
+            settings.hit_and_run_min_ratio,
+            settings.hit_and_run_grace_period_days,
+            settings.hit_and_run_warning_threshold,
+            settings.hit_and_run_clear_bonus_points_cost,
+            &settings.two_factor_required_permissions as &[UserPermission],
+            settings.cheat_flag_withhold_credit_min_severity as Option<CheatFlagSeverity>,
         )
-        .fetch_one(self.borrow())
+        .fetch_one(&mut *tx)
         .await
         .map_err(Error::CouldNotUpdateArcadiaSettings)?;
 
+        Self::queue_tracker_mutation_tx(
+            &mut tx,
+            TrackerMutation::UpdateSettings(ArcadiaSettingsForTracker {
+                global_upload_factor: updated_settings.global_upload_factor,
+                global_download_factor: updated_settings.global_download_factor,
+                snatched_torrent_bonus_points_transferred_to: updated_settings
+                    .snatched_torrent_bonus_points_transferred_to
+                    .clone(),
+                cheat_flag_withhold_credit_min_severity: updated_settings
+                    .cheat_flag_withhold_credit_min_severity,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
         Ok(updated_settings)
     }
 }
@@ -8,9 +8,11 @@ use crate::{
         },
         common::PaginatedResults,
         forum::RelatedForumThread,
+        user_edit_change_log::NewUserEditChangeLog,
     },
 };
 use arcadia_common::error::{Error, Result};
+use serde_json::json;
 use sqlx::PgPool;
 use std::borrow::Borrow;
 use std::collections::HashMap;
@@ -31,18 +33,19 @@ impl ConnectionPool {
             let artist = sqlx::query_as!(
                 Artist,
                 r#"
-                INSERT INTO artists (name, aliases, description, pictures, created_by_id)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO artists (name, aliases, description, pictures, created_by_id, external_links)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 ON CONFLICT (name) DO UPDATE SET
                     -- This is a no-op update that still triggers RETURNING
                     name = EXCLUDED.name
-                RETURNING id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+                RETURNING id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount, external_links
                 "#,
                 artist.name,
                 &artist.aliases,
                 artist.description,
                 &artist.pictures,
-                current_user_id
+                current_user_id,
+                &artist.external_links
             )
             .fetch_one(&mut *tx)
             .await
@@ -125,7 +128,7 @@ impl ConnectionPool {
         let fetched_artists: Vec<Artist> = sqlx::query_as!(
             Artist,
             r#"
-        SELECT id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount FROM artists WHERE id = ANY($1)
+        SELECT id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount, external_links FROM artists WHERE id = ANY($1)
         "#,
             &artist_ids
         )
@@ -179,6 +182,35 @@ impl ConnectionPool {
         Ok(found_artists)
     }
 
+    /// Artists having one of the external links, or whose name or an alias
+    /// is one of the names (case insensitive)
+    pub async fn find_artists_by_external_links_or_names(
+        &self,
+        external_links: &[String],
+        names: &[String],
+    ) -> Result<Vec<Artist>> {
+        let names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+
+        sqlx::query_as!(
+            Artist,
+            r#"
+            SELECT id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount, external_links
+            FROM artists
+            WHERE external_links && $1
+               OR LOWER(name) = ANY($2)
+               OR EXISTS (
+                   SELECT 1 FROM unnest(aliases) AS alias
+                   WHERE LOWER(alias) = ANY($2)
+               )
+            "#,
+            external_links,
+            &names
+        )
+        .fetch_all(self.borrow())
+        .await
+        .map_err(Error::CouldNotSearchForArtists)
+    }
+
     pub async fn search_artists(
         &self,
         form: &SearchArtistsQuery,
@@ -189,11 +221,8 @@ impl ConnectionPool {
             r#"
             SELECT COUNT(*) FROM artists
             WHERE $1::TEXT IS NULL
-               OR unaccent(name) ILIKE '%' || unaccent($1) || '%'
-               OR EXISTS (
-                   SELECT 1 FROM unnest(aliases) AS alias
-                   WHERE unaccent(alias) ILIKE '%' || unaccent($1) || '%'
-               )
+               OR f_unaccent($1) <% f_unaccent(name)
+               OR f_unaccent($1) <% f_unaccent_array(aliases)
             "#,
             form.name,
         )
@@ -208,18 +237,28 @@ impl ConnectionPool {
             SELECT id, name, aliases, created_at, created_by_id, pictures, title_groups_amount
             FROM artists
             WHERE $1::TEXT IS NULL
-               OR unaccent(name) ILIKE '%' || unaccent($1) || '%'
-               OR EXISTS (
-                   SELECT 1 FROM unnest(aliases) AS alias
-                   WHERE unaccent(alias) ILIKE '%' || unaccent($1) || '%'
-               )
+               OR f_unaccent($1) <% f_unaccent(name)
+               OR f_unaccent($1) <% f_unaccent_array(aliases)
             ORDER BY
                 CASE WHEN $4 = 'name' AND $5 = 'asc' THEN name END ASC,
                 CASE WHEN $4 = 'name' AND $5 = 'desc' THEN name END DESC,
                 CASE WHEN $4 = 'created_at' AND $5 = 'asc' THEN created_at END ASC,
                 CASE WHEN $4 = 'created_at' AND $5 = 'desc' THEN created_at END DESC,
                 CASE WHEN $4 = 'title_groups_amount' AND $5 = 'asc' THEN title_groups_amount END ASC,
-                CASE WHEN $4 = 'title_groups_amount' AND $5 = 'desc' THEN title_groups_amount END DESC
+                CASE WHEN $4 = 'title_groups_amount' AND $5 = 'desc' THEN title_groups_amount END DESC,
+                CASE WHEN $4 = 'relevance' AND $5 = 'asc' THEN
+                    GREATEST(
+                        similarity(f_unaccent(name), f_unaccent($1)),
+                        (SELECT MAX(similarity(f_unaccent(alias), f_unaccent($1))) FROM unnest(aliases) AS alias)
+                    )
+                END ASC NULLS LAST,
+                CASE WHEN $4 = 'relevance' AND $5 = 'desc' THEN
+                    GREATEST(
+                        similarity(f_unaccent(name), f_unaccent($1)),
+                        (SELECT MAX(similarity(f_unaccent(alias), f_unaccent($1))) FROM unnest(aliases) AS alias)
+                    )
+                END DESC NULLS LAST,
+                name ASC
             OFFSET $2 LIMIT $3
             "#,
             form.name,
@@ -243,7 +282,7 @@ impl ConnectionPool {
         sqlx::query_as!(
             Artist,
             r#"
-                SELECT id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+                SELECT id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount, external_links
                 FROM artists
                 WHERE id = $1;
             "#,
@@ -284,7 +323,11 @@ impl ConnectionPool {
                         WHERE art.artist_id = a.id
                     ), '[]'::jsonb) AS "related_threads!: sqlx::types::Json<Vec<RelatedForumThread>>"
                 FROM artists a
-                WHERE a.id = $1
+                -- artists merged into another one redirect to it
+                WHERE a.id = COALESCE(
+                    (SELECT artist_id FROM artist_redirects WHERE old_artist_id = $1),
+                    $1
+                )
             "#,
             artist_id
         )
@@ -306,7 +349,7 @@ impl ConnectionPool {
                 UPDATE artists
                 SET name = $1, aliases = $2, description = $3, pictures = $4
                 WHERE id = $5
-                RETURNING id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+                RETURNING id, name, aliases, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount, external_links
             "#,
             updated_artist.name,
             &updated_artist.aliases,
@@ -419,4 +462,236 @@ impl ConnectionPool {
 
         Ok(())
     }
+
+    /// Merges the source artist into the target one: affiliations, similar artists,
+    /// related threads and pictures are moved, the source name becomes an alias of the
+    /// target and a redirect is left so the source id keeps resolving.
+    pub async fn merge_artists(
+        &self,
+        source_artist_id: i64,
+        target_artist_id: i64,
+        merged_by_id: i32,
+    ) -> Result<Artist> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let target = sqlx::query!(
+            r#"
+            SELECT aliases, pictures FROM artists
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+            target_artist_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Error::CouldNotFindArtist)?;
+
+        // Move affiliated_artists (UNIQUE on title_group_id, artist_id),
+        // where both are affiliated the target gets the roles and nickname of the source
+        sqlx::query!(
+            r#"
+            UPDATE affiliated_artists target
+            SET roles = target.roles || ARRAY(
+                    SELECT role FROM unnest(source.roles) AS role
+                    WHERE role != ALL(target.roles)
+                ),
+                nickname = COALESCE(target.nickname, source.nickname)
+            FROM affiliated_artists source
+            WHERE target.artist_id = $2
+              AND source.artist_id = $1
+              AND source.title_group_id = target.title_group_id
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM affiliated_artists
+            WHERE artist_id = $1
+              AND title_group_id IN (
+                  SELECT title_group_id FROM affiliated_artists WHERE artist_id = $2
+              )
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE affiliated_artists
+            SET artist_id = $2
+            WHERE artist_id = $1
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        // the links of the source are removed with it, copy them to the target first
+        sqlx::query!(
+            r#"
+            INSERT INTO similar_artists (artist_1_id, artist_2_id)
+            SELECT
+                CASE WHEN artist_1_id = $1 THEN $2 ELSE artist_1_id END,
+                CASE WHEN artist_2_id = $1 THEN $2 ELSE artist_2_id END
+            FROM similar_artists
+            WHERE (artist_1_id = $1 OR artist_2_id = $1)
+              AND artist_1_id != $2
+              AND artist_2_id != $2
+            ON CONFLICT DO NOTHING
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO artist_related_threads (artist_id, forum_thread_id, created_at, created_by_id)
+            SELECT $2, forum_thread_id, created_at, created_by_id
+            FROM artist_related_threads
+            WHERE artist_id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE site_highlights
+            SET artist_id = $2
+            WHERE artist_id = $1
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        // artists previously merged into the source now point to the target
+        sqlx::query!(
+            r#"
+            UPDATE artist_redirects
+            SET artist_id = $2
+            WHERE artist_id = $1
+            "#,
+            source_artist_id,
+            target_artist_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO artist_redirects (old_artist_id, artist_id, created_by_id)
+            VALUES ($1, $2, $3)
+            "#,
+            source_artist_id,
+            target_artist_id,
+            merged_by_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        let source = sqlx::query!(
+            r#"
+            DELETE FROM artists
+            WHERE id = $1
+            RETURNING name, aliases, pictures, external_links
+            "#,
+            source_artist_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Error::CouldNotFindArtist)?;
+
+        let artist = sqlx::query_as!(
+            Artist,
+            r#"
+            WITH artist_stats AS (
+                SELECT COUNT(DISTINCT eg.id)::INT AS edition_groups,
+                       COUNT(t.id)::INT AS torrents,
+                       COALESCE(SUM(t.seeders), 0)::INT AS seeders,
+                       COALESCE(SUM(t.leechers), 0)::INT AS leechers,
+                       COALESCE(SUM(t.times_completed), 0)::INT AS snatches
+                FROM affiliated_artists aa
+                JOIN edition_groups eg ON eg.title_group_id = aa.title_group_id
+                LEFT JOIN torrents t ON t.edition_group_id = eg.id AND t.deleted_at IS NULL
+                WHERE aa.artist_id = $1
+            )
+            UPDATE artists a
+            SET aliases = a.aliases || ARRAY(
+                    SELECT alias
+                    FROM unnest($2::VARCHAR || $3::VARCHAR[]) WITH ORDINALITY AS source(alias, position)
+                    WHERE alias != a.name AND alias != ALL(a.aliases)
+                    GROUP BY alias
+                    ORDER BY MIN(position)
+                ),
+                pictures = a.pictures || ARRAY(
+                    SELECT picture FROM unnest($4::TEXT[]) AS picture
+                    WHERE picture != ALL(a.pictures)
+                ),
+                external_links = a.external_links || ARRAY(
+                    SELECT link FROM unnest($5::TEXT[]) AS link
+                    WHERE link != ALL(a.external_links)
+                ),
+                title_groups_amount = (SELECT COUNT(*) FROM affiliated_artists WHERE artist_id = $1),
+                edition_groups_amount = s.edition_groups,
+                torrents_amount = s.torrents,
+                seeders_amount = s.seeders,
+                leechers_amount = s.leechers,
+                snatches_amount = s.snatches
+            FROM artist_stats s
+            WHERE a.id = $1
+            RETURNING a.id, a.name, a.aliases, a.created_at, a.created_by_id, a.description, a.pictures, a.title_groups_amount, a.edition_groups_amount, a.torrents_amount, a.seeders_amount, a.leechers_amount, a.snatches_amount, a.external_links
+            "#,
+            target_artist_id,
+            source.name,
+            &source.aliases,
+            &source.pictures,
+            &source.external_links
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Error::CouldNotMergeArtists)?;
+
+        Self::create_user_edit_change_log_tx(
+            &mut tx,
+            &NewUserEditChangeLog {
+                item_type: "artist".to_string(),
+                item_id: artist.id,
+                edited_by_id: merged_by_id,
+                edits: json!({
+                    "merged_artist": {"id": source_artist_id, "name": source.name},
+                    "aliases": {"old": target.aliases, "new": artist.aliases},
+                    "pictures": {"old": target.pictures, "new": artist.pictures},
+                }),
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(artist)
+    }
 }
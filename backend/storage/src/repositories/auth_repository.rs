@@ -2,10 +2,14 @@ use crate::{
     connection_pool::ConnectionPool,
     models::{
         arcadia_settings::ArcadiaSettings,
+        auth_attempt::{AuthAttempt, AuthAttemptAction, SearchAuthAttemptsQuery},
         common::PaginatedResults,
         invitation::Invitation,
         unauthorized_access::{SearchUnauthorizedAccessQuery, UnauthorizedAccess},
-        user::{APIKey, Login, Register, User, UserCreatedAPIKey, UserLiteAvatar, UserPermission},
+        user::{
+            APIKey, Login, Register, User, UserCreatedAPIKey, UserLite, UserLiteAvatar,
+            UserPermission,
+        },
     },
 };
 use arcadia_common::error::{Error, Result};
@@ -113,6 +117,9 @@ impl ConnectionPool {
             .await?;
         }
 
+        // so that the tracker accepts their announces
+        Self::queue_users_for_tracker_tx(&mut tx, &[registered_user.id]).await?;
+
         tx.commit().await?;
 
         Ok(registered_user)
@@ -152,6 +159,22 @@ impl ConnectionPool {
         Ok(user)
     }
 
+    pub async fn find_user_lite_with_email(&self, email: &str) -> Result<Option<UserLite>> {
+        let user = sqlx::query_as!(
+            UserLite,
+            r#"
+                SELECT id, username, warned, banned
+                FROM users
+                WHERE LOWER(email) = LOWER($1)
+            "#,
+            email
+        )
+        .fetch_optional(self.borrow())
+        .await?;
+
+        Ok(user)
+    }
+
     pub async fn find_user_id_with_api_key(&self, api_key: &str) -> Result<User> {
         let user = sqlx::query_as!(
             User,
@@ -180,6 +203,19 @@ impl ConnectionPool {
         Ok(user)
     }
 
+    pub async fn find_user_id_with_passkey(&self, passkey: &str) -> Result<i32> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT id FROM users
+            WHERE passkey = $1 AND banned = FALSE
+            "#,
+            passkey
+        )
+        .fetch_one(self.borrow())
+        .await
+        .map_err(|_| Error::InvalidPasskeyOrBanned)
+    }
+
     pub async fn find_user_with_id(&self, id: i32) -> Result<User> {
         sqlx::query_as!(
             User,
@@ -264,7 +300,17 @@ impl ConnectionPool {
         let result = sqlx::query_scalar!(
             r#"
             SELECT EXISTS(
-                SELECT 1 FROM users WHERE id = $1 AND $2 = ANY(permissions)
+                SELECT 1 FROM users u, arcadia_settings s
+                WHERE u.id = $1
+                  AND $2 = ANY(u.permissions)
+                  -- some permissions can only be used with two-factor authentication
+                  AND (
+                    NOT $2 = ANY(s.two_factor_required_permissions)
+                    OR EXISTS(
+                        SELECT 1 FROM user_totps t
+                        WHERE t.user_id = u.id AND t.enabled_at IS NOT NULL
+                    )
+                  )
             ) as "exists!"
             "#,
             user_id,
@@ -298,6 +344,16 @@ impl ConnectionPool {
             .execute(self.borrow())
             .await;
 
+            if self
+                .is_missing_required_two_factor(user_id, permission)
+                .await?
+            {
+                return Err(Error::TwoFactorAuthenticationRequired(format!(
+                    "{:?}",
+                    permission
+                )));
+            }
+
             return Err(Error::InsufficientPermissions(format!("{:?}", permission)));
         }
 
@@ -393,6 +449,85 @@ impl ConnectionPool {
         })
     }
 
+    pub async fn create_auth_attempt(
+        &self,
+        action: AuthAttemptAction,
+        ip: Option<IpNetwork>,
+        username: Option<&str>,
+        successful: bool,
+        locked_out: bool,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO auth_attempts (action, ip, username, successful, locked_out)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            action as AuthAttemptAction,
+            ip,
+            username,
+            successful,
+            locked_out
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_auth_attempts(
+        &self,
+        query: SearchAuthAttemptsQuery,
+    ) -> Result<PaginatedResults<AuthAttempt>> {
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM auth_attempts
+            WHERE ($1::INET IS NULL OR ip <<= $1)
+              AND ($2::TEXT IS NULL OR username = $2)
+              AND ($3::auth_attempt_action_enum IS NULL OR action = $3)
+              AND ($4::BOOLEAN IS NULL OR successful = $4)
+            "#,
+            query.ip,
+            query.username,
+            query.action as Option<AuthAttemptAction>,
+            query.successful
+        )
+        .fetch_one(self.borrow())
+        .await?
+        .unwrap_or(0);
+
+        let results = sqlx::query_as!(
+            AuthAttempt,
+            r#"
+            SELECT id, created_at, action AS "action: AuthAttemptAction", ip, username,
+                   successful, locked_out
+            FROM auth_attempts
+            WHERE ($1::INET IS NULL OR ip <<= $1)
+              AND ($2::TEXT IS NULL OR username = $2)
+              AND ($3::auth_attempt_action_enum IS NULL OR action = $3)
+              AND ($4::BOOLEAN IS NULL OR successful = $4)
+            ORDER BY created_at DESC
+            OFFSET ($5 - 1) * LEAST($6, 100)
+            LIMIT LEAST($6, 100)
+            "#,
+            query.ip,
+            query.username,
+            query.action as Option<AuthAttemptAction>,
+            query.successful,
+            query.page as i32,
+            query.page_size as i32
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: query.page as u32,
+            page_size: query.page_size.min(100) as u32,
+        })
+    }
+
     pub async fn update_user_password_hash(&self, user_id: i32, password_hash: &str) -> Result<()> {
         sqlx::query!(
             r#"
@@ -0,0 +1,109 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        cheat_flag::{CheatFlag, CheatFlagReason, CheatFlagSeverity, SearchCheatFlagsQuery},
+        common::PaginatedResults,
+        user::UserLiteAvatar,
+    },
+};
+use arcadia_common::error::Result;
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    pub async fn find_cheat_flags(
+        &self,
+        query: SearchCheatFlagsQuery,
+    ) -> Result<PaginatedResults<CheatFlag>> {
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM cheat_flags
+            WHERE ($1::INT IS NULL OR user_id = $1)
+              AND ($2::INT IS NULL OR torrent_id = $2)
+              AND ($3::cheat_flag_reason_enum IS NULL OR reason = $3)
+              AND ($4::cheat_flag_severity_enum IS NULL OR severity >= $4)
+            "#,
+            query.user_id,
+            query.torrent_id,
+            query.reason as Option<CheatFlagReason>,
+            query.min_severity as Option<CheatFlagSeverity>
+        )
+        .fetch_one(self.borrow())
+        .await?
+        .unwrap_or(0);
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                cf.id,
+                cf.created_at,
+                u.id as user_id,
+                u.username,
+                u.class_name,
+                u.banned,
+                u.avatar,
+                u.warned,
+                u.custom_title,
+                cf.torrent_id,
+                cf.ip,
+                cf.reason as "reason: CheatFlagReason",
+                cf.severity as "severity: CheatFlagSeverity",
+                cf.uploaded_delta,
+                cf.seconds_since_last_announce,
+                cf.swarm_leechers,
+                cf.swarm_downloaded,
+                cf.credit_withheld
+            FROM cheat_flags cf
+            JOIN users u ON cf.user_id = u.id
+            WHERE ($1::INT IS NULL OR cf.user_id = $1)
+              AND ($2::INT IS NULL OR cf.torrent_id = $2)
+              AND ($3::cheat_flag_reason_enum IS NULL OR cf.reason = $3)
+              AND ($4::cheat_flag_severity_enum IS NULL OR cf.severity >= $4)
+            ORDER BY cf.created_at DESC, cf.id DESC
+            OFFSET ($5 - 1) * LEAST($6, 100)
+            LIMIT LEAST($6, 100)
+            "#,
+            query.user_id,
+            query.torrent_id,
+            query.reason as Option<CheatFlagReason>,
+            query.min_severity as Option<CheatFlagSeverity>,
+            query.page as i32,
+            query.page_size as i32
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        let results = rows
+            .into_iter()
+            .map(|row| CheatFlag {
+                id: row.id,
+                created_at: row.created_at,
+                user: UserLiteAvatar {
+                    id: row.user_id,
+                    username: row.username,
+                    class_name: row.class_name,
+                    banned: row.banned,
+                    avatar: row.avatar,
+                    warned: row.warned,
+                    custom_title: row.custom_title,
+                },
+                torrent_id: row.torrent_id,
+                ip: row.ip,
+                reason: row.reason,
+                severity: row.severity,
+                uploaded_delta: row.uploaded_delta,
+                seconds_since_last_announce: row.seconds_since_last_announce,
+                swarm_leechers: row.swarm_leechers,
+                swarm_downloaded: row.swarm_downloaded,
+                credit_withheld: row.credit_withheld,
+            })
+            .collect();
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: query.page as u32,
+            page_size: query.page_size.min(100) as u32,
+        })
+    }
+}
@@ -0,0 +1,472 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        common::PaginatedResults,
+        entity::{
+            AffiliatedEntity, AffiliatedEntityHierarchy, EditedEntity, Entity, EntityEnriched,
+            EntityLite, EntityRole, EntitySearchResult, EntityTitleGroup, SearchEntitiesQuery,
+            UserCreatedAffiliatedEntity, UserCreatedEntity,
+        },
+        title_group::ContentType,
+        user_edit_change_log::NewUserEditChangeLog,
+    },
+    utils::escape_like_pattern,
+};
+use arcadia_common::error::{Error, Result};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::{borrow::Borrow, collections::BTreeMap};
+
+impl ConnectionPool {
+    pub async fn create_entities(
+        &self,
+        entities: &Vec<UserCreatedEntity>,
+        current_user_id: i32,
+    ) -> Result<Vec<Entity>> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let mut created_entities = Vec::new();
+
+        for entity in entities {
+            let entity = sqlx::query_as!(
+                Entity,
+                r#"
+                INSERT INTO entities (name, description, pictures, created_by_id)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (name) DO UPDATE SET
+                    -- This is a no-op update that still triggers RETURNING
+                    name = EXCLUDED.name
+                RETURNING id, name, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+                "#,
+                entity.name,
+                entity.description,
+                &entity.pictures,
+                current_user_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(Error::CouldNotCreateEntity)?;
+
+            created_entities.push(entity);
+        }
+
+        tx.commit().await?;
+
+        Ok(created_entities)
+    }
+
+    pub async fn create_entities_affiliation(
+        &self,
+        entities: &Vec<UserCreatedAffiliatedEntity>,
+        current_user_id: i32,
+    ) -> Result<Vec<AffiliatedEntityHierarchy>> {
+        let values: Vec<String> = (0..entities.len())
+            .map(|i| {
+                format!(
+                    "(${}, ${}, ${}::entity_role_enum[], ${})",
+                    i * 4 + 1,
+                    i * 4 + 2,
+                    i * 4 + 3,
+                    i * 4 + 4
+                )
+            })
+            .collect();
+
+        let insert_query = format!(
+            "INSERT INTO affiliated_entities (title_group_id, entity_id, roles, created_by_id) VALUES {} RETURNING id, title_group_id, entity_id, created_by_id, created_at, roles",
+            values.join(", ")
+        );
+
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let mut q_insert = sqlx::query_as::<_, AffiliatedEntity>(&insert_query);
+        for entity in entities {
+            q_insert = q_insert
+                .bind(entity.title_group_id)
+                .bind(entity.entity_id)
+                .bind(&entity.roles)
+                .bind(current_user_id);
+        }
+
+        let created_affiliations = q_insert.fetch_all(&mut *tx).await.map_err(|e| {
+            if let sqlx::Error::Database(ref db_err) = e
+                && db_err.code().as_deref() == Some("23505")
+            {
+                return Error::DuplicateEntityAffiliation;
+            }
+            Error::CouldNotCreateEntityAffiliation(e)
+        })?;
+
+        // Update title_groups_amount for each affected entity
+        for affiliation in &created_affiliations {
+            sqlx::query!(
+                r#"
+                UPDATE entities
+                SET title_groups_amount = title_groups_amount + 1
+                WHERE id = $1
+                "#,
+                affiliation.entity_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        Self::log_affiliation_changes_tx(&mut tx, &created_affiliations, current_user_id, false)
+            .await?;
+
+        tx.commit().await?;
+
+        let entity_ids: Vec<i64> = created_affiliations
+            .iter()
+            .map(|aff| aff.entity_id)
+            .collect();
+
+        let fetched_entities: Vec<Entity> = sqlx::query_as!(
+            Entity,
+            r#"
+        SELECT id, name, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount FROM entities WHERE id = ANY($1)
+        "#,
+            &entity_ids
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        let mut affiliated_entity_hierarchies: Vec<AffiliatedEntityHierarchy> = Vec::new();
+
+        for affiliation in created_affiliations {
+            if let Some(entity) = fetched_entities
+                .iter()
+                .find(|e| e.id == affiliation.entity_id)
+            {
+                affiliated_entity_hierarchies.push(AffiliatedEntityHierarchy {
+                    id: affiliation.id,
+                    title_group_id: affiliation.title_group_id,
+                    entity_id: affiliation.entity_id,
+                    created_by_id: affiliation.created_by_id,
+                    created_at: affiliation.created_at,
+                    roles: affiliation.roles,
+                    entity: entity.clone(),
+                });
+            }
+        }
+
+        Ok(affiliated_entity_hierarchies)
+    }
+
+    pub async fn find_entities_lite(&self, name: &str, limit: i64) -> Result<Vec<EntityLite>> {
+        let found_entities = sqlx::query_as!(
+            EntityLite,
+            r#"
+            SELECT name, id, pictures
+            FROM entities
+            WHERE unaccent(name) ILIKE '%' || unaccent($1) || '%' ESCAPE '\'
+            LIMIT $2
+        "#,
+            escape_like_pattern(name),
+            limit
+        )
+        .fetch_all(self.borrow())
+        .await
+        .map_err(Error::CouldNotSearchForEntities)?;
+
+        Ok(found_entities)
+    }
+
+    /// The entities named exactly like one of the given names, case insensitively
+    pub async fn find_entities_by_names(&self, names: &[String]) -> Result<Vec<Entity>> {
+        let found_entities = sqlx::query_as!(
+            Entity,
+            r#"
+            SELECT id, name, created_at, created_by_id, description, pictures, title_groups_amount,
+                edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+            FROM entities
+            WHERE LOWER(name) = ANY(SELECT LOWER(unnest($1::TEXT[])))
+            "#,
+            names
+        )
+        .fetch_all(self.borrow())
+        .await
+        .map_err(Error::CouldNotSearchForEntities)?;
+
+        Ok(found_entities)
+    }
+
+    pub async fn search_entities(
+        &self,
+        form: &SearchEntitiesQuery,
+    ) -> Result<PaginatedResults<EntitySearchResult>> {
+        let offset = (form.page - 1) * form.page_size;
+        let name = form.name.as_deref().map(escape_like_pattern);
+
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) FROM entities
+            WHERE $1::TEXT IS NULL
+               OR unaccent(name) ILIKE '%' || unaccent($1) || '%' ESCAPE '\'
+            "#,
+            name,
+        )
+        .fetch_one(self.borrow())
+        .await
+        .map_err(Error::CouldNotSearchForEntities)?
+        .unwrap_or(0);
+
+        let results = sqlx::query_as!(
+            EntitySearchResult,
+            r#"
+            SELECT id, name, created_at, created_by_id, pictures, title_groups_amount
+            FROM entities
+            WHERE $1::TEXT IS NULL
+               OR unaccent(name) ILIKE '%' || unaccent($1) || '%' ESCAPE '\'
+            ORDER BY
+                CASE WHEN $4 = 'name' AND $5 = 'asc' THEN name END ASC,
+                CASE WHEN $4 = 'name' AND $5 = 'desc' THEN name END DESC,
+                CASE WHEN $4 = 'created_at' AND $5 = 'asc' THEN created_at END ASC,
+                CASE WHEN $4 = 'created_at' AND $5 = 'desc' THEN created_at END DESC,
+                CASE WHEN $4 = 'title_groups_amount' AND $5 = 'asc' THEN title_groups_amount END ASC,
+                CASE WHEN $4 = 'title_groups_amount' AND $5 = 'desc' THEN title_groups_amount END DESC
+            OFFSET $2 LIMIT $3
+            "#,
+            name,
+            offset as i64,
+            form.page_size as i64,
+            form.order_by_column.to_string(),
+            form.order_by_direction.to_string()
+        )
+        .fetch_all(self.borrow())
+        .await
+        .map_err(Error::CouldNotSearchForEntities)?;
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: form.page,
+            page_size: form.page_size,
+        })
+    }
+
+    pub async fn find_entity_by_id(&self, entity_id: i64) -> Result<Entity> {
+        sqlx::query_as!(
+            Entity,
+            r#"
+                SELECT id, name, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+                FROM entities
+                WHERE id = $1;
+            "#,
+            entity_id
+        )
+        .fetch_one(self.borrow())
+        .await
+        .map_err(Error::CouldNotFindEntity)
+    }
+
+    pub async fn find_entity_enriched(&self, entity_id: i64) -> Result<EntityEnriched> {
+        let entity = self.find_entity_by_id(entity_id).await?;
+
+        let title_groups = sqlx::query_as!(
+            EntityTitleGroup,
+            r#"
+                SELECT
+                    tg.id, tg.name, tg.content_type AS "content_type: ContentType", tg.covers,
+                    tg.original_release_date, tg.original_release_date_only_year_known,
+                    ae.roles AS "roles: Vec<EntityRole>",
+                    COALESCE(SUM(t.seeders), 0)::BIGINT AS "seeders_amount!",
+                    COALESCE(SUM(t.leechers), 0)::BIGINT AS "leechers_amount!",
+                    COALESCE(SUM(t.times_completed), 0)::BIGINT AS "snatches_amount!"
+                FROM affiliated_entities ae
+                JOIN title_groups tg ON tg.id = ae.title_group_id
+                LEFT JOIN edition_groups eg ON eg.title_group_id = tg.id
+                LEFT JOIN torrents t ON t.edition_group_id = eg.id AND t.deleted_at IS NULL
+                WHERE ae.entity_id = $1
+                GROUP BY tg.id, ae.roles
+                ORDER BY tg.original_release_date DESC NULLS LAST, tg.name
+            "#,
+            entity_id
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(EntityEnriched {
+            entity,
+            title_groups,
+        })
+    }
+
+    pub async fn update_entity_data(&self, updated_entity: &EditedEntity) -> Result<Entity> {
+        sqlx::query_as!(
+            Entity,
+            r#"
+                UPDATE entities
+                SET name = $1, description = $2, pictures = $3
+                WHERE id = $4
+                RETURNING id, name, created_at, created_by_id, description, pictures, title_groups_amount, edition_groups_amount, torrents_amount, seeders_amount, leechers_amount, snatches_amount
+            "#,
+            updated_entity.name,
+            updated_entity.description,
+            &updated_entity.pictures,
+            updated_entity.id
+        )
+        .fetch_one(self.borrow())
+        .await
+        .map_err(Error::CouldNotUpdateEntity)
+    }
+
+    pub async fn delete_entities_affiliation(
+        &self,
+        affiliation_ids: &[i64],
+        current_user_id: i32,
+    ) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let deleted_affiliations = sqlx::query_as!(
+            AffiliatedEntity,
+            r#"
+            DELETE FROM affiliated_entities
+            WHERE id = ANY($1)
+            RETURNING id, title_group_id, entity_id, created_by_id, created_at,
+                roles AS "roles: Vec<EntityRole>"
+            "#,
+            affiliation_ids
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Update title_groups_amount for each affected entity
+        for affiliation in &deleted_affiliations {
+            sqlx::query!(
+                r#"
+                UPDATE entities
+                SET title_groups_amount = title_groups_amount - 1
+                WHERE id = $1
+                "#,
+                affiliation.entity_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        Self::log_affiliation_changes_tx(&mut tx, &deleted_affiliations, current_user_id, true)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Writes one change log per title group whose affiliated entities were added or removed
+    async fn log_affiliation_changes_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        affiliations: &[AffiliatedEntity],
+        edited_by_id: i32,
+        removed: bool,
+    ) -> Result<()> {
+        let mut changes_per_title_group: BTreeMap<i32, Vec<Value>> = BTreeMap::new();
+        for affiliation in affiliations {
+            changes_per_title_group
+                .entry(affiliation.title_group_id)
+                .or_default()
+                .push(json!({"entity_id": affiliation.entity_id, "roles": affiliation.roles}));
+        }
+
+        for (title_group_id, changes) in changes_per_title_group {
+            let (old, new) = if removed {
+                (changes, Vec::new())
+            } else {
+                (Vec::new(), changes)
+            };
+            Self::create_user_edit_change_log_tx(
+                tx,
+                &NewUserEditChangeLog {
+                    item_type: "title_group".to_string(),
+                    item_id: title_group_id as i64,
+                    edited_by_id,
+                    edits: json!({"affiliated_entities": {"old": old, "new": new}}),
+                },
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The title groups the given affiliations belong to
+    pub async fn find_entities_affiliation_title_group_ids(
+        &self,
+        affiliation_ids: &[i64],
+    ) -> Result<Vec<i32>> {
+        let title_group_ids = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT title_group_id
+            FROM affiliated_entities
+            WHERE id = ANY($1)
+            "#,
+            affiliation_ids
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(title_group_ids)
+    }
+
+    /// Aggregates the edition groups, torrents, `seeders`, `leechers` and
+    /// `times_completed` of all title groups affiliated with each entity and
+    /// stores them in the entities table.
+    /// Returns the number of entity rows that were actually changed.
+    pub async fn update_entity_peer_stats(&self) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            WITH entity_stats AS (
+                SELECT ae.entity_id,
+                       COUNT(DISTINCT eg.id)::INT AS edition_groups,
+                       COUNT(DISTINCT t.id)::INT AS torrents,
+                       COALESCE(SUM(t.seeders), 0)::INT AS seeders,
+                       COALESCE(SUM(t.leechers), 0)::INT AS leechers,
+                       COALESCE(SUM(t.times_completed), 0)::INT AS snatches
+                FROM affiliated_entities ae
+                JOIN edition_groups eg ON eg.title_group_id = ae.title_group_id
+                LEFT JOIN torrents t ON t.edition_group_id = eg.id AND t.deleted_at IS NULL
+                GROUP BY ae.entity_id
+            )
+            UPDATE entities e
+            SET edition_groups_amount = COALESCE(s.edition_groups, 0),
+                torrents_amount = COALESCE(s.torrents, 0),
+                seeders_amount = COALESCE(s.seeders, 0),
+                leechers_amount = COALESCE(s.leechers, 0),
+                snatches_amount = COALESCE(s.snatches, 0)
+            FROM entities e2
+            LEFT JOIN entity_stats s ON s.entity_id = e2.id
+            WHERE e.id = e2.id
+              AND (e.edition_groups_amount != COALESCE(s.edition_groups, 0)
+                OR e.torrents_amount != COALESCE(s.torrents, 0)
+                OR e.seeders_amount != COALESCE(s.seeders, 0)
+                OR e.leechers_amount != COALESCE(s.leechers, 0)
+                OR e.snatches_amount != COALESCE(s.snatches, 0))
+            "#
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_entity(&self, entity_id: i64) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM entities
+            WHERE id = $1
+            "#,
+            entity_id
+        )
+        .execute(self.borrow())
+        .await
+        .map_err(Error::CouldNotDeleteEntity)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::feed::{FeedFilter, FeedItem, FeedSearch, UserCreatedFeedFilter},
+    utils::tag_expression::parse_tag_expression,
+};
+use arcadia_common::error::{Error, Result};
+use sqlx::types::Json;
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    pub async fn create_feed_filter(
+        &self,
+        feed_filter: &UserCreatedFeedFilter,
+        current_user_id: i32,
+    ) -> Result<FeedFilter> {
+        let name = feed_filter.name.trim();
+        if name.is_empty() {
+            return Err(Error::FeedFilterNameEmpty);
+        }
+        // reject invalid expressions now rather than when the feed is read
+        if let Some(tag_expression) = &feed_filter.tag_expression {
+            parse_tag_expression(tag_expression).map_err(Error::InvalidTagExpression)?;
+        }
+
+        let created_feed_filter = sqlx::query_as!(
+            FeedFilter,
+            r#"
+                INSERT INTO feed_filters (user_id, name, search, tag_expression)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, user_id, name, created_at,
+                          search AS "search: Json<FeedSearch>", tag_expression
+            "#,
+            current_user_id,
+            name,
+            Json(&feed_filter.search) as Json<&FeedSearch>,
+            feed_filter.tag_expression,
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(created_feed_filter)
+    }
+
+    pub async fn find_user_feed_filters(&self, user_id: i32) -> Result<Vec<FeedFilter>> {
+        let feed_filters = sqlx::query_as!(
+            FeedFilter,
+            r#"
+                SELECT id, user_id, name, created_at,
+                       search AS "search: Json<FeedSearch>", tag_expression
+                FROM feed_filters
+                WHERE user_id = $1
+                ORDER BY created_at
+            "#,
+            user_id
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(feed_filters)
+    }
+
+    pub async fn find_feed_filter(&self, feed_filter_id: i64, user_id: i32) -> Result<FeedFilter> {
+        sqlx::query_as!(
+            FeedFilter,
+            r#"
+                SELECT id, user_id, name, created_at,
+                       search AS "search: Json<FeedSearch>", tag_expression
+                FROM feed_filters
+                WHERE id = $1 AND user_id = $2
+            "#,
+            feed_filter_id,
+            user_id
+        )
+        .fetch_optional(self.borrow())
+        .await?
+        .ok_or(Error::FeedFilterNotFound)
+    }
+
+    pub async fn delete_feed_filter(&self, feed_filter_id: i64, user_id: i32) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM feed_filters
+                WHERE id = $1 AND user_id = $2
+            "#,
+            feed_filter_id,
+            user_id
+        )
+        .execute(self.borrow())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::FeedFilterNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Latest torrents matching the filter, found with the torrent search
+    pub async fn find_feed_items(
+        &self,
+        search: &FeedSearch,
+        tag_expression: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<FeedItem>> {
+        // the search is paginated by title group, ordered by their latest upload,
+        // so the latest `limit` torrents are all within the first `limit` title groups
+        let title_groups = self
+            .search_torrents(&search.to_torrent_search(tag_expression, limit), None)
+            .await?
+            .results;
+        let torrent_ids: Vec<i32> = title_groups
+            .iter()
+            .flat_map(|tg| tg.edition_groups.0.iter())
+            .flat_map(|eg| eg.torrents.0.iter().map(|torrent| torrent.id))
+            .collect();
+
+        let items = sqlx::query_as!(
+            FeedItem,
+            r#"
+            SELECT t.id AS torrent_id, tg.id AS title_group_id, tg.name AS title_group_name,
+                   t.release_name, t.size, t.created_at, tags.names AS "tags!"
+            FROM torrents t
+            JOIN edition_groups eg ON eg.id = t.edition_group_id
+            JOIN title_groups tg ON tg.id = eg.title_group_id
+            CROSS JOIN LATERAL (
+                SELECT COALESCE(
+                    ARRAY(
+                        SELECT tgt.name
+                        FROM title_group_applied_tags tat
+                        JOIN title_group_tags tgt ON tgt.id = tat.tag_id
+                        WHERE tat.title_group_id = tg.id
+                          AND tgt.deleted_at IS NULL
+                    ),
+                    ARRAY[]::varchar[]
+                ) AS names
+            ) tags
+            WHERE t.id = ANY($1)
+            ORDER BY t.created_at DESC
+            LIMIT $2
+            "#,
+            &torrent_ids,
+            limit
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(items)
+    }
+}
@@ -17,6 +17,7 @@ use crate::{
         notification::NotificationEvent,
         site_highlight::SiteHighlightItemType,
         user::{UserLite, UserLiteAvatar},
+        webhook::WebhookTrigger,
     },
 };
 use arcadia_common::error::{Error, Result};
@@ -167,6 +168,11 @@ impl ConnectionPool {
             let _ = notification_sender.send(NotificationEvent::ForumThreadPost { user_ids });
         }
 
+        self.enqueue_webhooks(WebhookTrigger::ForumPost {
+            forum_post_id: created_forum_post.id,
+        })
+        .await;
+
         Ok(created_forum_post)
     }
 
@@ -1168,9 +1174,14 @@ impl ConnectionPool {
             JOIN forum_sub_categories s ON s.id = t.forum_sub_category_id
             JOIN forum_categories c ON c.id = s.forum_category_id
 
-            WHERE $1::TEXT IS NULL OR t.name ILIKE '%' || $1 || '%'
+            WHERE $1::TEXT IS NULL
+               OR to_tsvector('simple', f_unaccent(t.name)) @@ websearch_to_tsquery('simple', f_unaccent($1))
+               OR f_unaccent($1) <% f_unaccent(t.name)
 
-            ORDER BY p.created_at DESC
+            ORDER BY
+                ts_rank(to_tsvector('simple', f_unaccent(t.name)), websearch_to_tsquery('simple', f_unaccent($1)))
+                    + word_similarity(f_unaccent($1), f_unaccent(t.name)) DESC NULLS LAST,
+                p.created_at DESC
 
             LIMIT $2 OFFSET $3;
             "#,
@@ -1183,7 +1194,12 @@ impl ConnectionPool {
         .map_err(Error::CouldNotFindForumThreadsFirstPost)?;
 
         let total_results = sqlx::query!(
-            "SELECT COUNT(*) AS total FROM forum_threads WHERE name ILIKE '%' || $1 || '%'",
+            r#"
+            SELECT COUNT(*) AS total FROM forum_threads
+            WHERE $1::TEXT IS NULL
+               OR to_tsvector('simple', f_unaccent(name)) @@ websearch_to_tsquery('simple', f_unaccent($1))
+               OR f_unaccent($1) <% f_unaccent(name)
+            "#,
             form.thread_name
         )
         .fetch_one(self.borrow())
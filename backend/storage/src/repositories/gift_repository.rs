@@ -90,25 +90,44 @@ impl ConnectionPool {
         Ok(inserted_gift)
     }
 
+    /// Fails without changing anything if the user doesn't have enough bonus points or
+    /// freeleech tokens, which is checked by the update itself so that concurrent spendings
+    /// can't overdraw them
     pub async fn decrement_bonus_points_and_freeleech_tokens(
         tx: &mut Transaction<'_, Postgres>,
         user_id: i32,
         bonus_points: i64,
         freeleech_tokens: i32,
     ) -> Result<()> {
-        sqlx::query!(
+        let updated = sqlx::query_scalar!(
             r#"
               UPDATE users SET bonus_points = bonus_points - $1,
               freeleech_tokens = freeleech_tokens - $2
               WHERE id = $3
+                AND bonus_points >= $1
+                AND freeleech_tokens >= $2
+              RETURNING id
             "#,
             bonus_points,
             freeleech_tokens,
             user_id
         )
-        .execute(&mut **tx)
+        .fetch_optional(&mut **tx)
         .await?;
 
+        if updated.is_none() {
+            let available_bonus_points =
+                sqlx::query_scalar!(r#"SELECT bonus_points FROM users WHERE id = $1"#, user_id)
+                    .fetch_one(&mut **tx)
+                    .await?;
+
+            return Err(if available_bonus_points < bonus_points {
+                Error::NotEnoughBonusPointsAvailable
+            } else {
+                Error::NotEnoughFreeleechTokensAvailable
+            });
+        }
+
         Ok(())
     }
 
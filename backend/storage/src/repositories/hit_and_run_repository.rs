@@ -0,0 +1,253 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        bonus_points_log::BonusPointsLogAction,
+        hit_and_run::{HitAndRun, HitAndRunClearedBy},
+        user::UserCreatedUserWarning,
+    },
+};
+use arcadia_common::error::{Error, Result};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    /// Flags snatches older than the grace period that have neither been seeded
+    /// for `min_seed_time_hours` nor reached `min_ratio` (real upload / torrent size).
+    /// Uploaders are never flagged on their own torrents, and a snatch is only
+    /// ever flagged once. Returns the number of new hit and runs.
+    pub async fn flag_hit_and_runs(
+        &self,
+        min_seed_time_hours: i32,
+        min_ratio: f64,
+        grace_period_days: i32,
+    ) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO hit_and_runs (user_id, torrent_id)
+            SELECT ta.user_id, ta.torrent_id
+            FROM torrent_activities ta
+            INNER JOIN torrents t ON t.id = ta.torrent_id
+            WHERE ta.completed_at IS NOT NULL
+              AND ta.completed_at < NOW() - make_interval(days => $3)
+              AND t.deleted_at IS NULL
+              AND t.created_by_id <> ta.user_id
+              AND ta.total_seed_time < $1
+              AND ta.real_uploaded < t.size * $2::FLOAT8
+            ON CONFLICT (user_id, torrent_id) DO NOTHING
+            "#,
+            min_seed_time_hours as i64 * 3600,
+            min_ratio,
+            grace_period_days
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Clears the active hit and runs whose snatch now meets the requirements,
+    /// and lifts the hit and run warnings of the users now under `warning_threshold`.
+    pub async fn clear_seeded_hit_and_runs(
+        &self,
+        min_seed_time_hours: i32,
+        min_ratio: f64,
+        warning_threshold: Option<i32>,
+    ) -> Result<u64> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE hit_and_runs hnr
+            SET cleared_at = NOW(), cleared_by = 'seeding'
+            FROM torrent_activities ta
+            INNER JOIN torrents t ON t.id = ta.torrent_id
+            WHERE hnr.cleared_at IS NULL
+              AND ta.user_id = hnr.user_id
+              AND ta.torrent_id = hnr.torrent_id
+              AND (
+                ta.total_seed_time >= $1
+                OR ta.real_uploaded >= t.size * $2::FLOAT8
+              )
+            "#,
+            min_seed_time_hours as i64 * 3600,
+            min_ratio
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        Self::lift_hit_and_run_warnings(&mut tx, warning_threshold, None).await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Warns the users who have at least `threshold` active hit and runs and
+    /// no active hit and run warning yet. Returns the number of users warned.
+    pub async fn warn_users_with_hit_and_runs(&self, threshold: i32) -> Result<u64> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let offenders = sqlx::query!(
+            r#"
+            SELECT hnr.user_id, COUNT(*) AS "hit_and_runs!"
+            FROM hit_and_runs hnr
+            WHERE hnr.cleared_at IS NULL
+              AND NOT EXISTS (
+                SELECT 1
+                FROM user_warnings uw
+                WHERE uw.user_id = hnr.user_id
+                  AND uw.hit_and_run
+                  AND uw.removed_at IS NULL
+                  AND (uw.expires_at IS NULL OR uw.expires_at > NOW())
+              )
+            GROUP BY hnr.user_id
+            HAVING COUNT(*) >= $1
+            "#,
+            threshold as i64
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for offender in &offenders {
+            // the warnings are given by the system user
+            Self::create_user_warning_tx(
+                &mut tx,
+                1,
+                &UserCreatedUserWarning {
+                    user_id: offender.user_id,
+                    expires_at: None,
+                    reason: format!("Hit and runs: {}", offender.hit_and_runs),
+                    ban: false,
+                },
+                true,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(offenders.len() as u64)
+    }
+
+    /// Removes the hit and run warnings of the users (or of `user_id` only) who
+    /// have less than `threshold` active hit and runs, all of them when there is
+    /// no threshold anymore. The users are not warned anymore unless they have
+    /// other warnings.
+    async fn lift_hit_and_run_warnings(
+        tx: &mut Transaction<'_, Postgres>,
+        threshold: Option<i32>,
+        user_id: Option<i32>,
+    ) -> Result<()> {
+        // the warnings are removed by the system user
+        sqlx::query!(
+            r#"
+            WITH lifted AS (
+                UPDATE user_warnings uw
+                SET removed_at = NOW(), removed_by_id = 1
+                WHERE uw.hit_and_run
+                  AND uw.removed_at IS NULL
+                  AND ($2::INT IS NULL OR uw.user_id = $2)
+                  AND (
+                    $1::INT IS NULL
+                    OR (
+                        SELECT COUNT(*)
+                        FROM hit_and_runs hnr
+                        WHERE hnr.user_id = uw.user_id
+                          AND hnr.cleared_at IS NULL
+                    ) < $1
+                  )
+                RETURNING uw.id, uw.user_id
+            )
+            UPDATE users u
+            SET warned = false
+            WHERE u.id IN (SELECT user_id FROM lifted)
+              AND NOT EXISTS (
+                SELECT 1
+                FROM user_warnings uw
+                WHERE uw.user_id = u.id
+                  AND uw.removed_at IS NULL
+                  AND (uw.expires_at IS NULL OR uw.expires_at > NOW())
+                  AND uw.id NOT IN (SELECT id FROM lifted)
+              )
+            "#,
+            threshold,
+            user_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_user_hit_and_runs(&self, user_id: i32) -> Result<Vec<HitAndRun>> {
+        let hit_and_runs = sqlx::query_as!(
+            HitAndRun,
+            r#"
+            SELECT id, user_id, torrent_id, created_at, cleared_at,
+                   cleared_by AS "cleared_by: HitAndRunClearedBy"
+            FROM hit_and_runs
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(hit_and_runs)
+    }
+
+    /// Clears one of the user's active hit and runs in exchange for `cost` bonus points,
+    /// and lifts their hit and run warning if they are now under `warning_threshold`.
+    pub async fn clear_hit_and_run_with_bonus_points(
+        &self,
+        hit_and_run_id: i64,
+        user_id: i32,
+        cost: i64,
+        warning_threshold: Option<i32>,
+    ) -> Result<HitAndRun> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let hit_and_run = sqlx::query_as!(
+            HitAndRun,
+            r#"
+            UPDATE hit_and_runs
+            SET cleared_at = NOW(), cleared_by = 'bonus_points'
+            WHERE id = $1
+              AND user_id = $2
+              AND cleared_at IS NULL
+            RETURNING id, user_id, torrent_id, created_at, cleared_at,
+                      cleared_by AS "cleared_by: HitAndRunClearedBy"
+            "#,
+            hit_and_run_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(Error::HitAndRunNotFound)?;
+
+        Self::decrement_bonus_points_and_freeleech_tokens(&mut tx, user_id, cost, 0).await?;
+
+        Self::log_bonus_points_change_tx(
+            &mut tx,
+            user_id,
+            BonusPointsLogAction::HitAndRunCleared,
+            -cost,
+            None,
+            Some(hit_and_run.torrent_id as i64),
+        )
+        .await?;
+
+        Self::lift_hit_and_run_warnings(&mut tx, warning_threshold, Some(user_id)).await?;
+
+        tx.commit().await?;
+
+        Ok(hit_and_run)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        common::PaginatedResults,
+        ip_ban::{IpBan, SearchIpBansQuery, UserCreatedIpBan},
+    },
+};
+use arcadia_common::{
+    error::{Error, Result},
+    services::tracker_client::TrackerMutation,
+};
+use arcadia_shared::tracker::models::ip_ban::APIInsertIpBan;
+use sqlx::{types::ipnetwork::IpNetwork, PgPool};
+use std::{borrow::Borrow, net::IpAddr};
+
+impl ConnectionPool {
+    pub async fn create_ip_ban(
+        &self,
+        ip_ban: &UserCreatedIpBan,
+        current_user_id: i32,
+    ) -> Result<IpBan> {
+        let reason = ip_ban.reason.trim();
+        if reason.is_empty() {
+            return Err(Error::IpBanReasonEmpty);
+        }
+        // the cidr column rejects ranges with host bits set, e.g. 10.0.0.1/24
+        let ip_range = IpNetwork::new(ip_ban.ip_range.network(), ip_ban.ip_range.prefix())
+            .expect("the prefix comes from a valid network");
+
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let created_ip_ban = sqlx::query_as!(
+            IpBan,
+            r#"
+                INSERT INTO ip_bans (created_by_id, ip_range, reason, expires_at)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id, created_at, created_by_id, ip_range, reason, expires_at
+            "#,
+            current_user_id,
+            ip_range,
+            reason,
+            ip_ban.expires_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // so that announces from the range are refused
+        Self::queue_tracker_mutation_tx(
+            &mut tx,
+            TrackerMutation::UpsertIpBan(APIInsertIpBan {
+                id: created_ip_ban.id,
+                ip_range: created_ip_ban.ip_range,
+                expires_at: created_ip_ban.expires_at,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(created_ip_ban)
+    }
+
+    pub async fn delete_ip_ban(&self, ip_ban_id: i64) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM ip_bans
+                WHERE id = $1
+            "#,
+            ip_ban_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::IpBanNotFound);
+        }
+
+        Self::queue_tracker_mutation_tx(&mut tx, TrackerMutation::DeleteIpBan { id: ip_ban_id })
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn find_ip_bans(&self, query: SearchIpBansQuery) -> Result<PaginatedResults<IpBan>> {
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM ip_bans
+            WHERE ($1::INET IS NULL OR ip_range >>= $1)
+              AND ($2 OR expires_at IS NULL OR expires_at > NOW())
+            "#,
+            query.ip,
+            query.include_expired
+        )
+        .fetch_one(self.borrow())
+        .await?
+        .unwrap_or(0);
+
+        let results = sqlx::query_as!(
+            IpBan,
+            r#"
+            SELECT id, created_at, created_by_id, ip_range, reason, expires_at
+            FROM ip_bans
+            WHERE ($1::INET IS NULL OR ip_range >>= $1)
+              AND ($2 OR expires_at IS NULL OR expires_at > NOW())
+            ORDER BY created_at DESC
+            OFFSET ($3 - 1) * LEAST($4, 100)
+            LIMIT LEAST($4, 100)
+            "#,
+            query.ip,
+            query.include_expired,
+            query.page as i32,
+            query.page_size as i32
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: query.page as u32,
+            page_size: query.page_size.min(100) as u32,
+        })
+    }
+
+    pub async fn is_ip_banned(&self, ip: IpAddr) -> Result<bool> {
+        let is_banned = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM ip_bans
+                WHERE ip_range >>= $1
+                  AND (expires_at IS NULL OR expires_at > NOW())
+            ) AS "is_banned!"
+            "#,
+            // dual stack sockets report ipv4 clients as ipv4-mapped ipv6 addresses
+            IpNetwork::from(ip.to_canonical())
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(is_banned)
+    }
+}
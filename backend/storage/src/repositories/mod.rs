@@ -2,17 +2,24 @@ pub mod arcadia_settings_repository;
 pub mod artist_repository;
 pub mod auth_repository;
 pub mod bonus_points_log_repository;
+pub mod cheat_flag_repository;
 pub mod collage_repository;
 pub mod conversation_repository;
 pub mod css_sheet_repository;
 pub mod donation_repository;
 pub mod edition_group_repository;
+pub mod entity_repository;
+pub mod feed_repository;
 pub mod forum_repository;
 pub mod forum_stats_repository;
 pub mod gift_repository;
+pub mod hit_and_run_repository;
 pub mod invitation_repository;
+pub mod ip_ban_repository;
 pub mod master_group_repository;
 pub mod notification_repository;
+pub mod personal_freeleech_repository;
+pub mod promotion_event_repository;
 pub mod series_repository;
 pub mod shop_repository;
 pub mod site_highlight_repository;
@@ -29,8 +36,12 @@ pub mod torrent_request_comment_repository;
 pub mod torrent_request_repository;
 pub mod torrent_request_vote_repository;
 pub mod torrent_stats_repository;
+pub mod tracker_outbox_repository;
+pub mod two_factor_repository;
+pub mod unregistered_info_hash_repository;
 pub mod user_application_repository;
 pub mod user_badge_repository;
 pub mod user_edit_change_log_repository;
 pub mod user_repository;
+pub mod webhook_repository;
 pub mod wiki_repository;
@@ -0,0 +1,58 @@
+use crate::{connection_pool::ConnectionPool, models::personal_freeleech::PersonalFreeleech};
+use arcadia_common::{
+    error::{Error, Result},
+    services::tracker_client::TrackerMutation,
+};
+use arcadia_shared::tracker::models::personal_freeleech::APIInsertPersonalFreeleech;
+use sqlx::PgPool;
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    /// Spends one of the user's freeleech tokens on a torrent.
+    ///
+    /// If the user already has an active window on this torrent, it is extended
+    /// by `duration_hours` instead of being reset.
+    pub async fn create_personal_freeleech(
+        &self,
+        user_id: i32,
+        torrent_id: i32,
+        duration_hours: i32,
+    ) -> Result<PersonalFreeleech> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        Self::decrement_bonus_points_and_freeleech_tokens(&mut tx, user_id, 0, 1).await?;
+
+        let personal_freeleech = sqlx::query_as!(
+            PersonalFreeleech,
+            r#"
+                INSERT INTO personal_freeleeches (user_id, torrent_id, expires_at)
+                VALUES ($1, $2, NOW() + make_interval(hours => $3))
+                ON CONFLICT (user_id, torrent_id) DO UPDATE
+                SET expires_at = GREATEST(personal_freeleeches.expires_at, NOW()) + make_interval(hours => $3)
+                RETURNING user_id, torrent_id, created_at, expires_at
+            "#,
+            user_id,
+            torrent_id,
+            duration_hours
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Error::CouldNotCreatePersonalFreeleech)?;
+
+        Self::queue_tracker_mutation_tx(
+            &mut tx,
+            TrackerMutation::UpsertPersonalFreeleech(APIInsertPersonalFreeleech {
+                user_id: personal_freeleech.user_id as u32,
+                torrent_id: personal_freeleech.torrent_id as u32,
+                expires_at: personal_freeleech.expires_at,
+            }),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(personal_freeleech)
+    }
+}
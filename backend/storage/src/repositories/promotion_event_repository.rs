@@ -0,0 +1,285 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        common::PaginatedResults,
+        promotion_event::{
+            PromotionEvent, PublicPromotionEvent, SearchPromotionEventsQuery,
+            UserCreatedPromotionEvent,
+        },
+        title_group::{ContentType, TitleGroupCategory},
+    },
+    utils::tag_expression::parse_tag_expression,
+};
+use arcadia_common::{
+    error::{Error, Result},
+    services::tracker_client::TrackerMutation,
+};
+use arcadia_shared::tracker::models::promotion_event::APIInsertPromotionEvent;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::borrow::Borrow;
+
+/// Highest upload factor of an event, 10 times the normal credit
+const MAX_PROMOTION_UPLOAD_FACTOR: i16 = 1000;
+
+/// Returns the tag expression, trimmed, with its parsed form
+fn validate_promotion_event(
+    promotion_event: &UserCreatedPromotionEvent,
+) -> Result<(Option<&str>, Option<serde_json::Value>)> {
+    if promotion_event.name.trim().is_empty() {
+        return Err(Error::InvalidPromotionEvent("name cannot be empty".into()));
+    }
+    if promotion_event.ends_at <= promotion_event.starts_at {
+        return Err(Error::InvalidPromotionEvent(
+            "the event must end after it starts".into(),
+        ));
+    }
+    if promotion_event.upload_factor < 0 || promotion_event.download_factor < 0 {
+        return Err(Error::InvalidPromotionEvent(
+            "factors cannot be negative".into(),
+        ));
+    }
+    if promotion_event.upload_factor > MAX_PROMOTION_UPLOAD_FACTOR {
+        return Err(Error::InvalidPromotionEvent(format!(
+            "upload factor cannot be above {MAX_PROMOTION_UPLOAD_FACTOR}"
+        )));
+    }
+    // events only ever lower what downloads count for
+    if promotion_event.download_factor > 100 {
+        return Err(Error::InvalidPromotionEvent(
+            "download factor cannot be above 100".into(),
+        ));
+    }
+    if promotion_event
+        .min_size
+        .is_some_and(|min_size| min_size < 0)
+    {
+        return Err(Error::InvalidPromotionEvent(
+            "minimum size cannot be negative".into(),
+        ));
+    }
+
+    let tag_expression = promotion_event
+        .tag_expression
+        .as_deref()
+        .map(str::trim)
+        .filter(|tag_expression| !tag_expression.is_empty());
+    let tag_filter = match tag_expression {
+        Some(tag_expression) => {
+            parse_tag_expression(tag_expression).map_err(Error::InvalidTagExpression)?
+        }
+        None => None,
+    };
+
+    Ok((tag_expression, tag_filter))
+}
+
+impl ConnectionPool {
+    pub async fn create_promotion_event(
+        &self,
+        promotion_event: &UserCreatedPromotionEvent,
+        current_user_id: i32,
+    ) -> Result<PromotionEvent> {
+        let (tag_expression, tag_filter) = validate_promotion_event(promotion_event)?;
+
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let created_promotion_event = sqlx::query_as!(
+            PromotionEvent,
+            r#"
+                INSERT INTO promotion_events (created_by_id, name, starts_at, ends_at, upload_factor,
+                                              download_factor, content_type, category, tag_expression,
+                                              tag_filter, min_size)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                RETURNING id, created_at, created_by_id, name, starts_at, ends_at, upload_factor,
+                    download_factor, content_type AS "content_type: ContentType",
+                    category AS "category: TitleGroupCategory", tag_expression, min_size
+            "#,
+            current_user_id,
+            promotion_event.name.trim(),
+            promotion_event.starts_at,
+            promotion_event.ends_at,
+            promotion_event.upload_factor,
+            promotion_event.download_factor,
+            promotion_event.content_type.clone() as Option<ContentType>,
+            promotion_event.category.clone() as Option<TitleGroupCategory>,
+            tag_expression,
+            tag_filter,
+            promotion_event.min_size
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::queue_promotion_event_for_tracker_tx(&mut tx, created_promotion_event.id).await?;
+
+        tx.commit().await?;
+
+        Ok(created_promotion_event)
+    }
+
+    pub async fn edit_promotion_event(
+        &self,
+        promotion_event_id: i64,
+        promotion_event: &UserCreatedPromotionEvent,
+    ) -> Result<PromotionEvent> {
+        let (tag_expression, tag_filter) = validate_promotion_event(promotion_event)?;
+
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let edited_promotion_event = sqlx::query_as!(
+            PromotionEvent,
+            r#"
+                UPDATE promotion_events
+                SET name = $2, starts_at = $3, ends_at = $4, upload_factor = $5,
+                    download_factor = $6, content_type = $7, category = $8,
+                    tag_expression = $9, tag_filter = $10, min_size = $11
+                WHERE id = $1
+                RETURNING id, created_at, created_by_id, name, starts_at, ends_at, upload_factor,
+                    download_factor, content_type AS "content_type: ContentType",
+                    category AS "category: TitleGroupCategory", tag_expression, min_size
+            "#,
+            promotion_event_id,
+            promotion_event.name.trim(),
+            promotion_event.starts_at,
+            promotion_event.ends_at,
+            promotion_event.upload_factor,
+            promotion_event.download_factor,
+            promotion_event.content_type.clone() as Option<ContentType>,
+            promotion_event.category.clone() as Option<TitleGroupCategory>,
+            tag_expression,
+            tag_filter,
+            promotion_event.min_size
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(Error::PromotionEventNotFound)?;
+
+        Self::queue_promotion_event_for_tracker_tx(&mut tx, edited_promotion_event.id).await?;
+
+        tx.commit().await?;
+
+        Ok(edited_promotion_event)
+    }
+
+    pub async fn delete_promotion_event(&self, promotion_event_id: i64) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM promotion_events
+                WHERE id = $1
+            "#,
+            promotion_event_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::PromotionEventNotFound);
+        }
+
+        Self::queue_tracker_mutation_tx(
+            &mut tx,
+            TrackerMutation::DeletePromotionEvent {
+                id: promotion_event_id,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn find_promotion_events(
+        &self,
+        query: SearchPromotionEventsQuery,
+    ) -> Result<PaginatedResults<PromotionEvent>> {
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM promotion_events
+            WHERE $1 OR ends_at > NOW()
+            "#,
+            query.include_ended
+        )
+        .fetch_one(self.borrow())
+        .await?
+        .unwrap_or(0);
+
+        let results = sqlx::query_as!(
+            PromotionEvent,
+            r#"
+            SELECT id, created_at, created_by_id, name, starts_at, ends_at, upload_factor,
+                download_factor, content_type AS "content_type: ContentType",
+                category AS "category: TitleGroupCategory", tag_expression, min_size
+            FROM promotion_events
+            WHERE $1 OR ends_at > NOW()
+            ORDER BY starts_at DESC
+            OFFSET ($2 - 1) * LEAST($3, 100)
+            LIMIT LEAST($3, 100)
+            "#,
+            query.include_ended,
+            query.page as i32,
+            query.page_size as i32
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: query.page as u32,
+            page_size: query.page_size.min(100) as u32,
+        })
+    }
+
+    /// The running and upcoming events, soonest first
+    pub async fn find_public_promotion_events(&self) -> Result<Vec<PublicPromotionEvent>> {
+        let promotion_events = sqlx::query_as!(
+            PublicPromotionEvent,
+            r#"
+            SELECT name, starts_at, ends_at, upload_factor, download_factor,
+                content_type AS "content_type: ContentType",
+                category AS "category: TitleGroupCategory", tag_expression, min_size
+            FROM promotion_events
+            WHERE ends_at > NOW()
+            ORDER BY starts_at, id
+            "#
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(promotion_events)
+    }
+
+    /// The events that haven't ended, as the tracker holds them
+    pub async fn find_tracker_promotion_events(&self) -> Result<Vec<APIInsertPromotionEvent>> {
+        Ok(APIInsertPromotionEvent::find_not_ended(self.borrow()).await?)
+    }
+
+    /// Queues the event for the tracker, or its removal if it was deleted or
+    /// has ended
+    pub async fn queue_promotion_event_for_tracker_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        promotion_event_id: i64,
+    ) -> Result<()> {
+        let promotion_events = APIInsertPromotionEvent::find_not_ended(&mut **tx).await?;
+
+        let mutation = match promotion_events
+            .into_iter()
+            .find(|promotion_event| promotion_event.id == promotion_event_id)
+        {
+            Some(promotion_event) => TrackerMutation::UpsertPromotionEvent(promotion_event),
+            None => TrackerMutation::DeletePromotionEvent {
+                id: promotion_event_id,
+            },
+        };
+        Self::queue_tracker_mutation_tx(tx, mutation).await
+    }
+}
@@ -223,7 +223,8 @@ impl ConnectionPool {
                     a.torrents_amount AS a_torrents_amount,
                     a.seeders_amount AS a_seeders_amount,
                     a.leechers_amount AS a_leechers_amount,
-                    a.snatches_amount AS a_snatches_amount
+                    a.snatches_amount AS a_snatches_amount,
+                    a.external_links AS a_external_links
                 FROM affiliated_artists aa
                 JOIN artists a ON a.id = aa.artist_id
                 WHERE aa.title_group_id = $1
@@ -239,7 +240,10 @@ impl ConnectionPool {
                     ae.roles AS "roles: Vec<EntityRole>",
                     e.id AS e_id, e.name AS e_name, e.created_at AS e_created_at,
                     e.created_by_id AS e_created_by_id, e.description AS e_description,
-                    e.pictures AS e_pictures
+                    e.pictures AS e_pictures, e.title_groups_amount AS e_title_groups_amount,
+                    e.edition_groups_amount AS e_edition_groups_amount,
+                    e.torrents_amount AS e_torrents_amount, e.seeders_amount AS e_seeders_amount,
+                    e.leechers_amount AS e_leechers_amount, e.snatches_amount AS e_snatches_amount
                 FROM affiliated_entities ae
                 JOIN entities e ON e.id = ae.entity_id
                 WHERE ae.title_group_id = $1
@@ -569,6 +573,7 @@ impl ConnectionPool {
                     created_by_id: row.a_created_by_id,
                     description: row.a_description,
                     pictures: row.a_pictures,
+                    external_links: row.a_external_links,
                     title_groups_amount: row.a_title_groups_amount,
                     edition_groups_amount: row.a_edition_groups_amount,
                     torrents_amount: row.a_torrents_amount,
@@ -587,15 +592,21 @@ impl ConnectionPool {
                 title_group_id: row.title_group_id,
                 entity_id: row.entity_id,
                 created_by_id: row.created_by_id,
-                created_at: row.created_at.with_timezone(&Local),
+                created_at: row.created_at,
                 roles: row.roles,
                 entity: Entity {
                     id: row.e_id,
                     name: row.e_name,
-                    created_at: row.e_created_at.with_timezone(&Local),
+                    created_at: row.e_created_at,
                     created_by_id: row.e_created_by_id,
                     description: row.e_description,
                     pictures: row.e_pictures,
+                    title_groups_amount: row.e_title_groups_amount,
+                    edition_groups_amount: row.e_edition_groups_amount,
+                    torrents_amount: row.e_torrents_amount,
+                    seeders_amount: row.e_seeders_amount,
+                    leechers_amount: row.e_leechers_amount,
+                    snatches_amount: row.e_snatches_amount,
                 },
             })
             .collect();
@@ -1311,6 +1322,20 @@ impl ConnectionPool {
         .execute(self.borrow())
         .await?;
 
+        // the other counters of the entities are recomputed with their peer stats
+        sqlx::query!(
+            r#"
+            UPDATE entities
+            SET title_groups_amount = title_groups_amount - 1
+            WHERE id IN (
+                SELECT entity_id FROM affiliated_entities WHERE title_group_id = $1
+            )
+            "#,
+            title_group_id
+        )
+        .execute(self.borrow())
+        .await?;
+
         // Delete the title group (cascades to edition_groups, affiliated_artists, etc.)
         sqlx::query!(
             r#"
@@ -17,14 +17,23 @@ use crate::{
             TorrentActivityOrderByColumn,
         },
         user::UserLite,
+        webhook::WebhookTrigger,
     },
 };
 use arcadia_common::{
     error::{Error, Result},
-    services::torrent_service::{get_announce_url, looks_like_url},
+    services::{
+        torrent_service::{get_announce_url, looks_like_url},
+        tracker_client::TrackerMutation,
+    },
+};
+use arcadia_shared::{
+    tracker::models::torrent::{APIUpdateTorrentFactors, InfoHash},
+    utils::format_title_group_name,
+};
+use bip_metainfo::{
+    Info, InfoBuilder, InfoHashV2, Metainfo, MetainfoBuilder, PieceLayers, PieceLength,
 };
-use arcadia_shared::{tracker::models::torrent::InfoHash, utils::format_title_group_name};
-use bip_metainfo::{Info, InfoBuilder, Metainfo, MetainfoBuilder, PieceLength};
 use serde_json::{json, Value};
 use sqlx::{types::Json, PgPool};
 use std::{borrow::Borrow, collections::HashMap, str::FromStr};
@@ -105,7 +114,7 @@ impl ConnectionPool {
                 staff_checked, size, duration, audio_codec, audio_bitrate, audio_bitrate_sampling,
                 audio_channels, video_codec, features, subtitle_languages, video_resolution,
                 video_resolution_other_x, video_resolution_other_y, container, languages, info_hash, info_dict, extras,
-                extra_text, bonus_points_snatch_cost
+                extra_text, bonus_points_snatch_cost, info_hash_v2, piece_layers
             ) VALUES (
                 $1, $2, $3, $4, $5, $6, $7, $8,
                 $9, $10, $11, $12, $13, $14,
@@ -113,7 +122,7 @@ impl ConnectionPool {
                 $18::audio_channels_enum, $19::video_codec_enum, $20::features_enum[],
                 $21::language_enum[], $22::video_resolution_enum, $23, $24, $25,
                 $26::language_enum[], $27::bytea, $28::bytea, $29::extras_enum[],
-                $30, $31
+                $30, $31, $32::bytea, $33::bytea
             )
             RETURNING id, info_hash, upload_factor, download_factor, seeders, leechers, times_completed, grabbed, edition_group_id, created_at, updated_at, created_by_id, deleted_at, deleted_by_id, extras, release_name, release_group, description, file_amount_per_type, uploaded_as_anonymous, upload_method, file_list, mediainfo, trumpable, staff_checked, languages, container, size, duration, audio_codec, audio_bitrate, audio_bitrate_sampling, audio_channels, video_codec, features, subtitle_languages, video_resolution, video_resolution_other_x, video_resolution_other_y, extra_text, bonus_points_snatch_cost
         "#;
@@ -132,12 +141,22 @@ impl ConnectionPool {
             .build(1, info, |_| {})
             .map_err(|_| Error::TorrentFileInvalid)?;
 
-        let info_hash = bip_metainfo::InfoHash::from_bytes(&info_normalized);
+        let info_hash_v2 = info
+            .is_v2()
+            .then(|| InfoHashV2::from_bytes(&info_normalized));
+        // v2 only torrents have no v1 info hash, clients announce them with the
+        // truncated v2 one
+        let info_hash = match info_hash_v2 {
+            Some(info_hash_v2) if !info.is_v1() => info_hash_v2.truncated(),
+            _ => bip_metainfo::InfoHash::from_bytes(&info_normalized),
+        };
 
         // TODO: torrent metadata extraction should be done on the client side
         let parent_folder = info.directory().map(|d| d.to_str().unwrap()).unwrap_or("");
+        // padding files of hybrid torrents are not part of the content
         let files = info
             .files()
+            .filter(|f| !f.is_padding())
             .map(|f| json!({"name": f.path().to_str().unwrap(), "size": f.length()}))
             .collect::<Vec<_>>();
 
@@ -145,6 +164,7 @@ impl ConnectionPool {
 
         let file_amount_per_type = json!(info
             .files()
+            .filter(|file| !file.is_padding())
             .flat_map(|file| file.path().to_str().unwrap().split('.').next_back())
             .fold(std::collections::HashMap::new(), |mut acc, ext| {
                 *acc.entry(ext.to_string()).or_insert(0) += 1;
@@ -154,6 +174,7 @@ impl ConnectionPool {
         let size = metainfo
             .info()
             .files()
+            .filter(|file| !file.is_padding())
             .map(|file| file.length())
             .sum::<u64>() as i64;
 
@@ -233,6 +254,12 @@ impl ConnectionPool {
                 }
             }))
             .bind(bonus_points_snatch_cost)
+            .bind(
+                info_hash_v2
+                    .as_ref()
+                    .map(|info_hash_v2| info_hash_v2.as_ref()),
+            )
+            .bind(metainfo.piece_layers().map(PieceLayers::to_bytes))
             .fetch_one(&mut *tx)
             .await
             .map_err(Error::CouldNotCreateTorrent)?;
@@ -309,12 +336,19 @@ impl ConnectionPool {
             .await?;
         }
 
+        Self::queue_torrents_for_tracker_tx(&mut tx, &[uploaded_torrent.id]).await?;
+
         tx.commit().await?;
 
         if !user_ids.is_empty() {
             let _ = notification_sender.send(NotificationEvent::TitleGroupTorrent { user_ids });
         }
 
+        self.enqueue_webhooks(WebhookTrigger::TorrentUploaded {
+            torrent_id: uploaded_torrent.id,
+        })
+        .await;
+
         Ok(uploaded_torrent)
     }
 
@@ -486,6 +520,7 @@ impl ConnectionPool {
             WHERE id = $1 AND deleted_at IS NULL
             RETURNING
                 info_dict,
+                piece_layers,
                 EXTRACT(EPOCH FROM created_at)::BIGINT AS "created_at_secs!",
                 release_name;
             "#,
@@ -496,6 +531,11 @@ impl ConnectionPool {
         .map_err(|_| Error::TorrentFileInvalid)?;
 
         let info = Info::from_bytes(torrent.info_dict).map_err(|_| Error::TorrentFileInvalid)?;
+        let piece_layers = torrent
+            .piece_layers
+            .map(PieceLayers::from_bytes)
+            .transpose()
+            .map_err(|_| Error::TorrentFileInvalid)?;
 
         let user = self.find_user_with_id(user_id).await?;
         let announce_url = get_announce_url(user.passkey, tracker_url);
@@ -508,6 +548,7 @@ impl ConnectionPool {
             .set_comment(Some(&frontend_url))
             .set_created_by(Some(tracker_name))
             .set_piece_length(PieceLength::Custom(info.piece_length() as usize))
+            .set_piece_layers(piece_layers.as_ref())
             .set_private_flag(Some(true))
             .build(1, &info, |_| {})
             .map_err(|_| Error::TorrentFileInvalid)?;
@@ -598,12 +639,13 @@ impl ConnectionPool {
                 $9::BIGINT IS NULL OR
                 EXISTS (SELECT 1 FROM affiliated_artists aa WHERE aa.title_group_id = tgh.title_group_id AND aa.artist_id = $9)
             )
-            -- name filter (partial match) or external link match or series name match
+            -- name filter (full-text match, or fuzzy partial match on the names) or external link match
             AND (
                 $10::TEXT IS NULL OR
-                tgh.title_group_name ILIKE '%' || $10 || '%' ESCAPE '\' OR
-                tgh.title_group_series_name ILIKE '%' || $10 || '%' ESCAPE '\' OR
-                EXISTS (SELECT 1 FROM unnest(tgh.title_group_name_aliases) alias WHERE alias ILIKE '%' || $10 || '%')
+                tgh.title_group_search_vector @@ websearch_to_tsquery('simple', f_unaccent($10)) OR
+                f_unaccent($10) <% f_unaccent(tgh.title_group_name) OR
+                f_unaccent($10) <% f_unaccent_array(tgh.title_group_name_aliases) OR
+                f_unaccent($10) <% f_unaccent(tgh.title_group_series_name)
             )
             AND (
                 $11::TEXT IS NULL
@@ -648,6 +690,10 @@ impl ConnectionPool {
                     AND tgb.user_id = $22
                 )
             )
+            AND (
+                $23::BIGINT IS NULL OR
+                EXISTS (SELECT 1 FROM affiliated_entities ae WHERE ae.title_group_id = tgh.title_group_id AND ae.entity_id = $23)
+            )
 
             GROUP BY title_group_id, title_group_name, title_group_covers, title_group_category,
             title_group_content_type, title_group_tag_names, title_group_original_release_date,
@@ -655,6 +701,14 @@ impl ConnectionPool {
             tgh.title_group_series_id, tgh.title_group_series_name
 
             ORDER BY
+                CASE WHEN $1 = 'relevance' AND $6 = 'asc' THEN
+                    MAX(ts_rank(tgh.title_group_search_vector, websearch_to_tsquery('simple', f_unaccent($10)))
+                        + similarity(f_unaccent(title_group_name), f_unaccent($10)))
+                END ASC NULLS LAST,
+                CASE WHEN $1 = 'relevance' AND $6 = 'desc' THEN
+                    MAX(ts_rank(tgh.title_group_search_vector, websearch_to_tsquery('simple', f_unaccent($10)))
+                        + similarity(f_unaccent(title_group_name), f_unaccent($10)))
+                END DESC NULLS LAST,
                 CASE WHEN $1 = 'title_group_original_release_date' AND $6 = 'asc' THEN title_group_original_release_date END ASC NULLS LAST,
                 CASE WHEN $1 = 'title_group_original_release_date' AND $6 = 'desc' THEN title_group_original_release_date END DESC NULLS LAST,
                 CASE WHEN $1 = 'torrent_size' AND $6 = 'asc' THEN MIN(torrent_size) END ASC,
@@ -699,7 +753,8 @@ impl ConnectionPool {
             form.torrent_language.as_slice() as &[Language],
             form.torrent_snatched_by_id,
             tag_filter_jsonb.clone() as Option<serde_json::Value>,
-            form.user_id_bookmarks
+            form.user_id_bookmarks,
+            form.entity_id
         )
         .fetch_all(self.borrow())
         .await
@@ -724,9 +779,10 @@ impl ConnectionPool {
 
             AND (
                 $5::TEXT IS NULL OR
-                    tgh.title_group_name ILIKE '%' || $5 || '%' ESCAPE '\' OR
-                    tgh.title_group_series_name ILIKE '%' || $5 || '%' ESCAPE '\' OR
-                    EXISTS (SELECT 1 FROM unnest(tgh.title_group_name_aliases) alias WHERE alias ILIKE '%' || $5 || '%')
+                    tgh.title_group_search_vector @@ websearch_to_tsquery('simple', f_unaccent($5)) OR
+                    f_unaccent($5) <% f_unaccent(tgh.title_group_name) OR
+                    f_unaccent($5) <% f_unaccent_array(tgh.title_group_name_aliases) OR
+                    f_unaccent($5) <% f_unaccent(tgh.title_group_series_name)
             )
             AND (
                 $6::TEXT IS NULL
@@ -771,6 +827,10 @@ impl ConnectionPool {
                     AND tgb.user_id = $17
                 )
             )
+            AND (
+                $18::BIGINT IS NULL OR
+                EXISTS (SELECT 1 FROM affiliated_entities ae WHERE ae.title_group_id = tgh.title_group_id AND ae.entity_id = $18)
+            )
             "#,
             form.torrent_staff_checked,
             form.torrent_reported,
@@ -788,7 +848,8 @@ impl ConnectionPool {
             form.series_id,
             form.torrent_snatched_by_id,
             tag_filter_jsonb as Option<serde_json::Value>,
-            form.user_id_bookmarks
+            form.user_id_bookmarks,
+            form.entity_id
         )
         .fetch_optional(self.borrow())
         .await
@@ -1058,6 +1119,39 @@ impl ConnectionPool {
         })
     }
 
+    /// Title group, artist and series names close to `name`, used as
+    /// "did you mean" suggestions when a search returns nothing.
+    pub async fn find_search_suggestions(&self, name: &str, limit: i64) -> Result<Vec<String>> {
+        let suggestions = sqlx::query_scalar!(
+            r#"
+            SELECT suggestion AS "suggestion!"
+            FROM (
+                SELECT name AS suggestion, similarity(f_unaccent(name), f_unaccent($1)) AS score
+                FROM title_groups
+                WHERE f_unaccent(name) % f_unaccent($1)
+                UNION ALL
+                SELECT name, similarity(f_unaccent(name), f_unaccent($1))
+                FROM artists
+                WHERE f_unaccent(name) % f_unaccent($1)
+                UNION ALL
+                SELECT name, similarity(f_unaccent(name), f_unaccent($1))
+                FROM series
+                WHERE f_unaccent(name) % f_unaccent($1)
+            ) candidates
+            GROUP BY suggestion
+            ORDER BY MAX(score) DESC, suggestion ASC
+            LIMIT $2
+            "#,
+            name,
+            limit
+        )
+        .fetch_all(self.borrow())
+        .await
+        .map_err(|error| Error::ErrorSearchingForTorrents(error.to_string()))?;
+
+        Ok(suggestions)
+    }
+
     pub async fn find_top_torrents(&self, _period: &str, _amount: i64) -> Result<Value> {
         Ok(Value::Array(vec![]))
         // let search_results = sqlx::query!(
@@ -1162,6 +1256,9 @@ impl ConnectionPool {
         .execute(&mut *tx)
         .await?;
 
+        // sent as a whole so that the tracker can tell why to the users still announcing it
+        Self::queue_torrents_for_tracker_tx(&mut tx, &[torrent_to_delete.id]).await?;
+
         tx.commit().await?;
 
         if !notified_user_ids.is_empty() {
@@ -1170,6 +1267,12 @@ impl ConnectionPool {
             });
         }
 
+        self.enqueue_webhooks(WebhookTrigger::TorrentDeleted {
+            torrent_id: torrent_to_delete.id,
+            deletion_reason: torrent_to_delete.deletion_reason,
+        })
+        .await;
+
         Ok(())
     }
 
@@ -1231,7 +1334,8 @@ impl ConnectionPool {
                 p.downloaded,
                 p.left,
                 p.seeder,
-                p.agent
+                p.agent,
+                p.connectable
             FROM peers p
             JOIN users u ON p.user_id = u.id
             WHERE p.torrent_id = $1 AND p.active = true
@@ -1260,6 +1364,7 @@ impl ConnectionPool {
                     left: row.left,
                     seeder: row.seeder,
                     agent: row.agent,
+                    connectable: row.connectable,
                 }
             })
             .collect();
@@ -1273,6 +1378,10 @@ impl ConnectionPool {
         upload_factor: i16,
         download_factor: i16,
     ) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
         sqlx::query!(
             r#"
             UPDATE torrents
@@ -1286,9 +1395,23 @@ impl ConnectionPool {
             upload_factor,
             download_factor
         )
-        .execute(self.borrow())
+        .execute(&mut *tx)
+        .await?;
+
+        Self::queue_tracker_mutation_tx(
+            &mut tx,
+            TrackerMutation::UpdateTorrentFactors {
+                id: torrent_id as u32,
+                factors: APIUpdateTorrentFactors {
+                    upload_factor,
+                    download_factor,
+                },
+            },
+        )
         .await?;
 
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -9,6 +9,7 @@ use crate::{
             EditedTorrentRequest, TorrentRequest, TorrentRequestWithTitleGroupLite,
             UserCreatedTorrentRequest,
         },
+        webhook::WebhookTrigger,
     },
 };
 use arcadia_common::error::{Error, Result};
@@ -322,6 +323,9 @@ impl ConnectionPool {
         )
         .await?;
 
+        self.enqueue_webhooks(WebhookTrigger::TorrentRequestFilled { torrent_request_id })
+            .await;
+
         Ok(torrent_upload_info.created_by_id == current_user_id)
     }
 
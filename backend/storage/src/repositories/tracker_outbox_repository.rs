@@ -0,0 +1,506 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::tracker_outbox::{PendingTrackerMutation, TrackerState, TrackerStateBuckets},
+};
+use arcadia_common::{error::Result, services::tracker_client::TrackerMutation};
+use arcadia_shared::tracker::models::{
+    env::ArcadiaSettingsForTracker,
+    ip_ban::APIInsertIpBan,
+    personal_freeleech::APIInsertPersonalFreeleech,
+    state_digest::{StateDigest, DIGEST_BUCKETS},
+    torrent::{APIInsertTorrent, InfoHash, TorrentDeletionReason, TorrentScope},
+    user::APIInsertUser,
+};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use sqlx::{types::Json, PgExecutor, Postgres, Transaction};
+use std::borrow::Borrow;
+
+/// A row can't be upserted twice by the same statement, the last mutation of
+/// a key wins
+async fn insert_tracker_mutations<'c>(
+    executor: impl PgExecutor<'c>,
+    mutations: Vec<TrackerMutation>,
+) -> Result<()> {
+    if mutations.is_empty() {
+        return Ok(());
+    }
+    let mutations: IndexMap<String, TrackerMutation> = mutations
+        .into_iter()
+        .map(|mutation| (mutation.key(), mutation))
+        .collect();
+    let (keys, mutations): (Vec<String>, Vec<serde_json::Value>) = mutations
+        .into_iter()
+        .map(|(key, mutation)| {
+            (
+                key,
+                serde_json::to_value(mutation).expect("tracker mutations serialize"),
+            )
+        })
+        .unzip();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tracker_outbox (key, mutation)
+        SELECT * FROM UNNEST($1::TEXT[], $2::JSONB[])
+        ON CONFLICT (key) DO UPDATE
+        SET mutation = EXCLUDED.mutation, revision = tracker_outbox.revision + 1,
+            queued_at = NOW(), attempts = 0, next_attempt_at = NOW(), last_error = NULL
+        "#,
+        &keys,
+        &mutations
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+impl ConnectionPool {
+    /// Queues the mutations for the periodic task that sends them to the tracker,
+    /// in the transaction of the change they reflect so that neither is kept
+    /// without the other
+    pub async fn queue_tracker_mutations_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        mutations: Vec<TrackerMutation>,
+    ) -> Result<()> {
+        insert_tracker_mutations(&mut **tx, mutations).await
+    }
+
+    pub async fn queue_tracker_mutation_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        mutation: TrackerMutation,
+    ) -> Result<()> {
+        Self::queue_tracker_mutations_tx(tx, vec![mutation]).await
+    }
+
+    /// For mutations that don't go with a change of the database, e.g. the
+    /// ones repairing the tracker's state
+    pub async fn queue_tracker_mutations(&self, mutations: Vec<TrackerMutation>) -> Result<()> {
+        insert_tracker_mutations(self.borrow(), mutations).await
+    }
+
+    /// Takes the due mutations, oldest first, and pushes their next attempt
+    /// forward so that they are not picked again while being sent.
+    pub async fn claim_pending_tracker_mutations(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<PendingTrackerMutation>> {
+        let mutations = sqlx::query_as!(
+            PendingTrackerMutation,
+            r#"
+            WITH claimed AS (
+                UPDATE tracker_outbox
+                SET next_attempt_at = NOW() + INTERVAL '5 minutes'
+                WHERE id IN (
+                    SELECT id
+                    FROM tracker_outbox
+                    WHERE next_attempt_at <= NOW()
+                    ORDER BY id
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, revision, mutation, attempts
+            )
+            SELECT
+                id AS "id!",
+                revision AS "revision!",
+                mutation AS "mutation!: Json<TrackerMutation>",
+                attempts AS "attempts!"
+            FROM claimed
+            ORDER BY id
+            "#,
+            limit
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(mutations)
+    }
+
+    /// Unless it was replaced by a newer mutation in the meantime
+    pub async fn mark_tracker_mutation_sent(&self, id: i64, revision: i64) -> Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM tracker_outbox
+            WHERE id = $1 AND revision = $2
+            "#,
+            id,
+            revision
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+
+    /// The mutation is given up on when there is no `next_attempt_at`
+    pub async fn mark_tracker_mutation_attempt_failed(
+        &self,
+        id: i64,
+        revision: i64,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tracker_outbox
+            SET attempts = attempts + 1, last_error = $3, next_attempt_at = $4
+            WHERE id = $1 AND revision = $2
+            "#,
+            id,
+            revision,
+            error,
+            next_attempt_at
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Queues the users for the tracker as they are in the transaction
+    pub async fn queue_users_for_tracker_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        user_ids: &[i32],
+    ) -> Result<()> {
+        let users = fetch_tracker_users(
+            &mut **tx,
+            TrackerRows {
+                ids: Some(user_ids),
+                ..Default::default()
+            },
+        )
+        .await?;
+        Self::queue_tracker_mutations_tx(
+            tx,
+            users.into_iter().map(TrackerMutation::UpsertUser).collect(),
+        )
+        .await
+    }
+
+    /// Queues the torrents for the tracker as they are in the transaction
+    pub async fn queue_torrents_for_tracker_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        torrent_ids: &[i32],
+    ) -> Result<()> {
+        let torrents = fetch_tracker_torrents(
+            &mut **tx,
+            TrackerRows {
+                ids: Some(torrent_ids),
+                ..Default::default()
+            },
+        )
+        .await?;
+        Self::queue_tracker_mutations_tx(
+            tx,
+            torrents
+                .into_iter()
+                .map(TrackerMutation::UpsertTorrent)
+                .collect(),
+        )
+        .await
+    }
+
+    /// Computed the same way as the tracker's, see its state digest endpoint.
+    /// The users and torrents are read by chunks, they aren't kept around.
+    pub async fn find_tracker_state_digest(&self) -> Result<StateDigest> {
+        let mut digest = StateDigest::default();
+
+        let mut after_id = 0;
+        loop {
+            let users = fetch_tracker_users(
+                self.borrow(),
+                TrackerRows {
+                    after_id,
+                    limit: Some(TRACKER_STATE_CHUNK_SIZE),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            let Some(last_user) = users.last() else {
+                break;
+            };
+            after_id = last_user.id as i32;
+            for user in &users {
+                digest.add_user(
+                    user.id,
+                    (!user.banned).then_some(&user.passkey),
+                    user.max_snatches_per_day,
+                    user.max_leeching,
+                );
+            }
+        }
+
+        let mut after_id = 0;
+        loop {
+            let torrents = fetch_tracker_torrents(
+                self.borrow(),
+                TrackerRows {
+                    after_id,
+                    limit: Some(TRACKER_STATE_CHUNK_SIZE),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            let Some(last_torrent) = torrents.last() else {
+                break;
+            };
+            after_id = last_torrent.id as i32;
+            for torrent in &torrents {
+                digest.add_torrent(
+                    torrent.id,
+                    std::iter::once(&torrent.info_hash).chain(&torrent.info_hash_v2),
+                    torrent.is_deleted,
+                    torrent.deletion_reason,
+                    torrent.replacement_torrent_id,
+                    torrent.upload_factor,
+                    torrent.download_factor,
+                    &torrent.scope,
+                );
+            }
+        }
+
+        let state = self
+            .find_tracker_state(&TrackerStateBuckets::default())
+            .await?;
+        for personal_freeleech in &state.personal_freeleeches {
+            digest.add_personal_freeleech(
+                personal_freeleech.user_id,
+                personal_freeleech.torrent_id,
+                personal_freeleech.expires_at,
+            );
+        }
+        for ip_ban in &state.ip_bans {
+            digest.add_ip_ban(ip_ban.id, ip_ban.ip_range, ip_ban.expires_at);
+        }
+        for promotion_event in &state.promotion_events {
+            digest.add_promotion_event(
+                promotion_event.id,
+                promotion_event.starts_at,
+                promotion_event.ends_at,
+                promotion_event.upload_factor,
+                promotion_event.download_factor,
+                &promotion_event.scope,
+            );
+        }
+        digest.set_settings(&state.settings);
+
+        Ok(digest)
+    }
+
+    /// What the tracker should hold in memory. The users and torrents are
+    /// only read for the given buckets, the other entries are few enough to be
+    /// read as a whole.
+    pub async fn find_tracker_state(&self, buckets: &TrackerStateBuckets) -> Result<TrackerState> {
+        let users = if buckets.users.is_empty() {
+            Vec::new()
+        } else {
+            fetch_tracker_users(
+                self.borrow(),
+                TrackerRows {
+                    buckets: Some(&buckets.users),
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        let torrents = if buckets.torrents.is_empty() {
+            Vec::new()
+        } else {
+            fetch_tracker_torrents(
+                self.borrow(),
+                TrackerRows {
+                    buckets: Some(&buckets.torrents),
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        let personal_freeleeches = sqlx::query!(
+            r#"
+            SELECT user_id, torrent_id, expires_at
+            FROM personal_freeleeches
+            WHERE expires_at > NOW()
+            "#
+        )
+        .fetch_all(self.borrow())
+        .await?
+        .into_iter()
+        .map(|personal_freeleech| APIInsertPersonalFreeleech {
+            user_id: personal_freeleech.user_id as u32,
+            torrent_id: personal_freeleech.torrent_id as u32,
+            expires_at: personal_freeleech.expires_at,
+        })
+        .collect();
+
+        let ip_bans = sqlx::query_as!(
+            APIInsertIpBan,
+            r#"
+            SELECT id, ip_range, expires_at
+            FROM ip_bans
+            WHERE expires_at IS NULL OR expires_at > NOW()
+            "#
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        let promotion_events = self.find_tracker_promotion_events().await?;
+
+        let settings = sqlx::query_as!(
+            ArcadiaSettingsForTracker,
+            r#"
+            SELECT
+                global_upload_factor,
+                global_download_factor,
+                snatched_torrent_bonus_points_transferred_to AS "snatched_torrent_bonus_points_transferred_to: _",
+                cheat_flag_withhold_credit_min_severity AS "cheat_flag_withhold_credit_min_severity: _"
+            FROM arcadia_settings
+            LIMIT 1
+            "#
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(TrackerState {
+            users,
+            torrents,
+            personal_freeleeches,
+            ip_bans,
+            promotion_events,
+            settings,
+        })
+    }
+}
+
+/// Users and torrents read per query when computing the digest of the whole state
+const TRACKER_STATE_CHUNK_SIZE: i64 = 10_000;
+
+/// Which of the users or torrents to read for the tracker, all of them by
+/// default
+#[derive(Default)]
+struct TrackerRows<'a> {
+    ids: Option<&'a [i32]>,
+    /// see [`arcadia_shared::tracker::models::state_digest::bucket_of`]
+    buckets: Option<&'a [i32]>,
+    /// to read them by chunks, they are ordered by id
+    after_id: i32,
+    limit: Option<i64>,
+}
+
+/// The users as the tracker holds them
+async fn fetch_tracker_users<'c>(
+    executor: impl PgExecutor<'c>,
+    rows: TrackerRows<'_>,
+) -> Result<Vec<APIInsertUser>> {
+    let users = sqlx::query!(
+        r#"
+        SELECT id, passkey, max_snatches_per_day, banned,
+            user_max_leeching(class_name, uploaded, downloaded) AS max_leeching
+        FROM users
+        WHERE ($1::INT[] IS NULL OR id = ANY($1))
+          AND ($2::INT[] IS NULL OR id % $3 = ANY($2))
+          AND id > $4
+        ORDER BY id
+        LIMIT $5
+        "#,
+        rows.ids,
+        rows.buckets,
+        DIGEST_BUCKETS as i32,
+        rows.after_id,
+        rows.limit
+    )
+    .fetch_all(executor)
+    .await?
+    .into_iter()
+    .filter_map(|user| match user.passkey.parse() {
+        Ok(passkey) => Some(APIInsertUser {
+            id: user.id as u32,
+            passkey,
+            max_snatches_per_day: user.max_snatches_per_day.map(|x| x as u32),
+            max_leeching: user.max_leeching.map(|x| x as u32),
+            banned: user.banned,
+        }),
+        Err(error) => {
+            log::warn!("User {} has an invalid passkey: {error}", user.id);
+            None
+        }
+    })
+    .collect();
+
+    Ok(users)
+}
+
+/// The torrents as the tracker holds them
+async fn fetch_tracker_torrents<'c>(
+    executor: impl PgExecutor<'c>,
+    rows: TrackerRows<'_>,
+) -> Result<Vec<APIInsertTorrent>> {
+    let torrents = sqlx::query!(
+        r#"
+        SELECT
+            t.id AS "id!",
+            t.info_hash AS "info_hash!: InfoHash",
+            substring(t.info_hash_v2 FROM 1 FOR 20) AS "info_hash_v2: InfoHash",
+            t.deleted_at IS NOT NULL AS "is_deleted!",
+            td.deletion_reason AS "deletion_reason?: TorrentDeletionReason",
+            td.replacement_torrent_id AS "replacement_torrent_id?",
+            t.seeders AS "seeders!",
+            t.leechers AS "leechers!",
+            t.times_completed AS "times_completed!",
+            t.upload_factor AS "upload_factor!",
+            t.download_factor AS "download_factor!",
+            tg.content_type::TEXT AS "content_type!",
+            tg.category::TEXT AS category,
+            ARRAY(
+                SELECT tgt.name
+                FROM title_group_applied_tags tat
+                JOIN title_group_tags tgt ON tgt.id = tat.tag_id
+                WHERE tat.title_group_id = tg.id AND tgt.deleted_at IS NULL
+                ORDER BY tgt.name
+            )::TEXT[] AS "tags!",
+            t.size AS "size!"
+        FROM torrents t
+        JOIN edition_groups eg ON eg.id = t.edition_group_id
+        JOIN title_groups tg ON tg.id = eg.title_group_id
+        LEFT JOIN torrent_deletions td ON td.torrent_id = t.id
+        WHERE ($1::INT[] IS NULL OR t.id = ANY($1))
+          AND ($2::INT[] IS NULL OR t.id % $3 = ANY($2))
+          AND t.id > $4
+        ORDER BY t.id
+        LIMIT $5
+        "#,
+        rows.ids,
+        rows.buckets,
+        DIGEST_BUCKETS as i32,
+        rows.after_id,
+        rows.limit
+    )
+    .fetch_all(executor)
+    .await?
+    .into_iter()
+    .map(|torrent| APIInsertTorrent {
+        id: torrent.id as u32,
+        info_hash: torrent.info_hash,
+        info_hash_v2: torrent.info_hash_v2,
+        is_deleted: torrent.is_deleted,
+        deletion_reason: torrent.deletion_reason,
+        replacement_torrent_id: torrent.replacement_torrent_id.map(|id| id as u32),
+        seeders: torrent.seeders as u32,
+        leechers: torrent.leechers as u32,
+        times_completed: torrent.times_completed as u32,
+        upload_factor: torrent.upload_factor,
+        download_factor: torrent.download_factor,
+        scope: TorrentScope {
+            content_type: torrent.content_type,
+            category: torrent.category,
+            tags: torrent.tags,
+            size: torrent.size,
+        },
+    })
+    .collect();
+
+    Ok(torrents)
+}
@@ -0,0 +1,206 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{two_factor::UserTotp, user::UserPermission},
+};
+use arcadia_common::error::Result;
+use sqlx::PgPool;
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    pub async fn find_user_totp(&self, user_id: i32) -> Result<Option<UserTotp>> {
+        let user_totp = sqlx::query_as!(
+            UserTotp,
+            r#"
+            SELECT user_id, secret, created_at, enabled_at
+            FROM user_totps
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(self.borrow())
+        .await?;
+
+        Ok(user_totp)
+    }
+
+    pub async fn is_two_factor_enabled(&self, user_id: i32) -> Result<bool> {
+        let enabled = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM user_totps WHERE user_id = $1 AND enabled_at IS NOT NULL
+            ) AS "exists!"
+            "#,
+            user_id
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(enabled)
+    }
+
+    /// Records that a code of the given time step was accepted. Returns false if a code of this
+    /// step or a later one was already used, in which case the code is a replay.
+    pub async fn use_totp_step(&self, user_id: i32, step: i64) -> Result<bool> {
+        let updated = sqlx::query_scalar!(
+            r#"
+            UPDATE user_totps
+            SET last_used_step = $2
+            WHERE user_id = $1
+              AND (last_used_step IS NULL OR last_used_step < $2)
+            RETURNING user_id
+            "#,
+            user_id,
+            step
+        )
+        .fetch_optional(self.borrow())
+        .await?;
+
+        Ok(updated.is_some())
+    }
+
+    /// Stores a new secret waiting to be confirmed, replacing any previous
+    /// unconfirmed one. An enabled secret is never replaced.
+    pub async fn create_pending_user_totp(&self, user_id: i32, secret: &str) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_totps (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, created_at = NOW()
+            WHERE user_totps.enabled_at IS NULL
+            "#,
+            user_id,
+            secret
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Enables the pending secret and replaces the user's recovery codes.
+    pub async fn enable_user_totp(
+        &self,
+        user_id: i32,
+        recovery_code_hashes: &[String],
+    ) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE user_totps
+            SET enabled_at = NOW()
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM user_totp_recovery_codes
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO user_totp_recovery_codes (user_id, code_hash)
+            SELECT $1, unnest($2::VARCHAR[])
+            "#,
+            user_id,
+            recovery_code_hashes
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn disable_user_totp(&self, user_id: i32) -> Result<()> {
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM user_totps
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM user_totp_recovery_codes
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Marks the recovery code as used. Returns `false` if it doesn't exist or was already used.
+    pub async fn use_totp_recovery_code(&self, user_id: i32, code_hash: &str) -> Result<bool> {
+        let used = sqlx::query_scalar!(
+            r#"
+            UPDATE user_totp_recovery_codes
+            SET used_at = NOW()
+            WHERE user_id = $1
+              AND code_hash = $2
+              AND used_at IS NULL
+            RETURNING id
+            "#,
+            user_id,
+            code_hash
+        )
+        .fetch_optional(self.borrow())
+        .await?;
+
+        Ok(used.is_some())
+    }
+
+    /// Whether the user is only missing `permission` because the arcadia settings
+    /// require two-factor authentication for it and the user didn't enable it.
+    pub async fn is_missing_required_two_factor(
+        &self,
+        user_id: i32,
+        permission: &UserPermission,
+    ) -> Result<bool> {
+        let missing = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users u, arcadia_settings s
+                WHERE u.id = $1
+                  AND $2 = ANY(u.permissions)
+                  AND $2 = ANY(s.two_factor_required_permissions)
+                  AND NOT EXISTS(
+                    SELECT 1 FROM user_totps t
+                    WHERE t.user_id = u.id AND t.enabled_at IS NOT NULL
+                  )
+            ) AS "exists!"
+            "#,
+            user_id,
+            permission as &UserPermission
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(missing)
+    }
+}
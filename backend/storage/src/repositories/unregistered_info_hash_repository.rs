@@ -0,0 +1,49 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{torrent::TorrentDeletionReason, unregistered_info_hash::UnregisteredInfoHash},
+};
+use arcadia_common::error::Result;
+use std::borrow::Borrow;
+
+impl ConnectionPool {
+    /// The info hashes logged by the tracker for the user, most recently
+    /// announced first. The ones that were registered since then are skipped.
+    pub async fn find_user_unregistered_info_hashes(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<UnregisteredInfoHash>> {
+        let unregistered_info_hashes = sqlx::query_as!(
+            UnregisteredInfoHash,
+            r#"
+            SELECT
+                encode(uih.info_hash, 'hex') AS "info_hash!",
+                uih.torrent_id,
+                td.title_group_name AS "title_group_name?",
+                td.deletion_reason AS "deletion_reason?: TorrentDeletionReason",
+                td.extra_information,
+                td.replacement_torrent_id,
+                td.deleted_at AS "deleted_at?",
+                uih.created_at,
+                uih.updated_at
+            FROM unregistered_info_hashes uih
+            LEFT JOIN torrent_deletions td ON td.torrent_id = uih.torrent_id
+            WHERE uih.user_id = $1
+            AND NOT EXISTS (
+                SELECT 1 FROM torrents t
+                WHERE (
+                    t.info_hash = uih.info_hash
+                    -- v2 clients announce the v2 info hash truncated to 20 bytes
+                    OR substring(t.info_hash_v2 FROM 1 FOR 20) = uih.info_hash
+                )
+                AND t.deleted_at IS NULL
+            )
+            ORDER BY uih.updated_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(unregistered_info_hashes)
+    }
+}
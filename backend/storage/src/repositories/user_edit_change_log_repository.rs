@@ -9,25 +9,19 @@ use crate::{
     },
 };
 use arcadia_common::error::{Error, Result};
+use sqlx::{PgExecutor, Postgres, Transaction};
 use std::borrow::Borrow;
 
 impl ConnectionPool {
     pub async fn create_user_edit_change_log(&self, log: &NewUserEditChangeLog) -> Result<()> {
-        sqlx::query!(
-            r#"
-            INSERT INTO user_edit_change_logs (item_type, item_id, edited_by_id, edits)
-            VALUES ($1, $2, $3, $4)
-            "#,
-            log.item_type,
-            log.item_id,
-            log.edited_by_id,
-            log.edits
-        )
-        .execute(self.borrow())
-        .await
-        .map_err(Error::CouldNotCreateUserEditChangeLog)?;
+        insert_user_edit_change_log(self.borrow(), log).await
+    }
 
-        Ok(())
+    pub async fn create_user_edit_change_log_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        log: &NewUserEditChangeLog,
+    ) -> Result<()> {
+        insert_user_edit_change_log(&mut **tx, log).await
     }
 
     pub async fn delete_user_edit_change_log(&self, id: i64) -> Result<()> {
@@ -138,3 +132,24 @@ impl ConnectionPool {
         })
     }
 }
+
+async fn insert_user_edit_change_log<'c>(
+    executor: impl PgExecutor<'c>,
+    log: &NewUserEditChangeLog,
+) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO user_edit_change_logs (item_type, item_id, edited_by_id, edits)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        log.item_type,
+        log.item_id,
+        log.edited_by_id,
+        log.edits
+    )
+    .execute(executor)
+    .await
+    .map_err(Error::CouldNotCreateUserEditChangeLog)?;
+
+    Ok(())
+}
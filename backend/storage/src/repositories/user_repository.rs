@@ -10,9 +10,12 @@ use crate::{
         },
     },
 };
-use arcadia_common::error::{Error, Result};
+use arcadia_common::{
+    error::{Error, Result},
+    services::tracker_client::TrackerMutation,
+};
 use arcadia_shared::tracker::models::user::APIUpdateUserMaxSnatchesPerDay;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use std::borrow::Borrow;
 
 impl ConnectionPool {
@@ -177,6 +180,22 @@ impl ConnectionPool {
             .begin()
             .await?;
 
+        let user_warning =
+            Self::create_user_warning_tx(&mut tx, current_user_id, user_warning, false).await?;
+
+        tx.commit().await?;
+
+        Ok(user_warning)
+    }
+
+    /// `hit_and_run` marks the warnings given for hit and runs, which are lifted
+    /// once the user is back under the threshold
+    pub async fn create_user_warning_tx(
+        tx: &mut Transaction<'_, Postgres>,
+        current_user_id: i32,
+        user_warning: &UserCreatedUserWarning,
+        hit_and_run: bool,
+    ) -> Result<UserWarning> {
         let _ = sqlx::query!(
             r#"
                 UPDATE users
@@ -190,28 +209,27 @@ impl ConnectionPool {
             user_warning.user_id,
             user_warning.ban
         )
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
         let user_warning = sqlx::query_as!(
             UserWarning,
             r#"
-                INSERT INTO user_warnings (user_id, expires_at, reason, created_by_id, ban)
-                VALUES ($1, $2, $3, $4, $5)
+                INSERT INTO user_warnings (user_id, expires_at, reason, created_by_id, ban, hit_and_run)
+                VALUES ($1, $2, $3, $4, $5, $6)
                 RETURNING id, user_id, created_at, expires_at, reason, created_by_id, ban, removed_at, removed_by_id
             "#,
             user_warning.user_id,
             user_warning.expires_at,
             user_warning.reason,
             current_user_id,
-            user_warning.ban
+            user_warning.ban,
+            hit_and_run
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await
         .map_err(Error::CouldNotCreateGift)?;
 
-        tx.commit().await?;
-
         Ok(user_warning)
     }
 
@@ -373,9 +391,11 @@ impl ConnectionPool {
                     required_title_group_comments,
                     required_seeding_size,
                     max_snatches_per_day,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
                 RETURNING
                     name,
                     new_permissions as "new_permissions: Vec<UserPermission>",
@@ -395,7 +415,9 @@ impl ConnectionPool {
                     required_forum_posts_in_unique_threads,
                     required_title_group_comments,
                     required_seeding_size,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
             "#,
             user_class.name,
             &user_class.new_permissions as &[UserPermission],
@@ -415,7 +437,9 @@ impl ConnectionPool {
             user_class.required_title_group_comments,
             user_class.required_seeding_size,
             user_class.max_snatches_per_day,
-            user_class.promotion_cost_bonus_points
+            user_class.promotion_cost_bonus_points,
+            user_class.max_leeching,
+            user_class.max_leeching_scaled_by_ratio
         )
         .fetch_one(self.borrow())
         .await
@@ -452,7 +476,9 @@ impl ConnectionPool {
                     required_forum_posts_in_unique_threads,
                     required_title_group_comments,
                     required_seeding_size,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
                 FROM user_classes
                 WHERE name = $1
             "#,
@@ -499,7 +525,9 @@ impl ConnectionPool {
                     uc.required_forum_posts_in_unique_threads,
                     uc.required_title_group_comments,
                     uc.required_seeding_size,
-                    uc.promotion_cost_bonus_points
+                    uc.promotion_cost_bonus_points,
+                    uc.max_leeching,
+                    uc.max_leeching_scaled_by_ratio
                 FROM user_classes uc
                 LEFT JOIN class_chain cc ON uc.name = cc.name
                 ORDER BY cc.position IS NULL, cc.position, uc.name
@@ -518,6 +546,10 @@ impl ConnectionPool {
         // First fetch old class to get old permissions for propagation
         let old_class = self.get_user_class_by_name(old_name).await?;
 
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
         // Update the class definition
         let updated_class = sqlx::query_as!(
             UserClass,
@@ -542,7 +574,9 @@ impl ConnectionPool {
                     required_title_group_comments = $17,
                     required_seeding_size = $18,
                     max_snatches_per_day = $19,
-                    promotion_cost_bonus_points = $20
+                    promotion_cost_bonus_points = $20,
+                    max_leeching = $21,
+                    max_leeching_scaled_by_ratio = $22
                 WHERE name = $1
                 RETURNING
                     name,
@@ -563,7 +597,9 @@ impl ConnectionPool {
                     required_title_group_comments,
                     required_seeding_size,
                     max_snatches_per_day,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
             "#,
             old_name,
             edited_class.name,
@@ -584,9 +620,11 @@ impl ConnectionPool {
             edited_class.required_title_group_comments,
             edited_class.required_seeding_size,
             edited_class.max_snatches_per_day,
-            edited_class.promotion_cost_bonus_points
+            edited_class.promotion_cost_bonus_points,
+            edited_class.max_leeching,
+            edited_class.max_leeching_scaled_by_ratio
         )
-        .fetch_one(self.borrow())
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             if let sqlx::Error::RowNotFound = e {
@@ -602,7 +640,8 @@ impl ConnectionPool {
         })?;
 
         // Propagate changes to all users with this class (only if something changed)
-        self.propagate_user_class_changes(
+        Self::propagate_user_class_changes_tx(
+            &mut tx,
             &edited_class.name,
             &old_class.new_permissions,
             &edited_class.new_permissions,
@@ -611,6 +650,27 @@ impl ConnectionPool {
         )
         .await?;
 
+        // The leeching slots are computed from the class of the users, they
+        // are sent again with the users. Ratio changes are caught up by the
+        // periodic reconciliation with the tracker.
+        if old_class.max_leeching != edited_class.max_leeching
+            || old_class.max_leeching_scaled_by_ratio != edited_class.max_leeching_scaled_by_ratio
+        {
+            let user_ids = sqlx::query_scalar!(
+                r#"
+                    SELECT id
+                    FROM users
+                    WHERE class_name = $1
+                "#,
+                edited_class.name
+            )
+            .fetch_all(&mut *tx)
+            .await?;
+            Self::queue_users_for_tracker_tx(&mut tx, &user_ids).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(updated_class)
     }
 
@@ -621,8 +681,8 @@ impl ConnectionPool {
     /// drop removed ones. max_snatches_per_day stays scoped to the edited class
     /// since each class owns its own limit. Tracker is notified for the direct
     /// class users when max_snatches_per_day changed.
-    async fn propagate_user_class_changes(
-        &self,
+    async fn propagate_user_class_changes_tx(
+        tx: &mut Transaction<'_, Postgres>,
         class_name: &str,
         old_permissions: &[UserPermission],
         new_permissions: &[UserPermission],
@@ -667,7 +727,7 @@ impl ConnectionPool {
                 old_permissions as &[UserPermission],
                 new_permissions as &[UserPermission],
             )
-            .execute(self.borrow())
+            .execute(&mut **tx)
             .await?;
         }
 
@@ -686,40 +746,22 @@ impl ConnectionPool {
             class_name,
             new_max_snatches_per_day,
         )
-        .fetch_all(self.borrow())
+        .fetch_all(&mut **tx)
         .await?;
 
-        let tracker_config = &self.tracker_config;
-
-        for user_id in affected_user_ids {
-            let mut url = tracker_config.url_internal.clone();
-            url.path_segments_mut()
-                .unwrap()
-                .push("api")
-                .push("users")
-                .push(&user_id.to_string())
-                .push("max-snatches-per-day");
-
-            let payload = APIUpdateUserMaxSnatchesPerDay {
-                id: user_id as u32,
-                max_snatches_per_day: new_max_snatches_per_day.map(|x| x as u32),
-            };
-
-            if let Err(e) = self
-                .internal_http_client
-                .put(url)
-                .header("x-api-key", tracker_config.api_key.clone())
-                .json(&payload)
-                .send()
-                .await
-            {
-                log::warn!(
-                    "Failed to update user {} snatch limit in tracker: {}",
-                    user_id,
-                    e
-                );
-            }
-        }
+        Self::queue_tracker_mutations_tx(
+            tx,
+            affected_user_ids
+                .into_iter()
+                .map(|user_id| {
+                    TrackerMutation::UpdateUserMaxSnatchesPerDay(APIUpdateUserMaxSnatchesPerDay {
+                        id: user_id as u32,
+                        max_snatches_per_day: new_max_snatches_per_day.map(|x| x as u32),
+                    })
+                })
+                .collect(),
+        )
+        .await?;
 
         Ok(())
     }
@@ -728,22 +770,27 @@ impl ConnectionPool {
         // Verify target class exists
         self.get_user_class_by_name(target_class_name).await?;
 
+        let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
+            .begin()
+            .await?;
+
         // Migrate all users from the deleted class to the target class
-        sqlx::query!(
+        let migrated_user_ids = sqlx::query_scalar!(
             r#"
                 UPDATE users
                 SET class_name = $2
                 WHERE class_name = $1
+                RETURNING id
             "#,
             name,
             target_class_name
         )
-        .execute(self.borrow())
+        .fetch_all(&mut *tx)
         .await?;
 
         // Delete the user class
         let result = sqlx::query!(r#"DELETE FROM user_classes WHERE name = $1"#, name)
-            .execute(self.borrow())
+            .execute(&mut *tx)
             .await
             .map_err(Error::CouldNotDeleteUserClass)?;
 
@@ -751,6 +798,11 @@ impl ConnectionPool {
             return Err(Error::UserClassNotFound(name.to_string()));
         }
 
+        // their leeching slots now come from the target class
+        Self::queue_users_for_tracker_tx(&mut tx, &migrated_user_ids).await?;
+
+        tx.commit().await?;
+
         Ok(())
     }
 
@@ -802,13 +854,7 @@ impl ConnectionPool {
     }
 
     /// Changes the user's class and updates their permissions accordingly.
-    /// Set `notify_tracker` to false in tests to skip the HTTP call to the tracker.
-    pub async fn change_user_class(
-        &self,
-        user_id: i32,
-        new_class_name: &str,
-        notify_tracker: bool,
-    ) -> Result<()> {
+    pub async fn change_user_class(&self, user_id: i32, new_class_name: &str) -> Result<()> {
         let mut tx = <ConnectionPool as Borrow<PgPool>>::borrow(self)
             .begin()
             .await?;
@@ -865,7 +911,9 @@ impl ConnectionPool {
                     required_forum_posts_in_unique_threads,
                     required_title_group_comments,
                     required_seeding_size,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
                 FROM user_classes
                 WHERE name = $1
             "#,
@@ -898,7 +946,9 @@ impl ConnectionPool {
                     required_forum_posts_in_unique_threads,
                     required_title_group_comments,
                     required_seeding_size,
-                    promotion_cost_bonus_points
+                    promotion_cost_bonus_points,
+                    max_leeching,
+                    max_leeching_scaled_by_ratio
                 FROM user_classes
                 WHERE name = $1
             "#,
@@ -951,35 +1001,10 @@ impl ConnectionPool {
         .execute(&mut *tx)
         .await?;
 
-        tx.commit().await?;
+        // the leeching slots come with the class as well
+        Self::queue_users_for_tracker_tx(&mut tx, &[user_id]).await?;
 
-        // Notify tracker about the new max_snatches_per_day
-        if notify_tracker {
-            let tracker_config = &self.tracker_config;
-            let mut url = tracker_config.url_internal.clone();
-            url.path_segments_mut()
-                .unwrap()
-                .push("api")
-                .push("users")
-                .push(&user_id.to_string())
-                .push("max-snatches-per-day");
-
-            let payload = APIUpdateUserMaxSnatchesPerDay {
-                id: user_id as u32,
-                max_snatches_per_day: new_class.max_snatches_per_day.map(|x| x as u32),
-            };
-
-            if let Err(e) = self
-                .internal_http_client
-                .put(url)
-                .header("x-api-key", tracker_config.api_key.clone())
-                .json(&payload)
-                .send()
-                .await
-            {
-                log::warn!("Failed to update user snatch limit in tracker: {}", e);
-            }
-        }
+        tx.commit().await?;
 
         Ok(())
     }
@@ -1016,7 +1041,8 @@ impl ConnectionPool {
                 MAX(updated_at)::timestamptz AS "last_seen_at!",
                 SUM(uploaded)::bigint AS "real_uploaded!",
                 SUM(downloaded)::bigint AS "real_downloaded!",
-                agent
+                agent,
+                BOOL_OR(connectable) AS "connectable!"
             FROM peers
             WHERE user_id = $1
             GROUP BY agent, ip, port
@@ -1240,7 +1266,9 @@ impl ConnectionPool {
                 required_forum_posts_in_unique_threads,
                 required_title_group_comments,
                 required_seeding_size,
-                promotion_cost_bonus_points
+                promotion_cost_bonus_points,
+                max_leeching,
+                max_leeching_scaled_by_ratio
             FROM user_classes
             WHERE previous_user_class = $1
             "#,
@@ -1331,6 +1359,6 @@ impl ConnectionPool {
 
         tx.commit().await?;
 
-        self.change_user_class(user_id, new_class_name, true).await
+        self.change_user_class(user_id, new_class_name).await
     }
 }
@@ -0,0 +1,429 @@
+use crate::{
+    connection_pool::ConnectionPool,
+    models::{
+        common::PaginatedResults,
+        webhook::{
+            EditedWebhook, PendingWebhookDelivery, SearchWebhookDeliveriesQuery,
+            UserCreatedWebhook, Webhook, WebhookDeletedTorrent, WebhookDelivery,
+            WebhookDeliveryStatus, WebhookEvent, WebhookForumPost, WebhookPayload, WebhookTorrent,
+            WebhookTorrentRequestFill, WebhookTrigger,
+        },
+    },
+    utils::is_global_ip,
+};
+use arcadia_common::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use std::borrow::Borrow;
+
+async fn validate_webhook(
+    name: &str,
+    url: &str,
+    secret: Option<&str>,
+    events: &[WebhookEvent],
+) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(Error::InvalidWebhook("name cannot be empty".into()));
+    }
+    let url = match url::Url::parse(url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url,
+        _ => {
+            return Err(Error::InvalidWebhook(
+                "url must be a valid http(s) url".into(),
+            ))
+        }
+    };
+    if !targets_public_host(&url).await {
+        return Err(Error::InvalidWebhook(
+            "url must point to a public host".into(),
+        ));
+    }
+    if secret.is_some_and(|secret| secret.trim().is_empty()) {
+        return Err(Error::InvalidWebhook("secret cannot be empty".into()));
+    }
+    if events.is_empty() {
+        return Err(Error::InvalidWebhook(
+            "at least one event must be selected".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Hosts that do not resolve yet are accepted, the deliveries are checked again when sent
+async fn targets_public_host(url: &url::Url) -> bool {
+    let port = url.port_or_known_default().unwrap_or(80);
+    match url.host() {
+        Some(url::Host::Ipv4(ip)) => is_global_ip(ip.into()),
+        Some(url::Host::Ipv6(ip)) => is_global_ip(ip.into()),
+        Some(url::Host::Domain(domain)) => match tokio::net::lookup_host((domain, port)).await {
+            Ok(addresses) => addresses
+                .into_iter()
+                .all(|address| is_global_ip(address.ip())),
+            Err(_) => true,
+        },
+        None => false,
+    }
+}
+
+impl ConnectionPool {
+    pub async fn create_webhook(
+        &self,
+        webhook: &UserCreatedWebhook,
+        current_user_id: i32,
+    ) -> Result<Webhook> {
+        validate_webhook(
+            &webhook.name,
+            &webhook.url,
+            Some(&webhook.secret),
+            &webhook.events,
+        )
+        .await?;
+
+        let created_webhook = sqlx::query_as!(
+            Webhook,
+            r#"
+                INSERT INTO webhooks (created_by_id, name, url, secret, events, forum_sub_category_ids, enabled)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id, created_at, created_by_id, name, url,
+                    events AS "events: Vec<WebhookEvent>", forum_sub_category_ids, enabled
+            "#,
+            current_user_id,
+            webhook.name.trim(),
+            webhook.url,
+            webhook.secret,
+            &webhook.events as &[WebhookEvent],
+            &webhook.forum_sub_category_ids,
+            webhook.enabled
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(created_webhook)
+    }
+
+    pub async fn find_webhooks(&self) -> Result<Vec<Webhook>> {
+        let webhooks = sqlx::query_as!(
+            Webhook,
+            r#"
+                SELECT id, created_at, created_by_id, name, url,
+                    events AS "events: Vec<WebhookEvent>", forum_sub_category_ids, enabled
+                FROM webhooks
+                ORDER BY id
+            "#
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(webhooks)
+    }
+
+    pub async fn edit_webhook(&self, webhook_id: i32, webhook: &EditedWebhook) -> Result<Webhook> {
+        validate_webhook(
+            &webhook.name,
+            &webhook.url,
+            webhook.secret.as_deref(),
+            &webhook.events,
+        )
+        .await?;
+
+        let edited_webhook = sqlx::query_as!(
+            Webhook,
+            r#"
+                UPDATE webhooks
+                SET name = $2, url = $3, secret = COALESCE($4, secret), events = $5,
+                    forum_sub_category_ids = $6, enabled = $7
+                WHERE id = $1
+                RETURNING id, created_at, created_by_id, name, url,
+                    events AS "events: Vec<WebhookEvent>", forum_sub_category_ids, enabled
+            "#,
+            webhook_id,
+            webhook.name.trim(),
+            webhook.url,
+            webhook.secret,
+            &webhook.events as &[WebhookEvent],
+            &webhook.forum_sub_category_ids,
+            webhook.enabled
+        )
+        .fetch_optional(self.borrow())
+        .await?
+        .ok_or(Error::WebhookNotFound)?;
+
+        Ok(edited_webhook)
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: i32) -> Result<()> {
+        let result = sqlx::query!(
+            r#"
+                DELETE FROM webhooks
+                WHERE id = $1
+            "#,
+            webhook_id
+        )
+        .execute(self.borrow())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::WebhookNotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn find_webhook_deliveries(
+        &self,
+        query: SearchWebhookDeliveriesQuery,
+    ) -> Result<PaginatedResults<WebhookDelivery>> {
+        let total_items: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*)
+            FROM webhook_deliveries
+            WHERE ($1::INT IS NULL OR webhook_id = $1)
+              AND ($2::webhook_delivery_status_enum IS NULL OR status = $2)
+            "#,
+            query.webhook_id,
+            query.status as Option<WebhookDeliveryStatus>
+        )
+        .fetch_one(self.borrow())
+        .await?
+        .unwrap_or(0);
+
+        let results = sqlx::query_as!(
+            WebhookDelivery,
+            r#"
+            SELECT id, webhook_id, created_at, event AS "event: WebhookEvent", payload,
+                status AS "status: WebhookDeliveryStatus", attempts, next_attempt_at,
+                last_attempt_at, response_status, error
+            FROM webhook_deliveries
+            WHERE ($1::INT IS NULL OR webhook_id = $1)
+              AND ($2::webhook_delivery_status_enum IS NULL OR status = $2)
+            ORDER BY created_at DESC, id DESC
+            OFFSET ($3 - 1) * LEAST($4, 100)
+            LIMIT LEAST($4, 100)
+            "#,
+            query.webhook_id,
+            query.status as Option<WebhookDeliveryStatus>,
+            query.page as i32,
+            query.page_size as i32
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(PaginatedResults {
+            results,
+            total_items,
+            page: query.page as u32,
+            page_size: query.page_size.min(100) as u32,
+        })
+    }
+
+    /// Queues a delivery for every enabled webhook subscribed to the event.
+    /// Errors are only logged, a webhook must never fail the action that triggered it.
+    pub async fn enqueue_webhooks(&self, trigger: WebhookTrigger) {
+        if let Err(error) = self.try_enqueue_webhooks(trigger).await {
+            log::warn!("Could not queue webhook deliveries for {trigger:?}: {error}");
+        }
+    }
+
+    async fn try_enqueue_webhooks(&self, trigger: WebhookTrigger) -> Result<u64> {
+        let (payload, forum_sub_category_id) = match trigger {
+            WebhookTrigger::TorrentUploaded { torrent_id } => (
+                WebhookPayload::TorrentUploaded(self.find_webhook_torrent(torrent_id).await?),
+                None,
+            ),
+            WebhookTrigger::TorrentDeleted {
+                torrent_id,
+                deletion_reason,
+            } => (
+                WebhookPayload::TorrentDeleted(WebhookDeletedTorrent {
+                    torrent: self.find_webhook_torrent(torrent_id).await?,
+                    deletion_reason,
+                }),
+                None,
+            ),
+            WebhookTrigger::TorrentRequestFilled { torrent_request_id } => {
+                let fill = sqlx::query_as!(
+                    WebhookTorrentRequestFill,
+                    r#"
+                    SELECT
+                        tr.id AS torrent_request_id,
+                        tg.id AS title_group_id,
+                        tg.name AS title_group_name,
+                        tr.filled_by_torrent_id AS "torrent_id!",
+                        u.username AS filled_by
+                    FROM torrent_requests tr
+                    JOIN title_groups tg ON tg.id = tr.title_group_id
+                    JOIN users u ON u.id = tr.filled_by_user_id
+                    WHERE tr.id = $1
+                    "#,
+                    torrent_request_id
+                )
+                .fetch_one(self.borrow())
+                .await?;
+                (WebhookPayload::TorrentRequestFilled(fill), None)
+            }
+            WebhookTrigger::ForumPost { forum_post_id } => {
+                let forum_post = sqlx::query_as!(
+                    WebhookForumPost,
+                    r#"
+                    SELECT
+                        fp.id AS forum_post_id,
+                        fp.forum_thread_id,
+                        ft.name AS forum_thread_name,
+                        ft.forum_sub_category_id,
+                        fsc.name AS forum_sub_category_name,
+                        u.username AS author,
+                        fp.content,
+                        fp.created_at
+                    FROM forum_posts fp
+                    JOIN forum_threads ft ON ft.id = fp.forum_thread_id
+                    JOIN forum_sub_categories fsc ON fsc.id = ft.forum_sub_category_id
+                    JOIN users u ON u.id = fp.created_by_id
+                    WHERE fp.id = $1
+                    "#,
+                    forum_post_id
+                )
+                .fetch_one(self.borrow())
+                .await?;
+                let forum_sub_category_id = forum_post.forum_sub_category_id;
+                (
+                    WebhookPayload::ForumPost(forum_post),
+                    Some(forum_sub_category_id),
+                )
+            }
+        };
+
+        let payload_json = serde_json::to_value(&payload).expect("webhook payloads serialize");
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT id, $1, $2
+            FROM webhooks
+            WHERE enabled
+              AND $1 = ANY(events)
+              AND ($3::INT IS NULL OR $3 = ANY(forum_sub_category_ids))
+            "#,
+            payload.event() as WebhookEvent,
+            payload_json,
+            forum_sub_category_id
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_webhook_torrent(&self, torrent_id: i32) -> Result<WebhookTorrent> {
+        let torrent = sqlx::query_as!(
+            WebhookTorrent,
+            r#"
+            SELECT
+                t.id AS torrent_id,
+                tg.id AS title_group_id,
+                tg.name AS title_group_name,
+                t.release_name,
+                t.size,
+                CASE WHEN t.uploaded_as_anonymous THEN NULL ELSE u.username END AS uploader,
+                t.created_at
+            FROM torrents t
+            JOIN edition_groups eg ON eg.id = t.edition_group_id
+            JOIN title_groups tg ON tg.id = eg.title_group_id
+            LEFT JOIN users u ON u.id = t.created_by_id
+            WHERE t.id = $1
+            "#,
+            torrent_id
+        )
+        .fetch_one(self.borrow())
+        .await?;
+
+        Ok(torrent)
+    }
+
+    /// Takes the due deliveries and pushes their next attempt forward so that
+    /// they are not picked again while being sent.
+    pub async fn claim_pending_webhook_deliveries(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<PendingWebhookDelivery>> {
+        let deliveries = sqlx::query_as!(
+            PendingWebhookDelivery,
+            r#"
+            WITH claimed AS (
+                UPDATE webhook_deliveries
+                SET next_attempt_at = NOW() + INTERVAL '5 minutes'
+                WHERE id IN (
+                    SELECT id
+                    FROM webhook_deliveries
+                    WHERE status = 'pending' AND next_attempt_at <= NOW()
+                    ORDER BY next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, webhook_id, event, payload, attempts
+            )
+            SELECT
+                c.id AS "id!",
+                w.url,
+                w.secret,
+                c.event AS "event!: WebhookEvent",
+                c.payload AS "payload!",
+                c.attempts AS "attempts!"
+            FROM claimed c
+            JOIN webhooks w ON w.id = c.webhook_id
+            ORDER BY c.id
+            "#,
+            limit
+        )
+        .fetch_all(self.borrow())
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    pub async fn mark_webhook_delivery_delivered(
+        &self,
+        delivery_id: i64,
+        response_status: i32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'delivered', attempts = attempts + 1, last_attempt_at = NOW(),
+                response_status = $2, error = NULL
+            WHERE id = $1
+            "#,
+            delivery_id,
+            response_status
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+
+    /// The delivery is given up on when there is no `next_attempt_at`
+    pub async fn mark_webhook_delivery_attempt_failed(
+        &self,
+        delivery_id: i64,
+        response_status: Option<i32>,
+        error: &str,
+        next_attempt_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'failed' ELSE 'pending' END::webhook_delivery_status_enum,
+                attempts = attempts + 1, last_attempt_at = NOW(),
+                response_status = $2, error = $3, next_attempt_at = COALESCE($4, next_attempt_at)
+            WHERE id = $1
+            "#,
+            delivery_id,
+            response_status,
+            error,
+            next_attempt_at
+        )
+        .execute(self.borrow())
+        .await?;
+
+        Ok(())
+    }
+}
@@ -190,8 +190,12 @@ impl ConnectionPool {
         let total_items: i64 = sqlx::query_scalar!(
             r#"
             SELECT COUNT(*) FROM wiki_articles
-            WHERE unaccent(title) ILIKE '%' || unaccent($1) || '%'
-               OR ($2 = false AND unaccent(body) ILIKE '%' || unaccent($1) || '%')
+            WHERE $1 = ''
+               OR f_unaccent($1) <% f_unaccent(title)
+               OR ($2 = true AND to_tsvector('simple', f_unaccent(title)) @@ websearch_to_tsquery('simple', f_unaccent($1)))
+               OR ($2 = false AND (
+                   setweight(to_tsvector('simple', f_unaccent(title)), 'A') || setweight(to_tsvector('simple', f_unaccent(body)), 'D')
+               ) @@ websearch_to_tsquery('simple', f_unaccent($1)))
             "#,
             form.search_string,
             form.title_only,
@@ -206,9 +210,18 @@ impl ConnectionPool {
             r#"
             SELECT id, title, created_at, updated_at
             FROM wiki_articles
-            WHERE unaccent(title) ILIKE '%' || unaccent($1) || '%'
-               OR ($2 = false AND unaccent(body) ILIKE '%' || unaccent($1) || '%')
-            ORDER BY created_at DESC
+            WHERE $1 = ''
+               OR f_unaccent($1) <% f_unaccent(title)
+               OR ($2 = true AND to_tsvector('simple', f_unaccent(title)) @@ websearch_to_tsquery('simple', f_unaccent($1)))
+               OR ($2 = false AND (
+                   setweight(to_tsvector('simple', f_unaccent(title)), 'A') || setweight(to_tsvector('simple', f_unaccent(body)), 'D')
+               ) @@ websearch_to_tsquery('simple', f_unaccent($1)))
+            ORDER BY
+                ts_rank(
+                    setweight(to_tsvector('simple', f_unaccent(title)), 'A') || setweight(to_tsvector('simple', f_unaccent(body)), 'D'),
+                    websearch_to_tsquery('simple', f_unaccent($1))
+                ) + word_similarity(f_unaccent($1), f_unaccent(title)) DESC,
+                created_at DESC
             OFFSET $3 LIMIT $4
             "#,
             form.search_string,
@@ -103,6 +103,8 @@ mod tests {
             required_title_group_comments: 0,
             required_seeding_size: 10_000_000_000,
             promotion_cost_bonus_points: 500,
+            max_leeching: None,
+            max_leeching_scaled_by_ratio: false,
         }
     }
 
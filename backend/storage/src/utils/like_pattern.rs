@@ -0,0 +1,23 @@
+/// Escapes the `LIKE` wildcards of a user provided search term, so that it is matched literally
+/// by a pattern using `ESCAPE '\'`.
+pub fn escape_like_pattern(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape_like_pattern;
+
+    #[test]
+    fn escapes_wildcards_and_backslashes() {
+        assert_eq!(escape_like_pattern("100%_pure\\"), "100\\%\\_pure\\\\");
+        assert_eq!(escape_like_pattern("Parlophone"), "Parlophone");
+    }
+}
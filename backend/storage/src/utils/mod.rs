@@ -1,8 +1,12 @@
 mod diff;
 pub mod format;
+mod ip;
+mod like_pattern;
 pub mod tag_expression;
 pub mod user_badge;
 
 pub use diff::compute_diff;
 pub use format::bytes_to_readable;
+pub use ip::is_global_ip;
+pub use like_pattern::escape_like_pattern;
 pub use user_badge::validate_badge_criteria_shape;
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::ipnetwork::IpNetwork, PgPool};
+
+use crate::{
+    error::Error,
+    tracker::models::{peer_id::PeerId, Flushable, Mergeable, Queue},
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    sqlx::Type,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "cheat_flag_severity_enum", rename_all = "snake_case")]
+pub enum CheatFlagSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    sqlx::Type,
+    utoipa::ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "cheat_flag_reason_enum", rename_all = "snake_case")]
+pub enum CheatFlagReason {
+    /// More was uploaded than the configured max speed allows since the
+    /// previous announce
+    ImpossibleUploadSpeed,
+    /// Nobody else was leeching the torrent
+    UploadWithoutLeechers,
+    /// More was uploaded than the other peers of the swarm downloaded
+    UploadExceedsSwarmDownload,
+}
+
+// A flagged announce is never merged with another one
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Index {
+    pub user_id: u32,
+    pub torrent_id: u32,
+    pub peer_id: PeerId,
+    pub reason: CheatFlagReason,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheatFlag {
+    pub ip: std::net::IpAddr,
+    pub severity: CheatFlagSeverity,
+    pub uploaded_delta: u64,
+    pub seconds_since_last_announce: u64,
+    /// Leechers among the other users' peers
+    pub swarm_leechers: u32,
+    /// Downloaded by the other users' peers during their current session
+    pub swarm_downloaded: u64,
+    pub credit_withheld: bool,
+}
+
+impl Mergeable for CheatFlag {
+    fn merge(&mut self, new: &Self) {
+        *self = new.clone();
+    }
+}
+
+impl Flushable<CheatFlag> for Mutex<Queue<Index, CheatFlag>> {
+    async fn flush_to_database(&self, db: &PgPool) -> u64 {
+        let flags = self.lock().take_due();
+        if flags.is_empty() {
+            return 0;
+        }
+        let amount_of_flags = flags.len();
+
+        let mut created_ats: Vec<DateTime<Utc>> = Vec::with_capacity(flags.len());
+        let mut user_ids: Vec<i32> = Vec::with_capacity(flags.len());
+        let mut torrent_ids: Vec<i32> = Vec::with_capacity(flags.len());
+        let mut peer_ids: Vec<Vec<u8>> = Vec::with_capacity(flags.len());
+        let mut ips: Vec<IpNetwork> = Vec::with_capacity(flags.len());
+        let mut reasons: Vec<CheatFlagReason> = Vec::with_capacity(flags.len());
+        let mut severities: Vec<CheatFlagSeverity> = Vec::with_capacity(flags.len());
+        let mut uploaded_deltas: Vec<i64> = Vec::with_capacity(flags.len());
+        let mut seconds_since_last_announces: Vec<i64> = Vec::with_capacity(flags.len());
+        let mut swarm_leechers: Vec<i32> = Vec::with_capacity(flags.len());
+        let mut swarm_downloadeds: Vec<i64> = Vec::with_capacity(flags.len());
+        let mut credit_withhelds: Vec<bool> = Vec::with_capacity(flags.len());
+
+        for (index, flag) in &flags {
+            created_ats.push(index.created_at);
+            user_ids.push(index.user_id as i32);
+            torrent_ids.push(index.torrent_id as i32);
+            peer_ids.push(index.peer_id.to_vec());
+            ips.push(IpNetwork::from(flag.ip));
+            reasons.push(index.reason);
+            severities.push(flag.severity);
+            uploaded_deltas.push(flag.uploaded_delta as i64);
+            seconds_since_last_announces.push(flag.seconds_since_last_announce as i64);
+            swarm_leechers.push(flag.swarm_leechers as i32);
+            swarm_downloadeds.push(flag.swarm_downloaded as i64);
+            credit_withhelds.push(flag.credit_withheld);
+        }
+
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO cheat_flags (
+                    created_at,
+                    user_id,
+                    torrent_id,
+                    peer_id,
+                    ip,
+                    reason,
+                    severity,
+                    uploaded_delta,
+                    seconds_since_last_announce,
+                    swarm_leechers,
+                    swarm_downloaded,
+                    credit_withheld
+                )
+                SELECT * FROM unnest(
+                    $1::timestamptz[],
+                    $2::int[],
+                    $3::int[],
+                    $4::bytea[],
+                    $5::inet[],
+                    $6::cheat_flag_reason_enum[],
+                    $7::cheat_flag_severity_enum[],
+                    $8::bigint[],
+                    $9::bigint[],
+                    $10::int[],
+                    $11::bigint[],
+                    $12::boolean[]
+                )
+            "#,
+            &created_ats,
+            &user_ids,
+            &torrent_ids,
+            &peer_ids,
+            &ips,
+            &reasons as &[CheatFlagReason],
+            &severities as &[CheatFlagSeverity],
+            &uploaded_deltas,
+            &seconds_since_last_announces,
+            &swarm_leechers,
+            &swarm_downloadeds,
+            &credit_withhelds
+        )
+        .execute(db)
+        .await
+        .map_err(|e| Error::DatabseError(e.to_string()));
+
+        match result {
+            Ok(query_result) => {
+                log::info!("Inserted {amount_of_flags} cheat flags");
+                self.lock().flushed();
+                query_result.rows_affected()
+            }
+            Err(error) => {
+                log::error!("Failed to insert cheat flags, they will be retried: {error}");
+                self.lock().requeue(flags);
+                0
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
+use crate::tracker::models::cheat_flag::CheatFlagSeverity;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::Type, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 #[sqlx(
@@ -18,6 +20,8 @@ pub struct ArcadiaSettingsForTracker {
     pub global_download_factor: i16,
     pub snatched_torrent_bonus_points_transferred_to:
         Option<SnatchedTorrentBonusPointsTransferredTo>,
+    /// Flagged announces are not credited any upload from this severity on
+    pub cheat_flag_withhold_credit_min_severity: Option<CheatFlagSeverity>,
 }
 
 impl ArcadiaSettingsForTracker {
@@ -27,7 +31,8 @@ impl ArcadiaSettingsForTracker {
             r#"SELECT
                 global_upload_factor,
                 global_download_factor,
-                snatched_torrent_bonus_points_transferred_to as "snatched_torrent_bonus_points_transferred_to: _"
+                snatched_torrent_bonus_points_transferred_to as "snatched_torrent_bonus_points_transferred_to: _",
+                cheat_flag_withhold_credit_min_severity as "cheat_flag_withhold_credit_min_severity: _"
             FROM arcadia_settings LIMIT 1"#
         )
         .fetch_one(db)
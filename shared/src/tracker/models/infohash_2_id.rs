@@ -1,6 +1,7 @@
 use crate::tracker::models::torrent::InfoHash;
 use indexmap::IndexMap;
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
 #[derive(Debug)]
@@ -24,6 +25,8 @@ impl DerefMut for Map {
 pub struct DBImportInfohash2Id {
     pub id: i32,
     pub info_hash: InfoHash,
+    /// truncated to 20 bytes, set for BEP 52 (v2 and hybrid) torrents
+    pub info_hash_v2: Option<InfoHash>,
 }
 
 impl Map {
@@ -33,7 +36,8 @@ impl Map {
             r#"
                     SELECT
                         id,
-                        info_hash as "info_hash: InfoHash"
+                        info_hash as "info_hash: InfoHash",
+                        substring(info_hash_v2 FROM 1 FOR 20) as "info_hash_v2: InfoHash"
                     FROM torrents
                 "#
         )
@@ -44,8 +48,23 @@ impl Map {
         let mut map: Map = Map(IndexMap::with_capacity(rows.len()));
         for r in rows {
             map.insert(r.info_hash, r.id as u32);
+            if let Some(info_hash_v2) = r.info_hash_v2 {
+                map.insert(info_hash_v2, r.id as u32);
+            }
         }
 
         map
     }
+
+    /// The info hashes each torrent can be announced with
+    pub fn info_hashes_by_torrent(&self) -> HashMap<u32, Vec<InfoHash>> {
+        let mut info_hashes_by_torrent: HashMap<u32, Vec<InfoHash>> = HashMap::new();
+        for (info_hash, torrent_id) in self.iter() {
+            info_hashes_by_torrent
+                .entry(*torrent_id)
+                .or_default()
+                .push(*info_hash);
+        }
+        info_hashes_by_torrent
+    }
 }
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::ipnetwork::IpNetwork, PgPool};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::IpAddr,
+    ops::Deref,
+};
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct APIInsertIpBan {
+    pub id: i64,
+    pub ip_range: IpNetwork,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpBan {
+    pub ip_range: IpNetwork,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Banned addresses and ranges, by ban id
+#[derive(Debug, Default)]
+pub struct Map {
+    bans: IndexMap<i64, IpBan>,
+    /// Ban ids by range, the ranges being keyed by their network address
+    by_range: HashMap<IpNetwork, Vec<i64>>,
+    /// Amount of ranges per address family (ipv6 or not) and prefix length,
+    /// an address is looked up once for each of them
+    prefixes: BTreeMap<(bool, u8), usize>,
+}
+
+impl Deref for Map {
+    type Target = IndexMap<i64, IpBan>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.bans
+    }
+}
+
+#[derive(Debug)]
+pub struct DBImportIpBan {
+    pub id: i64,
+    pub ip_range: IpNetwork,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Map {
+    pub async fn from_database(db: &PgPool) -> Self {
+        let rows = sqlx::query_as!(
+            DBImportIpBan,
+            r#"
+                    SELECT
+                        id,
+                        ip_range,
+                        expires_at
+                    FROM ip_bans
+                    WHERE expires_at IS NULL OR expires_at > NOW()
+                "#
+        )
+        .fetch_all(db)
+        .await
+        .expect("could not get ip bans");
+
+        let mut map = Map::default();
+        for r in rows {
+            map.insert(
+                r.id,
+                IpBan {
+                    ip_range: r.ip_range,
+                    expires_at: r.expires_at,
+                },
+            );
+        }
+
+        map
+    }
+
+    pub fn insert(&mut self, id: i64, ban: IpBan) {
+        self.shift_remove(&id);
+
+        let range = normalize_range(ban.ip_range);
+        self.by_range.entry(range).or_default().push(id);
+        *self
+            .prefixes
+            .entry((range.is_ipv6(), range.prefix()))
+            .or_default() += 1;
+        self.bans.insert(id, ban);
+    }
+
+    pub fn shift_remove(&mut self, id: &i64) -> Option<IpBan> {
+        let ban = self.bans.shift_remove(id)?;
+
+        let range = normalize_range(ban.ip_range);
+        if let Some(ids) = self.by_range.get_mut(&range) {
+            ids.retain(|ban_id| ban_id != id);
+            if ids.is_empty() {
+                self.by_range.remove(&range);
+            }
+        }
+        let prefix = (range.is_ipv6(), range.prefix());
+        if let Some(amount) = self.prefixes.get_mut(&prefix) {
+            *amount -= 1;
+            if *amount == 0 {
+                self.prefixes.remove(&prefix);
+            }
+        }
+
+        Some(ban)
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&i64, &IpBan) -> bool) {
+        let removed_ids: Vec<i64> = self
+            .bans
+            .iter()
+            .filter(|(id, ban)| !keep(id, ban))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in removed_ids {
+            self.shift_remove(&id);
+        }
+    }
+
+    /// Whether an unexpired ban covers the address
+    pub fn is_banned(&self, ip: IpAddr, now: DateTime<Utc>) -> bool {
+        // dual stack sockets report ipv4 clients as ipv4-mapped ipv6 addresses
+        let ip = ip.to_canonical();
+        self.prefixes
+            .keys()
+            .filter(|(is_ipv6, _)| *is_ipv6 == ip.is_ipv6())
+            .filter_map(|(_, prefix)| IpNetwork::new(ip, *prefix).ok())
+            .filter_map(|range| self.by_range.get(&normalize_range(range)))
+            .flatten()
+            .any(|id| {
+                self.bans[id]
+                    .expires_at
+                    .is_none_or(|expires_at| expires_at > now)
+            })
+    }
+}
+
+/// The range starting at the network address, so that ranges covering the same
+/// addresses are equal
+fn normalize_range(range: IpNetwork) -> IpNetwork {
+    IpNetwork::new(range.network(), range.prefix()).expect("the prefix comes from a valid range")
+}
@@ -1,17 +1,29 @@
 use ringmap::RingMap;
 use serde::{de::DeserializeOwned, Serialize};
 use sqlx::PgPool;
-use std::hash::Hash;
+use std::{
+    fs::{self, File, OpenOptions},
+    hash::Hash,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
+pub mod cheat_flag;
 pub mod env;
 pub mod infohash_2_id;
+pub mod ip_ban;
 pub mod passkey_2_id;
 pub mod peer;
 pub mod peer_id;
 pub mod peer_update;
+pub mod personal_freeleech;
+pub mod promotion_event;
+pub mod state_digest;
 pub mod torrent;
 pub mod torrent_activity_update;
 pub mod torrent_update;
+pub mod unregistered_info_hash_update;
 pub mod user;
 pub mod user_update;
 
@@ -22,6 +34,49 @@ where
     V: Serialize + DeserializeOwned,
 {
     pub records: RingMap<K, V>,
+    pub retry_policy: RetryPolicy,
+    /// Flushes that failed in a row
+    pub failed_attempts: u32,
+    /// Set while backing off after a failed flush
+    pub next_attempt_at: Option<Instant>,
+    pub stats: QueueStats,
+}
+
+/// What happens to the records of a queue when flushing them fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Failed flushes in a row after which the records are spilled to disk,
+    /// or dropped if there is no spill file
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled after every failed flush
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Where the records are kept while the database stays down
+    pub spill_file: Option<PathBuf>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            spill_file: None,
+        }
+    }
+}
+
+/// Amount of records, since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Put back in the queue after a failed flush
+    pub retried: u64,
+    /// Written to the spill file
+    pub spilled: u64,
+    /// Read back from the spill file
+    pub restored: u64,
+    /// Lost because they could not be flushed nor spilled
+    pub dropped: u64,
 }
 
 pub trait Mergeable {
@@ -32,7 +87,8 @@ pub trait Mergeable {
 #[allow(async_fn_in_trait)]
 pub trait Flushable<T> {
     /// Flushes updates to postgresql database, returning the number of rows
-    /// affected (0 on failure).
+    /// affected (0 on failure). The updates of a failed flush are put back in
+    /// the queue and retried with the queue's retry policy.
     async fn flush_to_database(&self, db: &PgPool) -> u64;
 }
 
@@ -44,6 +100,10 @@ where
     pub fn is_empty(&self) -> bool {
         self.records.is_empty()
     }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
 }
 
 impl<K, V> Default for Queue<K, V>
@@ -52,9 +112,7 @@ where
     V: Clone + Mergeable + Serialize + DeserializeOwned,
 {
     fn default() -> Self {
-        Self {
-            records: RingMap::new(),
-        }
+        Self::with_retry_policy(RetryPolicy::default())
     }
 }
 
@@ -70,4 +128,171 @@ where
             .and_modify(|update| update.merge(&value))
             .or_push_back(value);
     }
+
+    pub fn with_retry_policy(retry_policy: RetryPolicy) -> Self {
+        Self {
+            records: RingMap::new(),
+            retry_policy,
+            failed_attempts: 0,
+            next_attempt_at: None,
+            stats: QueueStats::default(),
+        }
+    }
+
+    /// Drains the records to flush, nothing is returned while backing off
+    /// after a failed flush
+    pub fn take_due(&mut self) -> Vec<(K, V)> {
+        if self
+            .next_attempt_at
+            .is_some_and(|next_attempt_at| Instant::now() < next_attempt_at)
+        {
+            return Vec::new();
+        }
+        // with nothing new to flush, the spilled records are retried so they
+        // don't wait on disk for the next update
+        if self.records.is_empty() && self.failed_attempts >= self.retry_policy.max_attempts {
+            self.restore_spilled();
+        }
+        let amount_of_updates = self.records.len();
+        self.records.drain(0..amount_of_updates).collect()
+    }
+
+    /// Ends the current backoff, the next flush sends the queued records
+    pub fn retry_now(&mut self) {
+        self.next_attempt_at = None;
+    }
+
+    /// To be called once a flush succeeded
+    pub fn flushed(&mut self) {
+        let had_spilled = self.failed_attempts >= self.retry_policy.max_attempts;
+        self.failed_attempts = 0;
+        self.next_attempt_at = None;
+        if had_spilled {
+            self.restore_spilled();
+        }
+    }
+
+    /// Merges the records of a failed flush back into the queue and backs off.
+    /// Once the flushes failed `max_attempts` times in a row, the queue is
+    /// spilled to disk to bound its memory usage.
+    pub fn requeue(&mut self, failed: Vec<(K, V)>) {
+        self.stats.retried += failed.len() as u64;
+        for (key, value) in failed {
+            // the failed records are older than the ones queued since the flush started
+            self.records
+                .entry(key)
+                .and_modify(|newer| {
+                    let mut merged = value.clone();
+                    merged.merge(newer);
+                    *newer = merged;
+                })
+                .or_push_back(value);
+        }
+
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        let delay = self
+            .retry_policy
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(self.failed_attempts - 1))
+            .min(self.retry_policy.max_delay);
+        self.next_attempt_at = Some(Instant::now() + delay);
+
+        if self.failed_attempts >= self.retry_policy.max_attempts {
+            self.spill();
+        }
+    }
+
+    /// Appends all the queued records to the spill file, they are dropped if
+    /// there is none or if writing it fails
+    pub fn spill(&mut self) {
+        let amount_of_updates = self.records.len();
+        if amount_of_updates == 0 {
+            return;
+        }
+        let records = self
+            .records
+            .drain(0..amount_of_updates)
+            .collect::<Vec<(K, V)>>();
+
+        let Some(spill_file) = &self.retry_policy.spill_file else {
+            log::error!("Dropped {amount_of_updates} updates that could not be flushed");
+            self.stats.dropped += amount_of_updates as u64;
+            return;
+        };
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(spill_file)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                for record in &records {
+                    serde_json::to_writer(&mut writer, record)?;
+                    writer.write_all(b"\n")?;
+                }
+                writer.flush()
+            });
+
+        match result {
+            Ok(()) => {
+                log::warn!(
+                    "Spilled {amount_of_updates} updates to {}",
+                    spill_file.display()
+                );
+                self.stats.spilled += amount_of_updates as u64;
+            }
+            Err(error) => {
+                log::error!(
+                    "Dropped {amount_of_updates} updates that could not be written to {}: {error}",
+                    spill_file.display()
+                );
+                self.stats.dropped += amount_of_updates as u64;
+            }
+        }
+    }
+
+    /// Moves the records of the spill file back into the queue
+    pub fn restore_spilled(&mut self) {
+        let Some(spill_file) = self.retry_policy.spill_file.clone() else {
+            return;
+        };
+        let file = match File::open(&spill_file) {
+            Ok(file) => file,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return,
+            Err(error) => {
+                log::error!("Failed to open {}: {error}", spill_file.display());
+                return;
+            }
+        };
+
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let record: Result<(K, V), String> = line
+                .map_err(|error| error.to_string())
+                .and_then(|line| serde_json::from_str(&line).map_err(|error| error.to_string()));
+            match record {
+                Ok(record) => records.push(record),
+                Err(error) => {
+                    log::error!("Skipped a record of {}: {error}", spill_file.display());
+                    self.stats.dropped += 1;
+                }
+            }
+        }
+
+        // the records would be restored twice if the file stayed around
+        if let Err(error) = fs::remove_file(&spill_file) {
+            log::error!("Failed to remove {}: {error}", spill_file.display());
+            return;
+        }
+
+        let restored_count = records.len();
+        for (key, value) in records {
+            self.upsert(key, value);
+        }
+        self.stats.restored += restored_count as u64;
+        log::info!(
+            "Restored {restored_count} updates from {}",
+            spill_file.display()
+        );
+    }
 }
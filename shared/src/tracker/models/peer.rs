@@ -1,12 +1,14 @@
 use crate::error::Error;
 use crate::tracker::models::peer_id::PeerId;
-use crate::tracker::models::peer_update;
+use crate::tracker::models::{peer_update, Flushable, Mergeable, Queue};
 use chrono::serde::ts_seconds;
 use indexmap::IndexMap;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize, Serializer};
 use sqlx::types::chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::ops::{Deref, DerefMut};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -18,21 +20,66 @@ pub struct Index {
     pub peer_id: PeerId,
 }
 
+/// The `key` sent by the client in its announces, which other peers never
+/// see, so that only the client can change the addresses of its peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerKey(pub u32);
+
+impl PeerKey {
+    /// Keys are usually 8 hex digits, like the 32-bit keys of UDP announces,
+    /// other keys are hashed (FNV-1a) to fit
+    pub fn from_http_param(key: &[u8]) -> Self {
+        if let Ok(key) = std::str::from_utf8(key)
+            && key.len() <= 8
+            && let Ok(key) = u32::from_str_radix(key, 16)
+        {
+            return PeerKey(key);
+        }
+
+        PeerKey(key.iter().fold(0x811c9dc5, |hash, &byte| {
+            (hash ^ byte as u32).wrapping_mul(0x01000193)
+        }))
+    }
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Peer {
-    pub ip_address: std::net::IpAddr,
+    /// The address the last announce was received from
+    pub ip_address: IpAddr,
+    /// The addresses given out to other peers (BEP 7), a dual-stack peer
+    /// has both
+    pub ipv4: Option<Ipv4Addr>,
+    pub ipv6: Option<Ipv6Addr>,
+    #[serde(skip)]
+    pub key: Option<PeerKey>,
     pub port: u16,
     pub is_seeder: bool,
     pub is_active: bool,
+    /// Whether the tracker could open a connection to the peer, always
+    /// false when connectability isn't checked
+    pub is_connectable: bool,
     // pub is_visible: bool,
     pub has_sent_completed: bool,
     #[serde(with = "ts_seconds")]
     pub updated_at: DateTime<Utc>,
     pub uploaded: u64,
     pub downloaded: u64,
+    /// `downloaded_by_peers` of its torrent as of its latest announce, none
+    /// for the peers loaded from the database which haven't announced since
+    #[serde(skip)]
+    pub swarm_downloaded_at_announce: Option<u64>,
 }
 
 impl Peer {
+    /// The addresses to give out for a peer only known by the address it
+    /// announced from
+    pub fn addresses_of(ip_address: IpAddr) -> (Option<Ipv4Addr>, Option<Ipv6Addr>) {
+        match ip_address.to_canonical() {
+            IpAddr::V4(ip) => (Some(ip), None),
+            IpAddr::V6(ip) => (None, Some(ip)),
+        }
+    }
+
     /// Determines if the peer should be included in the peer list
     #[inline(always)]
     pub fn is_included_in_peer_list(&self) -> bool {
@@ -93,48 +140,77 @@ impl Serialize for Index {
     }
 }
 
-// we use peer_update::Index because we also need the torrent_id, which isn't in peer::Index
-pub async fn remove_peers_from_database(db: &PgPool, peers: &Vec<peer_update::Index>) {
-    if peers.is_empty() {
-        return;
-    }
-
-    let mut user_ids: Vec<i32> = Vec::with_capacity(peers.len());
-    let mut torrent_ids: Vec<i32> = Vec::with_capacity(peers.len());
-    let mut peer_ids: Vec<Vec<u8>> = Vec::with_capacity(peers.len());
+/// A peer to delete from the database, unless it announced again since
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PeerRemoval {
+    pub updated_at: DateTime<Utc>,
+}
 
-    for index in peers {
-        user_ids.push(index.user_id as i32);
-        torrent_ids.push(index.torrent_id as i32);
-        peer_ids.push(index.peer_id.to_vec());
+impl Mergeable for PeerRemoval {
+    fn merge(&mut self, new: &Self) {
+        self.updated_at = std::cmp::max(self.updated_at, new.updated_at);
     }
+}
 
-    let result = sqlx::query!(
-        r#"
+// we use peer_update::Index because we also need the torrent_id, which isn't in peer::Index
+impl Flushable<PeerRemoval> for Mutex<Queue<peer_update::Index, PeerRemoval>> {
+    async fn flush_to_database(&self, db: &PgPool) -> u64 {
+        let removals = self.lock().take_due();
+        if removals.is_empty() {
+            return 0;
+        }
+
+        let mut user_ids: Vec<i32> = Vec::with_capacity(removals.len());
+        let mut torrent_ids: Vec<i32> = Vec::with_capacity(removals.len());
+        let mut peer_ids: Vec<Vec<u8>> = Vec::with_capacity(removals.len());
+        let mut updated_ats: Vec<DateTime<Utc>> = Vec::with_capacity(removals.len());
+
+        for (index, removal) in &removals {
+            user_ids.push(index.user_id as i32);
+            torrent_ids.push(index.torrent_id as i32);
+            peer_ids.push(index.peer_id.to_vec());
+            updated_ats.push(removal.updated_at);
+        }
+
+        // a removal retried after a failed flush must not delete a peer that
+        // announced again in the meantime
+        let result = sqlx::query!(
+            r#"
                     DELETE FROM peers
-                    WHERE (user_id, torrent_id, peer_id) IN (
-                        SELECT t.user_id, t.torrent_id, t.peer_id
-                        FROM (
-                            SELECT * FROM unnest(
-                                $1::int[],
-                                $2::int[],
-                                $3::bytea[]
-                            ) AS t(user_id, torrent_id, peer_id)
-                        ) AS t
-                    )
+                    USING (
+                        SELECT * FROM unnest(
+                            $1::int[],
+                            $2::int[],
+                            $3::bytea[],
+                            $4::timestamptz[]
+                        ) AS t(user_id, torrent_id, peer_id, updated_at)
+                    ) AS t
+                    WHERE peers.user_id = t.user_id
+                        AND peers.torrent_id = t.torrent_id
+                        AND peers.peer_id = t.peer_id
+                        -- stored as timestamp without time zone in DB
+                        AND peers.updated_at <= (t.updated_at AT TIME ZONE 'UTC')::timestamp
                 "#,
-        &user_ids,
-        &torrent_ids,
-        &peer_ids
-    )
-    .execute(db)
-    .await
-    .map_err(|e| Error::DatabseError(e.to_string()));
-
-    if result.is_err() {
-        // TODO: reinsert the updates that failed and retry
-        panic!("Failed removing peers from db: {}", result.err().unwrap());
-    } else {
-        log::info!("Removed {} peers", peers.len());
+            &user_ids,
+            &torrent_ids,
+            &peer_ids,
+            &updated_ats
+        )
+        .execute(db)
+        .await
+        .map_err(|e| Error::DatabseError(e.to_string()));
+
+        match result {
+            Ok(query_result) => {
+                log::info!("Removed {} peers", removals.len());
+                self.lock().flushed();
+                query_result.rows_affected()
+            }
+            Err(error) => {
+                log::error!("Failed removing peers from db, they will be retried: {error}");
+                self.lock().requeue(removals);
+                0
+            }
+        }
     }
 }
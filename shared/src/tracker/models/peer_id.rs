@@ -3,12 +3,12 @@ use std::{
     ops::Deref,
 };
 
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::{Database, Decode};
 
-use crate::utils::hex_encode;
+use crate::utils::{hex_decode, hex_encode};
 
-#[derive(Clone, Copy, Eq, Deserialize, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct PeerId(pub [u8; 20]);
 
 impl Deref for PeerId {
@@ -72,3 +72,24 @@ impl Serialize for PeerId {
         serializer.serialize_str(&self.to_string())
     }
 }
+
+impl<'de> Deserialize<'de> for PeerId {
+    /// Decodes the hex string produced by the Serialize impl
+    fn deserialize<D>(deserializer: D) -> std::prelude::v1::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let hex = String::deserialize(deserializer)?;
+        let hex = hex.as_bytes();
+        if hex.len() != 40 {
+            return Err(D::Error::custom("a peer id is 40 hex digits long"));
+        }
+
+        let mut peer_id = [0u8; 20];
+        for (i, byte) in peer_id.iter_mut().enumerate() {
+            *byte = hex_decode([hex[2 * i], hex[2 * i + 1]]).map_err(D::Error::custom)?;
+        }
+
+        Ok(PeerId(peer_id))
+    }
+}
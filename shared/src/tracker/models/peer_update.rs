@@ -25,6 +25,7 @@ pub struct PeerUpdate {
     pub downloaded: u64,
     pub is_active: bool,
     pub is_seeder: bool,
+    pub is_connectable: bool,
     pub left: u64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -47,6 +48,7 @@ impl Mergeable for PeerUpdate {
             self.downloaded = new.downloaded;
             self.is_active = new.is_active;
             self.is_seeder = new.is_seeder;
+            self.is_connectable = new.is_connectable;
             self.left = new.left;
             self.updated_at = new.updated_at;
         }
@@ -69,15 +71,11 @@ impl Mergeable for PeerUpdate {
 
 impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
     async fn flush_to_database(&self, db: &PgPool) -> u64 {
-        let amount_of_updates = self.lock().records.len();
-        let updates = self
-            .lock()
-            .records
-            .drain(0..amount_of_updates)
-            .collect::<Vec<(Index, PeerUpdate)>>();
+        let updates = self.lock().take_due();
         if updates.is_empty() {
             return 0;
         }
+        let amount_of_updates = updates.len();
 
         let mut user_ids: Vec<i32> = Vec::with_capacity(updates.len());
         let mut torrent_ids: Vec<i32> = Vec::with_capacity(updates.len());
@@ -90,6 +88,7 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
         let mut lefts: Vec<i64> = Vec::with_capacity(updates.len());
         let mut actives: Vec<bool> = Vec::with_capacity(updates.len());
         let mut seeders: Vec<bool> = Vec::with_capacity(updates.len());
+        let mut connectables: Vec<bool> = Vec::with_capacity(updates.len());
         let mut created_ats: Vec<DateTime<Utc>> = Vec::with_capacity(updates.len());
         let mut updated_ats: Vec<DateTime<Utc>> = Vec::with_capacity(updates.len());
         // torrent_activity fields
@@ -99,7 +98,7 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
         let mut real_uploaded_deltas: Vec<i64> = Vec::with_capacity(updates.len());
         let mut real_downloaded_deltas: Vec<i64> = Vec::with_capacity(updates.len());
 
-        for (index, update) in updates {
+        for (index, update) in &updates {
             user_ids.push(index.user_id as i32);
             torrent_ids.push(index.torrent_id as i32);
             peer_ids.push(index.peer_id.to_vec());
@@ -111,6 +110,7 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
             lefts.push(update.left as i64);
             actives.push(update.is_active);
             seeders.push(update.is_seeder);
+            connectables.push(update.is_connectable);
             created_ats.push(update.created_at);
             updated_ats.push(update.updated_at);
             // torrent_activities fields
@@ -121,8 +121,13 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
             real_downloaded_deltas.push(update.real_downloaded_delta as i64);
         }
 
-        let result = sqlx::query!(
-            r#"
+        // both statements are applied in one transaction, so that retrying a
+        // failed flush doesn't count the torrent_activities deltas twice
+        let result = async {
+            let mut transaction = db.begin().await?;
+
+            let peers_result = sqlx::query!(
+                r#"
                 INSERT INTO peers (
                     peer_id,
                     ip,
@@ -136,7 +141,8 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                     created_at,
                     updated_at,
                     torrent_id,
-                    user_id
+                    user_id,
+                    connectable
                 )
                 SELECT
                     t.peer_id,
@@ -152,7 +158,8 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                     (t.created_at AT TIME ZONE 'UTC')::timestamp,
                     (t.updated_at AT TIME ZONE 'UTC')::timestamp,
                     t.torrent_id,
-                    t.user_id
+                    t.user_id,
+                    t.connectable
                 FROM (
                     SELECT * FROM unnest(
                         $1::bytea[],
@@ -167,7 +174,8 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                         $10::timestamptz[],
                         $11::timestamptz[],
                         $12::int[],
-                        $13::int[]
+                        $13::int[],
+                        $14::boolean[]
                     ) AS t(
                         peer_id,
                         ip,
@@ -181,7 +189,8 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                         created_at,
                         updated_at,
                         torrent_id,
-                        user_id
+                        user_id,
+                        connectable
                     )
                 ) AS t
                 ON CONFLICT (user_id, torrent_id, peer_id) DO UPDATE SET
@@ -193,40 +202,29 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                     "left" = EXCLUDED."left",
                     active = EXCLUDED.active,
                     seeder = EXCLUDED.seeder,
+                    connectable = EXCLUDED.connectable,
                     updated_at = EXCLUDED.updated_at
             "#,
-            &peer_ids,
-            &ips,
-            &ports,
-            &agents,
-            &uploadeds,
-            &downloadeds,
-            &lefts,
-            &actives,
-            &seeders,
-            &created_ats,
-            &updated_ats,
-            &torrent_ids,
-            &user_ids
-        )
-        .execute(db)
-        .await
-        .map_err(|e| Error::DatabseError(e.to_string()));
+                &peer_ids,
+                &ips,
+                &ports,
+                &agents,
+                &uploadeds,
+                &downloadeds,
+                &lefts,
+                &actives,
+                &seeders,
+                &created_ats,
+                &updated_ats,
+                &torrent_ids,
+                &user_ids,
+                &connectables
+            )
+            .execute(&mut *transaction)
+            .await?;
 
-        let peers_rows_affected = match result {
-            Ok(query_result) => {
-                log::info!("Inserted {amount_of_updates} peer updates");
-                query_result.rows_affected()
-            }
-            Err(error) => {
-                // TODO: reinsert the updates that failed and retry
-                log::error!("Failed to insert peer updates: {error}");
-                0
-            }
-        };
-
-        let torrent_activities_result = sqlx::query!(
-            r#"
+            let torrent_activities_result = sqlx::query!(
+                r#"
                 INSERT INTO torrent_activities (
                     torrent_id,
                     user_id,
@@ -294,28 +292,38 @@ impl Flushable<PeerUpdate> for Mutex<Queue<Index, PeerUpdate>> {
                     downloaded = torrent_activities.downloaded + COALESCE(EXCLUDED.downloaded, 0),
                     real_downloaded = torrent_activities.real_downloaded + COALESCE(EXCLUDED.real_downloaded, 0)
             "#,
-            &torrent_ids,
-            &user_ids,
-            &completed_ats as &[Option<DateTime<Utc>>],
-            &uploaded_deltas,
-            &downloaded_deltas,
-            &real_uploaded_deltas,
-            &real_downloaded_deltas,
-            &seeders,
-            &updated_ats
-        )
-        .execute(db)
+                &torrent_ids,
+                &user_ids,
+                &completed_ats as &[Option<DateTime<Utc>>],
+                &uploaded_deltas,
+                &downloaded_deltas,
+                &real_uploaded_deltas,
+                &real_downloaded_deltas,
+                &seeders,
+                &updated_ats
+            )
+            .execute(&mut *transaction)
+            .await?;
+
+            transaction.commit().await?;
+            Ok::<_, sqlx::Error>(
+                peers_result.rows_affected() + torrent_activities_result.rows_affected(),
+            )
+        }
         .await
         .map_err(|e| Error::DatabseError(e.to_string()));
 
-        let torrent_activities_rows_affected = match torrent_activities_result {
-            Ok(query_result) => query_result.rows_affected(),
+        match result {
+            Ok(rows_affected) => {
+                log::info!("Inserted {amount_of_updates} peer updates");
+                self.lock().flushed();
+                rows_affected
+            }
             Err(error) => {
-                log::error!("Failed to update torrent_activities: {error}");
+                log::error!("Failed to insert peer updates, they will be retried: {error}");
+                self.lock().requeue(updates);
                 0
             }
-        };
-
-        peers_rows_affected + torrent_activities_rows_affected
+        }
     }
 }
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::ops::{Deref, DerefMut};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, Hash, PartialEq)]
+pub struct Index {
+    pub user_id: u32,
+    pub torrent_id: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct APIInsertPersonalFreeleech {
+    pub user_id: u32,
+    pub torrent_id: u32,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Expiry date of the freeleech windows users bought on torrents
+#[derive(Debug, Default)]
+pub struct Map(pub IndexMap<Index, DateTime<Utc>>);
+
+impl Deref for Map {
+    type Target = IndexMap<Index, DateTime<Utc>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Map {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct DBImportPersonalFreeleech {
+    pub user_id: i32,
+    pub torrent_id: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Map {
+    pub async fn from_database(db: &PgPool) -> Self {
+        let rows = sqlx::query_as!(
+            DBImportPersonalFreeleech,
+            r#"
+                    SELECT
+                        user_id,
+                        torrent_id,
+                        expires_at
+                    FROM personal_freeleeches
+                    WHERE expires_at > NOW()
+                "#
+        )
+        .fetch_all(db)
+        .await
+        .expect("could not get personal freeleeches");
+
+        let mut map: Map = Map(IndexMap::with_capacity(rows.len()));
+        for r in rows {
+            map.insert(
+                Index {
+                    user_id: r.user_id as u32,
+                    torrent_id: r.torrent_id as u32,
+                },
+                r.expires_at,
+            );
+        }
+
+        map
+    }
+
+    /// Whether the user currently has a freeleech window on the torrent
+    pub fn is_active(&self, user_id: u32, torrent_id: u32, now: DateTime<Utc>) -> bool {
+        self.get(&Index {
+            user_id,
+            torrent_id,
+        })
+        .is_some_and(|expires_at| *expires_at > now)
+    }
+}
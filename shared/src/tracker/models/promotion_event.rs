@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Json, PgExecutor, PgPool};
+use std::ops::{Deref, DerefMut};
+
+use crate::tracker::models::torrent::TorrentScope;
+
+/// Which torrents an event applies to, matched against their [`TorrentScope`]
+/// when they are announced. The event applies to every torrent when nothing is
+/// set.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct PromotionEventScope {
+    pub content_type: Option<String>,
+    pub category: Option<String>,
+    /// The tag expression in disjunctive normal form
+    pub tag_filter: Option<Vec<TagClause>>,
+    pub min_size: Option<i64>,
+}
+
+/// Matches the torrents having all the `include` tags and none of the `exclude` ones
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct TagClause {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl PromotionEventScope {
+    pub fn matches(&self, torrent: &TorrentScope) -> bool {
+        self.content_type
+            .as_ref()
+            .is_none_or(|content_type| *content_type == torrent.content_type)
+            && self
+                .category
+                .as_ref()
+                .is_none_or(|category| Some(category) == torrent.category.as_ref())
+            && self
+                .min_size
+                .is_none_or(|min_size| torrent.size >= min_size)
+            && self.tag_filter.as_ref().is_none_or(|clauses| {
+                clauses.iter().any(|clause| {
+                    clause.include.iter().all(|tag| torrent.tags.contains(tag))
+                        && !clause.exclude.iter().any(|tag| torrent.tags.contains(tag))
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct APIInsertPromotionEvent {
+    pub id: i64,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    #[serde(default)]
+    pub scope: PromotionEventScope,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromotionEvent {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    pub scope: PromotionEventScope,
+}
+
+impl From<APIInsertPromotionEvent> for PromotionEvent {
+    fn from(event: APIInsertPromotionEvent) -> Self {
+        Self {
+            starts_at: event.starts_at,
+            ends_at: event.ends_at,
+            upload_factor: event.upload_factor,
+            download_factor: event.download_factor,
+            scope: event.scope,
+        }
+    }
+}
+
+impl PromotionEvent {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+}
+
+/// Scheduled promotion events that haven't ended yet, by event id
+#[derive(Debug, Default)]
+pub struct Map(pub IndexMap<i64, PromotionEvent>);
+
+impl Deref for Map {
+    type Target = IndexMap<i64, PromotionEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Map {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct DBImportPromotionEvent {
+    pub id: i64,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    pub content_type: Option<String>,
+    pub category: Option<String>,
+    pub tag_filter: Option<Json<Vec<TagClause>>>,
+    pub min_size: Option<i64>,
+}
+
+impl APIInsertPromotionEvent {
+    /// The events that haven't ended yet
+    pub async fn find_not_ended<'c>(db: impl PgExecutor<'c>) -> sqlx::Result<Vec<Self>> {
+        let rows = sqlx::query_as!(
+            DBImportPromotionEvent,
+            r#"
+                    SELECT
+                        id,
+                        starts_at,
+                        ends_at,
+                        upload_factor,
+                        download_factor,
+                        content_type::TEXT AS content_type,
+                        category::TEXT AS category,
+                        tag_filter AS "tag_filter: Json<Vec<TagClause>>",
+                        min_size
+                    FROM promotion_events
+                    WHERE ends_at > NOW()
+                "#
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| APIInsertPromotionEvent {
+                id: r.id,
+                starts_at: r.starts_at,
+                ends_at: r.ends_at,
+                upload_factor: r.upload_factor,
+                download_factor: r.download_factor,
+                scope: PromotionEventScope {
+                    content_type: r.content_type,
+                    category: r.category,
+                    tag_filter: r.tag_filter.map(|tag_filter| tag_filter.0),
+                    min_size: r.min_size,
+                },
+            })
+            .collect())
+    }
+}
+
+impl Map {
+    pub async fn from_database(db: &PgPool) -> Self {
+        let events = APIInsertPromotionEvent::find_not_ended(db)
+            .await
+            .expect("could not get promotion events");
+
+        let mut map: Map = Map(IndexMap::with_capacity(events.len()));
+        for event in events {
+            map.insert(event.id, event.into());
+        }
+
+        map
+    }
+
+    /// The factors of the active events applying to the torrent, combined so
+    /// that users get the most of each: the highest upload factor and the
+    /// lowest download factor
+    pub fn best_active_for(
+        &self,
+        torrent: &TorrentScope,
+        now: DateTime<Utc>,
+    ) -> Option<(i16, i16)> {
+        self.values()
+            .filter(|event| event.is_active(now) && event.scope.matches(torrent))
+            .fold(None, |factors, event| {
+                Some(match factors {
+                    Some((upload_factor, download_factor)) => (
+                        std::cmp::max(upload_factor, event.upload_factor),
+                        std::cmp::min(download_factor, event.download_factor),
+                    ),
+                    None => (event.upload_factor, event.download_factor),
+                })
+            })
+    }
+}
@@ -0,0 +1,254 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
+use std::collections::BTreeSet;
+
+use crate::tracker::models::{
+    cheat_flag::CheatFlagSeverity,
+    env::{ArcadiaSettingsForTracker, SnatchedTorrentBonusPointsTransferredTo},
+    promotion_event::PromotionEventScope,
+    torrent::{InfoHash, TorrentDeletionReason, TorrentScope},
+    user::Passkey,
+};
+
+/// Amount of buckets the entries of each kind are spread into, by id
+pub const DIGEST_BUCKETS: usize = 64;
+
+/// Summary of the state the backend syncs to the tracker. The backend computes
+/// it from the database and compares it with the tracker's to find the entries
+/// to send again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StateDigest {
+    pub users: BucketDigest,
+    pub torrents: BucketDigest,
+    pub personal_freeleeches: BucketDigest,
+    pub ip_bans: BucketDigest,
+    /// ip bans are few, they are listed so that the ones deleted by the
+    /// backend can be removed from the tracker
+    pub ip_ban_ids: Vec<i64>,
+    pub promotion_events: BucketDigest,
+    /// same as the ip bans
+    pub promotion_event_ids: Vec<i64>,
+    pub settings: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BucketDigest {
+    pub count: u64,
+    /// Sum of the hashes of the entries in each bucket, so that the order in
+    /// which they are added doesn't matter
+    pub buckets: Vec<u64>,
+}
+
+impl Default for BucketDigest {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            buckets: vec![0; DIGEST_BUCKETS],
+        }
+    }
+}
+
+impl BucketDigest {
+    fn add(&mut self, id: u64, entry_hash: u64) {
+        let bucket = bucket_of(id);
+        self.buckets[bucket] = self.buckets[bucket].wrapping_add(entry_hash);
+        self.count += 1;
+    }
+
+    /// Buckets whose entries differ between the two digests
+    pub fn mismatched_buckets(&self, other: &BucketDigest) -> BTreeSet<usize> {
+        if self.buckets.len() != other.buckets.len() {
+            return (0..DIGEST_BUCKETS).collect();
+        }
+        self.buckets
+            .iter()
+            .zip(&other.buckets)
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(bucket, _)| bucket)
+            .collect()
+    }
+}
+
+pub fn bucket_of(id: u64) -> usize {
+    (id % DIGEST_BUCKETS as u64) as usize
+}
+
+impl StateDigest {
+    /// `passkey` is only set for users allowed to announce
+    pub fn add_user(
+        &mut self,
+        id: u32,
+        passkey: Option<&Passkey>,
+        max_snatches_per_day: Option<u32>,
+        max_leeching: Option<u32>,
+    ) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&id.to_le_bytes());
+        hasher.write_option(passkey.map(|passkey| passkey.0));
+        hasher.write_option(max_snatches_per_day.map(u32::to_le_bytes));
+        hasher.write_option(max_leeching.map(u32::to_le_bytes));
+        self.users.add(id as u64, hasher.finish());
+    }
+
+    /// `info_hashes` are the ones the torrent can be announced with (v1 and
+    /// truncated v2), in any order
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_torrent<'a>(
+        &mut self,
+        id: u32,
+        info_hashes: impl Iterator<Item = &'a InfoHash>,
+        is_deleted: bool,
+        deletion_reason: Option<TorrentDeletionReason>,
+        replacement_torrent_id: Option<u32>,
+        upload_factor: i16,
+        download_factor: i16,
+        scope: &TorrentScope,
+    ) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&id.to_le_bytes());
+        let info_hashes: BTreeSet<[u8; 20]> = info_hashes.map(|info_hash| info_hash.0).collect();
+        for info_hash in info_hashes {
+            hasher.write(&info_hash);
+        }
+        hasher.write(&[is_deleted as u8]);
+        hasher.write_option(deletion_reason.map(|reason| [reason as u8]));
+        hasher.write_option(replacement_torrent_id.map(u32::to_le_bytes));
+        hasher.write(&upload_factor.to_le_bytes());
+        hasher.write(&download_factor.to_le_bytes());
+        hasher.write_str(&scope.content_type);
+        hasher.write_option_str(scope.category.as_deref());
+        for tag in &scope.tags {
+            hasher.write_str(tag);
+        }
+        hasher.write(&scope.size.to_le_bytes());
+        self.torrents.add(id as u64, hasher.finish());
+    }
+
+    /// Bucketed by user
+    pub fn add_personal_freeleech(
+        &mut self,
+        user_id: u32,
+        torrent_id: u32,
+        expires_at: DateTime<Utc>,
+    ) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&user_id.to_le_bytes());
+        hasher.write(&torrent_id.to_le_bytes());
+        hasher.write(&expires_at.timestamp_micros().to_le_bytes());
+        self.personal_freeleeches
+            .add(user_id as u64, hasher.finish());
+    }
+
+    pub fn add_ip_ban(&mut self, id: i64, ip_range: IpNetwork, expires_at: Option<DateTime<Utc>>) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&id.to_le_bytes());
+        hasher.write(ip_range.to_string().as_bytes());
+        hasher
+            .write_option(expires_at.map(|expires_at| expires_at.timestamp_micros().to_le_bytes()));
+        self.ip_bans.add(id as u64, hasher.finish());
+        self.ip_ban_ids.push(id);
+    }
+
+    pub fn add_promotion_event(
+        &mut self,
+        id: i64,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        upload_factor: i16,
+        download_factor: i16,
+        scope: &PromotionEventScope,
+    ) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&id.to_le_bytes());
+        hasher.write(&starts_at.timestamp_micros().to_le_bytes());
+        hasher.write(&ends_at.timestamp_micros().to_le_bytes());
+        hasher.write(&upload_factor.to_le_bytes());
+        hasher.write(&download_factor.to_le_bytes());
+        hasher.write_option_str(scope.content_type.as_deref());
+        hasher.write_option_str(scope.category.as_deref());
+        match &scope.tag_filter {
+            Some(clauses) => {
+                hasher.write(&[1]);
+                for clause in clauses {
+                    hasher.write(&(clause.include.len() as u64).to_le_bytes());
+                    for tag in clause.include.iter().chain(&clause.exclude) {
+                        hasher.write_str(tag);
+                    }
+                }
+            }
+            None => hasher.write(&[0]),
+        }
+        hasher.write_option(scope.min_size.map(i64::to_le_bytes));
+        self.promotion_events.add(id as u64, hasher.finish());
+        self.promotion_event_ids.push(id);
+    }
+
+    pub fn set_settings(&mut self, settings: &ArcadiaSettingsForTracker) {
+        let mut hasher = EntryHasher::new();
+        hasher.write(&settings.global_upload_factor.to_le_bytes());
+        hasher.write(&settings.global_download_factor.to_le_bytes());
+        hasher.write(&[
+            match settings.snatched_torrent_bonus_points_transferred_to {
+                None => 0,
+                Some(SnatchedTorrentBonusPointsTransferredTo::Uploader) => 1,
+                Some(SnatchedTorrentBonusPointsTransferredTo::CurrentSeeders) => 2,
+            },
+            match settings.cheat_flag_withhold_credit_min_severity {
+                None => 0,
+                Some(CheatFlagSeverity::Low) => 1,
+                Some(CheatFlagSeverity::Medium) => 2,
+                Some(CheatFlagSeverity::High) => 3,
+            },
+        ]);
+        self.settings = hasher.finish();
+    }
+}
+
+/// FNV-1a, the digests are compared across processes so the hash must not
+/// depend on the build like the std hashers may
+struct EntryHasher(u64);
+
+impl EntryHasher {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_option<const N: usize>(&mut self, bytes: Option<[u8; N]>) {
+        match bytes {
+            Some(bytes) => {
+                self.write(&[1]);
+                self.write(&bytes);
+            }
+            None => self.write(&[0]),
+        }
+    }
+
+    /// Length-prefixed, so that consecutive strings can't be confused
+    fn write_str(&mut self, string: &str) {
+        self.write(&(string.len() as u64).to_le_bytes());
+        self.write(string.as_bytes());
+    }
+
+    fn write_option_str(&mut self, string: Option<&str>) {
+        match string {
+            Some(string) => {
+                self.write(&[1]);
+                self.write_str(string);
+            }
+            None => self.write(&[0]),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
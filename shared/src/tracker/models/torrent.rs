@@ -4,6 +4,8 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgTypeInfo;
 use sqlx::{Database, Decode, PgPool, Postgres, Type};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::net::IpAddr;
 use std::ops::{Deref, DerefMut};
 use std::str::FromStr;
@@ -11,11 +13,42 @@ use utoipa::ToSchema;
 
 use crate::tracker::models::peer::{self, Peer};
 use crate::tracker::models::peer_id::PeerId;
-use crate::utils::hex_decode;
+use crate::utils::{hex_decode, hex_encode};
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, Hash, PartialEq, ToSchema)]
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, ToSchema,
+)]
 pub struct InfoHash(pub [u8; 20]);
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "torrent_deletion_reason_enum", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TorrentDeletionReason {
+    Trumped,
+    Duplicate,
+    Other,
+}
+
+impl fmt::Display for TorrentDeletionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Trumped => "trumped",
+            Self::Duplicate => "duplicate",
+            Self::Other => "other",
+        })
+    }
+}
+
+/// What the scope of the promotion events is matched against
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TorrentScope {
+    pub content_type: String,
+    pub category: Option<String>,
+    /// The tags of the title group, sorted
+    pub tags: Vec<String>,
+    pub size: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Torrent {
     pub upload_factor: i16,
@@ -24,19 +57,36 @@ pub struct Torrent {
     pub leechers: u32,
     pub times_completed: u32,
     pub is_deleted: bool,
+    /// Told to the users still announcing the torrent once it is deleted
+    pub deletion_reason: Option<TorrentDeletionReason>,
+    pub replacement_torrent_id: Option<u32>,
+    pub scope: TorrentScope,
     pub peers: peer::Map,
+    /// What its peers downloaded since the tracker started, the ones that
+    /// left the swarm since included
+    pub downloaded_by_peers: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct APIInsertTorrent {
     pub id: u32,
     pub info_hash: InfoHash,
+    /// SHA-256 info hash of BEP 52 (v2 and hybrid) torrents, truncated to
+    /// the 20 bytes clients announce with
+    #[serde(default)]
+    pub info_hash_v2: Option<InfoHash>,
     pub is_deleted: bool,
+    #[serde(default)]
+    pub deletion_reason: Option<TorrentDeletionReason>,
+    #[serde(default)]
+    pub replacement_torrent_id: Option<u32>,
     pub seeders: u32,
     pub leechers: u32,
     pub times_completed: u32,
-    pub download_factor: u8,
-    pub upload_factor: u8,
+    pub download_factor: i16,
+    pub upload_factor: i16,
+    #[serde(default)]
+    pub scope: TorrentScope,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -57,6 +107,12 @@ pub struct DBImportTorrent {
     pub leechers: i64,
     pub times_completed: i32,
     pub is_deleted: bool,
+    pub deletion_reason: Option<TorrentDeletionReason>,
+    pub replacement_torrent_id: Option<i32>,
+    pub content_type: String,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub size: i64,
 }
 
 impl Map {
@@ -65,17 +121,33 @@ impl Map {
             DBImportTorrent,
             r#"
                     SELECT
-                        id,
-                        upload_factor,
-                        download_factor,
-                        seeders,
-                        leechers,
-                        times_completed,
+                        torrents.id AS "id!",
+                        torrents.upload_factor AS "upload_factor!",
+                        torrents.download_factor AS "download_factor!",
+                        torrents.seeders AS "seeders!",
+                        torrents.leechers AS "leechers!",
+                        torrents.times_completed AS "times_completed!",
                         CASE
-                            WHEN deleted_at IS NOT NULL THEN TRUE
+                            WHEN torrents.deleted_at IS NOT NULL THEN TRUE
                             ELSE FALSE
-                        END AS "is_deleted!"
+                        END AS "is_deleted!",
+                        torrent_deletions.deletion_reason AS "deletion_reason?: TorrentDeletionReason",
+                        torrent_deletions.replacement_torrent_id AS "replacement_torrent_id?",
+                        title_groups.content_type::TEXT AS "content_type!",
+                        title_groups.category::TEXT AS category,
+                        ARRAY(
+                            SELECT title_group_tags.name
+                            FROM title_group_applied_tags
+                            JOIN title_group_tags ON title_group_tags.id = title_group_applied_tags.tag_id
+                            WHERE title_group_applied_tags.title_group_id = title_groups.id
+                              AND title_group_tags.deleted_at IS NULL
+                            ORDER BY title_group_tags.name
+                        )::TEXT[] AS "tags!",
+                        torrents.size AS "size!"
                     FROM torrents
+                    JOIN edition_groups ON edition_groups.id = torrents.edition_group_id
+                    JOIN title_groups ON title_groups.id = edition_groups.title_group_id
+                    LEFT JOIN torrent_deletions ON torrent_deletions.torrent_id = torrents.id
                     "#
         )
         .fetch_all(db)
@@ -91,7 +163,16 @@ impl Map {
                 leechers: r.leechers as u32,
                 times_completed: r.times_completed as u32,
                 is_deleted: r.is_deleted,
+                deletion_reason: r.deletion_reason,
+                replacement_torrent_id: r.replacement_torrent_id.map(|id| id as u32),
+                scope: TorrentScope {
+                    content_type: r.content_type,
+                    category: r.category,
+                    tags: r.tags,
+                    size: r.size,
+                },
                 peers: peer::Map::new(),
+                downloaded_by_peers: 0,
             };
             map.insert(r.id as u32, torrent);
         }
@@ -106,6 +187,7 @@ impl Map {
                     peers.port AS "port",
                     peers.seeder AS "is_seeder: bool",
                     peers.active AS "is_active: bool",
+                    peers.connectable AS "is_connectable: bool",
                     peers.updated_at AS "updated_at: DateTime<Utc>",
                     peers.uploaded AS "uploaded",
                     peers.downloaded AS "downloaded",
@@ -127,6 +209,7 @@ impl Map {
                 peer
             ));
 
+            let (ipv4, ipv6) = Peer::addresses_of(peer.ip_address);
             map.entry(torrent_id).and_modify(|torrent| {
                 torrent.peers.insert(
                     peer::Index {
@@ -135,15 +218,20 @@ impl Map {
                     },
                     Peer {
                         ip_address: peer.ip_address,
+                        ipv4,
+                        ipv6,
+                        key: None,
                         port,
                         is_seeder: peer.is_seeder,
                         is_active: peer.is_active,
+                        is_connectable: peer.is_connectable,
                         has_sent_completed: false,
                         updated_at: peer
                             .updated_at
                             .expect("Peer with null updated_at found in database."),
                         uploaded: peer.uploaded as u64,
                         downloaded: peer.downloaded as u64,
+                        swarm_downloaded_at_announce: None,
                     },
                 );
             });
@@ -151,6 +239,20 @@ impl Map {
 
         map
     }
+
+    /// The torrents each user has a peer on
+    pub fn torrent_ids_by_user(&self) -> HashMap<u32, HashSet<u32>> {
+        let mut torrent_ids_by_user: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for (torrent_id, torrent) in self.iter() {
+            for index in torrent.peers.keys() {
+                torrent_ids_by_user
+                    .entry(index.user_id)
+                    .or_default()
+                    .insert(*torrent_id);
+            }
+        }
+        torrent_ids_by_user
+    }
 }
 
 impl Deref for Map {
@@ -195,6 +297,18 @@ where
     }
 }
 
+impl fmt::Display for InfoHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut hex = [0u8; 40];
+
+        for i in 0..self.0.len() {
+            [hex[2 * i], hex[2 * i + 1]] = hex_encode(self.0[i]);
+        }
+
+        f.write_str(&String::from_utf8_lossy(&hex))
+    }
+}
+
 impl FromStr for InfoHash {
     type Err = anyhow::Error;
 
@@ -31,21 +31,17 @@ impl Mergeable for TorrentUpdate {
 
 impl Flushable<TorrentUpdate> for Mutex<Queue<Index, TorrentUpdate>> {
     async fn flush_to_database(&self, db: &PgPool) -> u64 {
-        let amount_of_updates = self.lock().records.len();
-        let updates = self
-            .lock()
-            .records
-            .drain(0..amount_of_updates)
-            .collect::<Vec<(Index, TorrentUpdate)>>();
+        let updates = self.lock().take_due();
         if updates.is_empty() {
             return 0;
         }
+        let amount_of_updates = updates.len();
         let mut torrent_ids = Vec::new();
         let mut seeder_deltas = Vec::new();
         let mut leecher_deltas = Vec::new();
         let mut times_completed_deltas = Vec::new();
 
-        for (index, update) in updates {
+        for (index, update) in &updates {
             torrent_ids.push(index.torrent_id as i32);
             seeder_deltas.push(update.seeder_delta as i64);
             leecher_deltas.push(update.leecher_delta as i64);
@@ -77,11 +73,13 @@ impl Flushable<TorrentUpdate> for Mutex<Queue<Index, TorrentUpdate>> {
         match result {
             Ok(query_result) => {
                 log::info!("Inserted {amount_of_updates} torrent updates");
+                self.lock().flushed();
                 query_result.rows_affected()
             }
             Err(error) => {
-                // TODO: reinsert the updates that failed and retry
-                panic!("Failed to insert torrent updates: {error}");
+                log::error!("Failed to insert torrent updates, they will be retried: {error}");
+                self.lock().requeue(updates);
+                0
             }
         }
     }
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::{
+    error::Error,
+    tracker::models::{torrent::InfoHash, Flushable, Mergeable, Queue},
+};
+
+// Fields must be in same order as database primary key
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Index {
+    pub user_id: u32,
+    pub info_hash: InfoHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnregisteredInfoHashUpdate {
+    /// The deleted torrent the info hash belonged to, `None` when the tracker
+    /// never knew it
+    pub torrent_id: Option<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Mergeable for UnregisteredInfoHashUpdate {
+    fn merge(&mut self, new: &Self) {
+        self.torrent_id = new.torrent_id;
+        self.created_at = self.created_at.min(new.created_at);
+        self.updated_at = self.updated_at.max(new.updated_at);
+    }
+}
+
+impl Flushable<UnregisteredInfoHashUpdate> for Mutex<Queue<Index, UnregisteredInfoHashUpdate>> {
+    async fn flush_to_database(&self, db: &PgPool) -> u64 {
+        let updates = self.lock().take_due();
+        if updates.is_empty() {
+            return 0;
+        }
+        let amount_of_updates = updates.len();
+
+        let mut user_ids: Vec<i32> = Vec::with_capacity(updates.len());
+        let mut info_hashes: Vec<Vec<u8>> = Vec::with_capacity(updates.len());
+        let mut torrent_ids: Vec<Option<i32>> = Vec::with_capacity(updates.len());
+        let mut created_ats: Vec<DateTime<Utc>> = Vec::with_capacity(updates.len());
+        let mut updated_ats: Vec<DateTime<Utc>> = Vec::with_capacity(updates.len());
+
+        for (index, update) in &updates {
+            user_ids.push(index.user_id as i32);
+            info_hashes.push(index.info_hash.0.to_vec());
+            torrent_ids.push(update.torrent_id.map(|torrent_id| torrent_id as i32));
+            created_ats.push(update.created_at);
+            updated_ats.push(update.updated_at);
+        }
+
+        let result = sqlx::query!(
+            r#"
+                INSERT INTO unregistered_info_hashes (
+                    user_id,
+                    info_hash,
+                    torrent_id,
+                    created_at,
+                    updated_at
+                )
+                SELECT * FROM unnest(
+                    $1::int[],
+                    $2::bytea[],
+                    $3::int[],
+                    $4::timestamptz[],
+                    $5::timestamptz[]
+                )
+                ON CONFLICT (user_id, info_hash) DO UPDATE SET
+                    torrent_id = EXCLUDED.torrent_id,
+                    updated_at = EXCLUDED.updated_at
+            "#,
+            &user_ids,
+            &info_hashes,
+            &torrent_ids as &[Option<i32>],
+            &created_ats,
+            &updated_ats
+        )
+        .execute(db)
+        .await
+        .map_err(|e| Error::DatabseError(e.to_string()));
+
+        match result {
+            Ok(query_result) => {
+                log::info!("Upserted {amount_of_updates} unregistered info hash updates");
+                self.lock().flushed();
+                query_result.rows_affected()
+            }
+            Err(error) => {
+                log::error!(
+                    "Failed to upsert unregistered info hash updates, they will be retried: {error}"
+                );
+                self.lock().requeue(updates);
+                0
+            }
+        }
+    }
+}
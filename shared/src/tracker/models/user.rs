@@ -14,8 +14,12 @@ pub struct Passkey(pub [u8; 32]);
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct User {
     pub max_snatches_per_day: Option<u32>,
-    // those are unused (for now)
+    /// Torrents the user can leech at the same time, unlimited when `None`.
+    /// Enforced against `num_leeching`.
+    pub max_leeching: Option<u32>,
     pub num_seeding: u32,
+    /// Torrents the user is leeching, whatever the amount of their clients
+    /// on each of them
     pub num_leeching: u32,
     /// List of (torrent_id, unix_timestamp) for leeches started in the past 24h.
     /// Used to enforce max_snatches_per_day limit.
@@ -28,6 +32,11 @@ pub struct APIInsertUser {
     pub id: u32,
     pub passkey: Passkey,
     pub max_snatches_per_day: Option<u32>,
+    /// Already scaled by the ratio of the user when their class asks for it
+    #[serde(default)]
+    pub max_leeching: Option<u32>,
+    /// Banned users are kept, but their passkey is no longer accepted
+    pub banned: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -117,6 +126,7 @@ pub struct DBImportUser {
     pub max_snatches_per_day: Option<i32>,
     pub num_seeding: i32,
     pub num_leeching: i32,
+    pub max_leeching: Option<i32>,
 }
 
 impl Map {
@@ -128,8 +138,15 @@ impl Map {
                 id,
                 passkey as "passkey: Passkey",
                 max_snatches_per_day,
-                0::INT AS "num_seeding!",
-                0::INT AS "num_leeching!"
+                (
+                    SELECT COUNT(*) FROM peers
+                    WHERE peers.user_id = users.id AND peers.active AND peers.seeder
+                )::INT AS "num_seeding!",
+                (
+                    SELECT COUNT(DISTINCT peers.torrent_id) FROM peers
+                    WHERE peers.user_id = users.id AND peers.active AND NOT peers.seeder
+                )::INT AS "num_leeching!",
+                user_max_leeching(class_name, uploaded, downloaded) AS max_leeching
             FROM users
             "#
         )
@@ -141,6 +158,7 @@ impl Map {
         for r in rows {
             let user = User {
                 max_snatches_per_day: r.max_snatches_per_day.map(|x| x as u32),
+                max_leeching: r.max_leeching.map(|x| x as u32),
                 num_seeding: r.num_seeding as u32,
                 num_leeching: r.num_leeching as u32,
                 recent_leeches: Vec::new(),
@@ -150,4 +168,28 @@ impl Map {
 
         map
     }
+
+    /// The leeching slots of the users, which follow the ratio of their
+    /// stats for some classes
+    pub async fn find_max_leeching(
+        db: &PgPool,
+        user_ids: &[u32],
+    ) -> sqlx::Result<Vec<(u32, Option<u32>)>> {
+        let user_ids: Vec<i32> = user_ids.iter().map(|id| *id as i32).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, user_max_leeching(class_name, uploaded, downloaded) AS max_leeching
+            FROM users
+            WHERE id = ANY($1)
+            "#,
+            &user_ids
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| (r.id as u32, r.max_leeching.map(|x| x as u32)))
+            .collect())
+    }
 }
@@ -36,15 +36,11 @@ impl Mergeable for UserUpdate {
 
 impl Flushable<UserUpdate> for Mutex<Queue<Index, UserUpdate>> {
     async fn flush_to_database(&self, db: &PgPool) -> u64 {
-        let amount_of_updates = self.lock().records.len();
-        let updates = self
-            .lock()
-            .records
-            .drain(0..amount_of_updates)
-            .collect::<Vec<(Index, UserUpdate)>>();
+        let updates = self.lock().take_due();
         if updates.is_empty() {
             return 0;
         }
+        let amount_of_updates = updates.len();
 
         let mut user_ids = Vec::new();
         let mut uploaded_deltas = Vec::new();
@@ -52,7 +48,7 @@ impl Flushable<UserUpdate> for Mutex<Queue<Index, UserUpdate>> {
         let mut real_uploaded_deltas = Vec::new();
         let mut real_downloaded_deltas = Vec::new();
 
-        for (index, update) in updates {
+        for (index, update) in &updates {
             user_ids.push(index.user_id as i32);
             uploaded_deltas.push(update.uploaded_delta as i64);
             downloaded_deltas.push(update.downloaded_delta as i64);
@@ -86,11 +82,13 @@ impl Flushable<UserUpdate> for Mutex<Queue<Index, UserUpdate>> {
         match result {
             Ok(query_result) => {
                 log::info!("Inserted {amount_of_updates} user updates");
+                self.lock().flushed();
                 query_result.rows_affected()
             }
             Err(error) => {
-                // TODO: reinsert the updates that failed and retry
-                panic!("failed inserting user updates: {error}");
+                log::error!("Failed to insert user updates, they will be retried: {error}");
+                self.lock().requeue(updates);
+                0
             }
         }
     }
@@ -34,12 +34,18 @@ pub enum AnnounceError {
     InvalidLeft(#[source] std::num::ParseIntError),
     #[error("invalid ip")]
     InvalidIpAddr(#[source] std::net::AddrParseError),
+    #[error("invalid ipv4")]
+    InvalidIpv4Addr,
+    #[error("invalid ipv6")]
+    InvalidIpv6Addr,
     #[error("invalid numwant")]
     InvalidNumWant(#[source] std::num::ParseIntError),
     #[error("invalid compact")]
     InvalidCompact,
-    #[error("only compact=1 supported")]
-    UnsupportedCompact,
+    #[error("invalid no_peer_id")]
+    InvalidNoPeerId,
+    #[error("The key does not match the one of this peer.")]
+    InvalidKey,
     #[error("Abnormal access blocked.")]
     AbnormalAccess,
     #[error("user-agent is missing")]
@@ -62,8 +68,9 @@ pub enum AnnounceError {
     InvalidEvent,
     #[error("Torrent not found.")]
     TorrentNotFound,
-    #[error("Torrent has been deleted.")]
-    TorrentIsDeleted,
+    /// With the deletion reason and replacement torrent, when known
+    #[error("Torrent has been deleted{0}.")]
+    TorrentIsDeleted(String),
     #[error("Query parameter 'left' is missing.")]
     MissingLeft,
     #[error("Missing IP address in query")]
@@ -74,6 +81,8 @@ pub enum AnnounceError {
     PeersPerTorrentPerUserLimit(u8),
     #[error("You have already leeched {0} torrents in the past 24h.")]
     SnatchLimitReached(u32),
+    #[error("You are already leeching as many torrents as your class allows ({0}).")]
+    LeechingSlotsLimitReached(u32),
     #[error("Uploaded value is missing.")]
     MissingUploaded,
     #[error("Downloaded value is missing.")]
@@ -82,6 +91,8 @@ pub enum AnnounceError {
     InsufficientBonusPoints(i64),
     #[error("Stopped peer doesn't exist.")]
     StoppedPeerDoesNotExist,
+    #[error("Your IP address is banned.")]
+    IpBanned,
 }
 
 impl actix_web::ResponseError for AnnounceError {
@@ -1,6 +1,6 @@
 use std::{
     future::{self, Ready},
-    net::IpAddr,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     str::FromStr,
 };
 
@@ -12,7 +12,11 @@ use crate::{
             warning::{AnnounceWarning, WarningCollection},
         },
     },
-    services::announce_service::{check_and_deduct_snatch_cost, is_torrent_client_allowed},
+    services::{
+        announce_service::{check_and_deduct_snatch_cost, is_torrent_client_allowed},
+        cheat_detection_service::{detect_impossible_upload, Swarm},
+        connectability_service::check_connectability,
+    },
     Tracker,
 };
 use actix_web::{
@@ -21,13 +25,17 @@ use actix_web::{
     FromRequest, HttpRequest, HttpResponse,
 };
 use arcadia_shared::tracker::models::{
+    cheat_flag::{self, CheatFlag},
     peer::{self, Peer},
+    peer_id::PeerId,
     peer_update::{self, PeerUpdate},
+    torrent::{InfoHash, Torrent},
     torrent_update::{self, TorrentUpdate},
+    unregistered_info_hash_update::{self, UnregisteredInfoHashUpdate},
     user::Passkey,
     user_update::{self, UserUpdate},
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use log::debug;
 use opentelemetry::KeyValue;
 use rand::{rng, seq::IteratorRandom, Rng};
@@ -114,7 +122,285 @@ pub async fn exec(
     ann: Announce,
     ClientIp(client_ip): ClientIp,
 ) -> Result<HttpResponse> {
-    let result = handle(arc.clone(), passkey, user_agent, ann, client_ip).await;
+    // Peer lists are compact unless asked otherwise (BEP 23)
+    let compact = ann.compact != Some(false);
+    let no_peer_id = ann.no_peer_id;
+
+    let response = handle(&arc, &passkey, user_agent.0, ann, client_ip)
+        .await?
+        .into_bencode(&arc, compact, no_peer_id);
+
+    debug!(
+        "Announce response: {:?}",
+        String::from_utf8_lossy(&response)
+    );
+
+    Ok(HttpResponse::Ok().body(response))
+}
+
+/// A peer address given out to the client, a dual-stack peer has one per
+/// address family
+pub struct ResponsePeer {
+    pub peer_id: PeerId,
+    pub ip: IpAddr,
+    pub port: u16,
+}
+
+/// Outcome of a successful announce, independent of whether the client
+/// announced over HTTP or UDP
+pub struct AnnounceResponse {
+    pub seeders: u32,
+    pub leechers: u32,
+    pub times_completed: u32,
+    pub peers: Vec<ResponsePeer>,
+    pub warnings: WarningCollection,
+}
+
+impl AnnounceResponse {
+    /// Seeder count sent to the client, which is hidden when there are warnings
+    pub fn reported_seeders(&self) -> u32 {
+        if self.warnings.is_empty() {
+            self.seeders
+        } else {
+            0
+        }
+    }
+
+    /// Leecher count sent to the client, which is hidden when there are warnings
+    pub fn reported_leechers(&self) -> u32 {
+        if self.warnings.is_empty() {
+            self.leechers
+        } else {
+            0
+        }
+    }
+
+    /// Compact ipv4 peer list (6 bytes per peer)
+    pub fn compact_peers_ipv4(&self) -> Vec<u8> {
+        let mut peers = Vec::new();
+        for peer in &self.peers {
+            if let IpAddr::V4(ip) = peer.ip {
+                peers.extend(&ip.octets());
+                peers.extend(&peer.port.to_be_bytes());
+            }
+        }
+        peers
+    }
+
+    /// Compact ipv6 peer list (18 bytes per peer)
+    pub fn compact_peers_ipv6(&self) -> Vec<u8> {
+        let mut peers = Vec::new();
+        for peer in &self.peers {
+            if let IpAddr::V6(ip) = peer.ip {
+                peers.extend(&ip.octets());
+                peers.extend(&peer.port.to_be_bytes());
+            }
+        }
+        peers
+    }
+
+    fn into_bencode(self, arc: &Tracker, compact: bool, no_peer_id: bool) -> Vec<u8> {
+        // Write out bencoded response (keys must be sorted to be within spec)
+        let mut response: Vec<u8> = Vec::with_capacity(
+            82 // literal characters
+            + 5 * 5 // numbers with estimated digit quantity for each
+            + self.peers.len() * 80 + 10 // max bytes per dictionary peer plus list delimiters
+            + self.warnings.max_byte_length(), // max bytes per warning message plus separator
+        );
+
+        response.extend(b"d8:completei");
+        response.extend(self.reported_seeders().to_string().as_bytes());
+        response.extend(b"e10:downloadedi");
+        response.extend(self.times_completed.to_string().as_bytes());
+        response.extend(b"e10:incompletei");
+        response.extend(self.reported_leechers().to_string().as_bytes());
+
+        response.extend(b"e8:intervali");
+        response.extend(random_announce_interval(arc).to_string().as_bytes());
+        response.extend(b"e12:min intervali");
+        response.extend(arc.env.announce_min.to_string().as_bytes());
+        response.extend(b"e5:peers");
+
+        if compact {
+            let peers_ipv4 = self.compact_peers_ipv4();
+            let peers_ipv6 = self.compact_peers_ipv6();
+
+            if peers_ipv4.is_empty() {
+                response.extend(b"0:")
+            } else {
+                response.extend(peers_ipv4.len().to_string().as_bytes());
+                response.extend(b":");
+                response.extend(peers_ipv4);
+            }
+
+            if !peers_ipv6.is_empty() {
+                response.extend(b"6:peers6");
+                response.extend(peers_ipv6.len().to_string().as_bytes());
+                response.extend(b":");
+                response.extend(peers_ipv6);
+            }
+        } else {
+            // Dictionary model, with the peers of both address families
+            response.extend(b"l");
+            for peer in &self.peers {
+                let ip = peer.ip.to_string();
+                response.extend(b"d2:ip");
+                response.extend(ip.len().to_string().as_bytes());
+                response.extend(b":");
+                response.extend(ip.as_bytes());
+                if !no_peer_id {
+                    response.extend(b"7:peer id20:");
+                    response.extend(peer.peer_id.0);
+                }
+                response.extend(b"4:porti");
+                response.extend(peer.port.to_string().as_bytes());
+                response.extend(b"ee");
+            }
+            response.extend(b"e");
+        }
+
+        if let Some(warning_message) = self.warnings.into_message() {
+            response.extend(b"15:warning message");
+            response.extend(warning_message.len().to_string().as_bytes());
+            response.extend(b":");
+            response.extend(warning_message);
+        }
+
+        response.extend(b"e");
+
+        response
+    }
+}
+
+/// Random amount of seconds until the client should announce again
+pub fn random_announce_interval(arc: &Tracker) -> u32 {
+    rng().random_range(arc.env.announce_min..=arc.env.announce_max)
+}
+
+/// Addresses to give out for the peer: the one the announce comes from and,
+/// for the other address family, the one learned from a previous announce
+/// over that family or, if `trust_announced` is set, given in the announce
+/// (BEP 7). Those are only trusted along with the key, which can't change
+/// once the peer sent one.
+fn peer_addresses(
+    client_ip: IpAddr,
+    ann: &Announce,
+    old_peer: Option<&Peer>,
+    trust_announced: bool,
+) -> Result<(Option<Ipv4Addr>, Option<Ipv6Addr>)> {
+    if let Some(old_key) = old_peer.and_then(|peer| peer.key)
+        && ann.key != Some(old_key)
+    {
+        return Err(AnnounceError::InvalidKey);
+    }
+
+    let is_trusted = ann.key.is_some();
+    let other_ipv4 = ann
+        .ipv4
+        .filter(|_| trust_announced)
+        .or(old_peer.and_then(|peer| peer.ipv4))
+        .filter(|_| is_trusted);
+    let other_ipv6 = ann
+        .ipv6
+        .filter(|_| trust_announced)
+        .or(old_peer.and_then(|peer| peer.ipv6))
+        .filter(|_| is_trusted);
+
+    let (ipv4, ipv6) = Peer::addresses_of(client_ip);
+    Ok((ipv4.or(other_ipv4), ipv6.or(other_ipv6)))
+}
+
+/// Remembers that the user still announces an info hash that the tracker
+/// doesn't know or whose torrent was deleted, so that they can be told which
+/// torrents to remove from their client
+fn log_unregistered_info_hash(
+    arc: &Tracker,
+    user_id: u32,
+    info_hash: InfoHash,
+    torrent_id: Option<u32>,
+    now: DateTime<Utc>,
+) {
+    // hashes of deleted torrents are bounded, random ones announced by a user aren't
+    if torrent_id.is_none()
+        && !arc.unknown_info_hash_limiter.lock().allow(
+            user_id,
+            info_hash,
+            arc.env.max_unknown_info_hashes_per_user_per_hour,
+            now,
+        )
+    {
+        return;
+    }
+    arc.unregistered_info_hash_updates.lock().upsert(
+        unregistered_info_hash_update::Index { user_id, info_hash },
+        UnregisteredInfoHashUpdate {
+            torrent_id,
+            created_at: now,
+            updated_at: now,
+        },
+    );
+}
+
+/// Whether the user leeches the torrent with another client than the announcing one
+fn is_leeching_with_another_peer(torrent: &Torrent, user_id: u32, peer_id: PeerId) -> bool {
+    torrent.peers.iter().any(|(index, peer)| {
+        index.user_id == user_id && index.peer_id != peer_id && peer.is_included_in_leech_list()
+    })
+}
+
+/// The leeching slots of the user are checked when they start leeching the
+/// torrent, be it with a new client or a seeding one
+fn check_leeching_slots(
+    arc: &Tracker,
+    torrent: &Torrent,
+    user_id: u32,
+    peer_id: PeerId,
+) -> Result<()> {
+    let is_leeching = torrent
+        .peers
+        .get(&peer::Index { user_id, peer_id })
+        .is_some_and(|peer| peer.is_included_in_leech_list());
+
+    if !is_leeching
+        && !is_leeching_with_another_peer(torrent, user_id, peer_id)
+        && let Some(user) = arc.users.read().get(&user_id)
+        && let Some(max) = user.max_leeching
+        && user.num_leeching >= max
+    {
+        return Err(AnnounceError::LeechingSlotsLimitReached(max));
+    }
+
+    Ok(())
+}
+
+fn torrent_is_deleted(arc: &Tracker, torrent: &Torrent) -> AnnounceError {
+    let details = match (torrent.deletion_reason, torrent.replacement_torrent_id) {
+        (Some(reason), Some(replacement_torrent_id)) => {
+            let replacement = match &arc.env.arcadia_frontend_url {
+                Some(frontend_url) => format!(
+                    "{}/torrent/{replacement_torrent_id}",
+                    frontend_url.trim_end_matches('/')
+                ),
+                None => format!("torrent {replacement_torrent_id}"),
+            };
+            format!(" ({reason}), replaced by {replacement}")
+        }
+        (Some(reason), None) => format!(" ({reason})"),
+        (None, _) => String::new(),
+    };
+    AnnounceError::TorrentIsDeleted(details)
+}
+
+/// Validates and applies an announce, independently of the protocol it was
+/// received with, so that HTTP and UDP announces behave identically.
+pub async fn handle(
+    arc: &Tracker,
+    passkey: &str,
+    user_agent: String,
+    ann: Announce,
+    client_ip: IpAddr,
+) -> Result<AnnounceResponse> {
+    let result = apply_announce(arc, passkey, user_agent, ann, client_ip).await;
 
     if let Some(m) = arc.metrics.get() {
         match &result {
@@ -128,13 +414,13 @@ pub async fn exec(
     result
 }
 
-async fn handle(
-    arc: Data<Tracker>,
-    passkey: Path<String>,
-    user_agent: UserAgent,
+async fn apply_announce(
+    arc: &Tracker,
+    passkey: &str,
+    user_agent: String,
     ann: Announce,
     client_ip: IpAddr,
-) -> Result<HttpResponse> {
+) -> Result<AnnounceResponse> {
     // let headers = req.headers();
     // if headers.contains_key(ACCEPT_LANGUAGE)
     //     || headers.contains_key(REFERER)
@@ -152,7 +438,11 @@ async fn handle(
         return Err(AnnounceError::TorrentClientNotInWhitelist);
     }
 
-    let passkey = Passkey::from_str(&passkey).or(Err(AnnounceError::InvalidPasskey))?;
+    if arc.ip_bans.read().is_banned(client_ip, Utc::now()) {
+        return Err(AnnounceError::IpBanned);
+    }
+
+    let passkey = Passkey::from_str(passkey).or(Err(AnnounceError::InvalidPasskey))?;
     // Validate passkey
     let user_id = match arc.passkey2id.read().get(&passkey).cloned() {
         Some(user_id) => user_id,
@@ -162,6 +452,8 @@ async fn handle(
         }
     };
 
+    let now = Utc::now();
+
     // Validate torrent
     let torrent_id_res = arc
         .infohash2id
@@ -170,26 +462,18 @@ async fn handle(
         .ok_or(AnnounceError::InfoHashNotFound)
         .cloned();
 
-    // if let Ok(user) = &user {
-    //     if let Err(InfoHashNotFound) = torrent_id_res {
-    //         tracker.unregistered_info_hash_updates.lock().upsert(
-    //             unregistered_info_hash_update::Index {
-    //                 user_id: user.id,
-    //                 info_hash: queries.info_hash,
-    //             },
-    //             UnregisteredInfoHashUpdate {
-    //                 created_at: now,
-    //                 updated_at: now,
-    //             },
-    //         );
-    //     }
-    // }
+    if let Err(AnnounceError::InfoHashNotFound) = torrent_id_res {
+        log_unregistered_info_hash(arc, user_id, ann.info_hash, None, now);
+    }
 
     let torrent_id = torrent_id_res?;
 
     let mut warnings = WarningCollection::new();
 
-    let now = Utc::now();
+    let has_personal_freeleech = arc
+        .personal_freeleeches
+        .read()
+        .is_active(user_id, torrent_id, now);
 
     // check and deduct bonus points snatch cost for new leeches BEFORE acquiring the main lock
     // this avoids holding the lock across an async database call
@@ -201,9 +485,14 @@ async fn handle(
                 .ok_or(AnnounceError::TorrentNotFound)?;
 
             if torrent.is_deleted {
-                return Err(AnnounceError::TorrentIsDeleted);
+                log_unregistered_info_hash(arc, user_id, ann.info_hash, Some(torrent_id), now);
+                return Err(torrent_is_deleted(arc, torrent));
             }
 
+            // a leech refused for lack of slots must not be charged, its client
+            // retries until it gets one
+            check_leeching_slots(arc, torrent, user_id, ann.peer_id)?;
+
             !torrent.peers.contains_key(&peer::Index {
                 user_id,
                 peer_id: ann.peer_id,
@@ -221,6 +510,19 @@ async fn handle(
         }
     }
 
+    // new addresses are probed in the background, the peer keeps its previous
+    // connectability until the outcome is known
+    let is_connectable = if arc.env.check_connectability && ann.event != AnnounceEvent::Stopped {
+        check_connectability(
+            arc,
+            SocketAddr::new(client_ip, ann.port),
+            &ann.info_hash,
+            now,
+        )
+    } else {
+        None
+    };
+
     let (
         upload_factor,
         download_factor,
@@ -229,12 +531,15 @@ async fn handle(
         seeder_delta,
         leecher_delta,
         times_completed_delta,
+        user_leecher_delta,
         // is_visible,
         // is_active_after_stop,
         // user,
         user_id,
+        is_connectable,
         has_requested_seed_list,
         has_requested_leech_list,
+        cheat_flags,
         response,
     ) = {
         let mut torrent_guard = arc.torrents.lock();
@@ -243,7 +548,8 @@ async fn handle(
             .ok_or(AnnounceError::TorrentNotFound)?;
 
         if torrent.is_deleted {
-            return Err(AnnounceError::TorrentIsDeleted);
+            log_unregistered_info_hash(arc, user_id, ann.info_hash, Some(torrent_id), now);
+            return Err(torrent_is_deleted(arc, torrent));
         }
 
         // Change of upload/download compared to previous announce
@@ -252,6 +558,9 @@ async fn handle(
         let seeder_delta;
         let leecher_delta;
         let times_completed_delta;
+        let previous_announce_at;
+        let swarm_downloaded_at_previous_announce;
+        let peer_is_connectable;
         // let is_visible;
         // let mut is_active_after_stop = false;
 
@@ -267,9 +576,12 @@ async fn handle(
                 // announce
                 uploaded_delta = ann.uploaded.saturating_sub(peer.uploaded);
                 downloaded_delta = ann.downloaded.saturating_sub(peer.downloaded);
+                previous_announce_at = Some(peer.updated_at);
+                swarm_downloaded_at_previous_announce = peer.swarm_downloaded_at_announce;
 
                 leecher_delta = 0 - peer.is_included_in_leech_list() as i32;
                 seeder_delta = 0 - peer.is_included_in_seed_list() as i32;
+                peer_is_connectable = peer.is_connectable;
 
                 for (&index, &peer) in torrent.peers.iter() {
                     if index.user_id == user_id && peer.is_active {
@@ -293,27 +605,46 @@ async fn handle(
                 seeder_delta = 0;
                 uploaded_delta = 0;
                 downloaded_delta = 0;
+                previous_announce_at = None;
+                swarm_downloaded_at_previous_announce = None;
+                peer_is_connectable = false;
             }
 
             times_completed_delta = 0;
             // is_visible = false;
         } else {
+            let index = peer::Index {
+                user_id,
+                peer_id: ann.peer_id,
+            };
+            let old_peer: Option<Peer> = torrent.peers.get(&index).copied();
+            let (ipv4, ipv6) = peer_addresses(
+                client_ip,
+                &ann,
+                old_peer.as_ref(),
+                arc.env.trust_announced_addresses,
+            )?;
+
+            // checked again, another client of the user may have taken the
+            // last slot since
+            if ann.left != 0 {
+                check_leeching_slots(arc, torrent, user_id, ann.peer_id)?;
+            }
+
             // Insert the peer into the in-memory db
-            let mut old_peer: Option<Peer> = None;
             let new_peer = *torrent
                 .peers
-                .entry(peer::Index {
-                    user_id,
-                    peer_id: ann.peer_id,
-                })
+                .entry(index)
                 .and_modify(|peer| {
-                    old_peer = Some(*peer);
-
                     peer.ip_address = client_ip;
+                    peer.ipv4 = ipv4;
+                    peer.ipv6 = ipv6;
+                    peer.key = ann.key;
                     peer.port = ann.port;
                     peer.is_seeder = ann.left == 0;
                     // peer.is_visible = peer.is_included_in_leech_list();
                     peer.is_active = true;
+                    peer.is_connectable = is_connectable.unwrap_or(peer.is_connectable);
                     peer.has_sent_completed =
                         peer.has_sent_completed || ann.event == AnnounceEvent::Completed;
                     peer.updated_at = now;
@@ -322,17 +653,31 @@ async fn handle(
                 })
                 .or_insert(peer::Peer {
                     ip_address: client_ip,
+                    ipv4,
+                    ipv6,
+                    key: ann.key,
                     port: ann.port,
                     is_seeder: ann.left == 0,
                     is_active: true,
+                    is_connectable: is_connectable.unwrap_or(false),
                     // is_visible: true,
                     has_sent_completed: ann.event == AnnounceEvent::Completed,
                     updated_at: now,
                     uploaded: ann.uploaded,
                     downloaded: ann.downloaded,
+                    swarm_downloaded_at_announce: Some(torrent.downloaded_by_peers),
                 });
 
             // is_visible = new_peer.is_visible;
+            peer_is_connectable = new_peer.is_connectable;
+
+            if old_peer.is_none() {
+                arc.torrent_ids_by_user
+                    .write()
+                    .entry(user_id)
+                    .or_default()
+                    .insert(torrent_id);
+            }
 
             // Update the user and torrent seeding/leeching counts in the
             // in-memory db
@@ -343,6 +688,8 @@ async fn handle(
                     seeder_delta = new_peer.is_included_in_seed_list() as i32
                         - old_peer.is_included_in_seed_list() as i32;
                     times_completed_delta = (new_peer.is_seeder && !old_peer.is_seeder) as u32;
+                    previous_announce_at = Some(old_peer.updated_at);
+                    swarm_downloaded_at_previous_announce = old_peer.swarm_downloaded_at_announce;
 
                     // Calculate change in upload and download compared to previous
                     // announce
@@ -432,10 +779,70 @@ async fn handle(
                     // Since this is a new peer, they have no upload or download history
                     uploaded_delta = 0;
                     downloaded_delta = 0;
+                    previous_announce_at = None;
+                    swarm_downloaded_at_previous_announce = None;
                 }
             }
         }
 
+        // Flag the upload that the swarm could not have received
+        let mut cheat_flags = Vec::new();
+        if let Some(previous_announce_at) = previous_announce_at
+            && uploaded_delta > 0
+        {
+            let seconds_since_last_announce =
+                (now - previous_announce_at).num_seconds().max(0) as u64;
+            let swarm = Swarm::excluding_user(
+                user_id,
+                torrent.peers.iter(),
+                swarm_downloaded_at_previous_announce
+                    .map(|baseline| torrent.downloaded_by_peers.saturating_sub(baseline)),
+            );
+            for (reason, severity) in detect_impossible_upload(
+                &arc.env,
+                uploaded_delta,
+                seconds_since_last_announce,
+                swarm,
+            ) {
+                cheat_flags.push((
+                    cheat_flag::Index {
+                        user_id,
+                        torrent_id,
+                        peer_id: ann.peer_id,
+                        reason,
+                        created_at: now,
+                    },
+                    CheatFlag {
+                        ip: client_ip,
+                        severity,
+                        uploaded_delta,
+                        seconds_since_last_announce,
+                        swarm_leechers: swarm.leechers,
+                        swarm_downloaded: swarm.downloaded.unwrap_or(0),
+                        credit_withheld: false,
+                    },
+                ));
+            }
+        }
+
+        // The download of the peer is only counted once its upload was
+        // checked, it can't have downloaded from itself
+        torrent.downloaded_by_peers = torrent.downloaded_by_peers.saturating_add(downloaded_delta);
+        if let Some(peer) = torrent.peers.get_mut(&peer::Index {
+            user_id,
+            peer_id: ann.peer_id,
+        }) {
+            peer.swarm_downloaded_at_announce = Some(torrent.downloaded_by_peers);
+        }
+
+        // A user leeching a torrent with several clients uses a single slot
+        let user_leecher_delta =
+            if leecher_delta != 0 && is_leeching_with_another_peer(torrent, user_id, ann.peer_id) {
+                0
+            } else {
+                leecher_delta
+            };
+
         // Has to be adjusted before the peer list is generated
         torrent.seeders = torrent.seeders.saturating_add_signed(seeder_delta);
         torrent.leechers = torrent.leechers.saturating_add_signed(leecher_delta);
@@ -444,8 +851,7 @@ async fn handle(
             .saturating_add(times_completed_delta);
 
         // Generate peer lists to return to client
-        let mut peers_ipv4: Vec<u8> = Vec::new();
-        let mut peers_ipv6: Vec<u8> = Vec::new();
+        let mut response_peers: Vec<ResponsePeer> = Vec::new();
 
         let mut has_requested_seed_list = false;
         let mut has_requested_leech_list = false;
@@ -465,15 +871,22 @@ async fn handle(
                 index.user_id != user_id && peer.is_included_in_peer_list()
             });
 
-            // Make sure leech peer lists are filled with seeds
+            // Make sure leech peer lists are filled with seeds, the ones
+            // accepting incoming connections first
             if ann.left > 0 && torrent.seeders > 0 && ann.numwant > peers.len() {
                 has_requested_seed_list = true;
                 peers.extend(
                     valid_peers
                         .clone()
-                        .filter(|(_index, peer)| peer.is_seeder)
+                        .filter(|(_index, peer)| peer.is_seeder && peer.is_connectable)
                         .choose_multiple(&mut rng(), ann.numwant),
                 );
+                peers.extend(
+                    valid_peers
+                        .clone()
+                        .filter(|(_index, peer)| peer.is_seeder && !peer.is_connectable)
+                        .choose_multiple(&mut rng(), ann.numwant.saturating_sub(peers.len())),
+                );
             }
 
             // Otherwise only send leeches until the numwant is reached
@@ -486,85 +899,51 @@ async fn handle(
                 );
             }
 
-            // Split peers into ipv4 and ipv6 variants and serialize their socket
-            // to bytes according to the bittorrent spec
-            for (_index, peer) in peers.iter() {
-                match peer.ip_address {
-                    IpAddr::V4(ip) => {
-                        peers_ipv4.extend(&ip.octets());
-                        peers_ipv4.extend(&peer.port.to_be_bytes());
-                    }
-                    IpAddr::V6(ip) => {
-                        peers_ipv6.extend(&ip.octets());
-                        peers_ipv6.extend(&peer.port.to_be_bytes());
-                    }
+            // Dual-stack peers are given out with both of their addresses
+            for (index, peer) in peers.iter() {
+                let addresses = peer
+                    .ipv4
+                    .map(IpAddr::V4)
+                    .into_iter()
+                    .chain(peer.ipv6.map(IpAddr::V6));
+                for ip in addresses {
+                    response_peers.push(ResponsePeer {
+                        peer_id: index.peer_id,
+                        ip,
+                        port: peer.port,
+                    });
                 }
             }
         }
 
-        // Write out bencoded response (keys must be sorted to be within spec)
-        let mut response: Vec<u8> = Vec::with_capacity(
-            82 // literal characters
-            + 5 * 5 // numbers with estimated digit quantity for each
-            + peers_ipv4.len() * 6 + 5 // bytes per ipv4 plus estimated length prefix
-            + peers_ipv6.len() * 18 + 5 // bytes per ipv6 plus estimated length prefix
-            + warnings.max_byte_length(), // max bytes per warning message plus separator
-        );
-
-        response.extend(b"d8:completei");
-        if !warnings.is_empty() {
-            response.extend(b"0")
-        } else {
-            response.extend(torrent.seeders.to_string().as_bytes());
-        }
-        response.extend(b"e10:downloadedi");
-        response.extend(torrent.times_completed.to_string().as_bytes());
-        response.extend(b"e10:incompletei");
-        if !warnings.is_empty() {
-            response.extend(b"0");
-        } else {
-            response.extend(torrent.leechers.to_string().as_bytes());
-        }
-
-        response.extend(b"e8:intervali");
-        let interval = rng().random_range(arc.env.announce_min..=arc.env.announce_max);
-        response.extend(interval.to_string().as_bytes());
-        response.extend(b"e12:min intervali");
-        response.extend(arc.env.announce_min.to_string().as_bytes());
-        response.extend(b"e5:peers");
-
-        if peers_ipv4.is_empty() {
-            response.extend(b"0:")
-        } else {
-            response.extend(peers_ipv4.len().to_string().as_bytes());
-            response.extend(b":");
-            response.extend(&peers_ipv4);
-        }
-
-        if !peers_ipv6.is_empty() {
-            response.extend(b"6:peers6");
-            response.extend(peers_ipv6.len().to_string().as_bytes());
-            response.extend(b":");
-            response.extend(peers_ipv6);
-        }
-
-        if let Some(warning_message) = warnings.into_message() {
-            response.extend(b"15:warning message");
-            response.extend(warning_message.len().to_string().as_bytes());
-            response.extend(b":");
-            response.extend(warning_message);
-        }
-
-        response.extend(b"e");
+        let response = AnnounceResponse {
+            seeders: torrent.seeders,
+            leechers: torrent.leechers,
+            times_completed: torrent.times_completed,
+            peers: response_peers,
+            warnings,
+        };
 
-        let upload_factor = std::cmp::max(
+        let mut upload_factor = std::cmp::max(
             arc.settings.read().global_upload_factor,
             torrent.upload_factor,
         );
-        let download_factor = std::cmp::min(
-            arc.settings.read().global_download_factor,
-            torrent.download_factor,
-        );
+        let mut download_factor = if has_personal_freeleech {
+            0
+        } else {
+            std::cmp::min(
+                arc.settings.read().global_download_factor,
+                torrent.download_factor,
+            )
+        };
+        if let Some((event_upload_factor, event_download_factor)) = arc
+            .promotion_events
+            .read()
+            .best_active_for(&torrent.scope, now)
+        {
+            upload_factor = std::cmp::max(upload_factor, event_upload_factor);
+            download_factor = std::cmp::min(download_factor, event_download_factor);
+        }
 
         // Has to be dropped before any `await` calls.
         //
@@ -586,17 +965,48 @@ async fn handle(
             seeder_delta,
             leecher_delta,
             times_completed_delta,
+            user_leecher_delta,
             // is_visible,
             // is_active_after_stop,
             // user,
             user_id,
+            peer_is_connectable,
             has_requested_seed_list,
             has_requested_leech_list,
+            cheat_flags,
             response,
         )
     };
 
-    let credited_uploaded_delta = upload_factor as u64 * uploaded_delta / 100;
+    let credit_withheld = arc
+        .settings
+        .read()
+        .cheat_flag_withhold_credit_min_severity
+        .is_some_and(|min_severity| {
+            cheat_flags
+                .iter()
+                .any(|(_, flag)| flag.severity >= min_severity)
+        });
+
+    if !cheat_flags.is_empty() {
+        log::warn!(
+            "Flagged announce of user {} on torrent {}, credit withheld: {}",
+            user_id,
+            torrent_id,
+            credit_withheld
+        );
+        let mut cheat_flag_queue = arc.cheat_flags.lock();
+        for (index, mut flag) in cheat_flags {
+            flag.credit_withheld = credit_withheld;
+            cheat_flag_queue.upsert(index, flag);
+        }
+    }
+
+    let credited_uploaded_delta = if credit_withheld {
+        0
+    } else {
+        upload_factor as u64 * uploaded_delta / 100
+    };
     let credited_downloaded_delta = download_factor as u64 * downloaded_delta / 100;
 
     let completed_at = if ann.event == AnnounceEvent::Completed {
@@ -606,13 +1016,13 @@ async fn handle(
     };
 
     if seeder_delta != 0
-        || leecher_delta != 0
+        || user_leecher_delta != 0
         || has_requested_seed_list
         || has_requested_leech_list
     {
         arc.users.write().entry(user_id).and_modify(|user| {
             user.num_seeding = user.num_seeding.saturating_add_signed(seeder_delta);
-            user.num_leeching = user.num_leeching.saturating_add_signed(leecher_delta);
+            user.num_leeching = user.num_leeching.saturating_add_signed(user_leecher_delta);
 
             // TODO: setup seed/leech lists getting rate limiting
             // has been partially done in unit3d-announce
@@ -635,11 +1045,12 @@ async fn handle(
         PeerUpdate {
             ip: client_ip,
             port: ann.port,
-            agent: user_agent.0,
+            agent: user_agent,
             uploaded: ann.uploaded,
             downloaded: ann.downloaded,
             is_active: ann.event != AnnounceEvent::Stopped,
             is_seeder: ann.left == 0,
+            is_connectable,
             left: ann.left,
             created_at: now,
             updated_at: now,
@@ -679,10 +1090,5 @@ async fn handle(
             },
         );
     }
-    debug!(
-        "Announce response: {:?}",
-        String::from_utf8_lossy(&response)
-    );
-
-    Ok(HttpResponse::Ok().body(response))
+    Ok(response)
 }
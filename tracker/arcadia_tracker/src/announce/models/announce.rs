@@ -1,8 +1,9 @@
 use actix_web::{dev, web::Data, FromRequest, HttpRequest};
-use arcadia_shared::tracker::models::{peer_id::PeerId, torrent::InfoHash};
+use arcadia_shared::tracker::models::{peer::PeerKey, peer_id::PeerId, torrent::InfoHash};
 use std::{
     borrow::Cow,
     future::{self, Ready},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     str::FromStr,
 };
 
@@ -27,9 +28,14 @@ pub struct Announce {
     #[allow(dead_code)]
     pub numwant: usize,
     // corrupt: Option<u64>,
-    // key: Option<String>,
+    /// Addresses of the client given with the `ip`, `ipv4` and `ipv6` params
+    /// (BEP 7), trusted only along with the key
+    pub ipv4: Option<Ipv4Addr>,
+    pub ipv6: Option<Ipv6Addr>,
+    pub key: Option<PeerKey>,
     #[allow(dead_code)]
     pub compact: Option<bool>,
+    pub no_peer_id: bool,
 }
 
 impl FromRequest for Announce {
@@ -68,8 +74,8 @@ impl FromStr for AnnounceEvent {
     }
 }
 
-struct QueryPairs<'a> {
-    input: &'a str,
+pub(crate) struct QueryPairs<'a> {
+    pub(crate) input: &'a str,
 }
 
 impl<'a> Iterator for QueryPairs<'a> {
@@ -95,9 +101,9 @@ impl<'a> Iterator for QueryPairs<'a> {
     }
 }
 
-struct IncorrectLength;
+pub(crate) struct IncorrectLength;
 
-fn decode_into_20_byte_array(value: &str) -> Result<[u8; 20], IncorrectLength> {
+pub(crate) fn decode_into_20_byte_array(value: &str) -> Result<[u8; 20], IncorrectLength> {
     let x = Cow::from(percent_encoding::percent_decode_str(value));
 
     if x.len() != 20 {
@@ -110,6 +116,18 @@ fn decode_into_20_byte_array(value: &str) -> Result<[u8; 20], IncorrectLength> {
     }))
 }
 
+/// Parses an address param, which may also be given as `addr:port` or
+/// `[addr]:port`, the port being the one of the `port` param anyway
+fn decode_ip_addr(value: &str) -> Result<IpAddr, AnnounceError> {
+    let value = percent_encoding::percent_decode_str(value).decode_utf8_lossy();
+
+    IpAddr::from_str(&value).or_else(|error| {
+        SocketAddr::from_str(&value)
+            .map(|socket_addr| socket_addr.ip())
+            .map_err(|_| AnnounceError::InvalidIpAddr(error))
+    })
+}
+
 pub fn decode_from_query_str(query: &str, req: &HttpRequest) -> Result<Announce, AnnounceError> {
     let mut info_hash = Option::<[u8; 20]>::None;
     let mut peer_id = Option::<[u8; 20]>::None;
@@ -119,7 +137,12 @@ pub fn decode_from_query_str(query: &str, req: &HttpRequest) -> Result<Announce,
     let mut left = Option::<u64>::None;
     let mut event = Option::<AnnounceEvent>::None;
     let mut compact = Option::<bool>::None;
+    let mut no_peer_id = false;
     let mut numwant = Option::<usize>::None;
+    let mut ip = Option::<IpAddr>::None;
+    let mut ipv4 = Option::<Ipv4Addr>::None;
+    let mut ipv6 = Option::<Ipv6Addr>::None;
+    let mut key = Option::<PeerKey>::None;
 
     let pairs = QueryPairs { input: query };
 
@@ -153,19 +176,48 @@ pub fn decode_from_query_str(query: &str, req: &HttpRequest) -> Result<Announce,
             }
             "compact" => match value {
                 "1" => compact = Some(true),
-                "0" => return Err(AnnounceError::UnsupportedCompact),
+                "0" => compact = Some(false),
                 _ => return Err(AnnounceError::InvalidCompact),
             },
+            "no_peer_id" => match value {
+                "1" => no_peer_id = true,
+                "0" => no_peer_id = false,
+                _ => return Err(AnnounceError::InvalidNoPeerId),
+            },
 
             "numwant" => {
                 numwant = Some(usize::from_str(value).map_err(AnnounceError::InvalidNumWant)?);
             }
 
-            // key?
+            "ip" => {
+                ip = Some(decode_ip_addr(value)?);
+            }
+            "ipv4" => match decode_ip_addr(value)?.to_canonical() {
+                IpAddr::V4(addr) => ipv4 = Some(addr),
+                IpAddr::V6(_) => return Err(AnnounceError::InvalidIpv4Addr),
+            },
+            "ipv6" => match decode_ip_addr(value)? {
+                IpAddr::V6(addr) => ipv6 = Some(addr),
+                IpAddr::V4(_) => return Err(AnnounceError::InvalidIpv6Addr),
+            },
+            "key" => {
+                key = Some(PeerKey::from_http_param(&Cow::from(
+                    percent_encoding::percent_decode_str(value),
+                )));
+            }
+
             _ => continue,
         };
     }
 
+    // The legacy `ip` param is only used when its address family isn't
+    // explicitly given
+    match ip.map(|ip| ip.to_canonical()) {
+        Some(IpAddr::V4(addr)) => ipv4 = ipv4.or(Some(addr)),
+        Some(IpAddr::V6(addr)) => ipv6 = ipv6.or(Some(addr)),
+        None => {}
+    }
+
     let arc = req.app_data::<Data<Tracker>>().expect("app data set");
 
     Ok(Announce {
@@ -176,7 +228,11 @@ pub fn decode_from_query_str(query: &str, req: &HttpRequest) -> Result<Announce,
         downloaded: downloaded.ok_or(AnnounceError::MissingDownloaded)?,
         left: left.ok_or(AnnounceError::MissingLeft)?,
         event: event.unwrap_or_default(),
+        ipv4,
+        ipv6,
+        key,
         compact,
+        no_peer_id,
         numwant: {
             if event.unwrap_or_default() == AnnounceEvent::Stopped {
                 0
@@ -1,5 +1,31 @@
-use utoipa::OpenApi;
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    Modify, OpenApi,
+};
 
 #[derive(OpenApi)]
-#[openapi(info(title = "arcadia-tracker API",), paths(), components(schemas(),))]
+#[openapi(
+    info(title = "arcadia-tracker API",),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::handlers::torrents::get_torrent::exec,
+        crate::handlers::users::get_user::exec,
+        crate::handlers::state::get_flush_queues::exec,
+        crate::handlers::tasks::run_flush::exec,
+        crate::handlers::tasks::run_reap::exec,
+    ),
+    components(schemas(),)
+)]
 pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-api-key"))),
+        )
+    }
+}
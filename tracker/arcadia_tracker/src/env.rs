@@ -1,5 +1,6 @@
+use arcadia_shared::tracker::models::RetryPolicy;
 use envconfig::Envconfig;
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, path::PathBuf, str::FromStr, time::Duration};
 
 #[derive(Debug, Envconfig, Clone)]
 pub struct Env {
@@ -19,8 +20,32 @@ pub struct Env {
     pub announce_max: u32,
     #[envconfig(from = "MAX_PEERS_PER_TORRENT_PER_USER")]
     pub max_peers_per_torrent_per_user: u8,
+    #[envconfig(from = "CHEAT_DETECTION_MAX_UPLOAD_SPEED")]
+    pub cheat_detection_max_upload_speed: u64,
+    #[envconfig(from = "CHEAT_DETECTION_MIN_UPLOADED_DELTA")]
+    pub cheat_detection_min_uploaded_delta: u64,
+    #[envconfig(from = "TRUST_ANNOUNCED_ADDRESSES", default = "false")]
+    pub trust_announced_addresses: bool,
+    #[envconfig(from = "CHECK_CONNECTABILITY")]
+    pub check_connectability: bool,
+    #[envconfig(from = "CONNECTABILITY_CHECK_TIMEOUT_MILLISECONDS")]
+    pub connectability_check_timeout_milliseconds: u64,
+    #[envconfig(from = "CONNECTABILITY_CACHE_TTL")]
+    pub connectability_cache_ttl: u64,
+    #[envconfig(from = "CONNECTABILITY_MAX_PROBES_IN_FLIGHT")]
+    pub connectability_max_probes_in_flight: usize,
+    #[envconfig(from = "MAX_UNKNOWN_INFO_HASHES_PER_USER_PER_HOUR")]
+    pub max_unknown_info_hashes_per_user_per_hour: usize,
+    #[envconfig(from = "UDP_MAX_REQUESTS_IN_FLIGHT", default = "1024")]
+    pub udp_max_requests_in_flight: usize,
     #[envconfig(from = "FLUSH_INTERVAL_MILLISECONDS")]
     pub flush_interval_milliseconds: u64,
+    #[envconfig(from = "FLUSH_RETRY_MAX_ATTEMPTS")]
+    pub flush_retry_max_attempts: u32,
+    #[envconfig(from = "FLUSH_RETRY_MAX_DELAY_MILLISECONDS")]
+    pub flush_retry_max_delay_milliseconds: u64,
+    #[envconfig(from = "FLUSH_SPILL_DIRECTORY")]
+    pub flush_spill_directory: Option<String>,
     #[envconfig(from = "PEER_EXPIRY_INTERVAL")]
     pub peer_expiry_interval: u64,
     #[envconfig(from = "REVERSE_PROXY_CLIENT_IP_HEADER_NAME")]
@@ -29,10 +54,28 @@ pub struct Env {
     pub inactive_peer_ttl: u64,
     #[envconfig(from = "ACTIVE_PEER_TTL")]
     pub active_peer_ttl: u64,
+    #[envconfig(from = "ARCADIA_FRONTEND_URL")]
+    pub arcadia_frontend_url: Option<String>,
     #[envconfig(from = "OTEL_SERVICE_NAME")]
     pub otel_service_name: Option<String>,
 }
 
+impl Env {
+    /// Retry policy of a flush queue, its records are spilled to
+    /// `<FLUSH_SPILL_DIRECTORY>/<queue_name>.jsonl`
+    pub fn flush_retry_policy(&self, queue_name: &str) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.flush_retry_max_attempts,
+            base_delay: Duration::from_millis(self.flush_interval_milliseconds),
+            max_delay: Duration::from_millis(self.flush_retry_max_delay_milliseconds),
+            spill_file: self
+                .flush_spill_directory
+                .as_ref()
+                .map(|directory| PathBuf::from(directory).join(format!("{queue_name}.jsonl"))),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("env variable parse error '{0}'")]
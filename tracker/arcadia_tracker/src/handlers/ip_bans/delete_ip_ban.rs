@@ -0,0 +1,17 @@
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use log::info;
+
+use crate::Tracker;
+
+pub async fn exec(arc: Data<Tracker>, path: Path<i64>) -> HttpResponse {
+    let ip_ban_id = path.into_inner();
+
+    info!("Removing ip ban {ip_ban_id}.");
+
+    arc.ip_bans.write().shift_remove(&ip_ban_id);
+
+    HttpResponse::Ok().finish()
+}
@@ -0,0 +1,2 @@
+pub mod delete_ip_ban;
+pub mod upsert_ip_ban;
@@ -0,0 +1,25 @@
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_shared::tracker::models::ip_ban::{APIInsertIpBan, IpBan};
+use log::info;
+
+use crate::Tracker;
+
+pub async fn exec(arc: Data<Tracker>, ip_ban: Json<APIInsertIpBan>) -> HttpResponse {
+    info!(
+        "Inserting ip ban {} on {} until {:?}",
+        ip_ban.id, ip_ban.ip_range, ip_ban.expires_at
+    );
+
+    arc.ip_bans.write().insert(
+        ip_ban.id,
+        IpBan {
+            ip_range: ip_ban.ip_range,
+            expires_at: ip_ban.expires_at,
+        },
+    );
+
+    HttpResponse::Ok().finish()
+}
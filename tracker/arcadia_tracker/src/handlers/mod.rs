@@ -1,3 +1,8 @@
+pub mod ip_bans;
+pub mod personal_freeleeches;
+pub mod promotion_events;
 pub mod settings;
+pub mod state;
+pub mod tasks;
 pub mod torrents;
 pub mod users;
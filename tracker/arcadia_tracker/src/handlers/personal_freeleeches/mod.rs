@@ -0,0 +1 @@
+pub mod upsert_personal_freeleech;
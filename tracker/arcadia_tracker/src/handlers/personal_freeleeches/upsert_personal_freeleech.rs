@@ -0,0 +1,28 @@
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_shared::tracker::models::personal_freeleech::{APIInsertPersonalFreeleech, Index};
+use log::info;
+
+use crate::Tracker;
+
+pub async fn exec(
+    arc: Data<Tracker>,
+    personal_freeleech: Json<APIInsertPersonalFreeleech>,
+) -> HttpResponse {
+    info!(
+        "Inserting personal freeleech for user {} on torrent {} until {}",
+        personal_freeleech.user_id, personal_freeleech.torrent_id, personal_freeleech.expires_at
+    );
+
+    arc.personal_freeleeches.write().insert(
+        Index {
+            user_id: personal_freeleech.user_id,
+            torrent_id: personal_freeleech.torrent_id,
+        },
+        personal_freeleech.expires_at,
+    );
+
+    HttpResponse::Ok().finish()
+}
@@ -0,0 +1,19 @@
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use log::info;
+
+use crate::Tracker;
+
+pub async fn exec(arc: Data<Tracker>, path: Path<i64>) -> HttpResponse {
+    let promotion_event_id = path.into_inner();
+
+    info!("Removing promotion event {promotion_event_id}.");
+
+    arc.promotion_events
+        .write()
+        .shift_remove(&promotion_event_id);
+
+    HttpResponse::Ok().finish()
+}
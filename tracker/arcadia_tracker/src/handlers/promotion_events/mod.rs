@@ -0,0 +1,2 @@
+pub mod delete_promotion_event;
+pub mod upsert_promotion_event;
@@ -0,0 +1,31 @@
+use actix_web::{
+    web::{Data, Json},
+    HttpResponse,
+};
+use arcadia_shared::tracker::models::promotion_event::APIInsertPromotionEvent;
+use log::info;
+
+use crate::Tracker;
+
+pub async fn exec(
+    arc: Data<Tracker>,
+    promotion_event: Json<APIInsertPromotionEvent>,
+) -> HttpResponse {
+    let promotion_event = promotion_event.into_inner();
+
+    info!(
+        "Inserting promotion event {} from {} to {} with factors {}/{} on {:?}",
+        promotion_event.id,
+        promotion_event.starts_at,
+        promotion_event.ends_at,
+        promotion_event.upload_factor,
+        promotion_event.download_factor,
+        promotion_event.scope
+    );
+
+    arc.promotion_events
+        .write()
+        .insert(promotion_event.id, promotion_event.into());
+
+    HttpResponse::Ok().finish()
+}
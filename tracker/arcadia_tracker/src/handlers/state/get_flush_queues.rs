@@ -0,0 +1,47 @@
+use actix_web::{web::Data, HttpResponse};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{metrics::flush_queues, Tracker};
+
+/// A queue of updates waiting to be flushed to the database
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FlushQueue {
+    pub name: String,
+    /// Records currently waiting in the queue
+    pub queued: u64,
+    /// Since startup, put back in the queue after a failed flush
+    pub retried: u64,
+    /// Since startup, written to the spill file
+    pub spilled: u64,
+    /// Since startup, read back from the spill file
+    pub restored: u64,
+    /// Since startup, lost because they could not be flushed nor spilled
+    pub dropped: u64,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get flush queues",
+    tag = "State",
+    path = "/api/flush-queues",
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The depth and stats of every flush queue", body = Vec<FlushQueue>),
+    )
+)]
+pub async fn exec(arc: Data<Tracker>) -> HttpResponse {
+    let queues: Vec<FlushQueue> = flush_queues(&arc)
+        .into_iter()
+        .map(|(name, queued, stats)| FlushQueue {
+            name: name.to_owned(),
+            queued,
+            retried: stats.retried,
+            spilled: stats.spilled,
+            restored: stats.restored,
+            dropped: stats.dropped,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(queues)
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use actix_web::{web::Data, HttpResponse};
+use arcadia_shared::tracker::models::state_digest::StateDigest;
+use chrono::Utc;
+
+use crate::Tracker;
+
+/// Summary of the state synced from the backend, which compares it with the
+/// database to send again what the tracker missed
+pub async fn exec(arc: Data<Tracker>) -> HttpResponse {
+    let now = Utc::now();
+    let mut digest = StateDigest::default();
+
+    let passkeys: HashMap<_, _> = arc
+        .passkey2id
+        .read()
+        .iter()
+        .map(|(passkey, user_id)| (*user_id, *passkey))
+        .collect();
+    for (user_id, user) in arc.users.read().iter() {
+        digest.add_user(
+            *user_id,
+            passkeys.get(user_id),
+            user.max_snatches_per_day,
+            user.max_leeching,
+        );
+    }
+
+    let mut info_hashes: HashMap<_, Vec<_>> = HashMap::new();
+    for (info_hash, torrent_id) in arc.infohash2id.read().iter() {
+        info_hashes.entry(*torrent_id).or_default().push(*info_hash);
+    }
+    for (torrent_id, torrent) in arc.torrents.lock().iter() {
+        digest.add_torrent(
+            *torrent_id,
+            info_hashes.get(torrent_id).into_iter().flatten(),
+            torrent.is_deleted,
+            torrent.deletion_reason,
+            torrent.replacement_torrent_id,
+            torrent.upload_factor,
+            torrent.download_factor,
+            &torrent.scope,
+        );
+    }
+
+    for (index, expires_at) in arc.personal_freeleeches.read().iter() {
+        if *expires_at > now {
+            digest.add_personal_freeleech(index.user_id, index.torrent_id, *expires_at);
+        }
+    }
+
+    for (ip_ban_id, ip_ban) in arc.ip_bans.read().iter() {
+        if ip_ban.expires_at.is_none_or(|expires_at| expires_at > now) {
+            digest.add_ip_ban(*ip_ban_id, ip_ban.ip_range, ip_ban.expires_at);
+        }
+    }
+
+    for (promotion_event_id, promotion_event) in arc.promotion_events.read().iter() {
+        if promotion_event.ends_at > now {
+            digest.add_promotion_event(
+                *promotion_event_id,
+                promotion_event.starts_at,
+                promotion_event.ends_at,
+                promotion_event.upload_factor,
+                promotion_event.download_factor,
+                &promotion_event.scope,
+            );
+        }
+    }
+
+    digest.set_settings(&arc.settings.read());
+
+    HttpResponse::Ok().json(digest)
+}
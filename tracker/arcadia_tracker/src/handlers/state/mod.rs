@@ -0,0 +1,2 @@
+pub mod get_flush_queues;
+pub mod get_state_digest;
@@ -0,0 +1,2 @@
+pub mod run_flush;
+pub mod run_reap;
@@ -0,0 +1,24 @@
+use actix_web::{web::Data, HttpResponse};
+use log::info;
+
+use crate::{scheduler, Tracker};
+
+#[utoipa::path(
+    post,
+    operation_id = "Run flush",
+    tag = "Task",
+    path = "/api/tasks/flush",
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The queued updates were flushed, see the flush queues for the ones that failed"),
+    )
+)]
+pub async fn exec(arc: Data<Tracker>) -> HttpResponse {
+    info!("Flushing the queued updates on request.");
+
+    // don't wait for the end of the backoff of failed flushes
+    scheduler::retry_now(&arc);
+    scheduler::flush(&arc).await;
+
+    HttpResponse::Ok().finish()
+}
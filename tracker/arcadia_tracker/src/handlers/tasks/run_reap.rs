@@ -0,0 +1,20 @@
+use actix_web::{web::Data, HttpResponse};
+use log::info;
+
+use crate::{scheduler, Tracker};
+
+#[utoipa::path(
+    post,
+    operation_id = "Run reap",
+    tag = "Task",
+    path = "/api/tasks/reap",
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The amount of peers removed", body = u64),
+    )
+)]
+pub async fn exec(arc: Data<Tracker>) -> HttpResponse {
+    info!("Reaping the peers on request.");
+
+    HttpResponse::Ok().json(scheduler::reap(&arc).await)
+}
@@ -0,0 +1,111 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use arcadia_shared::tracker::models::torrent::{InfoHash, TorrentDeletionReason};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::Tracker;
+
+/// A torrent as the tracker currently holds it in memory
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackerTorrent {
+    pub id: u32,
+    /// The ones the torrent can be announced with, in hex
+    pub info_hashes: Vec<String>,
+    pub is_deleted: bool,
+    pub deletion_reason: Option<TorrentDeletionReason>,
+    pub replacement_torrent_id: Option<u32>,
+    pub upload_factor: i16,
+    pub download_factor: i16,
+    pub seeders: u32,
+    pub leechers: u32,
+    pub times_completed: u32,
+    pub peers: Vec<TrackerPeer>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackerPeer {
+    pub user_id: u32,
+    /// In hex
+    pub peer_id: String,
+    #[schema(value_type = String)]
+    pub ip_address: IpAddr,
+    #[schema(value_type = Option<String>)]
+    pub ipv4: Option<Ipv4Addr>,
+    #[schema(value_type = Option<String>)]
+    pub ipv6: Option<Ipv6Addr>,
+    pub port: u16,
+    pub is_seeder: bool,
+    pub is_active: bool,
+    pub is_connectable: bool,
+    pub has_sent_completed: bool,
+    pub uploaded: u64,
+    pub downloaded: u64,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get torrent",
+    tag = "Torrent",
+    path = "/api/torrents/{id}",
+    params(("id" = u32, Path, description = "Torrent id")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The torrent and its peers", body = TrackerTorrent),
+        (status = 404, description = "The tracker doesn't know the torrent"),
+    )
+)]
+pub async fn exec(arc: Data<Tracker>, path: Path<u32>) -> HttpResponse {
+    let torrent_id = path.into_inner();
+
+    let info_hashes = arc
+        .info_hashes_by_torrent
+        .read()
+        .get(&torrent_id)
+        .map(|info_hashes| info_hashes.iter().map(InfoHash::to_string).collect())
+        .unwrap_or_default();
+
+    let torrents = arc.torrents.lock();
+    let Some(torrent) = torrents.get(&torrent_id) else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    HttpResponse::Ok().json(TrackerTorrent {
+        id: torrent_id,
+        info_hashes,
+        is_deleted: torrent.is_deleted,
+        deletion_reason: torrent.deletion_reason,
+        replacement_torrent_id: torrent.replacement_torrent_id,
+        upload_factor: torrent.upload_factor,
+        download_factor: torrent.download_factor,
+        seeders: torrent.seeders,
+        leechers: torrent.leechers,
+        times_completed: torrent.times_completed,
+        peers: torrent
+            .peers
+            .iter()
+            .map(|(index, peer)| TrackerPeer {
+                user_id: index.user_id,
+                peer_id: index.peer_id.to_string(),
+                ip_address: peer.ip_address,
+                ipv4: peer.ipv4,
+                ipv6: peer.ipv6,
+                port: peer.port,
+                is_seeder: peer.is_seeder,
+                is_active: peer.is_active,
+                is_connectable: peer.is_connectable,
+                has_sent_completed: peer.has_sent_completed,
+                uploaded: peer.uploaded,
+                downloaded: peer.downloaded,
+                updated_at: peer.updated_at,
+            })
+            .collect(),
+    })
+}
@@ -1,3 +1,4 @@
 pub mod delete_torrent;
+pub mod get_torrent;
 pub mod update_torrent_factors;
 pub mod upsert_torrent;
@@ -11,26 +11,46 @@ use log::{debug, info};
 use crate::Tracker;
 
 pub async fn exec(arc: Data<Tracker>, torrent: Json<APIInsertTorrent>) -> HttpResponse {
-    info!("Inserting torrent with id {}.", torrent.id);
+    info!("Upserting torrent with id {}.", torrent.id);
 
-    arc.torrents.lock().insert(
-        torrent.id,
-        Torrent {
+    // the backend may send the same torrent again, its peers are kept
+    arc.torrents
+        .lock()
+        .entry(torrent.id)
+        .and_modify(|existing| {
+            existing.is_deleted = torrent.is_deleted;
+            existing.deletion_reason = torrent.deletion_reason;
+            existing.replacement_torrent_id = torrent.replacement_torrent_id;
+            existing.download_factor = torrent.download_factor;
+            existing.upload_factor = torrent.upload_factor;
+            existing.scope = torrent.scope.clone();
+        })
+        .or_insert_with(|| Torrent {
             is_deleted: torrent.is_deleted,
+            deletion_reason: torrent.deletion_reason,
+            replacement_torrent_id: torrent.replacement_torrent_id,
             seeders: torrent.seeders,
             leechers: torrent.leechers,
             times_completed: torrent.times_completed,
-            download_factor: torrent.download_factor as i16,
-            upload_factor: torrent.upload_factor as i16,
+            download_factor: torrent.download_factor,
+            upload_factor: torrent.upload_factor,
+            scope: torrent.scope.clone(),
             peers: peer::Map::new(),
-        },
-    );
+            downloaded_by_peers: 0,
+        });
 
-    arc.infohash2id
-        .write()
-        .insert(torrent.info_hash, torrent.id);
+    let mut infohash2id = arc.infohash2id.write();
+    let mut info_hashes_by_torrent = arc.info_hashes_by_torrent.write();
+    let info_hashes = info_hashes_by_torrent.entry(torrent.id).or_default();
+    // BEP 52 clients announce with the truncated v2 info hash, the swarm is shared
+    for info_hash in std::iter::once(torrent.info_hash).chain(torrent.info_hash_v2) {
+        infohash2id.insert(info_hash, torrent.id);
+        if !info_hashes.contains(&info_hash) {
+            info_hashes.push(info_hash);
+        }
+    }
 
-    debug!("inserted torrent: {:?}", torrent);
+    debug!("upserted torrent: {:?}", torrent);
 
     HttpResponse::Ok().finish()
 }
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::Tracker;
+
+/// A user as the tracker currently holds them in memory
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackerUser {
+    pub id: u32,
+    pub max_snatches_per_day: Option<u32>,
+    pub max_leeching: Option<u32>,
+    pub num_seeding: u32,
+    pub num_leeching: u32,
+    /// Torrents the user has an active seeding peer on
+    pub seeding: Vec<u32>,
+    /// Torrents the user has an active leeching peer on
+    pub leeching: Vec<u32>,
+    pub recent_leeches: Vec<TrackerRecentLeech>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TrackerRecentLeech {
+    pub torrent_id: u32,
+    #[schema(value_type = String, format = DateTime)]
+    pub started_at: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    get,
+    operation_id = "Get user",
+    tag = "User",
+    path = "/api/users/{id}",
+    params(("id" = u32, Path, description = "User id")),
+    security(("api_key" = [])),
+    responses(
+        (status = 200, description = "The user and the torrents they are on", body = TrackerUser),
+        (status = 404, description = "The tracker doesn't know the user"),
+    )
+)]
+pub async fn exec(arc: Data<Tracker>, path: Path<u32>) -> HttpResponse {
+    let user_id = path.into_inner();
+
+    let Some(user) = arc.users.read().get(&user_id).cloned() else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    // only the swarms of the torrents the user announced are looked at, so
+    // that announces don't wait on a walk of every swarm
+    let torrent_ids = arc
+        .torrent_ids_by_user
+        .read()
+        .get(&user_id)
+        .cloned()
+        .unwrap_or_default();
+    let mut seeding = BTreeSet::new();
+    let mut leeching = BTreeSet::new();
+    let torrents = arc.torrents.lock();
+    for torrent_id in torrent_ids {
+        let Some(torrent) = torrents.get(&torrent_id) else {
+            continue;
+        };
+        for (index, peer) in torrent.peers.iter() {
+            if index.user_id != user_id || !peer.is_active {
+                continue;
+            }
+            if peer.is_seeder {
+                seeding.insert(torrent_id);
+            } else {
+                leeching.insert(torrent_id);
+            }
+        }
+    }
+    drop(torrents);
+
+    HttpResponse::Ok().json(TrackerUser {
+        id: user_id,
+        max_snatches_per_day: user.max_snatches_per_day,
+        max_leeching: user.max_leeching,
+        num_seeding: user.num_seeding,
+        num_leeching: user.num_leeching,
+        seeding: seeding.into_iter().collect(),
+        leeching: leeching.into_iter().collect(),
+        recent_leeches: user
+            .recent_leeches
+            .iter()
+            .map(|(torrent_id, started_at)| TrackerRecentLeech {
+                torrent_id: *torrent_id,
+                started_at: DateTime::from_timestamp(*started_at, 0).unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
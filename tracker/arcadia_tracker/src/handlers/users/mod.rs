@@ -1,2 +1,3 @@
+pub mod get_user;
 pub mod update_user_max_snatches_per_day;
 pub mod upsert_user;
@@ -8,21 +8,32 @@ use log::{debug, info};
 use crate::Tracker;
 
 pub async fn exec(arc: Data<Tracker>, user: Json<APIInsertUser>) -> HttpResponse {
-    info!("Inserting user with id {}.", user.id);
+    info!("Upserting user with id {}.", user.id);
 
-    arc.users.write().insert(
-        user.id,
-        User {
+    // the backend may send the same user again, what was counted since is kept
+    arc.users
+        .write()
+        .entry(user.id)
+        .and_modify(|existing| {
+            existing.max_snatches_per_day = user.max_snatches_per_day;
+            existing.max_leeching = user.max_leeching;
+        })
+        .or_insert_with(|| User {
             max_snatches_per_day: user.max_snatches_per_day,
+            max_leeching: user.max_leeching,
             num_seeding: 0,
             num_leeching: 0,
             recent_leeches: Vec::new(),
-        },
-    );
+        });
 
-    arc.passkey2id.write().insert(user.passkey, user.id);
+    let mut passkey2id = arc.passkey2id.write();
+    // the passkey may have been changed
+    passkey2id.retain(|_, user_id| *user_id != user.id);
+    if !user.banned {
+        passkey2id.insert(user.passkey, user.id);
+    }
 
-    debug!("inserted user: {:?}", user);
+    debug!("upserted user: {:?}", user);
 
     HttpResponse::Ok().finish()
 }
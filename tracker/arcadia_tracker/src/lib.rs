@@ -1,15 +1,27 @@
 use arcadia_shared::tracker::models::{
+    cheat_flag::{self, CheatFlag},
     env::ArcadiaSettingsForTracker,
+    peer::PeerRemoval,
     peer_update::{self, PeerUpdate},
+    torrent::InfoHash,
     torrent_update::{self, TorrentUpdate},
+    unregistered_info_hash_update::{self, UnregisteredInfoHashUpdate},
     user_update::{self, UserUpdate},
-    Queue,
+    Mergeable, Queue,
 };
 use parking_lot::{Mutex, RwLock};
+use serde::{de::DeserializeOwned, Serialize};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 
 use crate::env::Env;
-use std::{io::Write, ops::Deref, sync::OnceLock, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    io::Write,
+    ops::Deref,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
 pub mod announce;
 pub mod api_doc;
@@ -19,7 +31,9 @@ pub mod metrics;
 pub mod middleware;
 pub mod routes;
 pub mod scheduler;
+pub mod scrape;
 pub mod services;
+pub mod udp;
 
 #[derive(Debug)]
 pub struct Tracker {
@@ -34,10 +48,28 @@ pub struct Tracker {
     pub users: RwLock<arcadia_shared::tracker::models::user::Map>,
     pub passkey2id: RwLock<arcadia_shared::tracker::models::passkey_2_id::Map>,
     pub infohash2id: RwLock<arcadia_shared::tracker::models::infohash_2_id::Map>,
+    /// The reverse of `infohash2id`, so that the info hashes of a torrent are
+    /// found without walking every info hash
+    pub info_hashes_by_torrent: RwLock<HashMap<u32, Vec<InfoHash>>>,
     pub torrents: Mutex<arcadia_shared::tracker::models::torrent::Map>,
+    /// The torrents each user announced since the latest reap, so that the
+    /// torrents of a user are found without walking every swarm. The user
+    /// may have no peer left on some of them.
+    pub torrent_ids_by_user: RwLock<HashMap<u32, HashSet<u32>>>,
+    pub personal_freeleeches: RwLock<arcadia_shared::tracker::models::personal_freeleech::Map>,
+    pub ip_bans: RwLock<arcadia_shared::tracker::models::ip_ban::Map>,
+    pub promotion_events: RwLock<arcadia_shared::tracker::models::promotion_event::Map>,
+    /// Shared with the probes running in the background
+    pub connectability_cache: Arc<Mutex<services::connectability_service::ConnectabilityCache>>,
     pub user_updates: Mutex<Queue<user_update::Index, UserUpdate>>,
     pub torrent_updates: Mutex<Queue<torrent_update::Index, TorrentUpdate>>,
     pub peer_updates: Mutex<Queue<peer_update::Index, PeerUpdate>>,
+    pub peer_removals: Mutex<Queue<peer_update::Index, PeerRemoval>>,
+    pub cheat_flags: Mutex<Queue<cheat_flag::Index, CheatFlag>>,
+    pub unknown_info_hash_limiter:
+        Mutex<services::unregistered_info_hash_service::UnknownInfoHashLimiter>,
+    pub unregistered_info_hash_updates:
+        Mutex<Queue<unregistered_info_hash_update::Index, UnregisteredInfoHashUpdate>>,
 }
 
 impl Deref for Tracker {
@@ -78,11 +110,42 @@ impl Tracker {
         let infohash2id =
             arcadia_shared::tracker::models::infohash_2_id::Map::from_database(&pool).await;
         log::info!("[Setup] Got {:?} infohash2ids", infohash2id.len());
+        let info_hashes_by_torrent = infohash2id.info_hashes_by_torrent();
 
         log::info!("[Setup] Getting torrents...");
         std::io::stdout().flush().unwrap();
         let torrents = arcadia_shared::tracker::models::torrent::Map::from_database(&pool).await;
         log::info!("[Setup] Got {:?} torrents", torrents.len());
+        let torrent_ids_by_user = torrents.torrent_ids_by_user();
+
+        log::info!("[Setup] Getting personal freeleeches...");
+        std::io::stdout().flush().unwrap();
+        let personal_freeleeches =
+            arcadia_shared::tracker::models::personal_freeleech::Map::from_database(&pool).await;
+        log::info!(
+            "[Setup] Got {:?} personal freeleeches",
+            personal_freeleeches.len()
+        );
+
+        log::info!("[Setup] Getting ip bans...");
+        std::io::stdout().flush().unwrap();
+        let ip_bans = arcadia_shared::tracker::models::ip_ban::Map::from_database(&pool).await;
+        log::info!("[Setup] Got {:?} ip bans", ip_bans.len());
+
+        log::info!("[Setup] Getting promotion events...");
+        std::io::stdout().flush().unwrap();
+        let promotion_events =
+            arcadia_shared::tracker::models::promotion_event::Map::from_database(&pool).await;
+        log::info!("[Setup] Got {:?} promotion events", promotion_events.len());
+
+        log::info!("[Setup] Restoring spilled updates...");
+        std::io::stdout().flush().unwrap();
+        let user_updates = restored_queue(&env, "user_updates");
+        let torrent_updates = restored_queue(&env, "torrent_updates");
+        let peer_updates = restored_queue(&env, "peer_updates");
+        let peer_removals = restored_queue(&env, "peer_removals");
+        let cheat_flags = restored_queue(&env, "cheat_flags");
+        let unregistered_info_hash_updates = restored_queue(&env, "unregistered_info_hash_updates");
 
         Self {
             env,
@@ -92,14 +155,35 @@ impl Tracker {
             users: RwLock::new(users),
             passkey2id: RwLock::new(passkey2id),
             infohash2id: RwLock::new(infohash2id),
+            info_hashes_by_torrent: RwLock::new(info_hashes_by_torrent),
             torrents: Mutex::new(torrents),
-            user_updates: Mutex::new(Queue::<user_update::Index, UserUpdate>::default()),
-            torrent_updates: Mutex::new(Queue::<torrent_update::Index, TorrentUpdate>::default()),
-            peer_updates: Mutex::new(Queue::<peer_update::Index, PeerUpdate>::default()),
+            torrent_ids_by_user: RwLock::new(torrent_ids_by_user),
+            personal_freeleeches: RwLock::new(personal_freeleeches),
+            ip_bans: RwLock::new(ip_bans),
+            promotion_events: RwLock::new(promotion_events),
+            connectability_cache: Default::default(),
+            user_updates: Mutex::new(user_updates),
+            torrent_updates: Mutex::new(torrent_updates),
+            peer_updates: Mutex::new(peer_updates),
+            peer_removals: Mutex::new(peer_removals),
+            cheat_flags: Mutex::new(cheat_flags),
+            unknown_info_hash_limiter: Default::default(),
+            unregistered_info_hash_updates: Mutex::new(unregistered_info_hash_updates),
         }
     }
 }
 
+/// Creates a flush queue, with the updates that were spilled to disk by a previous run
+fn restored_queue<K, V>(env: &Env, queue_name: &str) -> Queue<K, V>
+where
+    K: Hash + Eq + Ord + Serialize + DeserializeOwned,
+    V: Clone + Mergeable + Serialize + DeserializeOwned,
+{
+    let mut queue = Queue::with_retry_policy(env.flush_retry_policy(queue_name));
+    queue.restore_spilled();
+    queue
+}
+
 async fn connect_to_database() -> sqlx::Pool<sqlx::Postgres> {
     // Get pool of database connections.
     PgPoolOptions::new()
@@ -1,7 +1,8 @@
 use actix_web::{web::Data, App, HttpServer};
-use arcadia_tracker::{api_doc::ApiDoc, env::Env, routes::init, scheduler, Tracker};
+use arcadia_tracker::{api_doc::ApiDoc, env::Env, routes::init, scheduler, udp, Tracker};
 use envconfig::Envconfig;
 use std::env;
+use tokio::net::UdpSocket;
 use tracing_actix_web::TracingLogger;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -20,6 +21,9 @@ async fn main() -> std::io::Result<()> {
     let web_server_host = env::var("WEB_SERVER_HOST").expect("env var WEB_SERVER_HOST must be set");
     let server_url = format!("{}:{}", web_server_host, web_server_port);
     println!("Server running at http://{server_url}");
+    let udp_server_url = env::var("UDP_SERVER_PORT")
+        .ok()
+        .map(|udp_server_port| format!("{}:{}", web_server_host, udp_server_port));
 
     let arc = Data::new(Tracker::new(env).await);
 
@@ -39,6 +43,20 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
+    // Starts the udp tracker (BEP 15) if it is enabled
+    let udp_handle = match udp_server_url {
+        Some(udp_server_url) => {
+            let socket = UdpSocket::bind(&udp_server_url).await?;
+            println!("UDP tracker running at udp://{udp_server_url}");
+
+            let arc = arc.clone();
+            Some(tokio::spawn(async move {
+                udp::server::run(arc, socket).await;
+            }))
+        }
+        None => None,
+    };
+
     let arc2 = arc.clone();
     let server = HttpServer::new(move || {
         App::new()
@@ -55,26 +73,17 @@ async fn main() -> std::io::Result<()> {
 
     server.await?;
 
-    // Flush all remaining updates before shutting down.
-    let max_flushes = 1000;
-    let mut flushes = 0;
-
-    // stop the scheduler to avoid race conditions
+    // stop the scheduler and the udp tracker to avoid race conditions
     scheduler_handle.abort();
-
-    while flushes < max_flushes
-        && (!arc.peer_updates.lock().is_empty()
-            || !arc.torrent_updates.lock().is_empty()
-            || !arc.user_updates.lock().is_empty())
-    {
-        scheduler::flush(&arc).await;
-        flushes += 1;
+    if let Some(udp_handle) = udp_handle {
+        udp_handle.abort();
     }
 
-    if flushes == max_flushes {
-        log::error!("Graceful shutdown failed");
-    } else {
+    // Flush all remaining updates before shutting down.
+    if scheduler::flush_before_shutdown(&arc).await {
         log::info!("Graceful shutdown succeeded");
+    } else {
+        log::error!("Graceful shutdown failed");
     }
 
     Ok(())
@@ -1,5 +1,6 @@
 use actix_web::web::Data;
-use opentelemetry::metrics::Counter;
+use arcadia_shared::tracker::models::QueueStats;
+use opentelemetry::{metrics::Counter, KeyValue};
 
 use crate::Tracker;
 
@@ -7,6 +8,8 @@ use crate::Tracker;
 pub struct Instruments {
     pub announces_ok: Counter<u64>,
     pub announces_err: Counter<u64>,
+    pub scrapes_ok: Counter<u64>,
+    pub scrapes_err: Counter<u64>,
 }
 
 pub fn register(tracker: &Data<Tracker>, service_name: &str) {
@@ -56,6 +59,52 @@ pub fn register(tracker: &Data<Tracker>, service_name: &str) {
         })
         .build();
 
+    let t = tracker.clone();
+    let _flush_queued = meter
+        .u64_observable_gauge("flush.queued")
+        .with_description("Number of records waiting to be flushed to the database")
+        .with_callback(move |observer| {
+            for (queue, queued, _stats) in flush_queues(&t) {
+                observer.observe(queued, &[KeyValue::new("queue", queue)]);
+            }
+        })
+        .build();
+
+    let t = tracker.clone();
+    let _flush_retried = meter
+        .u64_observable_counter("flush.retried")
+        .with_description("Total number of records put back in their queue after a failed flush")
+        .with_callback(move |observer| {
+            for (queue, _queued, stats) in flush_queues(&t) {
+                observer.observe(stats.retried, &[KeyValue::new("queue", queue)]);
+            }
+        })
+        .build();
+
+    let t = tracker.clone();
+    let _flush_spilled = meter
+        .u64_observable_counter("flush.spilled")
+        .with_description("Total number of records spilled to disk while the database was down")
+        .with_callback(move |observer| {
+            for (queue, _queued, stats) in flush_queues(&t) {
+                observer.observe(stats.spilled, &[KeyValue::new("queue", queue)]);
+            }
+        })
+        .build();
+
+    let t = tracker.clone();
+    let _flush_dropped = meter
+        .u64_observable_counter("flush.dropped")
+        .with_description(
+            "Total number of records lost because they could not be flushed nor spilled",
+        )
+        .with_callback(move |observer| {
+            for (queue, _queued, stats) in flush_queues(&t) {
+                observer.observe(stats.dropped, &[KeyValue::new("queue", queue)]);
+            }
+        })
+        .build();
+
     let instruments = Instruments {
         announces_ok: meter
             .u64_counter("announces.ok")
@@ -65,7 +114,55 @@ pub fn register(tracker: &Data<Tracker>, service_name: &str) {
             .u64_counter("announces.err")
             .with_description("Total number of errored announces")
             .build(),
+        scrapes_ok: meter
+            .u64_counter("scrapes.ok")
+            .with_description("Total number of successful scrapes")
+            .build(),
+        scrapes_err: meter
+            .u64_counter("scrapes.err")
+            .with_description("Total number of errored scrapes")
+            .build(),
     };
 
     let _ = tracker.metrics.set(instruments);
 }
+
+/// Name, amount of queued records and stats of each flush queue
+pub fn flush_queues(tracker: &Tracker) -> [(&'static str, u64, QueueStats); 6] {
+    let user_updates = {
+        let queue = tracker.user_updates.lock();
+        ("user_updates", queue.len() as u64, queue.stats)
+    };
+    let torrent_updates = {
+        let queue = tracker.torrent_updates.lock();
+        ("torrent_updates", queue.len() as u64, queue.stats)
+    };
+    let peer_updates = {
+        let queue = tracker.peer_updates.lock();
+        ("peer_updates", queue.len() as u64, queue.stats)
+    };
+    let peer_removals = {
+        let queue = tracker.peer_removals.lock();
+        ("peer_removals", queue.len() as u64, queue.stats)
+    };
+    let cheat_flags = {
+        let queue = tracker.cheat_flags.lock();
+        ("cheat_flags", queue.len() as u64, queue.stats)
+    };
+    let unregistered_info_hash_updates = {
+        let queue = tracker.unregistered_info_hash_updates.lock();
+        (
+            "unregistered_info_hash_updates",
+            queue.len() as u64,
+            queue.stats,
+        )
+    };
+    [
+        user_updates,
+        torrent_updates,
+        peer_updates,
+        peer_removals,
+        cheat_flags,
+        unregistered_info_hash_updates,
+    ]
+}
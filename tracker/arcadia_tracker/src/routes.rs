@@ -1,13 +1,19 @@
-use actix_web::web::{self, delete, put, resource, scope};
+use actix_web::web::{self, delete, get, post, put, resource, scope};
 
 use crate::{
     announce::handlers::announce::config as AnnouncesConfig,
     handlers::{
+        ip_bans::{delete_ip_ban, upsert_ip_ban},
+        personal_freeleeches::upsert_personal_freeleech,
+        promotion_events::{delete_promotion_event, upsert_promotion_event},
         settings::update_settings,
-        torrents::{delete_torrent, update_torrent_factors, upsert_torrent},
-        users::{update_user_max_snatches_per_day, upsert_user},
+        state::{get_flush_queues, get_state_digest},
+        tasks::{run_flush, run_reap},
+        torrents::{delete_torrent, get_torrent, update_torrent_factors, upsert_torrent},
+        users::{get_user, update_user_max_snatches_per_day, upsert_user},
     },
     middleware::authenticate_backend,
+    scrape::handlers::scrape::config as ScrapesConfig,
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
 
@@ -17,17 +23,39 @@ pub fn init(cfg: &mut web::ServiceConfig) {
         web::scope("/api")
             .wrap(HttpAuthentication::with_fn(authenticate_backend))
             .service(resource("/torrents").route(put().to(upsert_torrent::exec)))
-            .service(resource("/torrents/{id}").route(delete().to(delete_torrent::exec)))
+            .service(
+                resource("/torrents/{id}")
+                    .route(get().to(get_torrent::exec))
+                    .route(delete().to(delete_torrent::exec)),
+            )
             .service(
                 resource("/torrents/{id}/up-down-factors")
                     .route(put().to(update_torrent_factors::exec)),
             )
             .service(resource("/users").route(put().to(upsert_user::exec)))
+            .service(resource("/users/{id}").route(get().to(get_user::exec)))
             .service(
                 resource("/users/{id}/max-snatches-per-day")
                     .route(put().to(update_user_max_snatches_per_day::exec)),
             )
-            .service(resource("/settings").route(put().to(update_settings::exec))),
+            .service(
+                resource("/personal-freeleeches").route(put().to(upsert_personal_freeleech::exec)),
+            )
+            .service(resource("/ip-bans").route(put().to(upsert_ip_ban::exec)))
+            .service(resource("/ip-bans/{id}").route(delete().to(delete_ip_ban::exec)))
+            .service(resource("/promotion-events").route(put().to(upsert_promotion_event::exec)))
+            .service(
+                resource("/promotion-events/{id}").route(delete().to(delete_promotion_event::exec)),
+            )
+            .service(resource("/settings").route(put().to(update_settings::exec)))
+            .service(resource("/state-digest").route(get().to(get_state_digest::exec)))
+            .service(resource("/flush-queues").route(get().to(get_flush_queues::exec)))
+            .service(resource("/tasks/flush").route(post().to(run_flush::exec)))
+            .service(resource("/tasks/reap").route(post().to(run_reap::exec))),
+    );
+    cfg.service(
+        scope("{passkey}")
+            .configure(AnnouncesConfig)
+            .configure(ScrapesConfig),
     );
-    cfg.service(scope("{passkey}").configure(AnnouncesConfig));
 }
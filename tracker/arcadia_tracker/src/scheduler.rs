@@ -1,10 +1,10 @@
 use actix_web::web::Data;
 use arcadia_shared::telemetry::{instrument_periodic_task, PeriodicTaskInstruments};
 use arcadia_shared::tracker::models::{
-    peer::remove_peers_from_database,
+    peer::PeerRemoval,
     peer_update,
     torrent_update::{self, TorrentUpdate},
-    Flushable,
+    user, Flushable,
 };
 use chrono::{Duration, Utc};
 use std::convert::Infallible;
@@ -13,6 +13,10 @@ use tokio::join;
 
 use crate::Tracker;
 
+/// Flushes attempted before giving up on the database and spilling the
+/// remaining updates to disk at shutdown
+const SHUTDOWN_FLUSH_ATTEMPTS: u32 = 5;
+
 static INSTRUMENTS: OnceLock<PeriodicTaskInstruments> = OnceLock::new();
 
 fn instruments() -> &'static PeriodicTaskInstruments {
@@ -46,7 +50,7 @@ pub async fn flush(arc: &Data<Tracker>) {
         instrument_periodic_task::<_, _, Infallible>(
             instruments(),
             "flush_user_updates",
-            || async { Ok(arc.user_updates.flush_to_database(&arc.pool).await) },
+            || async { Ok(flush_user_updates(arc).await) },
         ),
         instrument_periodic_task::<_, _, Infallible>(
             instruments(),
@@ -57,17 +61,114 @@ pub async fn flush(arc: &Data<Tracker>) {
             instruments(),
             "flush_peer_updates",
             || async { Ok(arc.peer_updates.flush_to_database(&arc.pool).await) },
+        ),
+        instrument_periodic_task::<_, _, Infallible>(
+            instruments(),
+            "flush_peer_removals",
+            || async { Ok(arc.peer_removals.flush_to_database(&arc.pool).await) },
+        ),
+        instrument_periodic_task::<_, _, Infallible>(
+            instruments(),
+            "flush_cheat_flags",
+            || async { Ok(arc.cheat_flags.flush_to_database(&arc.pool).await) },
+        ),
+        instrument_periodic_task::<_, _, Infallible>(
+            instruments(),
+            "flush_unregistered_info_hash_updates",
+            || async {
+                Ok(arc
+                    .unregistered_info_hash_updates
+                    .flush_to_database(&arc.pool)
+                    .await)
+            },
         )
     );
 }
 
+/// The leeching slots of the users whose stats were flushed are read again,
+/// so that the ones following the ratio stay in sync with the database
+async fn flush_user_updates(arc: &Data<Tracker>) -> u64 {
+    let user_ids: Vec<u32> = arc
+        .user_updates
+        .lock()
+        .records
+        .keys()
+        .map(|index| index.user_id)
+        .collect();
+    let flushed = arc.user_updates.flush_to_database(&arc.pool).await;
+    if flushed == 0 {
+        return flushed;
+    }
+
+    match user::Map::find_max_leeching(&arc.pool, &user_ids).await {
+        Ok(max_leeching) => {
+            let mut users = arc.users.write();
+            for (user_id, max_leeching) in max_leeching {
+                if let Some(user) = users.get_mut(&user_id) {
+                    user.max_leeching = max_leeching;
+                }
+            }
+        }
+        Err(error) => log::error!("Failed to refresh the leeching slots of users: {error}"),
+    }
+
+    flushed
+}
+
+/// Ends the backoff of every flush queue, the next flush sends their records
+pub fn retry_now(arc: &Data<Tracker>) {
+    arc.user_updates.lock().retry_now();
+    arc.torrent_updates.lock().retry_now();
+    arc.peer_updates.lock().retry_now();
+    arc.peer_removals.lock().retry_now();
+    arc.cheat_flags.lock().retry_now();
+    arc.unregistered_info_hash_updates.lock().retry_now();
+}
+
+/// Flushes all remaining updates, the ones that still cannot be written are
+/// spilled to disk. Returns false if some updates were not flushed.
+pub async fn flush_before_shutdown(arc: &Data<Tracker>) -> bool {
+    for attempt in 1..=SHUTDOWN_FLUSH_ATTEMPTS {
+        if all_flushed(arc) {
+            return true;
+        }
+        if attempt > 1 {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+
+        // don't wait for the end of the backoff of failed flushes
+        retry_now(arc);
+        flush(arc).await;
+    }
+    if all_flushed(arc) {
+        return true;
+    }
+
+    arc.user_updates.lock().spill();
+    arc.torrent_updates.lock().spill();
+    arc.peer_updates.lock().spill();
+    arc.peer_removals.lock().spill();
+    arc.cheat_flags.lock().spill();
+    arc.unregistered_info_hash_updates.lock().spill();
+    false
+}
+
+fn all_flushed(arc: &Data<Tracker>) -> bool {
+    arc.user_updates.lock().is_empty()
+        && arc.torrent_updates.lock().is_empty()
+        && arc.peer_updates.lock().is_empty()
+        && arc.peer_removals.lock().is_empty()
+        && arc.cheat_flags.lock().is_empty()
+        && arc.unregistered_info_hash_updates.lock().is_empty()
+}
+
 /// Remove peers that have not announced for some time
 pub async fn reap(arc: &Data<Tracker>) -> u64 {
     let ttl = Duration::seconds(arc.env.active_peer_ttl.try_into().unwrap());
     let active_cutoff = Utc::now().checked_sub_signed(ttl).unwrap();
     let ttl = Duration::seconds(arc.env.inactive_peer_ttl.try_into().unwrap());
     let inactive_cutoff = Utc::now().checked_sub_signed(ttl).unwrap();
-    let mut all_removed_peers: Vec<peer_update::Index> = Vec::new();
+    let mut all_removed_peers: Vec<(peer_update::Index, PeerRemoval)> = Vec::new();
 
     for (torrent_id, torrent) in arc.torrents.lock().iter_mut() {
         let mut seeder_delta: i32 = 0;
@@ -80,19 +181,22 @@ pub async fn reap(arc: &Data<Tracker>) -> u64 {
             .extract_if(.., |_index, peer| {
                 inactive_cutoff >= peer.updated_at || !peer.is_active
             })
-            .map(|(index, _peer)| index)
-            .collect::<Vec<arcadia_shared::tracker::models::peer::Index>>();
-        all_removed_peers.extend(
-            torrent_removed_peers
-                .into_iter()
-                .map(|index| peer_update::Index {
-                    torrent_id: *torrent_id,
-                    peer_id: index.peer_id,
-                    user_id: index.user_id,
-                })
-                .collect::<Vec<peer_update::Index>>(),
-        );
+            .map(|(index, peer)| {
+                (
+                    peer_update::Index {
+                        torrent_id: *torrent_id,
+                        peer_id: index.peer_id,
+                        user_id: index.user_id,
+                    },
+                    PeerRemoval {
+                        updated_at: peer.updated_at,
+                    },
+                )
+            })
+            .collect::<Vec<(peer_update::Index, PeerRemoval)>>();
+        all_removed_peers.extend(torrent_removed_peers);
 
+        let mut stopped_leeching_user_ids = Vec::new();
         for (index, peer) in torrent.peers.iter_mut() {
             // Peers get marked as inactive if not announced for more than
             // active_peer_ttl seconds. User peer count and torrent peer
@@ -100,19 +204,33 @@ pub async fn reap(arc: &Data<Tracker>) -> u64 {
             if peer.updated_at < active_cutoff && peer.is_active {
                 peer.is_active = false;
 
-                arc.users.write().entry(index.user_id).and_modify(|user| {
-                    if peer.is_seeder {
+                if peer.is_seeder {
+                    arc.users.write().entry(index.user_id).and_modify(|user| {
                         user.num_seeding = user.num_seeding.saturating_sub(1);
-                    } else {
-                        user.num_leeching = user.num_leeching.saturating_sub(1);
-                    }
-                });
+                    });
+                } else {
+                    stopped_leeching_user_ids.push(index.user_id);
+                }
                 match peer.is_seeder {
                     true => seeder_delta -= 1,
                     false => leecher_delta -= 1,
                 }
             }
         }
+        // users leech the torrent until their last client stops
+        stopped_leeching_user_ids.sort_unstable();
+        stopped_leeching_user_ids.dedup();
+        for user_id in stopped_leeching_user_ids {
+            if !torrent
+                .peers
+                .iter()
+                .any(|(index, peer)| index.user_id == user_id && peer.is_included_in_leech_list())
+            {
+                arc.users.write().entry(user_id).and_modify(|user| {
+                    user.num_leeching = user.num_leeching.saturating_sub(1);
+                });
+            }
+        }
 
         // Update peer count of torrents and users
         if seeder_delta != 0 || leecher_delta != 0 {
@@ -131,8 +249,26 @@ pub async fn reap(arc: &Data<Tracker>) -> u64 {
             );
         }
     }
+    // the torrents the users left are dropped from the index
+    *arc.torrent_ids_by_user.write() = arc.torrents.lock().torrent_ids_by_user();
+
+    // Expired personal freeleeches don't apply anymore
+    let now = Utc::now();
+    arc.personal_freeleeches
+        .write()
+        .retain(|_index, expires_at| *expires_at > now);
+    arc.ip_bans
+        .write()
+        .retain(|_id, ban| ban.expires_at.is_none_or(|expires_at| expires_at > now));
+    arc.connectability_cache.lock().remove_expired(now);
 
     let removed_count = all_removed_peers.len() as u64;
-    remove_peers_from_database(&arc.pool, &all_removed_peers).await;
+    {
+        let mut peer_removals = arc.peer_removals.lock();
+        for (index, removal) in all_removed_peers {
+            peer_removals.upsert(index, removal);
+        }
+    }
+    arc.peer_removals.flush_to_database(&arc.pool).await;
     removed_count
 }
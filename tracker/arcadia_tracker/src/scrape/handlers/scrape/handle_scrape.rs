@@ -0,0 +1,128 @@
+use std::str::FromStr;
+
+use crate::{
+    announce::error::{AnnounceError, Result},
+    scrape::models::scrape::Scrape,
+    Tracker,
+};
+use actix_web::{
+    web::{Data, Path},
+    HttpResponse,
+};
+use arcadia_shared::tracker::models::{torrent::InfoHash, user::Passkey};
+use log::debug;
+use opentelemetry::KeyValue;
+
+#[utoipa::path(
+    get,
+    operation_id = "Scrape",
+    tag = "Scrape",
+    path = "/{passkey}/scrape",
+    responses(
+        (status = 200, description = "Scrape"),
+    )
+)]
+pub async fn exec(
+    arc: Data<Tracker>,
+    passkey: Path<String>,
+    scrape: Scrape,
+) -> Result<HttpResponse> {
+    let result = handle(&arc, &passkey, scrape);
+
+    if let Some(m) = arc.metrics.get() {
+        match &result {
+            Ok(_) => m.scrapes_ok.add(1, &[]),
+            Err(e) => m
+                .scrapes_err
+                .add(1, &[KeyValue::new("error", e.as_ref().to_string())]),
+        }
+    }
+
+    result
+}
+
+fn handle(arc: &Tracker, passkey: &str, scrape: Scrape) -> Result<HttpResponse> {
+    let passkey = Passkey::from_str(passkey).or(Err(AnnounceError::InvalidPasskey))?;
+
+    if !arc.passkey2id.read().contains_key(&passkey) {
+        log::warn!("user not found for passkey {}", passkey);
+        return Err(AnnounceError::UserNotFound);
+    }
+
+    let mut files: Vec<(InfoHash, ScrapeStats)> = scrape
+        .info_hashes
+        .iter()
+        .zip(lookup_stats(arc, &scrape.info_hashes))
+        .filter_map(|(info_hash, stats)| stats.map(|stats| (*info_hash, stats)))
+        .collect();
+
+    // Dictionary keys must be sorted and unique to be within spec
+    files.sort_unstable_by(|a, b| a.0 .0.cmp(&b.0 .0));
+    files.dedup_by(|a, b| a.0 == b.0);
+
+    // Write out bencoded response
+    let mut response: Vec<u8> = Vec::with_capacity(
+        12 // literal characters outside of the files
+        + files.len() * (
+            3 + 20 // info hash with its length prefix
+            + 48 // literal characters
+            + 3 * 5 // numbers with estimated digit quantity for each
+        ),
+    );
+
+    response.extend(b"d5:filesd");
+
+    for (info_hash, stats) in files {
+        response.extend(b"20:");
+        response.extend(&info_hash.0);
+        response.extend(b"d8:completei");
+        response.extend(stats.seeders.to_string().as_bytes());
+        response.extend(b"e10:downloadedi");
+        response.extend(stats.times_completed.to_string().as_bytes());
+        response.extend(b"e10:incompletei");
+        response.extend(stats.leechers.to_string().as_bytes());
+        response.extend(b"ee");
+    }
+
+    response.extend(b"ee");
+
+    debug!("Scrape response: {:?}", String::from_utf8_lossy(&response));
+
+    Ok(HttpResponse::Ok().body(response))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrapeStats {
+    pub seeders: u32,
+    pub leechers: u32,
+    pub times_completed: u32,
+}
+
+/// Looks up the stats of the requested torrents, in the same order as
+/// requested. Unknown and deleted torrents are `None`.
+pub fn lookup_stats(arc: &Tracker, info_hashes: &[InfoHash]) -> Vec<Option<ScrapeStats>> {
+    // Resolve the requested info hashes before locking the torrents, so that
+    // both locks are never held at the same time
+    let torrent_ids: Vec<Option<u32>> = {
+        let infohash2id = arc.infohash2id.read();
+        info_hashes
+            .iter()
+            .map(|info_hash| infohash2id.get(info_hash).copied())
+            .collect()
+    };
+
+    let torrents = arc.torrents.lock();
+    torrent_ids
+        .into_iter()
+        .map(|torrent_id| {
+            torrent_id
+                .and_then(|torrent_id| torrents.get(&torrent_id))
+                .filter(|torrent| !torrent.is_deleted)
+                .map(|torrent| ScrapeStats {
+                    seeders: torrent.seeders,
+                    leechers: torrent.leechers,
+                    times_completed: torrent.times_completed,
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,7 @@
+pub mod handle_scrape;
+
+use actix_web::web::{get, resource, ServiceConfig};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(resource("/scrape").route(get().to(self::handle_scrape::exec)));
+}
@@ -0,0 +1,44 @@
+use actix_web::{dev, FromRequest, HttpRequest};
+use arcadia_shared::tracker::models::torrent::InfoHash;
+use std::future::{self, Ready};
+
+use crate::announce::{
+    error::AnnounceError,
+    models::announce::{decode_into_20_byte_array, QueryPairs},
+};
+
+#[derive(Debug)]
+pub struct Scrape {
+    /// Info hashes requested by the client, in the order they were sent.
+    /// A client may send the `info_hash` param multiple times (BEP 48).
+    pub info_hashes: Vec<InfoHash>,
+}
+
+impl FromRequest for Scrape {
+    type Error = AnnounceError;
+    type Future = Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        future::ready(decode_from_query_str(req.query_string()))
+    }
+}
+
+pub fn decode_from_query_str(query: &str) -> Result<Scrape, AnnounceError> {
+    let mut info_hashes = Vec::new();
+
+    let pairs = QueryPairs { input: query };
+
+    for (name, value) in pairs {
+        if name == "info_hash" {
+            let info_hash =
+                decode_into_20_byte_array(value).map_err(|_| AnnounceError::InvalidInfoHash)?;
+            info_hashes.push(InfoHash(info_hash));
+        }
+    }
+
+    if info_hashes.is_empty() {
+        return Err(AnnounceError::MissingInfoHash);
+    }
+
+    Ok(Scrape { info_hashes })
+}
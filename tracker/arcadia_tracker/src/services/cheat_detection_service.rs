@@ -0,0 +1,93 @@
+use arcadia_shared::tracker::models::{
+    cheat_flag::{CheatFlagReason, CheatFlagSeverity},
+    peer,
+};
+
+use crate::env::Env;
+
+/// The other users' peers of a torrent, the only ones that can have
+/// downloaded what a peer uploaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Swarm {
+    pub leechers: u32,
+    /// What the peers of the torrent downloaded since the previous announce
+    /// of the checked peer, the ones that left the swarm since included.
+    /// Unknown when the peer was loaded from the database and hasn't
+    /// announced since, the tracker only counts it while running
+    pub downloaded: Option<u64>,
+}
+
+impl Swarm {
+    pub fn excluding_user<'a>(
+        user_id: u32,
+        peers: impl Iterator<Item = (&'a peer::Index, &'a peer::Peer)>,
+        downloaded: Option<u64>,
+    ) -> Self {
+        let mut swarm = Swarm {
+            leechers: 0,
+            downloaded,
+        };
+        for (index, peer) in peers {
+            if index.user_id == user_id || !peer.is_active {
+                continue;
+            }
+            swarm.leechers += peer.is_included_in_leech_list() as u32;
+        }
+        swarm
+    }
+}
+
+/// Checks the upload reported by a peer since its previous announce against
+/// what was physically possible, returns the reasons it looks like cheating.
+///
+/// - the upload speed can't exceed `CHEAT_DETECTION_MAX_UPLOAD_SPEED`
+/// - somebody else must have been leeching
+/// - the other peers must have downloaded at least as much as was uploaded
+///
+/// The last two are skipped while what the swarm downloaded is unknown.
+pub fn detect_impossible_upload(
+    env: &Env,
+    uploaded_delta: u64,
+    seconds_since_last_announce: u64,
+    swarm: Swarm,
+) -> Vec<(CheatFlagReason, CheatFlagSeverity)> {
+    let mut flags = Vec::new();
+
+    if uploaded_delta < env.cheat_detection_min_uploaded_delta {
+        return flags;
+    }
+
+    let upload_speed = uploaded_delta / seconds_since_last_announce.max(1);
+    if upload_speed > env.cheat_detection_max_upload_speed {
+        let severity = if upload_speed > env.cheat_detection_max_upload_speed.saturating_mul(2) {
+            CheatFlagSeverity::High
+        } else {
+            CheatFlagSeverity::Medium
+        };
+        flags.push((CheatFlagReason::ImpossibleUploadSpeed, severity));
+    }
+
+    let Some(swarm_downloaded) = swarm.downloaded else {
+        return flags;
+    };
+
+    if swarm.leechers == 0 {
+        // a leecher may have completed since the previous announce, which is
+        // only impossible if nobody downloaded anything
+        let severity = if swarm_downloaded == 0 {
+            CheatFlagSeverity::High
+        } else {
+            CheatFlagSeverity::Medium
+        };
+        flags.push((CheatFlagReason::UploadWithoutLeechers, severity));
+    }
+
+    if uploaded_delta > swarm_downloaded {
+        flags.push((
+            CheatFlagReason::UploadExceedsSwarmDownload,
+            CheatFlagSeverity::Low,
+        ));
+    }
+
+    flags
+}
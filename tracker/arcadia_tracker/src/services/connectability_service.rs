@@ -0,0 +1,109 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
+
+use arcadia_shared::tracker::models::torrent::InfoHash;
+use chrono::{DateTime, Utc};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::Tracker;
+
+const PROTOCOL: &[u8; 19] = b"BitTorrent protocol";
+/// Peer id the tracker introduces itself with when probing peers
+const PROBE_PEER_ID: &[u8; 20] = b"-AR0001-connectcheck";
+
+/// Outcome of the latest probe of each peer address, with its expiration,
+/// along with the addresses being probed
+#[derive(Debug, Default)]
+pub struct ConnectabilityCache {
+    outcomes: HashMap<SocketAddr, (bool, DateTime<Utc>)>,
+    probing: HashSet<SocketAddr>,
+}
+
+impl ConnectabilityCache {
+    pub fn get(&self, addr: &SocketAddr, now: DateTime<Utc>) -> Option<bool> {
+        self.outcomes
+            .get(addr)
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(is_connectable, _)| *is_connectable)
+    }
+
+    pub fn insert(&mut self, addr: SocketAddr, is_connectable: bool, expires_at: DateTime<Utc>) {
+        self.probing.remove(&addr);
+        self.outcomes.insert(addr, (is_connectable, expires_at));
+    }
+
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) {
+        self.outcomes
+            .retain(|_addr, (_, expires_at)| *expires_at > now);
+    }
+
+    pub fn probes_in_flight(&self) -> usize {
+        self.probing.len()
+    }
+}
+
+/// Whether the peer listening on `addr` answers a handshake for the torrent,
+/// when it is known. Addresses are probed once per `CONNECTABILITY_CACHE_TTL`
+/// in the background so that announces never wait for a probe: the outcome
+/// applies from the next announce on.
+pub fn check_connectability(
+    arc: &Tracker,
+    addr: SocketAddr,
+    info_hash: &InfoHash,
+    now: DateTime<Utc>,
+) -> Option<bool> {
+    let mut cache = arc.connectability_cache.lock();
+    if let Some(is_connectable) = cache.get(&addr, now) {
+        return Some(is_connectable);
+    }
+    // the address is probed on a later announce when too many probes are running
+    if cache.probing.len() >= arc.env.connectability_max_probes_in_flight
+        || !cache.probing.insert(addr)
+    {
+        return None;
+    }
+    drop(cache);
+
+    let cache = Arc::clone(&arc.connectability_cache);
+    let info_hash = *info_hash;
+    let timeout = Duration::from_millis(arc.env.connectability_check_timeout_milliseconds);
+    let ttl = chrono::Duration::seconds(arc.env.connectability_cache_ttl as i64);
+    tokio::spawn(async move {
+        let is_connectable = probe(addr, &info_hash, timeout).await;
+        cache.lock().insert(addr, is_connectable, Utc::now() + ttl);
+    });
+
+    None
+}
+
+/// Sends a BitTorrent handshake and checks that the peer answers with its
+/// own for the same torrent
+async fn probe(addr: SocketAddr, info_hash: &InfoHash, timeout: Duration) -> bool {
+    let handshake = async {
+        let mut stream = TcpStream::connect(addr).await?;
+
+        let mut request: Vec<u8> = Vec::with_capacity(68);
+        request.push(PROTOCOL.len() as u8);
+        request.extend(PROTOCOL);
+        request.extend([0; 8]); // reserved
+        request.extend(info_hash.0);
+        request.extend(PROBE_PEER_ID);
+        stream.write_all(&request).await?;
+
+        // Only read up to the info hash, some clients don't send their peer
+        // id to peers they don't know
+        let mut response = [0; 48];
+        stream.read_exact(&mut response).await?;
+
+        Ok::<bool, std::io::Error>(response[..20] == request[..20] && response[28..] == info_hash.0)
+    };
+
+    matches!(tokio::time::timeout(timeout, handshake).await, Ok(Ok(true)))
+}
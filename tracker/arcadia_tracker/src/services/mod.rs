@@ -1 +1,4 @@
 pub mod announce_service;
+pub mod cheat_detection_service;
+pub mod connectability_service;
+pub mod unregistered_info_hash_service;
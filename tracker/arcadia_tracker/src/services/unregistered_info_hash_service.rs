@@ -0,0 +1,43 @@
+use std::collections::{HashMap, HashSet};
+
+use arcadia_shared::tracker::models::torrent::InfoHash;
+use chrono::{DateTime, Duration, Utc};
+
+/// Unknown info hashes logged for each user during the current hour, so that
+/// announcing random hashes can't grow the log without bound
+#[derive(Debug, Default)]
+pub struct UnknownInfoHashLimiter {
+    window_started_at: Option<DateTime<Utc>>,
+    logged: HashMap<u32, HashSet<InfoHash>>,
+}
+
+impl UnknownInfoHashLimiter {
+    /// Whether the unknown info hash announced by the user can be logged.
+    /// Hashes already logged during the hour are always allowed, so that
+    /// their last announce keeps being updated.
+    pub fn allow(
+        &mut self,
+        user_id: u32,
+        info_hash: InfoHash,
+        max_per_hour: usize,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if self
+            .window_started_at
+            .is_none_or(|started_at| now - started_at >= Duration::hours(1))
+        {
+            self.window_started_at = Some(now);
+            self.logged.clear();
+        }
+
+        let logged = self.logged.entry(user_id).or_default();
+        if logged.contains(&info_hash) {
+            return true;
+        }
+        if logged.len() >= max_per_hour {
+            return false;
+        }
+        logged.insert(info_hash);
+        true
+    }
+}
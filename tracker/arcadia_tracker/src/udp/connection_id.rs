@@ -0,0 +1,54 @@
+use std::{
+    hash::{BuildHasher, Hash, Hasher, RandomState},
+    net::SocketAddr,
+};
+
+/// Length of the time windows connection ids are bound to, in seconds
+const WINDOW_SECONDS: i64 = 60;
+
+/// Amount of previous windows in which a connection id is still accepted.
+///
+/// BEP 15 states that clients may use a connection id for one minute and
+/// that trackers should accept it for two minutes.
+const ACCEPTED_PREVIOUS_WINDOWS: i64 = 2;
+
+/// Issues and validates connection ids without keeping any state.
+///
+/// A connection id is a keyed hash of the client's address and the current
+/// time window, so that a client can't announce from an address it didn't
+/// connect from (i.e. spoof its source address).
+pub struct ConnectionIds {
+    hasher: RandomState,
+}
+
+impl Default for ConnectionIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionIds {
+    pub fn new() -> Self {
+        Self {
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn issue(&self, addr: SocketAddr, now: i64) -> u64 {
+        self.hash(addr, now.div_euclid(WINDOW_SECONDS))
+    }
+
+    pub fn is_valid(&self, connection_id: u64, addr: SocketAddr, now: i64) -> bool {
+        let window = now.div_euclid(WINDOW_SECONDS);
+
+        (0..=ACCEPTED_PREVIOUS_WINDOWS).any(|age| self.hash(addr, window - age) == connection_id)
+    }
+
+    fn hash(&self, addr: SocketAddr, window: i64) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        addr.ip().to_canonical().hash(&mut hasher);
+        addr.port().hash(&mut hasher);
+        hasher.write_i64(window);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod connection_id;
+pub mod protocol;
+pub mod server;
@@ -0,0 +1,307 @@
+use arcadia_shared::tracker::models::{peer_id::PeerId, torrent::InfoHash};
+
+use crate::{
+    announce::models::announce::AnnounceEvent, scrape::handlers::scrape::handle_scrape::ScrapeStats,
+};
+
+/// Magic constant sent by clients in connect requests
+pub const PROTOCOL_ID: u64 = 0x41727101980;
+
+/// Max amount of info hashes in a single scrape request, so that the
+/// response fits in a single packet
+pub const MAX_SCRAPE_INFO_HASHES: usize = 74;
+
+const ACTION_CONNECT: u32 = 0;
+const ACTION_ANNOUNCE: u32 = 1;
+const ACTION_SCRAPE: u32 = 2;
+const ACTION_ERROR: u32 = 3;
+
+/// BEP 41 options that can be appended to announce requests
+const OPTION_END_OF_OPTIONS: u8 = 0x0;
+const OPTION_NOP: u8 = 0x1;
+const OPTION_URL_DATA: u8 = 0x2;
+
+#[derive(Debug)]
+pub enum Request {
+    Connect(ConnectRequest),
+    Announce(AnnounceRequest),
+    Scrape(ScrapeRequest),
+}
+
+#[derive(Debug)]
+pub struct ConnectRequest {
+    pub transaction_id: u32,
+}
+
+#[derive(Debug)]
+pub struct AnnounceRequest {
+    pub connection_id: u64,
+    pub transaction_id: u32,
+    pub info_hash: InfoHash,
+    pub peer_id: PeerId,
+    pub downloaded: u64,
+    pub left: u64,
+    pub uploaded: u64,
+    pub event: AnnounceEvent,
+    pub key: u32,
+    /// Negative values mean the tracker should pick the default
+    pub num_want: i32,
+    pub port: u16,
+    /// Concatenated contents of the BEP 41 URL data options, i.e. the path and
+    /// query of the announce url (`/{passkey}/announce`)
+    pub url_data: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct ScrapeRequest {
+    pub connection_id: u64,
+    pub transaction_id: u32,
+    pub info_hashes: Vec<InfoHash>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RequestError {
+    /// The packet can't be answered because it's too short to contain
+    /// a transaction id
+    #[error("malformed packet")]
+    Malformed,
+    #[error("invalid protocol id")]
+    InvalidProtocolId { transaction_id: u32 },
+    #[error("unsupported action")]
+    UnsupportedAction { transaction_id: u32 },
+    #[error("malformed request")]
+    MalformedRequest { transaction_id: u32 },
+    #[error("invalid event")]
+    InvalidEvent { transaction_id: u32 },
+    #[error("invalid options")]
+    InvalidOptions { transaction_id: u32 },
+    #[error("missing info_hash")]
+    MissingInfoHash { transaction_id: u32 },
+    #[error("too many info hashes")]
+    TooManyInfoHashes { transaction_id: u32 },
+}
+
+impl RequestError {
+    /// Transaction id to answer the error with, if it could be parsed
+    pub fn transaction_id(&self) -> Option<u32> {
+        match self {
+            Self::Malformed => None,
+            Self::InvalidProtocolId { transaction_id }
+            | Self::UnsupportedAction { transaction_id }
+            | Self::MalformedRequest { transaction_id }
+            | Self::InvalidEvent { transaction_id }
+            | Self::InvalidOptions { transaction_id }
+            | Self::MissingInfoHash { transaction_id }
+            | Self::TooManyInfoHashes { transaction_id } => Some(*transaction_id),
+        }
+    }
+}
+
+/// Cursor reading big endian integers from a packet
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N).map(|bytes| bytes.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.array::<1>().map(|bytes| bytes[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.array().map(i32::from_be_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_be_bytes)
+    }
+}
+
+impl Request {
+    pub fn from_bytes(packet: &[u8]) -> Result<Self, RequestError> {
+        let mut reader = Reader { bytes: packet };
+
+        // Every request starts with the same 16 byte header
+        let connection_id = reader.u64().ok_or(RequestError::Malformed)?;
+        let action = reader.u32().ok_or(RequestError::Malformed)?;
+        let transaction_id = reader.u32().ok_or(RequestError::Malformed)?;
+
+        match action {
+            ACTION_CONNECT => {
+                if connection_id != PROTOCOL_ID {
+                    return Err(RequestError::InvalidProtocolId { transaction_id });
+                }
+
+                Ok(Request::Connect(ConnectRequest { transaction_id }))
+            }
+            ACTION_ANNOUNCE => {
+                let malformed = || RequestError::MalformedRequest { transaction_id };
+
+                let info_hash = InfoHash(reader.array().ok_or_else(malformed)?);
+                let peer_id = PeerId(reader.array().ok_or_else(malformed)?);
+                let downloaded = reader.u64().ok_or_else(malformed)?;
+                let left = reader.u64().ok_or_else(malformed)?;
+                let uploaded = reader.u64().ok_or_else(malformed)?;
+                let event = reader.u32().ok_or_else(malformed)?;
+                // The ip address is ignored, announces are only trusted
+                // for their source address
+                reader.take(4).ok_or_else(malformed)?;
+                let key = reader.u32().ok_or_else(malformed)?;
+                let num_want = reader.i32().ok_or_else(malformed)?;
+                let port = reader.u16().ok_or_else(malformed)?;
+
+                let event = match event {
+                    0 => AnnounceEvent::Empty,
+                    1 => AnnounceEvent::Completed,
+                    2 => AnnounceEvent::Started,
+                    3 => AnnounceEvent::Stopped,
+                    _ => return Err(RequestError::InvalidEvent { transaction_id }),
+                };
+
+                let url_data = read_url_data(&mut reader)
+                    .ok_or(RequestError::InvalidOptions { transaction_id })?;
+
+                Ok(Request::Announce(AnnounceRequest {
+                    connection_id,
+                    transaction_id,
+                    info_hash,
+                    peer_id,
+                    downloaded,
+                    left,
+                    uploaded,
+                    event,
+                    key,
+                    num_want,
+                    port,
+                    url_data,
+                }))
+            }
+            ACTION_SCRAPE => {
+                // The rest of the packet is made of info hashes only, BEP 41
+                // options can't be appended to scrapes.
+                let info_hashes: Vec<InfoHash> = reader
+                    .bytes
+                    .chunks_exact(20)
+                    .map(|chunk| InfoHash(chunk.try_into().unwrap()))
+                    .collect();
+
+                if info_hashes.is_empty() {
+                    return Err(RequestError::MissingInfoHash { transaction_id });
+                }
+
+                if info_hashes.len() > MAX_SCRAPE_INFO_HASHES {
+                    return Err(RequestError::TooManyInfoHashes { transaction_id });
+                }
+
+                Ok(Request::Scrape(ScrapeRequest {
+                    connection_id,
+                    transaction_id,
+                    info_hashes,
+                }))
+            }
+            _ => Err(RequestError::UnsupportedAction { transaction_id }),
+        }
+    }
+}
+
+/// Reads the BEP 41 options, concatenating the contents of all URL data
+/// options. Returns `None` if the options are truncated.
+fn read_url_data(reader: &mut Reader) -> Option<Vec<u8>> {
+    let mut url_data = Vec::new();
+
+    while let Some(option_type) = reader.u8() {
+        match option_type {
+            OPTION_END_OF_OPTIONS => break,
+            OPTION_NOP => continue,
+            OPTION_URL_DATA => {
+                let len = reader.u8()?;
+                url_data.extend(reader.take(len as usize)?);
+            }
+            // Unknown options all have a length byte, and should be skipped
+            _ => {
+                let len = reader.u8()?;
+                reader.take(len as usize)?;
+            }
+        }
+    }
+
+    Some(url_data)
+}
+
+/// Extracts the passkey from the URL data of an announce
+/// (`/{passkey}/announce?...`)
+pub fn passkey_from_url_data(url_data: &[u8]) -> Option<&str> {
+    let path = std::str::from_utf8(url_data).ok()?;
+    let path = path.split('?').next()?;
+
+    path.trim_start_matches('/')
+        .split('/')
+        .next()
+        .filter(|passkey| !passkey.is_empty())
+}
+
+pub fn write_connect_response(transaction_id: u32, connection_id: u64) -> Vec<u8> {
+    let mut response = Vec::with_capacity(16);
+    response.extend(ACTION_CONNECT.to_be_bytes());
+    response.extend(transaction_id.to_be_bytes());
+    response.extend(connection_id.to_be_bytes());
+    response
+}
+
+pub fn write_announce_response(
+    transaction_id: u32,
+    interval: u32,
+    leechers: u32,
+    seeders: u32,
+    peers: &[u8],
+) -> Vec<u8> {
+    let mut response = Vec::with_capacity(20 + peers.len());
+    response.extend(ACTION_ANNOUNCE.to_be_bytes());
+    response.extend(transaction_id.to_be_bytes());
+    response.extend(interval.to_be_bytes());
+    response.extend(leechers.to_be_bytes());
+    response.extend(seeders.to_be_bytes());
+    response.extend(peers);
+    response
+}
+
+/// `files` must be in the same order as the requested info hashes
+pub fn write_scrape_response(transaction_id: u32, files: &[ScrapeStats]) -> Vec<u8> {
+    let mut response = Vec::with_capacity(8 + files.len() * 12);
+    response.extend(ACTION_SCRAPE.to_be_bytes());
+    response.extend(transaction_id.to_be_bytes());
+    for stats in files {
+        response.extend(stats.seeders.to_be_bytes());
+        response.extend(stats.times_completed.to_be_bytes());
+        response.extend(stats.leechers.to_be_bytes());
+    }
+    response
+}
+
+pub fn write_error_response(transaction_id: u32, message: &str) -> Vec<u8> {
+    let mut response = Vec::with_capacity(8 + message.len());
+    response.extend(ACTION_ERROR.to_be_bytes());
+    response.extend(transaction_id.to_be_bytes());
+    response.extend(message.as_bytes());
+    response
+}
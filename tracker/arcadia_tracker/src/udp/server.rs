@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use actix_web::web::Data;
+use arcadia_shared::tracker::models::{peer::PeerKey, peer_id::PeerId};
+use chrono::Utc;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use tokio::{net::UdpSocket, sync::Semaphore};
+
+use crate::{
+    announce::{
+        handlers::announce::handle_announce::{self, random_announce_interval},
+        models::announce::{Announce, AnnounceEvent},
+    },
+    scrape::handlers::scrape::handle_scrape::lookup_stats,
+    udp::{
+        connection_id::ConnectionIds,
+        protocol::{
+            passkey_from_url_data, write_announce_response, write_connect_response,
+            write_error_response, write_scrape_response, AnnounceRequest, Request, ScrapeRequest,
+        },
+    },
+    Tracker,
+};
+
+/// Large enough for an announce with the max amount of BEP 41 options
+/// a client can fit in a single packet
+const MAX_PACKET_SIZE: usize = 1500;
+
+pub struct UdpTracker {
+    arc: Data<Tracker>,
+    connection_ids: ConnectionIds,
+    announced_addresses: Mutex<AnnouncedAddresses>,
+}
+
+/// Addresses that announced with a valid passkey, along with the time of
+/// their latest announce. Scrapes can't carry a passkey, so only those
+/// addresses are answered.
+#[derive(Default)]
+struct AnnouncedAddresses {
+    announced_at: HashMap<IpAddr, i64>,
+    pruned_at: i64,
+}
+
+impl AnnouncedAddresses {
+    fn insert(&mut self, ip: IpAddr, now: i64, ttl: i64) {
+        self.announced_at.insert(ip, now);
+        if now - self.pruned_at >= ttl {
+            self.announced_at
+                .retain(|_ip, announced_at| now - *announced_at < ttl);
+            self.pruned_at = now;
+        }
+    }
+
+    fn contains(&self, ip: &IpAddr, now: i64, ttl: i64) -> bool {
+        self.announced_at
+            .get(ip)
+            .is_some_and(|announced_at| now - *announced_at < ttl)
+    }
+}
+
+/// Receives requests on the socket forever, each request being handled
+/// in its own task, with at most `UDP_MAX_REQUESTS_IN_FLIGHT` of them at once
+pub async fn run(arc: Data<Tracker>, socket: UdpSocket) {
+    let socket = Arc::new(socket);
+    let requests_in_flight = Arc::new(Semaphore::new(arc.env.udp_max_requests_in_flight));
+    let udp_tracker = Arc::new(UdpTracker::new(arc));
+    let mut buf = [0u8; MAX_PACKET_SIZE];
+
+    loop {
+        // while waiting, the packets stay in the socket buffer, and the kernel
+        // drops them once it is full
+        let permit = requests_in_flight
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed");
+
+        let (len, addr) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(error) => {
+                warn!("Could not receive udp packet: {error}");
+                continue;
+            }
+        };
+
+        let packet = buf[..len].to_vec();
+        let socket = socket.clone();
+        let udp_tracker = udp_tracker.clone();
+
+        tokio::spawn(async move {
+            if let Some(response) = udp_tracker.handle_packet(&packet, addr).await
+                && let Err(error) = socket.send_to(&response, addr).await
+            {
+                debug!("Could not send udp response to {addr}: {error}");
+            }
+            drop(permit);
+        });
+    }
+}
+
+impl UdpTracker {
+    pub fn new(arc: Data<Tracker>) -> Self {
+        Self {
+            arc,
+            connection_ids: ConnectionIds::new(),
+            announced_addresses: Mutex::default(),
+        }
+    }
+
+    /// Returns the response to send back to the client, if any
+    pub async fn handle_packet(&self, packet: &[u8], addr: SocketAddr) -> Option<Vec<u8>> {
+        // ipv4 clients are seen as ipv4-mapped ipv6 addresses by dual stack sockets
+        let addr = SocketAddr::new(addr.ip().to_canonical(), addr.port());
+        let now = Utc::now().timestamp();
+
+        let request = match Request::from_bytes(packet) {
+            Ok(request) => request,
+            Err(error) => {
+                return error.transaction_id().map(|transaction_id| {
+                    write_error_response(transaction_id, &error.to_string())
+                });
+            }
+        };
+
+        let response = match request {
+            Request::Connect(request) => {
+                write_connect_response(request.transaction_id, self.connection_ids.issue(addr, now))
+            }
+            Request::Announce(request) => {
+                if !self
+                    .connection_ids
+                    .is_valid(request.connection_id, addr, now)
+                {
+                    return Some(invalid_connection_id(request.transaction_id));
+                }
+
+                self.announce(request, addr).await
+            }
+            Request::Scrape(request) => {
+                if !self
+                    .connection_ids
+                    .is_valid(request.connection_id, addr, now)
+                {
+                    return Some(invalid_connection_id(request.transaction_id));
+                }
+
+                if !self.announced_addresses.lock().contains(
+                    &addr.ip(),
+                    now,
+                    self.arc.env.inactive_peer_ttl as i64,
+                ) {
+                    return Some(write_error_response(
+                        request.transaction_id,
+                        "announce with a valid passkey before scraping",
+                    ));
+                }
+
+                self.scrape(request)
+            }
+        };
+
+        Some(response)
+    }
+
+    async fn announce(&self, request: AnnounceRequest, addr: SocketAddr) -> Vec<u8> {
+        // A missing passkey goes through the same validation as an invalid one
+        let passkey = passkey_from_url_data(&request.url_data).unwrap_or_default();
+
+        let ann = Announce {
+            info_hash: request.info_hash,
+            peer_id: request.peer_id,
+            port: request.port,
+            uploaded: request.uploaded,
+            downloaded: request.downloaded,
+            left: request.left,
+            event: request.event,
+            numwant: if request.event == AnnounceEvent::Stopped {
+                0
+            } else if request.num_want < 0 {
+                self.arc.env.numwant_default
+            } else {
+                request.num_want as usize
+            },
+            ipv4: None,
+            ipv6: None,
+            key: Some(PeerKey(request.key)),
+            compact: Some(true),
+            no_peer_id: false,
+        };
+
+        let result = handle_announce::handle(
+            &self.arc,
+            passkey,
+            user_agent_from_peer_id(&request.peer_id),
+            ann,
+            addr.ip(),
+        )
+        .await;
+
+        match result {
+            Ok(response) => {
+                self.announced_addresses.lock().insert(
+                    addr.ip(),
+                    Utc::now().timestamp(),
+                    self.arc.env.inactive_peer_ttl as i64,
+                );
+
+                // Peers are sent in the same address family as the request
+                let peers = if addr.is_ipv4() {
+                    response.compact_peers_ipv4()
+                } else {
+                    response.compact_peers_ipv6()
+                };
+
+                write_announce_response(
+                    request.transaction_id,
+                    random_announce_interval(&self.arc),
+                    response.reported_leechers(),
+                    response.reported_seeders(),
+                    &peers,
+                )
+            }
+            Err(error) => write_error_response(request.transaction_id, &format!("{error}")),
+        }
+    }
+
+    fn scrape(&self, request: ScrapeRequest) -> Vec<u8> {
+        let files: Vec<_> = lookup_stats(&self.arc, &request.info_hashes)
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+
+        if let Some(m) = self.arc.metrics.get() {
+            m.scrapes_ok.add(1, &[]);
+        }
+
+        write_scrape_response(request.transaction_id, &files)
+    }
+}
+
+fn invalid_connection_id(transaction_id: u32) -> Vec<u8> {
+    write_error_response(transaction_id, "invalid connection id")
+}
+
+/// UDP announces don't have a user agent, so the client prefix of the
+/// peer id (e.g. `-qB4650-`) is stored instead
+fn user_agent_from_peer_id(peer_id: &PeerId) -> String {
+    String::from_utf8_lossy(&peer_id.0[..8]).into_owned()
+}
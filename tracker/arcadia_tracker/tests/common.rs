@@ -1,3 +1,6 @@
+// Each test binary only uses some of these helpers
+#![allow(dead_code)]
+
 use actix_http::Request;
 use actix_web::{
     body::MessageBody,
@@ -5,7 +8,8 @@ use actix_web::{
     test, web, App, Error,
 };
 use arcadia_shared::tracker::models::{
-    env::ArcadiaSettingsForTracker, infohash_2_id, passkey_2_id, torrent, user,
+    env::ArcadiaSettingsForTracker, infohash_2_id, ip_ban, passkey_2_id, personal_freeleech,
+    promotion_event, torrent, user,
 };
 use arcadia_tracker::{
     env::{AllowedTorrentClientSet, Env},
@@ -15,13 +19,39 @@ use arcadia_tracker::{
 use parking_lot::{Mutex, RwLock};
 use serde::de::DeserializeOwned;
 use sqlx::PgPool;
-use std::sync::OnceLock;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::OnceLock,
+};
+
+/// Passkey of the user of the `with_test_user` fixture
+pub const TEST_PASSKEY: &str = "d2037c66dd3e13044e0d2f9b891c3837";
+
+/// Info hash of the torrent of the `with_test_torrent` fixture
+pub const TEST_INFO_HASH: [u8; 20] = [
+    0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00,
+    0x11, 0x22, 0x33, 0x44,
+];
 
 pub async fn create_test_app(
     pool: PgPool,
 ) -> impl Service<Request, Response = ServiceResponse, Error = Error> {
-    // Create a default env for testing
-    let env = Env {
+    create_test_app_with_tracker(create_test_tracker(pool).await).await
+}
+
+pub async fn create_test_app_with_tracker(
+    tracker: web::Data<Tracker>,
+) -> impl Service<Request, Response = ServiceResponse, Error = Error> {
+    test::init_service(App::new().app_data(tracker).configure(init)).await
+}
+
+pub async fn create_test_tracker(pool: PgPool) -> web::Data<Tracker> {
+    create_test_tracker_with_env(pool, test_env()).await
+}
+
+/// A default env for testing
+pub fn test_env() -> Env {
+    Env {
         api_key: "amazing_api_key".to_owned(),
         allowed_torrent_clients: AllowedTorrentClientSet {
             clients: vec![b"lt0F01-".to_vec(), b"qB".to_vec(), b"UTorrent".to_vec()]
@@ -34,20 +64,40 @@ pub async fn create_test_app(
         announce_min_enforced: 0, // Disable rate limiting for tests
         announce_max: 7200,
         max_peers_per_torrent_per_user: 10,
+        cheat_detection_max_upload_speed: 125_000_000,
+        cheat_detection_min_uploaded_delta: 10 * 1024 * 1024,
+        trust_announced_addresses: false,
+        check_connectability: false,
+        connectability_check_timeout_milliseconds: 1000,
+        connectability_cache_ttl: 3600,
+        connectability_max_probes_in_flight: 64,
+        max_unknown_info_hashes_per_user_per_hour: 100,
+        udp_max_requests_in_flight: 1024,
         flush_interval_milliseconds: 60000,
+        flush_retry_max_attempts: 10,
+        flush_retry_max_delay_milliseconds: 60000,
+        flush_spill_directory: None,
         peer_expiry_interval: 600,
         reverse_proxy_client_ip_header_name: None,
         inactive_peer_ttl: 300,
         active_peer_ttl: 3600,
+        arcadia_frontend_url: Some("https://site.com".to_owned()),
         otel_service_name: None,
-    };
+    }
+}
 
+pub async fn create_test_tracker_with_env(pool: PgPool, env: Env) -> web::Data<Tracker> {
     // Load data from test database
     let settings = ArcadiaSettingsForTracker::from_database(&pool).await;
     let users = user::Map::from_database(&pool).await;
     let passkey2id = passkey_2_id::Map::from_database(&pool).await;
     let infohash2id = infohash_2_id::Map::from_database(&pool).await;
+    let info_hashes_by_torrent = infohash2id.info_hashes_by_torrent();
     let torrents = torrent::Map::from_database(&pool).await;
+    let torrent_ids_by_user = torrents.torrent_ids_by_user();
+    let personal_freeleeches = personal_freeleech::Map::from_database(&pool).await;
+    let ip_bans = ip_ban::Map::from_database(&pool).await;
+    let promotion_events = promotion_event::Map::from_database(&pool).await;
 
     let tracker = Tracker {
         env,
@@ -57,13 +107,23 @@ pub async fn create_test_app(
         users: RwLock::new(users),
         passkey2id: RwLock::new(passkey2id),
         infohash2id: RwLock::new(infohash2id),
+        info_hashes_by_torrent: RwLock::new(info_hashes_by_torrent),
         torrents: Mutex::new(torrents),
+        torrent_ids_by_user: RwLock::new(torrent_ids_by_user),
+        personal_freeleeches: RwLock::new(personal_freeleeches),
+        ip_bans: RwLock::new(ip_bans),
+        promotion_events: RwLock::new(promotion_events),
+        connectability_cache: Default::default(),
         user_updates: Mutex::new(Default::default()),
         torrent_updates: Mutex::new(Default::default()),
         peer_updates: Mutex::new(Default::default()),
+        peer_removals: Mutex::new(Default::default()),
+        cheat_flags: Mutex::new(Default::default()),
+        unknown_info_hash_limiter: Default::default(),
+        unregistered_info_hash_updates: Mutex::new(Default::default()),
     };
 
-    test::init_service(App::new().app_data(web::Data::new(tracker)).configure(init)).await
+    web::Data::new(tracker)
 }
 
 pub async fn read_body_bencode<T: DeserializeOwned, B: MessageBody>(
@@ -72,3 +132,29 @@ pub async fn read_body_bencode<T: DeserializeOwned, B: MessageBody>(
     let body = test::read_body(resp).await;
     serde_bencode::from_bytes(&body)
 }
+
+/// Peer id of an allowed client, told apart by its last byte
+pub fn test_peer_id(last_byte: u8) -> [u8; 20] {
+    let mut peer_id = [b'1'; 20];
+    peer_id[..8].copy_from_slice(b"-lt0F01-");
+    peer_id[19] = last_byte;
+    peer_id
+}
+
+/// An announce of the `with_test_torrent` torrent, `params` being the
+/// parameters that follow the info hash and the peer id
+pub fn announce_request(passkey: &str, peer_id: [u8; 20], params: &str, ip: IpAddr) -> Request {
+    let encode = |bytes: &[u8]| {
+        percent_encoding::percent_encode(bytes, percent_encoding::NON_ALPHANUMERIC).to_string()
+    };
+
+    test::TestRequest::get()
+        .uri(&format!(
+            "/{passkey}/announce?info_hash={}&peer_id={}{params}",
+            encode(&TEST_INFO_HASH),
+            encode(&peer_id),
+        ))
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(ip, 0))
+        .to_request()
+}
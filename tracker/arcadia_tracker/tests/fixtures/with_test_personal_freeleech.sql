@@ -0,0 +1,2 @@
+INSERT INTO personal_freeleeches (user_id, torrent_id, expires_at)
+SELECT id, 1, NOW() + INTERVAL '1 day' FROM users WHERE passkey = 'd2037c66dd3e13044e0d2f9b891c3837';
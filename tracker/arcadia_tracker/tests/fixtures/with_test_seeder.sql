@@ -0,0 +1,18 @@
+INSERT INTO
+    peers (torrent_id, peer_id, ip, port, user_id, agent, uploaded, downloaded, "left", seeder, active, created_at, updated_at)
+VALUES
+    (
+        1,
+        '\x2d6c74304630312d313131313131313131313131',
+        '127.0.0.1',
+        6969,
+        1,
+        'test-agent/1.0',
+        1073741824,
+        0,
+        0,
+        true,
+        true,
+        NOW() - INTERVAL '1 hour',
+        NOW() - INTERVAL '1 hour'
+    );
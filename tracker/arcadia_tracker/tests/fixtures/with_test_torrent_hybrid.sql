@@ -0,0 +1 @@
+INSERT INTO torrents (id, edition_group_id, created_at, updated_at, created_by_id, info_hash, info_hash_v2, info_dict, piece_layers, languages, release_name, release_group, description, file_amount_per_type, uploaded_as_anonymous, file_list, mediainfo, trumpable, staff_checked, container, size, duration, audio_codec, audio_bitrate, audio_bitrate_sampling, audio_channels, video_codec, features, subtitle_languages, video_resolution, upload_factor, download_factor, seeders, leechers, times_completed) VALUES (4, 1, '2025-03-30 16:44:41.458969', '2025-03-30 16:44:41.458969', 1, '\x08f33445d4c0d0ac64b9034b437ad1372c6d49a5', '\x8224aa2d2cabe58a2bcbc6cddf1e9a76a62c3272fa6dc123b506ccd9d054b362', '\x64393a66696c65207472656564363a612e666c616364303a64363a6c656e6774686934303030306531313a70696563657320726f6f7433323a8084089d8658b273b6cd3bde5631c773409a7402b81534b548cb44fb45ed6f1d6565393a636f7665722e6a706764303a64363a6c656e6774686931303030306531313a70696563657320726f6f7433323a2958784b328489580f46965220417e41006a37ef5701355390dc10e922f94c2b656565353a66696c65736c64363a6c656e67746869343030303065343a706174686c363a612e666c6163656564343a61747472313a70363a6c656e67746869323535333665343a706174686c343a2e706164353a3235353336656564363a6c656e67746869313030303065343a706174686c393a636f7665722e6a706765656531323a6d6574612076657273696f6e693265343a6e616d6531393a617263616469612d6879627269642d7465737431323a7069656365206c656e67746869333237363865363a70696563657336303a06c9b05663b95a8d42f0f4fe3ad8091bb469c3336c99aabfc0908d2e3a2a7235a685d2fe679f4d63afc1cc358a82881beef42d58d26c77f3dd4290b465', '\x6433323a8084089d8658b273b6cd3bde5631c773409a7402b81534b548cb44fb45ed6f1d36343afdee58e987ca4312baf5664943d103b4ad7c7264efec2a178bb641f02520e23344fef3caed252c81653ab132aa7232ef223cb5bd08011afbde651503b34a70ac65', '{}', 'Test Hybrid Torrent', '', 'Test description', '{"flac": 1, "jpg": 1}', false, '{"files": [{"name": "a.flac", "size": 40000}, {"name": "cover.jpg", "size": 10000}], "parent_folder": "arcadia-hybrid-test"}', 'none', '', false, 'FLAC', 50000, NULL, 'flac', 650, '24bit Lossless', NULL, NULL, '{}', '{}', NULL, 100, 100, 0, 0, 0);
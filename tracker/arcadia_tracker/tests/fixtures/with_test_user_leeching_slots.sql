@@ -0,0 +1,8 @@
+INSERT INTO
+    user_classes (name, max_leeching, max_leeching_scaled_by_ratio)
+VALUES
+    ('limited_leecher', 2, TRUE);
+INSERT INTO
+    users (username, email, password_hash, registered_from_ip, passkey, class_name, css_sheet_name, uploaded, downloaded)
+VALUES
+    ('slots_user', 'test_slots@testdomain.com', '$argon2id$v=19$m=19456,t=2,p=1$WM6V9pJ2ya7+N+NNIUtolg$n128u9idizCHLwZ9xhKaxOttLaAVZZgvfRZlRAnfyKk', '10.10.4.88', 'e3037c66dd3e13044e0d2f9b891c3839', 'limited_leecher', 'arcadia', 1000, 2000);
@@ -0,0 +1,155 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use actix_web::{http::StatusCode, test};
+use arcadia_shared::tracker::models::torrent::{APIInsertTorrent, InfoHash};
+use arcadia_tracker::handlers::{
+    state::get_flush_queues::FlushQueue, torrents::get_torrent::TrackerTorrent,
+    users::get_user::TrackerUser,
+};
+use sqlx::PgPool;
+
+fn announce() -> actix_http::Request {
+    common::announce_request(
+        common::TEST_PASSKEY,
+        common::test_peer_id(b'1'),
+        "&port=6969&uploaded=0&downloaded=0&left=1000&event=started",
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    )
+}
+
+fn admin_request(method: &str, uri: &str) -> actix_http::Request {
+    let req = match method {
+        "POST" => test::TestRequest::post(),
+        _ => test::TestRequest::get(),
+    };
+    req.uri(uri)
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .to_request()
+}
+
+fn queued(queues: &[FlushQueue], name: &str) -> u64 {
+    queues
+        .iter()
+        .find(|queue| queue.name == name)
+        .expect("queue should be listed")
+        .queued
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_inspect_torrent_and_user(pool: PgPool) {
+    let user_id: (i32,) = sqlx::query_as("SELECT id FROM users WHERE passkey = $1")
+        .bind(common::TEST_PASSKEY)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query user");
+    let user_id = user_id.0 as u32;
+    let service = common::create_test_app(pool).await;
+
+    let resp = test::call_service(&service, announce()).await;
+    assert!(resp.status().is_success());
+
+    let torrent: TrackerTorrent =
+        test::call_and_read_body_json(&service, admin_request("GET", "/api/torrents/1")).await;
+    assert_eq!(
+        torrent.info_hashes,
+        [InfoHash(common::TEST_INFO_HASH).to_string()]
+    );
+    assert_eq!(torrent.leechers, 1);
+    assert_eq!(torrent.peers.len(), 1);
+    assert_eq!(torrent.peers[0].user_id, user_id);
+    assert!(!torrent.peers[0].is_seeder);
+
+    let user: TrackerUser = test::call_and_read_body_json(
+        &service,
+        admin_request("GET", &format!("/api/users/{user_id}")),
+    )
+    .await;
+    assert_eq!(user.num_leeching, 1);
+    assert_eq!(user.leeching, [1]);
+    assert!(user.seeding.is_empty());
+
+    let resp = test::call_service(&service, admin_request("GET", "/api/torrents/999")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = test::call_service(&service, admin_request("GET", "/api/users/999")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_forced_flush_empties_the_queues(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let resp = test::call_service(&service, announce()).await;
+    assert!(resp.status().is_success());
+
+    let queues: Vec<FlushQueue> =
+        test::call_and_read_body_json(&service, admin_request("GET", "/api/flush-queues")).await;
+    assert_eq!(queued(&queues, "peer_updates"), 1);
+
+    let resp = test::call_service(&service, admin_request("POST", "/api/tasks/flush")).await;
+    assert!(resp.status().is_success());
+
+    let queues: Vec<FlushQueue> =
+        test::call_and_read_body_json(&service, admin_request("GET", "/api/flush-queues")).await;
+    assert!(queues.iter().all(|queue| queue.queued == 0));
+
+    // the peer just announced
+    let removed_peers: u64 =
+        test::call_and_read_body_json(&service, admin_request("POST", "/api/tasks/reap")).await;
+    assert_eq!(removed_peers, 0);
+}
+
+#[sqlx::test(migrations = "../../backend/storage/migrations")]
+async fn test_upserted_torrent_lists_its_info_hashes(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let info_hash = InfoHash([0x11; 20]);
+    let info_hash_v2 = InfoHash([0x22; 20]);
+    // sent twice, the info hashes are only listed once
+    for _ in 0..2 {
+        let req = test::TestRequest::put()
+            .uri("/api/torrents")
+            .insert_header(("x-api-key", "amazing_api_key"))
+            .set_json(APIInsertTorrent {
+                id: 42,
+                info_hash,
+                info_hash_v2: Some(info_hash_v2),
+                is_deleted: false,
+                deletion_reason: None,
+                replacement_torrent_id: None,
+                seeders: 0,
+                leechers: 0,
+                times_completed: 0,
+                upload_factor: 100,
+                download_factor: 100,
+                scope: Default::default(),
+            })
+            .to_request();
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    let torrent: TrackerTorrent =
+        test::call_and_read_body_json(&service, admin_request("GET", "/api/torrents/42")).await;
+    assert_eq!(
+        torrent.info_hashes,
+        [info_hash.to_string(), info_hash_v2.to_string()]
+    );
+}
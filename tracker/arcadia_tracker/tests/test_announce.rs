@@ -3,6 +3,14 @@ mod common;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use actix_web::test;
+use arcadia_shared::tracker::models::{
+    ip_ban::APIInsertIpBan,
+    personal_freeleech::APIInsertPersonalFreeleech,
+    torrent::{APIInsertTorrent, InfoHash, TorrentDeletionReason},
+    user_update::{self, UserUpdate},
+    Flushable,
+};
+use arcadia_tracker::env::Env;
 use common::{create_test_app, read_body_bencode};
 use serde::Deserialize;
 use sqlx::PgPool;
@@ -174,7 +182,8 @@ async fn test_announce_passkey_not_found(pool: PgPool) {
     migrations = "../../backend/storage/migrations"
 )]
 async fn test_announce_info_hash_not_found(pool: PgPool) {
-    let service = common::create_test_app(pool).await;
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
 
     let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
     // Use an info_hash that doesn't exist in the database
@@ -200,6 +209,61 @@ async fn test_announce_info_hash_not_found(pool: PgPool) {
         .expect("Failed to decode error response");
 
     assert_eq!(error.failure_reason, "InfoHash not found.");
+
+    let updates = tracker.unregistered_info_hash_updates.lock();
+    let (index, update) = updates
+        .records
+        .iter()
+        .next()
+        .expect("info hash should be logged");
+    assert_eq!(index.info_hash, InfoHash(info_hash_bytes));
+    assert_eq!(update.torrent_id, None);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_unknown_info_hashes_logged_per_user_are_capped(pool: PgPool) {
+    let env = Env {
+        max_unknown_info_hashes_per_user_per_hour: 1,
+        ..common::test_env()
+    };
+    let tracker = common::create_test_tracker_with_env(pool, env).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+
+    // the second hash is over the cap, the first one keeps being logged
+    for info_hash_bytes in [[0xFF; 20], [0xEE; 20], [0xFF; 20]] {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=1000&event=started",
+                valid_passkey, url_encode_info_hash(&info_hash_bytes), peer_id_encoded
+            ))
+            .insert_header(("User-Agent", "test-agent/1.0"))
+            .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+            .to_request();
+
+        let resp = test::call_service(&service, req).await;
+        let error: WrappedError = read_body_bencode(resp)
+            .await
+            .expect("Failed to decode error response");
+        assert_eq!(error.failure_reason, "InfoHash not found.");
+    }
+
+    let updates = tracker.unregistered_info_hash_updates.lock();
+    assert_eq!(updates.records.len(), 1);
+    let (index, _) = updates.records.iter().next().unwrap();
+    assert_eq!(index.info_hash, InfoHash([0xFF; 20]));
 }
 
 #[sqlx::test(
@@ -633,6 +697,106 @@ async fn test_announce_snatch_limit_exceeded(pool: PgPool) {
     );
 }
 
+#[sqlx::test(
+    fixtures(
+        "with_test_user_leeching_slots",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_torrent_2"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_leeching_slots_limit(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // 2 slots scaled by a ratio of 0.5
+    let valid_passkey = "e3037c66dd3e13044e0d2f9b891c3839";
+    assert_eq!(tracker.users.read()[&2].max_leeching, Some(1));
+    let first_client = test_peer_id();
+    let mut second_client = test_peer_id();
+    second_client[19] = b'2';
+    let info_hash_1 = [
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x44,
+    ];
+    let info_hash_2 = [
+        0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x55,
+    ];
+    let announce = |info_hash: &[u8; 20], peer_id: &[u8; 20], left: u64| {
+        test::TestRequest::get()
+            .uri(&format!(
+                "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left={}&event=started&compact=1",
+                valid_passkey,
+                url_encode_info_hash(info_hash),
+                percent_encoding::percent_encode(peer_id, percent_encoding::NON_ALPHANUMERIC),
+                left
+            ))
+            .insert_header(("User-Agent", "test-agent/1.0"))
+            .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+            .to_request()
+    };
+    let expected_error = "You are already leeching as many torrents as your class allows (1).";
+
+    let resp = test::call_service(&service, announce(&info_hash_1, &first_client, 1000)).await;
+    assert!(resp.status().is_success(), "First leech should succeed");
+
+    // the slot is per torrent, not per client
+    let resp = test::call_service(&service, announce(&info_hash_1, &second_client, 1000)).await;
+    let response: AnnounceResponse = read_body_bencode(resp)
+        .await
+        .expect("Leeching the same torrent from another client should succeed");
+    assert_eq!(response.leechers, 2);
+    assert_eq!(tracker.users.read()[&2].num_leeching, 1);
+
+    let resp = test::call_service(&service, announce(&info_hash_2, &first_client, 1000)).await;
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(error.failure_reason, expected_error);
+    assert!(tracker.torrents.lock()[&2].peers.is_empty());
+
+    // seeding doesn't take a slot
+    let resp = test::call_service(&service, announce(&info_hash_2, &first_client, 0)).await;
+    assert!(resp.status().is_success(), "Seeding should succeed");
+
+    // but going back to leeching does
+    let resp = test::call_service(&service, announce(&info_hash_2, &first_client, 1000)).await;
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(error.failure_reason, expected_error);
+    assert!(tracker.torrents.lock()[&2]
+        .peers
+        .values()
+        .all(|peer| peer.is_seeder));
+}
+
+#[sqlx::test(
+    fixtures("with_test_user_leeching_slots"),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_leeching_slots_follow_the_flushed_ratio(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    assert_eq!(tracker.users.read()[&2].max_leeching, Some(1));
+
+    // from a ratio of 0.5 to 1
+    tracker.user_updates.lock().upsert(
+        user_update::Index { user_id: 2 },
+        UserUpdate {
+            uploaded_delta: 1000,
+            downloaded_delta: 0,
+            real_uploaded_delta: 1000,
+            real_downloaded_delta: 0,
+        },
+    );
+    arcadia_tracker::scheduler::flush(&tracker).await;
+
+    assert_eq!(tracker.users.read()[&2].max_leeching, Some(2));
+}
+
 #[sqlx::test(
     fixtures(
         "with_test_user",
@@ -896,6 +1060,67 @@ async fn test_announce_no_double_bonus_points_deduction(pool: PgPool) {
     assert_eq!(row.0, 50, "User should have 50 BP (only one deduction)");
 }
 
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_user_bonus_points",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent_snatch_cost"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_leech_over_the_slots_limit_is_not_charged(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool.clone()).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // the only slot of the user is already taken
+    {
+        let mut users = tracker.users.write();
+        let user = users.get_mut(&10).unwrap();
+        user.max_leeching = Some(1);
+        user.num_leeching = 1;
+    }
+
+    let valid_passkey = "f4037c66dd3e13044e0d2f9b891c3839";
+    let info_hash_bytes = [
+        0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88,
+        0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+    ];
+    let info_hash_encoded = url_encode_info_hash(&info_hash_bytes);
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+
+    // clients retry the refused announce
+    for _ in 0..3 {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=1000&event=started&compact=1",
+                valid_passkey, info_hash_encoded, peer_id_encoded
+            ))
+            .insert_header(("User-Agent", "test-agent/1.0"))
+            .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+            .to_request();
+
+        let resp = test::call_service(&service, req).await;
+        let error: WrappedError = read_body_bencode(resp)
+            .await
+            .expect("Failed to decode error response");
+        assert_eq!(
+            error.failure_reason,
+            "You are already leeching as many torrents as your class allows (1)."
+        );
+    }
+
+    let row: (i64,) = sqlx::query_as("SELECT bonus_points FROM users WHERE id = 10")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query user");
+
+    assert_eq!(row.0, 100, "Refused leeches should not be charged");
+}
+
 #[sqlx::test(
     fixtures(
         "with_test_user",
@@ -1237,3 +1462,359 @@ async fn test_delete_torrent_rejects_announce(pool: PgPool) {
         .expect("Failed to decode error response");
     assert_eq!(error.failure_reason, "Torrent has been deleted.");
 }
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_torrent_2"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_trumped_torrent_links_replacement(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool.clone()).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    let info_hash_bytes = [
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x44,
+    ];
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+
+    // the backend sends the deleted torrent along with why it was deleted
+    let req = test::TestRequest::put()
+        .uri("/api/torrents")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .set_json(APIInsertTorrent {
+            id: 1,
+            info_hash: InfoHash(info_hash_bytes),
+            info_hash_v2: None,
+            is_deleted: true,
+            deletion_reason: Some(TorrentDeletionReason::Trumped),
+            replacement_torrent_id: Some(2),
+            seeders: 0,
+            leechers: 0,
+            times_completed: 0,
+            upload_factor: 100,
+            download_factor: 100,
+            scope: Default::default(),
+        })
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=0&event=stopped",
+            valid_passkey, url_encode_info_hash(&info_hash_bytes), peer_id_encoded
+        ))
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(
+        error.failure_reason,
+        "Torrent has been deleted (trumped), replaced by https://site.com/torrent/2."
+    );
+
+    assert_eq!(
+        tracker
+            .unregistered_info_hash_updates
+            .flush_to_database(&pool)
+            .await,
+        1
+    );
+    let row: (Option<i32>,) =
+        sqlx::query_as("SELECT torrent_id FROM unregistered_info_hashes WHERE info_hash = $1")
+            .bind(info_hash_bytes.to_vec())
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query unregistered info hashes");
+    assert_eq!(row.0, Some(1));
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_personal_freeleech"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_personal_freeleech(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    let info_hash_bytes = [
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x44,
+    ];
+    let info_hash_encoded = url_encode_info_hash(&info_hash_bytes);
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+
+    for (downloaded, event) in [(0, "&event=started"), (1000, "")] {
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded={}&left=1000{}&compact=1",
+                valid_passkey, info_hash_encoded, peer_id_encoded, downloaded, event
+            ))
+            .insert_header(("User-Agent", "test-agent/1.0"))
+            .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+            .to_request();
+
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    // The download is recorded but not credited to the user
+    let user_updates = tracker.user_updates.lock();
+    let update = user_updates
+        .records
+        .values()
+        .next()
+        .expect("user update should be queued");
+    assert_eq!(update.real_downloaded_delta, 1000);
+    assert_eq!(update.downloaded_delta, 0);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_insert_personal_freeleech_applies_to_announce(pool: PgPool) {
+    let user_row: (i32,) =
+        sqlx::query_as("SELECT id FROM users WHERE passkey = 'd2037c66dd3e13044e0d2f9b891c3837'")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query user");
+
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    let info_hash_bytes = [
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x44,
+    ];
+    let info_hash_encoded = url_encode_info_hash(&info_hash_bytes);
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=1000&event=started&compact=1",
+            valid_passkey, info_hash_encoded, peer_id_encoded
+        ))
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    // Backend pushes the personal freeleech once the token is spent
+    let req = test::TestRequest::put()
+        .uri("/api/personal-freeleeches")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .set_json(APIInsertPersonalFreeleech {
+            user_id: user_row.0 as u32,
+            torrent_id: 1,
+            expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+        })
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=1000&left=1000&compact=1",
+            valid_passkey, info_hash_encoded, peer_id_encoded
+        ))
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let user_updates = tracker.user_updates.lock();
+    let update = user_updates
+        .records
+        .values()
+        .next()
+        .expect("user update should be queued");
+    assert_eq!(update.real_downloaded_delta, 1000);
+    assert_eq!(update.downloaded_delta, 0);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_ip_bans_reject_announce(pool: PgPool) {
+    // loaded when the tracker starts
+    sqlx::query(
+        "INSERT INTO ip_bans (created_by_id, ip_range, reason) SELECT id, '10.0.0.0/8', 'test' FROM users LIMIT 1",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to insert ip ban");
+
+    let service = create_test_app(pool).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    let info_hash_bytes = [
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF,
+        0x00, 0x11, 0x22, 0x33, 0x44,
+    ];
+    let info_hash_encoded = url_encode_info_hash(&info_hash_bytes);
+    let peer_id = test_peer_id();
+    let peer_id_encoded =
+        percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC).to_string();
+    let announce_uri = format!(
+        "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=1000&event=started&compact=1",
+        valid_passkey, info_hash_encoded, peer_id_encoded
+    );
+
+    let req = test::TestRequest::get()
+        .uri(&announce_uri)
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(error.failure_reason, "Your IP address is banned.");
+
+    // dual stack listeners see the same client as an ipv4-mapped ipv6 address
+    let req = test::TestRequest::get()
+        .uri(&announce_uri)
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(
+            IpAddr::V6(Ipv4Addr::new(10, 1, 2, 3).to_ipv6_mapped()),
+            0,
+        ))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(error.failure_reason, "Your IP address is banned.");
+
+    // Backend pushes new bans and lifted ones
+    let req = test::TestRequest::put()
+        .uri("/api/ip-bans")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .set_json(APIInsertIpBan {
+            id: 1000,
+            ip_range: "127.0.0.1/32".parse().unwrap(),
+            expires_at: None,
+        })
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&announce_uri)
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+    assert_eq!(error.failure_reason, "Your IP address is banned.");
+
+    let req = test::TestRequest::delete()
+        .uri("/api/ip-bans/1000")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&announce_uri)
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(read_body_bencode::<WrappedError, _>(resp).await.is_err());
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent_hybrid"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announce_hybrid_torrent_shares_swarm(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let valid_passkey = "d2037c66dd3e13044e0d2f9b891c3837";
+    // v1 info hash from with_test_torrent_hybrid.sql
+    let info_hash_v1 = [
+        0x08, 0xF3, 0x34, 0x45, 0xD4, 0xC0, 0xD0, 0xAC, 0x64, 0xB9, 0x03, 0x4B, 0x43, 0x7A, 0xD1,
+        0x37, 0x2C, 0x6D, 0x49, 0xA5,
+    ];
+    // v2 info hash truncated to 20 bytes, which v2 clients announce with
+    let info_hash_v2 = [
+        0x82, 0x24, 0xAA, 0x2D, 0x2C, 0xAB, 0xE5, 0x8A, 0x2B, 0xCB, 0xC6, 0xCD, 0xDF, 0x1E, 0x9A,
+        0x76, 0xA6, 0x2C, 0x32, 0x72,
+    ];
+    let announce = |info_hash: &[u8; 20], peer_id_fill: u8, left: u64| {
+        let mut peer_id = test_peer_id();
+        peer_id[8..].fill(peer_id_fill);
+        let peer_id_encoded =
+            percent_encoding::percent_encode(&peer_id, percent_encoding::NON_ALPHANUMERIC)
+                .to_string();
+        test::TestRequest::get()
+            .uri(&format!(
+                "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left={}&event=started&compact=1",
+                valid_passkey, url_encode_info_hash(info_hash), peer_id_encoded, left
+            ))
+            .insert_header(("User-Agent", "test-agent/1.0"))
+            .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+            .to_request()
+    };
+
+    let resp = test::call_service(&service, announce(&info_hash_v1, b'1', 1000)).await;
+    assert!(resp.status().is_success(), "v1 announce should succeed");
+
+    let resp = test::call_service(&service, announce(&info_hash_v2, b'2', 0)).await;
+    assert!(resp.status().is_success(), "v2 announce should succeed");
+    let announce_resp: AnnounceResponse = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode announce response");
+
+    assert_eq!(announce_resp.seeders, 1);
+    assert_eq!(announce_resp.leechers, 1);
+    assert_eq!(tracker.torrents.lock()[&4].peers.len(), 2);
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use actix_web::test;
+use arcadia_shared::tracker::models::{
+    cheat_flag::{CheatFlagReason, CheatFlagSeverity},
+    Flushable,
+};
+use sqlx::PgPool;
+
+const MIB: u64 = 1024 * 1024;
+const GIB: u64 = 1024 * MIB;
+
+fn announce(uploaded: u64, event: &str) -> actix_http::Request {
+    common::announce_request(
+        common::TEST_PASSKEY,
+        common::test_peer_id(b'1'),
+        &format!("&port=6969&uploaded={uploaded}&downloaded=0&left=0{event}&compact=1"),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    )
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_impossible_upload_is_flagged(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool.clone()).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // the only peer of the torrent uploads a gigabyte right after starting
+    for req in [announce(0, "&event=started"), announce(GIB, "")] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    let mut flags: Vec<_> = tracker
+        .cheat_flags
+        .lock()
+        .records
+        .iter()
+        .map(|(index, flag)| (index.reason, flag.severity, flag.credit_withheld))
+        .collect();
+    flags.sort();
+    assert_eq!(
+        flags,
+        [
+            (
+                CheatFlagReason::ImpossibleUploadSpeed,
+                CheatFlagSeverity::High,
+                false
+            ),
+            (
+                CheatFlagReason::UploadWithoutLeechers,
+                CheatFlagSeverity::High,
+                false
+            ),
+            (
+                CheatFlagReason::UploadExceedsSwarmDownload,
+                CheatFlagSeverity::Low,
+                false
+            ),
+        ]
+    );
+    // credited while the setting is disabled
+    assert_eq!(
+        tracker
+            .user_updates
+            .lock()
+            .records
+            .values()
+            .next()
+            .expect("user update should be queued")
+            .uploaded_delta,
+        GIB
+    );
+
+    assert_eq!(tracker.cheat_flags.flush_to_database(&pool).await, 3);
+    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM cheat_flags WHERE uploaded_delta = $1")
+        .bind(GIB as i64)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query cheat flags");
+    assert_eq!(row.0, 3);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_flagged_upload_is_not_credited(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    tracker
+        .settings
+        .write()
+        .cheat_flag_withhold_credit_min_severity = Some(CheatFlagSeverity::High);
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    for req in [announce(0, "&event=started"), announce(GIB, "")] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    assert!(tracker
+        .cheat_flags
+        .lock()
+        .records
+        .values()
+        .all(|flag| flag.credit_withheld));
+    let user_updates = tracker.user_updates.lock();
+    let update = user_updates
+        .records
+        .values()
+        .next()
+        .expect("user update should be queued");
+    assert_eq!(update.uploaded_delta, 0);
+    assert_eq!(update.real_uploaded_delta, GIB);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_leecher",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_upload_to_peers_that_left_is_counted(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+    let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    let seeder = |uploaded: u64, event: &str| {
+        common::announce_request(
+            common::TEST_PASSKEY,
+            common::test_peer_id(b's'),
+            &format!("&port=6969&uploaded={uploaded}&downloaded=0&left=0{event}&compact=1"),
+            ip,
+        )
+    };
+    let leecher = |downloaded: u64, event: &str| {
+        common::announce_request(
+            "a7037c66dd3e13044e0d2f9b891c3842",
+            common::test_peer_id(b'l'),
+            &format!("&port=6970&uploaded=0&downloaded={downloaded}&left=1000{event}&compact=1"),
+            ip,
+        )
+    };
+
+    // the leecher completes its download from the seeder and leaves before
+    // the seeder announces its upload
+    for req in [
+        seeder(0, "&event=started"),
+        leecher(0, "&event=started"),
+        leecher(100 * MIB, "&event=stopped"),
+        seeder(100 * MIB, ""),
+    ] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    let flags: Vec<_> = tracker
+        .cheat_flags
+        .lock()
+        .records
+        .iter()
+        .map(|(index, flag)| (index.reason, flag.severity, flag.swarm_downloaded))
+        .collect();
+    assert_eq!(
+        flags,
+        [(
+            CheatFlagReason::UploadWithoutLeechers,
+            CheatFlagSeverity::Medium,
+            100 * MIB
+        )]
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_seeder"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_peers_loaded_from_database_are_not_flagged_on_their_first_announce(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // what the swarm downloaded while the tracker was down is unknown, only
+    // the upload speed can be checked
+    let resp = test::call_service(&service, announce(GIB + 100 * MIB, "")).await;
+    assert!(resp.status().is_success());
+    assert!(tracker.cheat_flags.lock().records.is_empty());
+
+    // the next announce has a baseline
+    let resp = test::call_service(&service, announce(GIB + 200 * MIB, "")).await;
+    assert!(resp.status().is_success());
+    let mut reasons: Vec<_> = tracker
+        .cheat_flags
+        .lock()
+        .records
+        .keys()
+        .map(|index| index.reason)
+        .collect();
+    reasons.sort();
+    assert_eq!(
+        reasons,
+        [
+            CheatFlagReason::UploadWithoutLeechers,
+            CheatFlagReason::UploadExceedsSwarmDownload,
+        ]
+    );
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use actix_web::test;
+use arcadia_tracker::env::Env;
+use sqlx::PgPool;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+fn announce(peer_id_suffix: u8, port: u16) -> actix_http::Request {
+    common::announce_request(
+        common::TEST_PASSKEY,
+        common::test_peer_id(peer_id_suffix),
+        &format!("&port={port}&uploaded=0&downloaded=0&left=0&event=started&compact=1"),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    )
+}
+
+/// A peer answering the handshakes of the torrent
+async fn spawn_connectable_peer() -> u16 {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut handshake = [0; 68];
+            if stream.read_exact(&mut handshake).await.is_ok() {
+                // same protocol and info hash, with our own peer id
+                handshake[48..].fill(b'2');
+                let _ = stream.write_all(&handshake).await;
+            }
+        }
+    });
+
+    port
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announcing_peers_are_probed(pool: PgPool) {
+    let env = Env {
+        check_connectability: true,
+        ..common::test_env()
+    };
+    let tracker = common::create_test_tracker_with_env(pool, env).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let connectable_port = spawn_connectable_peer().await;
+    // nothing listens on the port anymore once the listener is dropped
+    let firewalled_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let announce_both = || async {
+        for req in [
+            announce(b'a', connectable_port),
+            announce(b'b', firewalled_port),
+        ] {
+            let resp = test::call_service(&service, req).await;
+            assert!(resp.status().is_success());
+        }
+    };
+
+    // the probes run in the background, announces don't wait for them
+    announce_both().await;
+    for _ in 0..50 {
+        if tracker.connectability_cache.lock().probes_in_flight() == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(tracker.connectability_cache.lock().probes_in_flight(), 0);
+
+    // the outcome applies from the next announce on
+    announce_both().await;
+
+    let mut connectable_peers: Vec<_> = tracker.torrents.lock()[&1]
+        .peers
+        .values()
+        .map(|peer| (peer.port, peer.is_connectable))
+        .collect();
+    connectable_peers.sort();
+    let mut expected = vec![(connectable_port, true), (firewalled_port, false)];
+    expected.sort();
+    assert_eq!(connectable_peers, expected);
+
+    // the outcome is stored with the peers
+    let peer_updates = tracker.peer_updates.lock();
+    assert!(peer_updates
+        .records
+        .values()
+        .any(|update| update.port == connectable_port && update.is_connectable));
+
+    // and cached for the address
+    let connectability_cache = tracker.connectability_cache.lock();
+    let now = chrono::Utc::now();
+    assert_eq!(
+        connectability_cache.get(
+            &SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), firewalled_port),
+            now
+        ),
+        Some(false)
+    );
+}
@@ -0,0 +1,243 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use actix_web::test;
+use arcadia_shared::tracker::models::{
+    peer::{self, Peer},
+    peer_id::PeerId,
+};
+use arcadia_tracker::env::Env;
+use chrono::Utc;
+use common::{read_body_bencode, test_peer_id};
+use serde_bencode::value::Value;
+use sqlx::PgPool;
+
+const TORRENT_ID: u32 = 1;
+const IPV4: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 10, 4, 88));
+const IPV6: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+
+fn announce(peer_id: [u8; 20], left: u64, params: &str, ip: IpAddr) -> actix_http::Request {
+    common::announce_request(
+        common::TEST_PASSKEY,
+        peer_id,
+        &format!("&port=6969&uploaded=0&downloaded=0&left={left}{params}"),
+        ip,
+    )
+}
+
+fn dict_get<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Dict(dict) => dict.get(key.as_bytes()),
+        _ => panic!("expected a dictionary, got {value:?}"),
+    }
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_dual_stack_addresses_require_the_key(pool: PgPool) {
+    let env = Env {
+        trust_announced_addresses: true,
+        ..common::test_env()
+    };
+    let tracker = common::create_test_tracker_with_env(pool, env).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let peer_addresses = |peer_id: [u8; 20]| {
+        let torrents = tracker.torrents.lock();
+        let (_, peer) = torrents[&TORRENT_ID]
+            .peers
+            .iter()
+            .find(|(index, _)| index.peer_id == PeerId(peer_id))
+            .expect("peer should be tracked");
+        (peer.ipv4, peer.ipv6)
+    };
+
+    // the explicit address of the other family is ignored without a key
+    let req = announce(test_peer_id(b'a'), 0, "&ipv6=2001%3Adb8%3A%3A1", IPV4);
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        peer_addresses(test_peer_id(b'a')),
+        (Some(Ipv4Addr::new(10, 10, 4, 88)), None)
+    );
+
+    let req = announce(
+        test_peer_id(b'b'),
+        0,
+        "&ipv6=2001%3Adb8%3A%3A1&key=1A2B3C4D",
+        IPV4,
+    );
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        peer_addresses(test_peer_id(b'b')),
+        (Some(Ipv4Addr::new(10, 10, 4, 88)), Some(IPV6))
+    );
+
+    // announcing over ipv6 keeps the ipv4 address from the previous announce
+    let req = announce(test_peer_id(b'b'), 0, "&key=1a2b3c4d", IpAddr::V6(IPV6));
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        peer_addresses(test_peer_id(b'b')),
+        (Some(Ipv4Addr::new(10, 10, 4, 88)), Some(IPV6))
+    );
+
+    // but the addresses can't be changed with another key
+    let req = announce(
+        test_peer_id(b'b'),
+        0,
+        "&ipv4=10.10.4.99&key=DEADBEEF",
+        IpAddr::V6(IPV6),
+    );
+    let error: Value = read_body_bencode(test::call_service(&service, req).await)
+        .await
+        .expect("Failed to decode announce response");
+    assert_eq!(
+        dict_get(&error, "failure reason"),
+        Some(&Value::Bytes(
+            b"The key does not match the one of this peer.".to_vec()
+        ))
+    );
+    assert_eq!(
+        peer_addresses(test_peer_id(b'b')),
+        (Some(Ipv4Addr::new(10, 10, 4, 88)), Some(IPV6))
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_announced_addresses_are_ignored_unless_trusted(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let peer_addresses = || {
+        let torrents = tracker.torrents.lock();
+        let (_, peer) = torrents[&TORRENT_ID]
+            .peers
+            .iter()
+            .find(|(index, _)| index.peer_id == PeerId(test_peer_id(b'a')))
+            .expect("peer should be tracked");
+        (peer.ipv4, peer.ipv6)
+    };
+
+    let req = announce(
+        test_peer_id(b'a'),
+        0,
+        "&ipv6=2001%3Adb8%3A%3A2&key=1A2B3C4D",
+        IPV4,
+    );
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(peer_addresses(), (Some(Ipv4Addr::new(10, 10, 4, 88)), None));
+
+    // the address of the other family is still learned by announcing over it
+    let req = announce(test_peer_id(b'a'), 0, "&key=1A2B3C4D", IpAddr::V6(IPV6));
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        peer_addresses(),
+        (Some(Ipv4Addr::new(10, 10, 4, 88)), Some(IPV6))
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_peer_list_formats(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // a dual-stack seeder of another user
+    {
+        let mut torrents = tracker.torrents.lock();
+        let torrent = torrents.get_mut(&TORRENT_ID).unwrap();
+        torrent.peers.insert(
+            peer::Index {
+                user_id: 99,
+                peer_id: PeerId(test_peer_id(b's')),
+            },
+            Peer {
+                ip_address: IPV4,
+                ipv4: Some(Ipv4Addr::new(10, 10, 4, 88)),
+                ipv6: Some(IPV6),
+                key: None,
+                port: 51413,
+                is_seeder: true,
+                is_active: true,
+                is_connectable: false,
+                has_sent_completed: false,
+                updated_at: Utc::now(),
+                uploaded: 0,
+                downloaded: 0,
+                swarm_downloaded_at_announce: None,
+            },
+        );
+        torrent.seeders += 1;
+    }
+
+    let req = announce(test_peer_id(b'c'), 1000, "&compact=1", IPV4);
+    let response: Value = read_body_bencode(test::call_service(&service, req).await)
+        .await
+        .expect("Failed to decode announce response");
+    assert_eq!(
+        dict_get(&response, "peers"),
+        Some(&Value::Bytes(vec![10, 10, 4, 88, 0xc8, 0xd5]))
+    );
+    let mut peers6 = IPV6.octets().to_vec();
+    peers6.extend([0xc8, 0xd5]);
+    assert_eq!(dict_get(&response, "peers6"), Some(&Value::Bytes(peers6)));
+
+    let req = announce(test_peer_id(b'd'), 1000, "&compact=0", IPV4);
+    let response: Value = read_body_bencode(test::call_service(&service, req).await)
+        .await
+        .expect("Failed to decode announce response");
+    let Some(Value::List(peers)) = dict_get(&response, "peers") else {
+        panic!("expected a dictionary model peer list, got {response:?}");
+    };
+    let ips: Vec<_> = peers.iter().map(|peer| dict_get(peer, "ip")).collect();
+    assert_eq!(
+        ips,
+        [
+            Some(&Value::Bytes(b"10.10.4.88".to_vec())),
+            Some(&Value::Bytes(b"2001:db8::1".to_vec()))
+        ]
+    );
+    assert_eq!(
+        dict_get(&peers[0], "peer id"),
+        Some(&Value::Bytes(test_peer_id(b's').to_vec()))
+    );
+    assert_eq!(dict_get(&peers[0], "port"), Some(&Value::Int(51413)));
+    assert_eq!(dict_get(&response, "peers6"), None);
+
+    let req = announce(test_peer_id(b'e'), 1000, "&compact=0&no_peer_id=1", IPV4);
+    let response: Value = read_body_bencode(test::call_service(&service, req).await)
+        .await
+        .expect("Failed to decode announce response");
+    let Some(Value::List(peers)) = dict_get(&response, "peers") else {
+        panic!("expected a dictionary model peer list, got {response:?}");
+    };
+    assert_eq!(peers.len(), 2);
+    assert!(peers.iter().all(|peer| dict_get(peer, "peer id").is_none()));
+}
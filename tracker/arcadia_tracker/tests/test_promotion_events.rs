@@ -0,0 +1,223 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use actix_web::test;
+use arcadia_shared::tracker::models::promotion_event::{
+    APIInsertPromotionEvent, PromotionEventScope,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+fn announce(uploaded: u64, downloaded: u64, event: &str) -> actix_http::Request {
+    common::announce_request(
+        common::TEST_PASSKEY,
+        common::test_peer_id(b'1'),
+        &format!(
+            "&port=6969&uploaded={uploaded}&downloaded={downloaded}&left=1000{event}&compact=1"
+        ),
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+    )
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_scoped_promotion_events_loaded_at_startup(pool: PgPool) {
+    // the test torrent is a 700MB music release
+    sqlx::query(
+        r#"
+        INSERT INTO promotion_events (created_by_id, name, starts_at, ends_at, upload_factor,
+                                      download_factor, content_type, min_size)
+        SELECT id, 'big music freeleech', NOW() - INTERVAL '1 hour', NOW() + INTERVAL '1 day',
+               100, 0, 'music', 1073741824
+        FROM users LIMIT 1
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to insert promotion event");
+    sqlx::query(
+        r#"
+        INSERT INTO promotion_events (created_by_id, name, starts_at, ends_at, upload_factor,
+                                      download_factor, content_type)
+        SELECT id, 'music half leech', NOW() - INTERVAL '1 hour', NOW() + INTERVAL '1 day',
+               100, 50, 'music'
+        FROM users LIMIT 1
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to insert promotion event");
+
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    for req in [announce(0, 0, "&event=started"), announce(0, 1000, "")] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    let user_updates = tracker.user_updates.lock();
+    let update = user_updates
+        .records
+        .values()
+        .next()
+        .expect("user update should be queued");
+    assert_eq!(update.real_downloaded_delta, 1000);
+    assert_eq!(update.downloaded_delta, 500);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_pushed_promotion_events_apply_while_active(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let now = Utc::now();
+    let events = [
+        // not started yet
+        APIInsertPromotionEvent {
+            id: 1,
+            starts_at: now + Duration::hours(1),
+            ends_at: now + Duration::hours(2),
+            upload_factor: 100,
+            download_factor: 0,
+            scope: Default::default(),
+        },
+        // other torrents, the test torrent is a music release
+        APIInsertPromotionEvent {
+            id: 2,
+            starts_at: now - Duration::hours(1),
+            ends_at: now + Duration::hours(1),
+            upload_factor: 100,
+            download_factor: 0,
+            scope: PromotionEventScope {
+                content_type: Some("movie".to_owned()),
+                ..Default::default()
+            },
+        },
+        APIInsertPromotionEvent {
+            id: 3,
+            starts_at: now - Duration::hours(1),
+            ends_at: now + Duration::hours(1),
+            upload_factor: 200,
+            download_factor: 100,
+            scope: Default::default(),
+        },
+    ];
+    for event in &events {
+        let req = test::TestRequest::put()
+            .uri("/api/promotion-events")
+            .insert_header(("x-api-key", "amazing_api_key"))
+            .set_json(event)
+            .to_request();
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    for req in [announce(0, 0, "&event=started"), announce(1000, 1000, "")] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    {
+        let user_updates = tracker.user_updates.lock();
+        let update = user_updates
+            .records
+            .values()
+            .next()
+            .expect("user update should be queued");
+        assert_eq!(update.uploaded_delta, 2000);
+        assert_eq!(update.downloaded_delta, 1000);
+    }
+
+    let req = test::TestRequest::delete()
+        .uri("/api/promotion-events/3")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        tracker
+            .promotion_events
+            .read()
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        [1, 2]
+    );
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_overlapping_promotion_events_combine_their_factors(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let now = Utc::now();
+    let events = [
+        APIInsertPromotionEvent {
+            id: 1,
+            starts_at: now - Duration::hours(1),
+            ends_at: now + Duration::hours(1),
+            upload_factor: 100,
+            download_factor: 0,
+            scope: PromotionEventScope {
+                content_type: Some("music".to_owned()),
+                ..Default::default()
+            },
+        },
+        APIInsertPromotionEvent {
+            id: 2,
+            starts_at: now - Duration::hours(1),
+            ends_at: now + Duration::hours(1),
+            upload_factor: 300,
+            download_factor: 100,
+            scope: Default::default(),
+        },
+    ];
+    for event in &events {
+        let req = test::TestRequest::put()
+            .uri("/api/promotion-events")
+            .insert_header(("x-api-key", "amazing_api_key"))
+            .set_json(event)
+            .to_request();
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    for req in [announce(0, 0, "&event=started"), announce(1000, 1000, "")] {
+        let resp = test::call_service(&service, req).await;
+        assert!(resp.status().is_success());
+    }
+
+    let user_updates = tracker.user_updates.lock();
+    let update = user_updates
+        .records
+        .values()
+        .next()
+        .expect("user update should be queued");
+    assert_eq!(update.uploaded_delta, 3000);
+    assert_eq!(update.downloaded_delta, 0);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use actix_web::test;
+use common::read_body_bencode;
+use serde::Deserialize;
+use sqlx::PgPool;
+
+#[derive(Debug, Deserialize)]
+struct WrappedError {
+    #[serde(rename = "failure reason")]
+    failure_reason: String,
+}
+
+const VALID_PASSKEY: &str = "d2037c66dd3e13044e0d2f9b891c3837";
+
+const TEST_INFO_HASH: [u8; 20] = [
+    0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00,
+    0x11, 0x22, 0x33, 0x44,
+];
+
+fn url_encode(bytes: &[u8]) -> String {
+    percent_encoding::percent_encode(bytes, percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+fn expected_file_entry(
+    info_hash: &[u8; 20],
+    complete: u32,
+    downloaded: u32,
+    incomplete: u32,
+) -> Vec<u8> {
+    let mut entry = b"20:".to_vec();
+    entry.extend(info_hash);
+    entry.extend(
+        format!("d8:completei{complete}e10:downloadedi{downloaded}e10:incompletei{incomplete}ee")
+            .as_bytes(),
+    );
+    entry
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_scrape_single_info_hash(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/scrape?info_hash={}",
+            VALID_PASSKEY,
+            url_encode(&TEST_INFO_HASH)
+        ))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let body = test::read_body(resp).await;
+
+    let mut expected = b"d5:filesd".to_vec();
+    expected.extend(expected_file_entry(&TEST_INFO_HASH, 0, 0, 0));
+    expected.extend(b"ee");
+
+    assert_eq!(body.to_vec(), expected);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_scrape_reflects_announced_peers(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let mut peer_id = [b'1'; 20];
+    peer_id[0] = b'-';
+    peer_id[1..8].copy_from_slice(b"lt0F01-");
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/announce?info_hash={}&peer_id={}&port=6969&uploaded=0&downloaded=0&left=1000&event=started&compact=1",
+            VALID_PASSKEY,
+            url_encode(&TEST_INFO_HASH),
+            url_encode(&peer_id)
+        ))
+        .insert_header(("User-Agent", "test-agent/1.0"))
+        .peer_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0))
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    // unknown info hashes are left out of the response
+    let unknown_info_hash = [0xFF; 20];
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/{}/scrape?info_hash={}&info_hash={}",
+            VALID_PASSKEY,
+            url_encode(&unknown_info_hash),
+            url_encode(&TEST_INFO_HASH)
+        ))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    let body = test::read_body(resp).await;
+
+    let mut expected = b"d5:filesd".to_vec();
+    expected.extend(expected_file_entry(&TEST_INFO_HASH, 0, 0, 1));
+    expected.extend(b"ee");
+
+    assert_eq!(body.to_vec(), expected);
+}
+
+#[sqlx::test(
+    fixtures("with_test_user"),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_scrape_passkey_not_found(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!(
+            "/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab/scrape?info_hash={}",
+            url_encode(&TEST_INFO_HASH)
+        ))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+
+    assert_eq!(
+        error.failure_reason,
+        "User does not exist. Please re-download the .torrent file."
+    );
+}
+
+#[sqlx::test(
+    fixtures("with_test_user"),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_scrape_missing_info_hash(pool: PgPool) {
+    let service = common::create_test_app(pool).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/{}/scrape", VALID_PASSKEY))
+        .to_request();
+
+    let resp = test::call_service(&service, req).await;
+
+    let error: WrappedError = read_body_bencode(resp)
+        .await
+        .expect("Failed to decode error response");
+
+    assert_eq!(error.failure_reason, "missing info_hash");
+}
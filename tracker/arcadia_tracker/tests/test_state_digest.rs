@@ -0,0 +1,137 @@
+mod common;
+
+use actix_web::test;
+use arcadia_shared::tracker::models::{
+    state_digest::{bucket_of, StateDigest},
+    torrent::APIInsertTorrent,
+    user::APIInsertUser,
+};
+use sqlx::PgPool;
+
+const PASSKEY: &str = "d2037c66dd3e13044e0d2f9b891c3837";
+
+async fn test_user_id(pool: &PgPool) -> u32 {
+    let row: (i32,) = sqlx::query_as("SELECT id FROM users WHERE passkey = $1")
+        .bind(PASSKEY)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to query user");
+    row.0 as u32
+}
+
+fn get_state_digest() -> actix_http::Request {
+    test::TestRequest::get()
+        .uri("/api/state-digest")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .to_request()
+}
+
+#[sqlx::test(
+    fixtures("with_test_user"),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_banned_user_changes_digest(pool: PgPool) {
+    let user_id = test_user_id(&pool).await;
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    // the migrations seed the `creator` user as well
+    let user_count = tracker.users.read().len();
+    let digest: StateDigest = test::call_and_read_body_json(&service, get_state_digest()).await;
+    assert_eq!(digest.users.count, user_count as u64);
+
+    let upsert_user = |banned| {
+        test::TestRequest::put()
+            .uri("/api/users")
+            .insert_header(("x-api-key", "amazing_api_key"))
+            .set_json(APIInsertUser {
+                id: user_id,
+                passkey: PASSKEY.parse().unwrap(),
+                max_snatches_per_day: None,
+                max_leeching: None,
+                banned,
+            })
+            .to_request()
+    };
+
+    let resp = test::call_service(&service, upsert_user(true)).await;
+    assert!(resp.status().is_success());
+    assert!(!tracker.passkey2id.read().values().any(|id| *id == user_id));
+    assert_eq!(tracker.users.read().len(), user_count);
+
+    let banned_digest: StateDigest =
+        test::call_and_read_body_json(&service, get_state_digest()).await;
+    assert_eq!(
+        banned_digest.users.mismatched_buckets(&digest.users),
+        [bucket_of(user_id as u64)].into()
+    );
+    assert_eq!(banned_digest.torrents, digest.torrents);
+
+    let resp = test::call_service(&service, upsert_user(false)).await;
+    assert!(resp.status().is_success());
+    let digest_after_unban: StateDigest =
+        test::call_and_read_body_json(&service, get_state_digest()).await;
+    assert_eq!(digest_after_unban, digest);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent",
+        "with_test_peers"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_torrent_sent_again_keeps_peers(pool: PgPool) {
+    let tracker = common::create_test_tracker(pool).await;
+    let service = common::create_test_app_with_tracker(tracker.clone()).await;
+
+    let info_hash = *tracker
+        .infohash2id
+        .read()
+        .iter()
+        .find(|(_, torrent_id)| **torrent_id == 1)
+        .expect("torrent should be loaded")
+        .0;
+    let (peer_count, scope) = {
+        let torrents = tracker.torrents.lock();
+        (torrents[&1].peers.len(), torrents[&1].scope.clone())
+    };
+    assert!(peer_count > 0);
+    let digest: StateDigest = test::call_and_read_body_json(&service, get_state_digest()).await;
+
+    let req = test::TestRequest::put()
+        .uri("/api/torrents")
+        .insert_header(("x-api-key", "amazing_api_key"))
+        .set_json(APIInsertTorrent {
+            id: 1,
+            info_hash,
+            info_hash_v2: None,
+            is_deleted: false,
+            deletion_reason: None,
+            replacement_torrent_id: None,
+            seeders: 0,
+            leechers: 0,
+            times_completed: 0,
+            upload_factor: 100,
+            download_factor: 0,
+            scope,
+        })
+        .to_request();
+    let resp = test::call_service(&service, req).await;
+    assert!(resp.status().is_success());
+
+    {
+        let torrents = tracker.torrents.lock();
+        assert_eq!(torrents[&1].peers.len(), peer_count);
+        assert_eq!(torrents[&1].download_factor, 0);
+    }
+
+    let new_digest: StateDigest = test::call_and_read_body_json(&service, get_state_digest()).await;
+    assert_eq!(
+        new_digest.torrents.mismatched_buckets(&digest.torrents),
+        [bucket_of(1)].into()
+    );
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use arcadia_tracker::udp::server::UdpTracker;
+use sqlx::PgPool;
+
+const VALID_PASSKEY: &str = "d2037c66dd3e13044e0d2f9b891c3837";
+
+const TEST_INFO_HASH: [u8; 20] = [
+    0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00,
+    0x11, 0x22, 0x33, 0x44,
+];
+
+const CLIENT_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 6881);
+
+fn test_peer_id() -> [u8; 20] {
+    let mut peer_id = [b'1'; 20];
+    peer_id[..8].copy_from_slice(b"-lt0F01-");
+    peer_id
+}
+
+fn connect_request(transaction_id: u32) -> Vec<u8> {
+    let mut request = 0x41727101980_u64.to_be_bytes().to_vec();
+    request.extend(0_u32.to_be_bytes());
+    request.extend(transaction_id.to_be_bytes());
+    request
+}
+
+fn announce_request(
+    connection_id: u64,
+    transaction_id: u32,
+    peer_id: [u8; 20],
+    event: u32,
+    url_data: &[u8],
+) -> Vec<u8> {
+    let mut request = connection_id.to_be_bytes().to_vec();
+    request.extend(1_u32.to_be_bytes());
+    request.extend(transaction_id.to_be_bytes());
+    request.extend(TEST_INFO_HASH);
+    request.extend(peer_id);
+    request.extend(0_u64.to_be_bytes()); // downloaded
+    request.extend(1000_u64.to_be_bytes()); // left
+    request.extend(0_u64.to_be_bytes()); // uploaded
+    request.extend(event.to_be_bytes());
+    request.extend(0_u32.to_be_bytes()); // ip
+    request.extend(0_u32.to_be_bytes()); // key
+    request.extend((-1_i32).to_be_bytes()); // num_want
+    request.extend(6881_u16.to_be_bytes());
+    // BEP 41 url data, split in two options to check they are concatenated
+    let (first, second) = url_data.split_at(url_data.len() / 2);
+    for part in [first, second] {
+        request.push(0x2);
+        request.push(part.len() as u8);
+        request.extend(part);
+    }
+    request.push(0x0);
+    request
+}
+
+fn scrape_request(connection_id: u64, transaction_id: u32, info_hashes: &[[u8; 20]]) -> Vec<u8> {
+    let mut request = connection_id.to_be_bytes().to_vec();
+    request.extend(2_u32.to_be_bytes());
+    request.extend(transaction_id.to_be_bytes());
+    for info_hash in info_hashes {
+        request.extend(info_hash);
+    }
+    request
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// Asserts the response is an error and returns its message
+fn error_message(response: &[u8], transaction_id: u32) -> String {
+    assert_eq!(read_u32(response, 0), 3);
+    assert_eq!(read_u32(response, 4), transaction_id);
+    String::from_utf8(response[8..].to_vec()).unwrap()
+}
+
+async fn connect(udp_tracker: &UdpTracker) -> u64 {
+    let response = udp_tracker
+        .handle_packet(&connect_request(42), CLIENT_ADDR)
+        .await
+        .expect("connect should be answered");
+
+    assert_eq!(response.len(), 16);
+    assert_eq!(read_u32(&response, 0), 0);
+    assert_eq!(read_u32(&response, 4), 42);
+    u64::from_be_bytes(response[8..16].try_into().unwrap())
+}
+
+#[sqlx::test(
+    fixtures("with_test_user"),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_udp_connect_invalid_protocol_id(pool: PgPool) {
+    let udp_tracker = UdpTracker::new(common::create_test_tracker(pool).await);
+
+    let mut request = connect_request(42);
+    request[..8].copy_from_slice(&1_u64.to_be_bytes());
+
+    let response = udp_tracker
+        .handle_packet(&request, CLIENT_ADDR)
+        .await
+        .unwrap();
+    assert_eq!(error_message(&response, 42), "invalid protocol id");
+
+    // too short to even contain a transaction id
+    assert!(udp_tracker
+        .handle_packet(&request[..10], CLIENT_ADDR)
+        .await
+        .is_none());
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_udp_announce_and_scrape(pool: PgPool) {
+    let udp_tracker = UdpTracker::new(common::create_test_tracker(pool).await);
+    let connection_id = connect(&udp_tracker).await;
+
+    let url_data = format!("/{VALID_PASSKEY}/announce");
+    let response = udp_tracker
+        .handle_packet(
+            &announce_request(connection_id, 7, test_peer_id(), 2, url_data.as_bytes()),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(read_u32(&response, 0), 1, "expected announce action");
+    assert_eq!(read_u32(&response, 4), 7);
+    let interval = read_u32(&response, 8);
+    assert!((1800..=7200).contains(&interval));
+    assert_eq!(read_u32(&response, 12), 1, "leechers");
+    assert_eq!(read_u32(&response, 16), 0, "seeders");
+    // the only peer is the announcing user
+    assert_eq!(response.len(), 20);
+
+    // unknown info hashes are answered with zeros, in request order
+    let response = udp_tracker
+        .handle_packet(
+            &scrape_request(connection_id, 8, &[[0xFF; 20], TEST_INFO_HASH]),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(read_u32(&response, 0), 2, "expected scrape action");
+    assert_eq!(read_u32(&response, 4), 8);
+    let files: Vec<u32> = (0..6).map(|i| read_u32(&response, 8 + i * 4)).collect();
+    assert_eq!(files, vec![0, 0, 0, 0, 0, 1]);
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_udp_scrape_requires_an_announce(pool: PgPool) {
+    let udp_tracker = UdpTracker::new(common::create_test_tracker(pool).await);
+    let connection_id = connect(&udp_tracker).await;
+
+    let response = udp_tracker
+        .handle_packet(
+            &scrape_request(connection_id, 7, &[TEST_INFO_HASH]),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        error_message(&response, 7),
+        "announce with a valid passkey before scraping"
+    );
+
+    // an announce with an unknown passkey doesn't count
+    udp_tracker
+        .handle_packet(
+            &announce_request(
+                connection_id,
+                8,
+                test_peer_id(),
+                2,
+                b"/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab/announce",
+            ),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    let response = udp_tracker
+        .handle_packet(
+            &scrape_request(connection_id, 9, &[TEST_INFO_HASH]),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    assert_eq!(read_u32(&response, 0), 3, "expected error action");
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_udp_announce_invalid_connection_id(pool: PgPool) {
+    let udp_tracker = UdpTracker::new(common::create_test_tracker(pool).await);
+    let connection_id = connect(&udp_tracker).await;
+
+    let url_data = format!("/{VALID_PASSKEY}/announce");
+    let request = announce_request(connection_id, 7, test_peer_id(), 2, url_data.as_bytes());
+
+    // the connection id is bound to the address it was issued to
+    let other_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 6881);
+    let response = udp_tracker
+        .handle_packet(&request, other_addr)
+        .await
+        .unwrap();
+    assert_eq!(error_message(&response, 7), "invalid connection id");
+}
+
+#[sqlx::test(
+    fixtures(
+        "with_test_user",
+        "with_test_title_group",
+        "with_test_edition_group",
+        "with_test_torrent"
+    ),
+    migrations = "../../backend/storage/migrations"
+)]
+async fn test_udp_announce_uses_http_validation(pool: PgPool) {
+    let udp_tracker = UdpTracker::new(common::create_test_tracker(pool).await);
+    let connection_id = connect(&udp_tracker).await;
+
+    // no passkey in the url data
+    let response = udp_tracker
+        .handle_packet(
+            &announce_request(connection_id, 7, test_peer_id(), 2, b""),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    assert_eq!(error_message(&response, 7), "Invalid passkey.");
+
+    // unknown passkey
+    let response = udp_tracker
+        .handle_packet(
+            &announce_request(
+                connection_id,
+                8,
+                test_peer_id(),
+                2,
+                b"/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab/announce",
+            ),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        error_message(&response, 8),
+        "User does not exist. Please re-download the .torrent file."
+    );
+
+    // client not in the whitelist
+    let mut peer_id = test_peer_id();
+    peer_id[..8].copy_from_slice(b"-XX0001-");
+    let url_data = format!("/{VALID_PASSKEY}/announce");
+    let response = udp_tracker
+        .handle_packet(
+            &announce_request(connection_id, 9, peer_id, 2, url_data.as_bytes()),
+            CLIENT_ADDR,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        error_message(&response, 9),
+        "torrent client not in whitelist"
+    );
+}